@@ -0,0 +1,377 @@
+//! Reading and writing the system clipboard, which is done through a [`Window`].
+//!
+//! The clipboard is bound to a window as that's who owns its contents on Win32 and X11,
+//! and changes to it are reported to the window as [`Event::ClipboardChanged`](crate::event::Event::ClipboardChanged).
+//!
+//! Contents can be in several formats at once, which other programs pick from:
+//! - [`Text`](ClipboardFormat::Text), which is UTF-8.
+//! - [`Image`](ClipboardFormat::Image), which is RGBA, see [`ClipboardImage`].
+//! - [`Custom`](ClipboardFormat::Custom) formats, which are named, and left as bytes.
+//!   On X11 & Wayland the name is a MIME type, such as `application/json`,
+//!   and on Win32 it's a registered clipboard format name.
+//!
+//! Platform-specific:
+//! - Win32: Images are exchanged as device-independent bitmaps (`CF_DIBV5`).
+//! - X11: Images are exchanged as `image/bmp`. The contents are gone once the window is dropped,
+//!   unless a clipboard manager has taken them over.
+//! - Wayland: Images are exchanged as `image/bmp`. The clipboard can only be read while the window
+//!   has been focused since it last changed, and only be set while the window has the keyboard focus.
+
+use crate::{error::Error, window::{self, Window}};
+
+/// Describes a format of data on the clipboard.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum ClipboardFormat {
+    /// UTF-8 text.
+    Text,
+
+    /// An RGBA image, see [`ClipboardImage`].
+    Image,
+
+    /// Any other format, by name, see the [module documentation](self).
+    Custom(String),
+}
+
+/// An image on the clipboard.
+///
+/// The pixels are RGBA with 8 bits per channel and straight (not premultiplied) alpha,
+/// going row by row from the top-left.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClipboardImage {
+    rgba: Vec<u8>,
+    size: (u32, u32),
+}
+
+impl ClipboardImage {
+    /// Creates an image for the clipboard out of its pixels.
+    ///
+    /// This fails with [`Error::InvalidParameter`] if the pixels don't match the size.
+    pub fn new(rgba: &[u8], size: (u32, u32)) -> Result<Self, Error> {
+        if !window::is_rgba_of_size(rgba, size) {
+            return Err(Error::InvalidParameter("clipboard image pixels must be RGBA and match the size"))
+        }
+        Ok(Self { rgba: rgba.to_vec(), size })
+    }
+
+    /// Gets the pixels of the image.
+    #[inline]
+    pub fn rgba(&self) -> &[u8] {
+        &self.rgba
+    }
+
+    /// Gets the width and height of the image, in pixels.
+    #[inline]
+    pub fn size(&self) -> (u32, u32) {
+        self.size
+    }
+
+    /// Encodes the image as a BMP file, which is what images are exchanged as (see `bmp_to_dib` for Win32).
+    ///
+    /// It's 32-bit with a `BITMAPV5HEADER`, as that's the only kind of header that can say there's alpha.
+    pub(crate) fn to_bmp(&self) -> Vec<u8> {
+        let (width, height) = self.size;
+        let pixels_size = self.rgba.len() as u32;
+        let mut bmp = Vec::with_capacity((BMP_FILE_HEADER_SIZE + BITMAPV5HEADER_SIZE + pixels_size) as usize);
+
+        // BITMAPFILEHEADER
+        bmp.extend_from_slice(b"BM");
+        bmp.extend_from_slice(&(BMP_FILE_HEADER_SIZE + BITMAPV5HEADER_SIZE + pixels_size).to_le_bytes());
+        bmp.extend_from_slice(&[0; 4]); // reserved
+        bmp.extend_from_slice(&(BMP_FILE_HEADER_SIZE + BITMAPV5HEADER_SIZE).to_le_bytes());
+
+        // BITMAPV5HEADER, with the rows going from the bottom up, which is what every program can read
+        for field in &[BITMAPV5HEADER_SIZE, width, height] {
+            bmp.extend_from_slice(&field.to_le_bytes());
+        }
+        bmp.extend_from_slice(&1u16.to_le_bytes()); // planes
+        bmp.extend_from_slice(&32u16.to_le_bytes()); // bits per pixel
+        for field in &[BI_BITFIELDS, pixels_size, 2835, 2835, 0, 0] {
+            bmp.extend_from_slice(&field.to_le_bytes()); // compression, image size, 72 DPI, no palette
+        }
+        for mask in &[0x00FF0000u32, 0x0000FF00, 0x000000FF, 0xFF000000] {
+            bmp.extend_from_slice(&mask.to_le_bytes());
+        }
+        bmp.extend_from_slice(&LCS_SRGB.to_le_bytes());
+        bmp.extend_from_slice(&[0; 48]); // endpoints & gamma, which sRGB ignores
+        for field in &[LCS_GM_IMAGES, 0, 0, 0] {
+            bmp.extend_from_slice(&field.to_le_bytes()); // intent, no profile, reserved
+        }
+
+        // BGRA pixels, which need no padding as each is 4 bytes
+        for row in self.rgba.chunks_exact(width as usize * 4).rev() {
+            for pixel in row.chunks_exact(4) {
+                bmp.extend_from_slice(&[pixel[2], pixel[1], pixel[0], pixel[3]]);
+            }
+        }
+        bmp
+    }
+
+    /// Decodes an image from a BMP file, which can be uncompressed 24-bit or 32-bit, with any header.
+    pub(crate) fn from_bmp(bmp: &[u8]) -> Option<Self> {
+        let u16_at = |i: usize| Some(u16::from_le_bytes([*bmp.get(i)?, *bmp.get(i + 1)?]));
+        let u32_at = |i: usize| Some(u32::from_le_bytes([*bmp.get(i)?, *bmp.get(i + 1)?, *bmp.get(i + 2)?, *bmp.get(i + 3)?]));
+        if bmp.get(..2)? != b"BM" {
+            return None
+        }
+        let pixels_offset = u32_at(10)? as usize;
+        let header_size = u32_at(14)?;
+        if header_size < BITMAPINFOHEADER_SIZE {
+            return None // `BITMAPCOREHEADER` is from OS/2, it's not around anymore
+        }
+        let width = u32_at(18)? as i32;
+        let height = u32_at(22)? as i32;
+        let bit_count = u16_at(28)?;
+        let compression = u32_at(30)?;
+        if width <= 0 || height == 0 {
+            return None
+        }
+
+        // The masks are in the header if it's big enough, otherwise they're right after, which is the same place
+        let (mut masks, mut has_alpha) = ([0x00FF0000, 0x0000FF00, 0x000000FF, 0xFF000000], bit_count == 32);
+        match compression {
+            BI_RGB => (),
+            BI_BITFIELDS | BI_ALPHABITFIELDS if bit_count == 32 => {
+                masks = [u32_at(54)?, u32_at(58)?, u32_at(62)?, 0];
+                if header_size >= BITMAPV3INFOHEADER_SIZE || compression == BI_ALPHABITFIELDS {
+                    masks[3] = u32_at(66)?;
+                }
+                has_alpha = masks[3] != 0;
+            },
+            _ => return None,
+        }
+
+        let (width, rows) = (width as u32, height.unsigned_abs());
+        let bytes_per_pixel = match bit_count {
+            24 | 32 => bit_count as usize / 8,
+            _ => return None,
+        };
+        let stride = (width as usize * bytes_per_pixel + 3) & !3;
+        let pixels = bmp.get(pixels_offset..)?.get(..stride.checked_mul(rows as usize)?)?;
+        let mut rgba = Vec::with_capacity(width as usize * rows as usize * 4);
+        let row_order: Box<dyn Iterator<Item = &[u8]>> = if height > 0 {
+            Box::new(pixels.chunks_exact(stride).rev())
+        } else {
+            Box::new(pixels.chunks_exact(stride))
+        };
+        for row in row_order {
+            for pixel in row[..width as usize * bytes_per_pixel].chunks_exact(bytes_per_pixel) {
+                let value = match *pixel {
+                    [b, g, r] => u32::from_le_bytes([b, g, r, 0xFF]),
+                    [b, g, r, a] => u32::from_le_bytes([b, g, r, a]),
+                    _ => unreachable!(),
+                };
+                let [r, g, b, a] = [0, 1, 2, 3].map(|i| mask_channel(value, masks[i]));
+                rgba.extend_from_slice(&[r, g, b, if has_alpha { a } else { 0xFF }]);
+            }
+        }
+
+        // Plenty of programs put 32-bit pixels on the clipboard with the alpha left as zero
+        if has_alpha && bit_count == 32 && rgba.chunks_exact(4).all(|pixel| pixel[3] == 0) {
+            rgba.chunks_exact_mut(4).for_each(|pixel| pixel[3] = 0xFF);
+        }
+        Some(Self { rgba, size: (width, rows) })
+    }
+}
+
+/// Gets a channel out of a pixel with a bit mask, scaled to 8 bits, which is fully on if there's no mask.
+fn mask_channel(value: u32, mask: u32) -> u8 {
+    if mask == 0 {
+        return 0xFF
+    }
+    let bits = mask.count_ones();
+    let channel = (value & mask) >> mask.trailing_zeros();
+    if bits >= 8 {
+        (channel >> (bits - 8)) as u8
+    } else {
+        (channel * 0xFF / ((1 << bits) - 1)) as u8
+    }
+}
+
+const BMP_FILE_HEADER_SIZE: u32 = 14;
+const BITMAPINFOHEADER_SIZE: u32 = 40;
+const BITMAPV3INFOHEADER_SIZE: u32 = 56;
+const BITMAPV5HEADER_SIZE: u32 = 124;
+const BI_RGB: u32 = 0;
+const BI_BITFIELDS: u32 = 3;
+const BI_ALPHABITFIELDS: u32 = 6;
+const LCS_SRGB: u32 = u32::from_be_bytes(*b"sRGB");
+const LCS_GM_IMAGES: u32 = 4;
+
+/// Turns a BMP file into a device-independent bitmap for the Win32 clipboard, which is the same minus the file header.
+#[cfg_attr(any(not(windows), feature = "headless"), allow(dead_code))]
+pub(crate) fn bmp_to_dib(bmp: &[u8]) -> &[u8] {
+    &bmp[BMP_FILE_HEADER_SIZE as usize..]
+}
+
+/// Turns a device-independent bitmap from the Win32 clipboard into a BMP file.
+///
+/// The pixels come right after the header, the masks (with a `BITMAPINFOHEADER`), and the palette.
+#[cfg_attr(any(not(windows), feature = "headless"), allow(dead_code))]
+pub(crate) fn dib_to_bmp(dib: &[u8]) -> Vec<u8> {
+    let u32_at = |i: usize| dib.get(i..i + 4).map_or(0, |b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]));
+    let (header_size, compression, colors_used) = (u32_at(0), u32_at(16), u32_at(32));
+    let masks_size = match compression {
+        BI_BITFIELDS if header_size == BITMAPINFOHEADER_SIZE => 12,
+        BI_ALPHABITFIELDS if header_size == BITMAPINFOHEADER_SIZE => 16,
+        _ => 0,
+    };
+    let pixels_offset = BMP_FILE_HEADER_SIZE + header_size + masks_size + colors_used.saturating_mul(4);
+    let mut bmp = Vec::with_capacity(BMP_FILE_HEADER_SIZE as usize + dib.len());
+    bmp.extend_from_slice(b"BM");
+    bmp.extend_from_slice(&(BMP_FILE_HEADER_SIZE + dib.len() as u32).to_le_bytes());
+    bmp.extend_from_slice(&[0; 4]);
+    bmp.extend_from_slice(&pixels_offset.to_le_bytes());
+    bmp.extend_from_slice(dib);
+    bmp
+}
+
+/// Data to put on the clipboard, in one or more formats, for other programs to pick from.
+///
+/// # Example
+///
+/// ```no_run
+/// use ramen::{clipboard::ClipboardContents, window::Window};
+///
+/// let window = Window::builder().build().unwrap();
+/// let mut contents = ClipboardContents::new();
+/// contents.text("{\"hello\": 1}").data("application/json", b"{\"hello\": 1}");
+/// window.clipboard().set(&contents);
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ClipboardContents {
+    pub(crate) data: Vec<(ClipboardFormat, Vec<u8>)>,
+}
+
+impl ClipboardContents {
+    /// Creates contents without anything in them, which clears the clipboard if set as they are.
+    #[inline]
+    pub const fn new() -> Self {
+        Self { data: Vec::new() }
+    }
+
+    /// Adds text, replacing any that was added already.
+    pub fn text(&mut self, text: &str) -> &mut Self {
+        self.insert(ClipboardFormat::Text, text.as_bytes().to_vec())
+    }
+
+    /// Adds an image, replacing any that was added already.
+    pub fn image(&mut self, image: &ClipboardImage) -> &mut Self {
+        self.insert(ClipboardFormat::Image, image.to_bmp())
+    }
+
+    /// Adds data in a custom format, see the [module documentation](self),
+    /// replacing any that was added already in that format.
+    pub fn data(&mut self, format: &str, data: &[u8]) -> &mut Self {
+        self.insert(ClipboardFormat::Custom(format.to_owned()), data.to_vec())
+    }
+
+    fn insert(&mut self, format: ClipboardFormat, bytes: Vec<u8>) -> &mut Self {
+        self.data.retain(|(f, _)| *f != format);
+        self.data.push((format, bytes));
+        self
+    }
+
+    /// Gets the data in a format, where text is UTF-8 and images are BMP files.
+    #[cfg_attr(all(windows, not(feature = "headless")), allow(dead_code))]
+    pub(crate) fn get(&self, format: &ClipboardFormat) -> Option<&[u8]> {
+        self.data.iter().find(|(f, _)| f == format).map(|(_, bytes)| bytes.as_slice())
+    }
+
+    /// Gets the formats the data is in.
+    #[cfg_attr(all(windows, not(feature = "headless")), allow(dead_code))]
+    pub(crate) fn formats(&self) -> Vec<ClipboardFormat> {
+        self.data.iter().map(|(format, _)| format.clone()).collect()
+    }
+}
+
+/// The clipboard as seen by a window, as returned by [`Window::clipboard`].
+///
+/// Reading waits until the program owning the contents has sent them,
+/// and both reading and writing wait for the window thread.
+pub struct Clipboard<'a> {
+    window: &'a Window,
+}
+
+impl<'a> Clipboard<'a> {
+    #[inline]
+    pub(crate) fn new(window: &'a Window) -> Self {
+        Self { window }
+    }
+
+    /// Gets the formats that the contents of the clipboard are in, if any.
+    pub fn formats(&self) -> Vec<ClipboardFormat> {
+        self.window.0.clipboard_formats()
+    }
+
+    /// Gets the text on the clipboard, if there is any.
+    ///
+    /// Text which isn't valid UTF-8 has the invalid parts replaced with `U+FFFD`.
+    pub fn text(&self) -> Option<String> {
+        let bytes = self.window.0.clipboard_data(&ClipboardFormat::Text)?;
+        Some(String::from_utf8(bytes).unwrap_or_else(|e| String::from_utf8_lossy(e.as_bytes()).into_owned()))
+    }
+
+    /// Gets the image on the clipboard, if there is one that can be read.
+    pub fn image(&self) -> Option<ClipboardImage> {
+        ClipboardImage::from_bmp(&self.window.0.clipboard_data(&ClipboardFormat::Image)?)
+    }
+
+    /// Gets the data on the clipboard in a custom format, see the [module documentation](self).
+    pub fn data(&self, format: &str) -> Option<Vec<u8>> {
+        self.window.0.clipboard_data(&ClipboardFormat::Custom(format.to_owned()))
+    }
+
+    /// Replaces the contents of the clipboard, returning whether that worked.
+    ///
+    /// Platform-specific:
+    /// - Win32: This fails if another program has the clipboard open.
+    /// - Wayland: This fails if the window doesn't have the keyboard focus.
+    pub fn set(&self, contents: &ClipboardContents) -> bool {
+        self.window.0.set_clipboard(contents)
+    }
+
+    /// Replaces the contents of the clipboard with text, like [`set`](Self::set).
+    pub fn set_text(&self, text: &str) -> bool {
+        self.set(ClipboardContents::new().text(text))
+    }
+
+    /// Replaces the contents of the clipboard with an image, like [`set`](Self::set).
+    pub fn set_image(&self, image: &ClipboardImage) -> bool {
+        self.set(ClipboardContents::new().image(image))
+    }
+
+    /// Empties the clipboard, like [`set`](Self::set).
+    pub fn clear(&self) -> bool {
+        self.set(&ClipboardContents::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bmp_round_trip() {
+        let rgba = [255, 0, 0, 255, 0, 255, 0, 128, 0, 0, 255, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12];
+        let image = ClipboardImage::new(&rgba, (3, 2)).unwrap();
+        let bmp = image.to_bmp();
+        assert_eq!(ClipboardImage::from_bmp(&bmp), Some(image));
+        assert_eq!(dib_to_bmp(bmp_to_dib(&bmp)), bmp);
+    }
+
+    #[test]
+    fn bmp_24_bit_padded() {
+        // A 1x2 image with a `BITMAPINFOHEADER`, bottom row first, each row padded to 4 bytes
+        let mut dib = Vec::new();
+        for field in &[40u32, 1, 2] {
+            dib.extend_from_slice(&field.to_le_bytes());
+        }
+        dib.extend_from_slice(&[1, 0, 24, 0]);
+        dib.extend_from_slice(&[0; 24]);
+        dib.extend_from_slice(&[0x30, 0x20, 0x10, 0, 0x60, 0x50, 0x40, 0]);
+        let image = ClipboardImage::from_bmp(&dib_to_bmp(&dib)).unwrap();
+        assert_eq!(image.size(), (1, 2));
+        assert_eq!(image.rgba(), &[0x40, 0x50, 0x60, 0xFF, 0x10, 0x20, 0x30, 0xFF]);
+    }
+}
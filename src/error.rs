@@ -1,13 +1,58 @@
 use std::{fmt, error};
 
+/// Describes why an operation, such as [`WindowBuilder::build`](crate::window::WindowBuilder::build), failed.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum Error {
+    /// The window class could not be registered.
+    ///
+    /// The associated value is the error code reported by the system (`GetLastError` on Win32).
+    ClassRegistration(u32),
 
+    /// The window could not be created.
+    ///
+    /// The associated value is the error code reported by the system
+    /// (`GetLastError` on Win32, the X protocol error code on X11), which might be zero if it's unknown.
+    WindowCreation(u32),
+
+    /// A graphics surface, such as with [`Window::create_vulkan_surface`](crate::window::Window::create_vulkan_surface),
+    /// could not be created.
+    ///
+    /// The associated value is the error code reported by the graphics API (a `VkResult` for Vulkan).
+    SurfaceCreation(i32),
+
+    /// An OpenGL context could not be created, or set up for the window.
+    ///
+    /// The associated value is the error code reported by the system
+    /// (`GetLastError` on Win32, the X protocol error code on X11, `eglGetError` on Wayland).
+    ContextCreation(u32),
+
+    /// A parameter, such as the window class name, was not accepted. The reason is attached.
+    InvalidParameter(&'static str),
+
+    /// A requested feature isn't supported on this platform. The feature is attached.
+    Unsupported(&'static str),
+
+    /// The windowing system isn't available, such as when there's no display server to connect to.
+    /// The reason is attached.
+    Unavailable(&'static str),
+
+    /// The window thread panicked before the window was created.
+    ThreadPanicked,
 }
 
 impl error::Error for Error {}
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "TODO") // TODO: !
+        match self {
+            Self::ClassRegistration(code) => write!(f, "failed to register window class (error code {:#X})", code),
+            Self::WindowCreation(code) => write!(f, "failed to create window (error code {:#X})", code),
+            Self::SurfaceCreation(code) => write!(f, "failed to create surface (error code {})", code),
+            Self::ContextCreation(code) => write!(f, "failed to create OpenGL context (error code {:#X})", code),
+            Self::InvalidParameter(reason) => write!(f, "invalid parameter: {}", reason),
+            Self::Unsupported(feature) => write!(f, "unsupported on this platform: {}", feature),
+            Self::Unavailable(reason) => write!(f, "windowing system unavailable: {}", reason),
+            Self::ThreadPanicked => f.write_str("the window thread panicked"),
+        }
     }
 }
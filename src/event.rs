@@ -1,8 +1,6 @@
-use crate::monitor::{Scale, Size};
-use std::num::NonZeroI32;
-
+use crate::monitor::{Point, Scale, Size};
 #[cfg(feature = "input")]
-use crate::monitor::Point;
+use std::{fmt, num::NonZeroI32, ops};
 
 /// Details the source of [`Event::CloseRequest`].
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
@@ -23,10 +21,170 @@ pub enum CloseReason {
     Unknown,
 }
 
+/// A handle to text attached to an [`Event`], such as [`ImeEvent::Commit`].
+///
+/// Events can't own their text, so it's kept alongside them until the next
+/// [`Window::swap_events`](crate::window::Window::swap_events).
+/// To get the text, use [`Window::event_text`](crate::window::Window::event_text).
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct EventText {
+    pub(crate) offset: usize,
+    pub(crate) len: usize,
+}
+
+/// A handle to the files attached to an [`Event`], such as [`Event::Drop`].
+///
+/// Like [`EventText`], the paths are kept alongside the events until the next
+/// [`Window::swap_events`](crate::window::Window::swap_events).
+/// To get them, use [`Window::event_paths`](crate::window::Window::event_paths).
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct EventPaths {
+    pub(crate) offset: usize,
+    pub(crate) len: usize,
+}
+
+/// Details an input method editor (IME) composition, as reported by [`Event::Ime`].
+///
+/// IMEs are how text in languages like Chinese, Japanese and Korean is typed:
+/// keystrokes build up some "preedit" text, which is converted and then committed.
+/// Only sent while the IME is allowed, see [`Window::set_ime_allowed`](crate::window::Window::set_ime_allowed).
+#[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "input")))]
+#[cfg_attr(not(feature = "nightly-docs"), cfg(feature = "input"))]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ImeEvent {
+    /// A composition has started.
+    Start,
+
+    /// The preedit text of the composition has changed, and should be drawn at the text caret.
+    ///
+    /// The second value is the byte range of the IME's cursor within the text, or `None` if it's hidden.
+    /// An empty text means the preedit was cleared.
+    Preedit(EventText, Option<(usize, usize)>),
+
+    /// Text has been committed and should be inserted at the text caret.
+    ///
+    /// This text is not also reported with [`Event::Character`].
+    Commit(EventText),
+
+    /// The composition has ended, and any preedit text should be cleared.
+    End,
+}
+
+/// How far something was scrolled, as reported by [`Event::MouseScroll`].
+///
+/// Positive values scroll right and up, the same as [`Event::MouseWheel`].
+#[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "input")))]
+#[cfg_attr(not(feature = "nightly-docs"), cfg(feature = "input"))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ScrollDelta {
+    /// `(x, y)` in lines, which is how mouse wheels scroll, one line per notch.
+    ///
+    /// High-resolution wheels and some touchpads can scroll fractions of a line.
+    Lines(f64, f64),
+
+    /// `(x, y)` in physical pixels, which is how touchpads and other precise devices scroll.
+    Pixels(f64, f64),
+}
+
+/// Where a scroll is within a gesture, such as two fingers moving on a touchpad,
+/// as reported by [`Event::MouseScroll`].
+#[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "input")))]
+#[cfg_attr(not(feature = "nightly-docs"), cfg(feature = "input"))]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ScrollPhase {
+    /// The gesture has started, such as fingers touching down.
+    Begin,
+
+    /// The gesture is ongoing.
+    Update,
+
+    /// The gesture has ended, such as fingers lifting up. This may come with a delta of zero.
+    End,
+
+    /// Scrolling is carrying on by itself after the gesture ended, for kinetic scrolling.
+    Momentum,
+}
+
+/// Where a touch or pen contact is in its lifetime, as reported by [`Event::Touch`] and [`Event::Pen`].
+#[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "input")))]
+#[cfg_attr(not(feature = "nightly-docs"), cfg(feature = "input"))]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum TouchPhase {
+    /// It has touched down on the window.
+    Start,
+
+    /// It has moved, or for pens, changed in some other way, such as pressure.
+    Move,
+
+    /// It has lifted up.
+    End,
+
+    /// The system has taken it over, such as for a gesture, so it won't be ending normally.
+    ///
+    /// Platform-specific:
+    /// - X11: Never sent, as touches only reach the window once it owns them, after which they always end normally.
+    Cancel,
+}
+
+/// A finger on a touchscreen, as reported by [`Event::Touch`].
+#[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "input")))]
+#[cfg_attr(not(feature = "nightly-docs"), cfg(feature = "input"))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Touch {
+    /// Tells fingers apart for as long as they're touching, after which the id can be reused.
+    pub id: u64,
+
+    pub phase: TouchPhase,
+
+    /// Position relative to the top-left of the inner area, which works the same as [`Event::MouseMove`].
+    pub position: Point,
+}
+
+/// A pen (or stylus) on a tablet or touchscreen, as reported by [`Event::Pen`].
+#[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "input")))]
+#[cfg_attr(not(feature = "nightly-docs"), cfg(feature = "input"))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Pen {
+    /// The tip touching down is a [`Start`](TouchPhase::Start), and lifting it up is an [`End`](TouchPhase::End).
+    ///
+    /// Pens are also reported while hovering close to the screen, which is a [`Move`](TouchPhase::Move)
+    /// outside of those.
+    pub phase: TouchPhase,
+
+    /// Position relative to the top-left of the inner area, which works the same as [`Event::MouseMove`].
+    pub position: Point,
+
+    /// How hard the tip is pressed down, from 0.0 to 1.0.
+    pub pressure: f64,
+
+    /// `(x, y)` tilt in degrees, from -90.0 to 90.0, where positive is towards the right and the user.
+    pub tilt: (f64, f64),
+
+    /// Whether it's the eraser end that's being used, or the eraser button is held.
+    pub eraser: bool,
+
+    /// Whether the button on the side of the pen is held.
+    pub barrel_button: bool,
+}
+
 /// An event received from the event loop of a [`Window`](crate::window::Window).
 #[derive(Copy, Clone, Debug, PartialEq)]
 #[non_exhaustive]
 pub enum Event {
+    /// A character of text has been typed, after any dead keys were composed.
+    ///
+    /// This is only sent while text input is enabled, see
+    /// [`Window::set_text_input`](crate::window::Window::set_text_input).
+    /// Control characters such as `'\r'` (enter) and `'\u{8}'` (backspace) are included as typed.
+    #[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "input")))]
+    #[cfg_attr(not(feature = "nightly-docs"), cfg(feature = "input"))]
+    Character(char),
+
+    /// The contents of the clipboard have changed, including by this window.
+    ///
+    /// For more info, see: [`Window::clipboard`](crate::window::Window::clipboard)
+    ClipboardChanged,
+
     /// The window has requested to close.
     /// For more information on why, see the associated [`CloseReason`].
     CloseRequest(CloseReason),
@@ -34,64 +192,168 @@ pub enum Event {
     /// The window focus state has been updated (`true` if focused).
     Focus(bool),
 
+    /// The window has entered (`true`) or left (`false`) fullscreen.
+    Fullscreen(bool),
+
+    /// An input method editor (IME) composition has been updated.
+    #[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "input")))]
+    #[cfg_attr(not(feature = "nightly-docs"), cfg(feature = "input"))]
+    Ime(ImeEvent),
+
     /// The window's maximize state has been updated (`true` if maximized).
     Maximize(bool),
 
     /// The window's minimize state has been updated (`true` if minimized).
     Minimize(bool),
 
+    /// The window has been moved.
+    ///
+    /// The associated values are the new outer position, and work the same as [`Event::Resize`].
+    /// For more info, see: [`Window::outer_position`](crate::window::Window::outer_position)
+    Move((Point, Scale)),
+
     /// The mouse has entered (`true`) or left (`false`) the inner area of the window.
     #[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "input")))]
     #[cfg_attr(not(feature = "nightly-docs"), cfg(feature = "input"))]
     MouseFocus(bool),
 
-    /// A [`MouseButton`] was pressed.
+    /// A [`MouseButton`] was pressed, with the [`Modifiers`] held at the time.
+    ///
+    /// The mouse is captured until every button is released, and each press gets a matching
+    /// [`Event::MouseUp`], even if the release happens outside of the window or the capture is lost.
     #[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "input")))]
     #[cfg_attr(not(feature = "nightly-docs"), cfg(feature = "input"))]
-    MouseDown(MouseButton),
+    MouseDown((MouseButton, Modifiers)),
 
-    /// A [`MouseButton`] was released.
+    /// A [`MouseButton`] was released, with the [`Modifiers`] held at the time.
     #[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "input")))]
     #[cfg_attr(not(feature = "nightly-docs"), cfg(feature = "input"))]
-    MouseUp(MouseButton),
+    MouseUp((MouseButton, Modifiers)),
 
     /// A mouse scrollwheel has been scrolled up or down over the window.
     ///
-    /// The associated value is in eighths of a degree, typically 120 (15 degrees).
+    /// The first value is in eighths of a degree, typically 120 (15 degrees).
     /// Positive if scrolling upwards, negative if scrolling downwards.
+    /// The second value is the [`Modifiers`] held at the time.
+    #[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "input")))]
+    #[cfg_attr(not(feature = "nightly-docs"), cfg(feature = "input"))]
+    MouseWheel((NonZeroI32, Modifiers)),
+
+    /// Something has been scrolled over the window, either vertically, horizontally, or both.
+    ///
+    /// The associated values are the [`ScrollDelta`], the [`ScrollPhase`] if it's part of a gesture
+    /// that the system tells the phases of, and the [`Modifiers`] held at the time.
+    /// Vertical scrolling is also reported as an [`Event::MouseWheel`], right before this.
+    ///
+    /// Platform-specific:
+    /// - Win32: Always in lines, as touchpads are turned into (fractional) wheel scrolling.
+    /// - X11: Always in whole lines, as only the core protocol's wheel buttons are used.
     #[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "input")))]
     #[cfg_attr(not(feature = "nightly-docs"), cfg(feature = "input"))]
-    MouseWheel(NonZeroI32),
+    MouseScroll((ScrollDelta, Option<ScrollPhase>, Modifiers)),
 
-    /// A [`Key`] was pressed.
+    /// A key was pressed.
+    ///
+    /// The associated values are the [`Key`] as the keyboard layout sees it,
+    /// the physical [`ScanCode`] which doesn't depend on the layout,
+    /// and the [`Modifiers`] held, including the key itself if it's a modifier.
     #[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "input")))]
     #[cfg_attr(not(feature = "nightly-docs"), cfg(feature = "input"))]
-    KeyboardDown(Key),
+    KeyboardDown((Key, ScanCode, Modifiers)),
 
-    /// A [`Key`] was auto-repeated by the system.
+    /// A key was auto-repeated by the system.
+    ///
+    /// The associated values work the same as [`Event::KeyboardDown`].
     #[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "input")))]
     #[cfg_attr(not(feature = "nightly-docs"), cfg(feature = "input"))]
-    KeyboardRepeat(Key),
+    KeyboardRepeat((Key, ScanCode, Modifiers)),
 
-    /// A [`Key`] was released.
+    /// A key was released.
+    ///
+    /// The associated values work the same as [`Event::KeyboardDown`].
     #[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "input")))]
     #[cfg_attr(not(feature = "nightly-docs"), cfg(feature = "input"))]
-    KeyboardUp(Key),
+    KeyboardUp((Key, ScanCode, Modifiers)),
 
     /// The position of the mouse inside the window has been updated.
     ///
     /// The associated values work the same as [`Event::Resize`].
+    /// While a mouse button is held down, positions outside of the inner area are reported too,
+    /// which can be negative or beyond the inner size.
     #[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "input")))]
     #[cfg_attr(not(feature = "nightly-docs"), cfg(feature = "input"))]
     MouseMove((Point, Scale)),
 
+    /// The mouse has physically moved, as reported by the device itself.
+    ///
+    /// The associated value is the `(x, y)` distance moved, in device units without any pointer
+    /// acceleration, so it keeps going when the cursor would've hit the edge of the screen.
+    /// This is only sent while enabled, see
+    /// [`Window::set_raw_mouse_motion`](crate::window::Window::set_raw_mouse_motion).
+    #[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "input")))]
+    #[cfg_attr(not(feature = "nightly-docs"), cfg(feature = "input"))]
+    MouseMotionRaw((f64, f64)),
+
+    /// A pen has touched, moved over, or lifted from the window.
+    ///
+    /// The associated values are the [`Pen`]'s state, and the scale factor like [`Event::MouseMove`].
+    /// Pens are also reported as the mouse, separately from this.
+    ///
+    /// Platform-specific:
+    /// - Wayland & X11: Never sent, as pens are only reported as the mouse.
+    #[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "input")))]
+    #[cfg_attr(not(feature = "nightly-docs"), cfg(feature = "input"))]
+    Pen((Pen, Scale)),
+
+    /// A finger has touched, moved over, or lifted from the window.
+    ///
+    /// The associated values are the [`Touch`], and the scale factor like [`Event::MouseMove`].
+    /// By default, the first finger is also reported as the left mouse button,
+    /// see [`WindowBuilder::touch_emulates_mouse`](crate::window::WindowBuilder::touch_emulates_mouse).
+    ///
+    /// Platform-specific:
+    /// - X11: Needs XInput 2.2.
+    #[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "input")))]
+    #[cfg_attr(not(feature = "nightly-docs"), cfg(feature = "input"))]
+    Touch((Touch, Scale)),
+
     /// The window has been resized or had its DPI scaling modified.
     ///
     /// For more info, see: [`Window::inner_size`](crate::window::Window::inner_size)
     Resize((Size, Scale)),
+
+    /// The DPI scale factor of the window has changed, such as by being moved to another monitor.
+    ///
+    /// Logical windows are resized to match, which is reported by an [`Event::Resize`] right after.
+    ///
+    /// Platform-specific:
+    /// - X11: The scale is global, so this is sent when `Xft.dpi` changes, such as through the desktop's settings.
+    ScaleChanged(Scale),
+
+    /// Files are being dragged into the window.
+    ///
+    /// The associated values are the files, which are gotten with
+    /// [`Window::event_paths`](crate::window::Window::event_paths),
+    /// and the cursor position and scale factor like [`Event::MouseMove`].
+    /// Only sent if the window accepts drops, see
+    /// [`WindowBuilder::accept_drops`](crate::window::WindowBuilder::accept_drops).
+    DragEnter((EventPaths, Point, Scale)),
+
+    /// The files being dragged have moved over the window, to the given position.
+    DragOver((Point, Scale)),
+
+    /// The files being dragged have left the window, or the drag was cancelled.
+    DragLeave,
+
+    /// The files being dragged have been dropped on the window, ending the drag.
+    ///
+    /// The associated values are the same as [`Event::DragEnter`].
+    Drop((EventPaths, Point, Scale)),
 }
 
-/// Represents a button on the keyboard.
+/// Represents a button on the keyboard, as the current keyboard layout sees it.
+///
+/// For where the button physically is, see [`ScanCode`].
 #[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "input")))]
 #[cfg_attr(not(feature = "nightly-docs"), cfg(feature = "input"))]
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -187,6 +449,137 @@ pub enum Key {
     LaunchApplication2,
     LaunchMail, // what the fuck?
     LaunchMediaSelect,
+
+    /// A key with no equivalent above, identified by its scancode.
+    Unknown(ScanCode),
+}
+
+/// A physical key on the keyboard, which doesn't depend on the keyboard layout.
+///
+/// The value is platform-specific:
+/// - Win32: The set 1 scancode, with extended keys prefixed by `0xE000`
+/// - X11 & Wayland: The Linux input event code (the X11 keycode minus 8)
+#[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "input")))]
+#[cfg_attr(not(feature = "nightly-docs"), cfg(feature = "input"))]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ScanCode(pub u32);
+
+/// A set of modifier keys which are held down, or lock keys which are toggled on.
+///
+/// Combine flags with `|`, and check for them with [`contains`](Self::contains).
+#[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "input")))]
+#[cfg_attr(not(feature = "nightly-docs"), cfg(feature = "input"))]
+#[derive(Copy, Clone, Default, Eq, Hash, PartialEq)]
+pub struct Modifiers(u8);
+
+#[cfg(feature = "input")]
+impl Modifiers {
+    /// Either Shift key.
+    pub const SHIFT: Self = Self(1 << 0);
+
+    /// Either Control key.
+    pub const CTRL: Self = Self(1 << 1);
+
+    /// Either Alt key, also known as Option.
+    pub const ALT: Self = Self(1 << 2);
+
+    /// Either Super key, also known as the Windows key or Command.
+    pub const SUPER: Self = Self(1 << 3);
+
+    /// Caps Lock, while toggled on.
+    pub const CAPS_LOCK: Self = Self(1 << 4);
+
+    /// Num Lock, while toggled on.
+    pub const NUM_LOCK: Self = Self(1 << 5);
+
+    /// No modifiers at all.
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Returns whether no modifiers are set.
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns whether all of the modifiers in `other` are set.
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Sets all of the modifiers in `other`.
+    pub fn insert(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+
+    /// Unsets all of the modifiers in `other`.
+    pub fn remove(&mut self, other: Self) {
+        self.0 &= !other.0;
+    }
+
+    /// Applies a key press or release, for backends which only know the state from before it.
+    #[cfg_attr(all(windows, not(feature = "headless")), allow(dead_code))]
+    pub(crate) fn update(&mut self, key: Key, pressed: bool) {
+        let modifier = match key {
+            Key::LShift | Key::RShift => Self::SHIFT,
+            Key::LControl | Key::RControl => Self::CTRL,
+            Key::LAlt | Key::RAlt => Self::ALT,
+            Key::LSuper | Key::RSuper => Self::SUPER,
+            Key::CapsLock | Key::NumLock => {
+                // Locks toggle on press, and releasing them does nothing
+                let lock = if key == Key::CapsLock { Self::CAPS_LOCK } else { Self::NUM_LOCK };
+                if pressed {
+                    self.0 ^= lock.0;
+                }
+                return
+            },
+            _ => return,
+        };
+        if pressed {
+            self.insert(modifier);
+        } else {
+            self.remove(modifier);
+        }
+    }
+}
+
+#[cfg(feature = "input")]
+impl ops::BitOr for Modifiers {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+#[cfg(feature = "input")]
+impl ops::BitOrAssign for Modifiers {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.insert(rhs);
+    }
+}
+
+#[cfg(feature = "input")]
+impl fmt::Debug for Modifiers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const NAMES: [(Modifiers, &str); 6] = [
+            (Modifiers::SHIFT, "SHIFT"),
+            (Modifiers::CTRL, "CTRL"),
+            (Modifiers::ALT, "ALT"),
+            (Modifiers::SUPER, "SUPER"),
+            (Modifiers::CAPS_LOCK, "CAPS_LOCK"),
+            (Modifiers::NUM_LOCK, "NUM_LOCK"),
+        ];
+        let mut set = NAMES.iter().filter(|(modifier, _)| self.contains(*modifier)).map(|(_, name)| name);
+        f.write_str("Modifiers(")?;
+        if let Some(name) = set.next() {
+            f.write_str(name)?;
+            for name in set {
+                write!(f, " | {}", name)?;
+            }
+        }
+        f.write_str(")")
+    }
 }
 
 /// Represents a button on the mouse.
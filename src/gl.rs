@@ -0,0 +1,262 @@
+//! OpenGL context creation, without linking to OpenGL.
+//!
+//! Contexts are made for a [`Window`] with a [`GlContextBuilder`], which picks the pixel format
+//! (sRGB, multisampling, depth & stencil bits) and the kind of context (version, profile, debug).
+//! Everything is loaded at runtime, through the platform's own interface:
+//!
+//! - Win32: WGL, from `opengl32.dll`. Versions past 2.1, profiles, debug contexts,
+//!   sRGB and multisampling need `WGL_ARB_create_context` and `WGL_ARB_pixel_format`.
+//! - X11: GLX, from `libGL.so.1`, on a connection of its own to the X server.
+//!   The pixel format has to match the visual of the window.
+//! - Wayland: EGL, from `libEGL.so.1` and `libwayland-egl.so.1`. The drawable doesn't follow
+//!   the window's size by itself, so [`GlContext::resize`] has to be called whenever it changes.
+//!
+//! Like everywhere else in OpenGL, a context is current on one thread at a time,
+//! and most calls (including [`swap_buffers`](GlContext::swap_buffers)) act on the current one.
+//!
+//! None of this needs a GPU, so Mesa's software rasterizer (llvmpipe) works as well,
+//! such as under Xvfb, or with `LIBGL_ALWAYS_SOFTWARE=1` set.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use ramen::{event::Event, gl::GlContext, window::Window};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut window = Window::builder().build()?;
+//! let context = unsafe { GlContext::builder().version(3, 3).samples(4).build(&window)? };
+//! let _ = context.make_current();
+//! let _ = context.set_swap_interval(1);
+//! let _clear = context.get_proc_address("glClear");
+//! loop {
+//!     window.swap_events();
+//!     for event in window.events() {
+//!         match event {
+//!             Event::Resize((size, scale)) => {
+//!                 let (width, height) = size.as_physical(*scale);
+//!                 context.resize(width, height);
+//!             },
+//!             Event::CloseRequest(_) => return Ok(()),
+//!             _ => (),
+//!         }
+//!     }
+//!     // ... draw ...
+//!     let _ = context.swap_buffers();
+//! }
+//! # }
+//! ```
+
+use crate::{error::Error, platform::imp, window::Window};
+use std::{ffi::CString, os::raw::c_void};
+
+/// Describes which profile of OpenGL a context has, for versions 3.2 and above.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum GlProfile {
+    /// Only the non-deprecated functionality of the version.
+    Core,
+
+    /// Everything, including the fixed-function pipeline and other deprecated functionality.
+    Compatibility,
+}
+
+/// Builder for creating [`GlContext`] instances.
+///
+/// To create a builder, use [`GlContext::builder`].
+#[derive(Clone, Debug)]
+pub struct GlContextBuilder {
+    pub(crate) debug: bool,
+    pub(crate) depth_bits: u8,
+    pub(crate) profile: GlProfile,
+    pub(crate) samples: u8,
+    pub(crate) srgb: bool,
+    pub(crate) stencil_bits: u8,
+    pub(crate) version: (u8, u8),
+}
+
+/// An OpenGL context, along with the drawable it renders to on its [`Window`]. Dropping it destroys both.
+///
+/// To instantiate contexts, use a [`builder`](Self::builder).
+pub struct GlContext(pub(crate) imp::GlContextRepr);
+
+impl GlContext {
+    pub const fn builder() -> GlContextBuilder {
+        GlContextBuilder::new()
+    }
+}
+
+impl GlContext {
+    /// Makes this the current context of the calling thread, with the window as its drawable.
+    ///
+    /// Returns `false` if that failed, such as when the context is current on another thread.
+    #[inline]
+    pub fn make_current(&self) -> bool {
+        self.0.make_current()
+    }
+
+    /// Releases the context from the calling thread, if it's current on it.
+    ///
+    /// Returns `false` if that failed, or if the context wasn't current.
+    #[inline]
+    pub fn make_not_current(&self) -> bool {
+        self.0.make_not_current()
+    }
+
+    /// Gets whether this is the current context of the calling thread.
+    #[inline]
+    pub fn is_current(&self) -> bool {
+        self.0.is_current()
+    }
+
+    /// Presents what's been drawn to the back buffer, which should be done with the context current.
+    ///
+    /// Returns `false` if that failed.
+    #[inline]
+    pub fn swap_buffers(&self) -> bool {
+        self.0.swap_buffers()
+    }
+
+    /// Sets how many vertical blanks [`swap_buffers`](Self::swap_buffers) waits for,
+    /// where `0` disables vertical sync, and a negative value allows late swaps to tear (if supported).
+    ///
+    /// This applies to the context current on the calling thread, which should be this one.
+    /// Returns `false` if the interval couldn't be set.
+    #[inline]
+    pub fn set_swap_interval(&self, interval: i32) -> bool {
+        self.0.set_swap_interval(interval)
+    }
+
+    /// Gets the address of an OpenGL function, such as `glClear`, or null if it isn't available.
+    ///
+    /// The addresses are specific to this context, or at least its pixel format on Win32.
+    pub fn get_proc_address(&self, name: &str) -> *const c_void {
+        match CString::new(name) {
+            Ok(name) => self.0.get_proc_address(&name),
+            Err(_) => std::ptr::null(),
+        }
+    }
+
+    /// Sets the size of the drawable in physical pixels, which should follow [`Event::Resize`](crate::event::Event::Resize).
+    ///
+    /// This is only needed on Wayland, and does nothing elsewhere, where the drawable is always the size of the window.
+    #[inline]
+    pub fn resize(&self, width: u32, height: u32) {
+        self.0.resize(width, height);
+    }
+}
+
+impl GlContextBuilder {
+    pub(crate) const fn new() -> Self {
+        Self {
+            debug: false,
+            depth_bits: 24,
+            profile: GlProfile::Core,
+            samples: 0,
+            srgb: false,
+            stencil_bits: 8,
+            version: (3, 3),
+        }
+    }
+
+    /// Creates the context for a window, or fails with an [`Error`] describing why it couldn't.
+    ///
+    /// If OpenGL or a requested feature isn't available, this fails with [`Error::Unsupported`],
+    /// and if it was refused by the driver, such as with too high of a version, with [`Error::ContextCreation`].
+    ///
+    /// # Safety
+    ///
+    /// The context renders to the window, so it has to be dropped before the window is.
+    pub unsafe fn build(&self, window: &Window) -> Result<GlContext, Error> {
+        window.0.create_gl_context(self).map(GlContext)
+    }
+}
+
+impl GlContextBuilder {
+    /// Sets whether the context is a debug context, which has more checks and reports more through `KHR_debug`.
+    ///
+    /// Defaults to `false`.
+    #[inline]
+    pub fn debug(&mut self, debug: bool) -> &mut Self {
+        self.debug = debug;
+        self
+    }
+
+    /// Sets the minimum amount of bits in the depth buffer, where `0` is fine with not having one.
+    ///
+    /// Defaults to `24`.
+    #[inline]
+    pub fn depth_bits(&mut self, depth_bits: u8) -> &mut Self {
+        self.depth_bits = depth_bits;
+        self
+    }
+
+    /// Sets the profile of the context, which only matters for versions 3.2 and above.
+    ///
+    /// Defaults to [`GlProfile::Core`].
+    #[inline]
+    pub fn profile(&mut self, profile: GlProfile) -> &mut Self {
+        self.profile = profile;
+        self
+    }
+
+    /// Sets the amount of samples per pixel for multisampling, where `0` disables it.
+    ///
+    /// Defaults to `0`.
+    #[inline]
+    pub fn samples(&mut self, samples: u8) -> &mut Self {
+        self.samples = samples;
+        self
+    }
+
+    /// Sets whether the back buffer is sRGB capable, for use with `GL_FRAMEBUFFER_SRGB`.
+    ///
+    /// Defaults to `false`.
+    #[inline]
+    pub fn srgb(&mut self, srgb: bool) -> &mut Self {
+        self.srgb = srgb;
+        self
+    }
+
+    /// Sets the minimum amount of bits in the stencil buffer, where `0` is fine with not having one.
+    ///
+    /// Defaults to `8`.
+    #[inline]
+    pub fn stencil_bits(&mut self, stencil_bits: u8) -> &mut Self {
+        self.stencil_bits = stencil_bits;
+        self
+    }
+
+    /// Sets the minimum OpenGL version of the context.
+    ///
+    /// Drivers usually give out the highest version that's compatible with the requested one,
+    /// so the actual version should be checked with `glGetString(GL_VERSION)`.
+    ///
+    /// Defaults to `3.3`.
+    #[inline]
+    pub fn version(&mut self, major: u8, minor: u8) -> &mut Self {
+        self.version = (major, minor);
+        self
+    }
+}
+
+/// Checks if a space-separated extension list, as returned by `glXQueryExtensionsString` & co., has an extension.
+///
+/// WGL extensions are just looked up instead, as they come with the context that's needed to list them anyway.
+#[cfg_attr(any(windows, feature = "headless"), allow(dead_code))]
+pub(crate) fn has_extension(extensions: &[u8], name: &str) -> bool {
+    extensions.split(|&b| b == b' ').any(|ext| ext == name.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extension_lists() {
+        let extensions = b"GLX_ARB_create_context GLX_ARB_create_context_profile GLX_EXT_swap_control";
+        assert!(has_extension(extensions, "GLX_ARB_create_context"));
+        assert!(has_extension(extensions, "GLX_EXT_swap_control"));
+        assert!(!has_extension(extensions, "GLX_ARB_create"));
+        assert!(!has_extension(b"", "GLX_EXT_swap_control"));
+    }
+}
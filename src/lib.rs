@@ -3,12 +3,17 @@
 #![cfg_attr(feature = "nightly-docs", feature(doc_cfg))]
 
 #[macro_use]
+// Most of the utilities are for the native backends, which `headless` compiles out
+#[cfg_attr(feature = "headless", allow(dead_code, unused_macros))]
 pub(crate) mod util;
 
+pub mod clipboard;
 pub mod error;
 pub mod event;
+pub mod gl;
 pub mod monitor;
 pub mod platform;
+pub mod vulkan;
 pub mod window;
 
 #[cfg(test)]
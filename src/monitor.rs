@@ -1,5 +1,7 @@
+use crate::platform::imp;
+
 macro_rules! dpi_vec2_impl {
-    ($($t_ident:ident ($m1:ident, $m2:ident) $name:literal),* $(,)?) => {
+    ($($t_ident:ident ($m1:ident, $m2:ident: $p_ty:ty) $name:literal),* $(,)?) => {
         $(
             // Type definition
             document!(
@@ -9,7 +11,7 @@ macro_rules! dpi_vec2_impl {
                     #[doc = "Logical"] #[doc = $name] #[doc = "that is scalable to monitor DPI."]
                     Logical(f64, f64),
                     #[doc = "Physical"] #[doc = $name] #[doc = "in absolute values regardless of DPI."]
-                    Physical(u32, u32),
+                    Physical($p_ty, $p_ty),
                 }
             );
 
@@ -38,10 +40,10 @@ macro_rules! dpi_vec2_impl {
                         "If `self` is already physical, no upscaling is done."
                     ),
                     #[inline]
-                    pub fn as_physical(self, scale: Scale) -> (u32, u32) {
+                    pub fn as_physical(self, scale: Scale) -> ($p_ty, $p_ty) {
                         // NOTE: `const fn` doesn't have floating point arithmetic yet.
                         match self {
-                            Self::Logical($m1, $m2) => (($m1 * scale) as u32, ($m2 * scale) as u32),
+                            Self::Logical($m1, $m2) => (($m1 * scale) as $p_ty, ($m2 * scale) as $p_ty),
                             Self::Physical($m1, $m2) => ($m1, $m2),
                         }
                     }
@@ -80,10 +82,155 @@ macro_rules! dpi_vec2_impl {
 }
 
 // This is where the magic happens.
+// Points are signed, as the desktop extends left of and above the primary monitor.
 dpi_vec2_impl! {
-    Point(x, y) "point",
-    Size(width, height) "size",
+    Point(x, y: i32) "point",
+    Size(width, height: u32) "size",
 }
 
 /// Represents a DPI scale factor to apply to a [`Point`] or [`Size`].
 pub type Scale = f64;
+
+/// Represents a video mode (resolution, bit depth and refresh rate) of a monitor.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct VideoMode {
+    /// The resolution, in physical pixels.
+    pub size: (u32, u32),
+
+    /// The number of bits per pixel.
+    pub bit_depth: u16,
+
+    /// The refresh rate in millihertz, so that 59.94Hz is `59940`.
+    pub refresh_rate: u32,
+}
+
+/// Represents a rectangle on the desktop, in physical pixels.
+///
+/// The desktop spans every monitor, so the position can be negative,
+/// such as for monitors that are to the left of or above the primary one.
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Rect {
+    /// Gets the area where `self` and `other` overlap, if they do.
+    pub fn intersection(self, other: Self) -> Option<Self> {
+        let left = self.x.max(other.x);
+        let top = self.y.max(other.y);
+        let right = (self.x as i64 + self.width as i64).min(other.x as i64 + other.width as i64);
+        let bottom = (self.y as i64 + self.height as i64).min(other.y as i64 + other.height as i64);
+        if right > left as i64 && bottom > top as i64 {
+            Some(Self {
+                x: left,
+                y: top,
+                width: (right - left as i64) as u32,
+                height: (bottom - top as i64) as u32,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+/// Represents a monitor connected to the system, as it was when it was queried.
+///
+/// Monitors can be plugged in, unplugged or rearranged at any time, so this is a snapshot,
+/// and not updated later on. To get one, use [`available`], [`primary`] or
+/// [`Window::current_monitor`](crate::window::Window::current_monitor).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Monitor {
+    pub(crate) name: String,
+    pub(crate) rect: Rect,
+    pub(crate) work_area: Rect,
+    pub(crate) scale: Scale,
+    pub(crate) refresh_rate: u32,
+    pub(crate) is_primary: bool,
+    pub(crate) video_modes: Vec<VideoMode>,
+}
+
+impl Monitor {
+    /// Gets the human-readable name of the monitor.
+    ///
+    /// Platform-specific:
+    /// - Win32: The name of the display device, such as `Generic PnP Monitor`.
+    /// - Wayland & X11: The name of the output, such as `DP-1`.
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Gets the area the monitor covers on the desktop.
+    ///
+    /// Platform-specific:
+    /// - Wayland: The position is as the compositor reports it, which can be scaled.
+    #[inline]
+    pub fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    /// Gets the area of the monitor not covered by taskbars, docks and such,
+    /// which is where windows are placed when maximized.
+    ///
+    /// Platform-specific:
+    /// - Wayland: Not reported by compositors, so it's always the same as [`rect`](Self::rect).
+    /// - X11: Based on `_NET_WORKAREA`, if the window manager supports it.
+    #[inline]
+    pub fn work_area(&self) -> Rect {
+        self.work_area
+    }
+
+    /// Gets the DPI scale factor of the monitor.
+    ///
+    /// Platform-specific:
+    /// - X11: There's only a global scale factor, which is the same for all monitors.
+    #[inline]
+    pub fn scale(&self) -> Scale {
+        self.scale
+    }
+
+    /// Gets the refresh rate of the current video mode in millihertz, so that 59.94Hz is `59940`.
+    ///
+    /// This is zero if it's not known.
+    #[inline]
+    pub fn refresh_rate(&self) -> u32 {
+        self.refresh_rate
+    }
+
+    /// Gets whether this is the primary monitor.
+    ///
+    /// Platform-specific:
+    /// - Wayland: There's no such thing as a primary monitor, so this is always `false`.
+    #[inline]
+    pub fn is_primary(&self) -> bool {
+        self.is_primary
+    }
+
+    /// Gets the video modes the monitor supports, for use with
+    /// [`Fullscreen::Exclusive`](crate::window::Fullscreen::Exclusive).
+    ///
+    /// Platform-specific:
+    /// - Wayland: Most compositors only report the current video mode.
+    #[inline]
+    pub fn video_modes(&self) -> &[VideoMode] {
+        &self.video_modes
+    }
+}
+
+/// Lists the monitors connected to the system.
+///
+/// This is empty if the windowing system isn't available.
+pub fn available() -> Vec<Monitor> {
+    imp::available_monitors()
+}
+
+/// Gets the primary monitor, which is usually where the taskbar and new windows are.
+///
+/// This is `None` if the windowing system isn't available, or if there's no primary monitor,
+/// which is always the case on Wayland.
+pub fn primary() -> Option<Monitor> {
+    available().into_iter().find(|monitor| monitor.is_primary)
+}
@@ -7,11 +7,53 @@
 //
 // The module `imp` (for implementation) should be pub(crate) exported, with:
 // - The type `WindowRepr` which is callable as a window is (see src/window.rs)
+// - The type `GlContextRepr` which is callable as an OpenGL context is (see src/gl.rs)
 // - The function `spawn_window` which is `fn(&WindowBuilder) -> Result<WindowRepr, Error>`
+// - The function `available_monitors` which is `fn() -> Vec<Monitor>`
+// - The function `vulkan_instance_extensions` which is `fn() -> &'static [&'static CStr]`
 // For an example, see `src/platform/win32.rs`
+//
+// On free Unix-likes, `unix` is the `imp` module, and picks between `wayland` and `x11` at runtime.
+// Those two follow the same layout, and share the cross-thread messaging code in `unix`.
+//
+// The `headless` feature makes `headless` the `imp` module everywhere, and compiles out the rest.
+
+#[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "headless")))]
+#[cfg_attr(not(feature = "nightly-docs"), cfg(feature = "headless"))]
+pub mod headless;
+#[cfg(feature = "headless")]
+pub(crate) use headless as imp;
 
 #[cfg_attr(feature = "nightly-docs", doc(cfg(target_os = "windows")))]
 #[cfg_attr(not(feature = "nightly-docs"), cfg(target_os = "windows"))]
+#[cfg(not(feature = "headless"))]
 pub mod win32;
-#[cfg(target_os = "windows")]
+#[cfg(all(target_os = "windows", not(feature = "headless")))]
 pub(crate) use win32 as imp;
+
+#[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android")), not(feature = "headless")))]
+pub(crate) mod unix;
+#[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android")), not(feature = "headless")))]
+pub(crate) use unix as imp;
+
+#[cfg_attr(
+    feature = "nightly-docs",
+    doc(cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android")))))
+)]
+#[cfg_attr(
+    not(feature = "nightly-docs"),
+    cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))
+)]
+#[cfg(not(feature = "headless"))]
+pub mod wayland;
+
+#[cfg_attr(
+    feature = "nightly-docs",
+    doc(cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android")))))
+)]
+#[cfg_attr(
+    not(feature = "nightly-docs"),
+    cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))
+)]
+#[cfg(not(feature = "headless"))]
+pub mod x11;
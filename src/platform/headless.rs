@@ -0,0 +1,33 @@
+//! Headless implementation and input injection, for running without a display.
+//!
+//! Enabling the `headless` feature replaces the native backend, on every platform.
+//! Windows are kept entirely in memory and never show up anywhere, however they behave
+//! the same way through the [`Window`](crate::window::Window) API, and with [`WindowExt`]
+//! it's possible to simulate what the user or the OS would do to a real window.
+//!
+//! # Only enable this for tests
+//!
+//! This is a compile-time switch, and Cargo turns a feature on for everything that depends
+//! on a crate if anything in the build asks for it. So if a library depending on ramen enables
+//! `headless`, even if only in its tests, every program built alongside it gets headless windows
+//! that never show up. To keep it out of normal builds, only ever enable it in `dev-dependencies`,
+//! and use the version 2 resolver (`resolver = "2"`, the default from Rust 2021 on), which stops
+//! features in `dev-dependencies` from leaking into anything but tests & examples:
+//!
+//! ```toml
+//! [dependencies]
+//! ramen = "0.0.1"
+//!
+//! [dev-dependencies]
+//! ramen = { version = "0.0.1", features = ["headless"] }
+//! ```
+
+pub(crate) mod imp;
+
+// Required re-exports
+pub(crate) use imp::{available_monitors, spawn_window, vulkan_instance_extensions};
+pub(crate) type WindowRepr = imp::WindowImpl;
+pub(crate) type GlContextRepr = imp::GlContextImpl;
+
+// Bonus
+pub use imp::{WindowExt, WindowState};
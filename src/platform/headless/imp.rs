@@ -0,0 +1,1473 @@
+use crate::{
+    clipboard::{ClipboardContents, ClipboardFormat},
+    error::Error,
+    event::{CloseReason, Event, EventPaths},
+    gl::GlContextBuilder,
+    monitor::{Monitor, Point, Rect, Scale, Size, VideoMode},
+    util::{sync::{self, Mutex}, LazyCell},
+    vulkan::{PFN_vkGetInstanceProcAddr, VkInstance, VkSurfaceKHR},
+    window::{self, Controls, CursorGrab, CursorIcon, Fullscreen, Icon, WindowBuilder},
+};
+use std::{
+    ffi::CStr,
+    mem,
+    os::raw::c_void,
+    path::{Path, PathBuf},
+};
+
+#[cfg(feature = "raw-window-handle")]
+use raw_window_handle::{HandleError, RawDisplayHandle, RawWindowHandle};
+
+#[cfg(feature = "input")]
+use crate::{
+    event::{EventText, ImeEvent, Key, Modifiers, MouseButton, ScanCode, ScrollDelta, ScrollPhase, Touch},
+    util::TouchMouse,
+};
+
+/// The clipboard shared by every headless window, along with how many times it's been set.
+static CLIPBOARD: LazyCell<Mutex<(u64, ClipboardContents)>> = LazyCell::new(Default::default);
+
+/// Implementation container for `window::Window`.
+///
+/// There's no window thread, so everything happens on the calling thread, under a lock.
+pub struct WindowImpl {
+    /// Events that were pushed before the last swap, only touched with `&mut self`
+    events: Vec<Event>,
+
+    /// The text which `events` refer to
+    events_text: String,
+
+    /// The paths which `events` refer to
+    events_paths: Vec<PathBuf>,
+
+    /// All the state of the "window", including the active event buffer
+    user: Mutex<WindowImplData>,
+}
+
+/// User data structure, the same as the native backends have, minus the native parts.
+struct WindowImplData {
+    accept_drops: bool,
+    class_name: String,
+    clipboard_generation: u64,
+    client_area_size: (u32, u32),
+    current_scale: Scale,
+    cursor: CursorIcon,
+    cursor_grab: CursorGrab,
+    cursor_position: (i32, i32),
+    fullscreen: Option<Fullscreen>,
+    icon: Option<Icon>,
+    #[cfg(feature = "input")]
+    ime_allowed: bool,
+    #[cfg(feature = "input")]
+    ime_cursor_area: Option<(Point, Size)>,
+    is_dpi_logical: bool,
+    position: (i32, i32),
+    #[cfg(feature = "input")]
+    raw_mouse_motion: bool,
+    style: window::Style,
+    #[cfg(feature = "input")]
+    text_input: bool,
+    title: String,
+    #[cfg(feature = "input")]
+    touch_emulates_mouse: bool,
+
+    event_queue: Vec<Event>,
+    event_text: String,
+    event_paths: Vec<PathBuf>,
+    /// The files being dragged over the window, if there's a drag.
+    drag_paths: Option<Vec<PathBuf>>,
+    #[cfg(feature = "input")]
+    held_keys: Vec<ScanCode>,
+    #[cfg(feature = "input")]
+    held_buttons: Vec<MouseButton>,
+    #[cfg(feature = "input")]
+    touch_mouse: TouchMouse,
+    #[cfg(feature = "input")]
+    is_composing: bool,
+    is_focused: bool,
+    is_maximized: bool,
+    is_minimized: bool,
+    #[cfg(feature = "input")]
+    modifiers: Modifiers,
+}
+
+/// A snapshot of the state of a headless window, as returned by [`WindowExt::headless_state`].
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct WindowState {
+    pub accept_drops: bool,
+    pub borderless: bool,
+    pub class_name: String,
+    pub controls: Option<Controls>,
+    pub cursor: CursorIcon,
+    pub cursor_grab: CursorGrab,
+    /// Whether the cursor grab is being held, which is only while focused and not minimized.
+    pub cursor_grabbed: bool,
+    /// Physical cursor position as it was last set, relative to the inner area.
+    pub cursor_position: (i32, i32),
+    pub focused: bool,
+    pub fullscreen: Option<Fullscreen>,
+    pub icon: Option<Icon>,
+    #[cfg(feature = "input")]
+    pub ime_allowed: bool,
+    /// The IME cursor area as it was last set, relative to the inner area.
+    #[cfg(feature = "input")]
+    pub ime_cursor_area: Option<(Point, Size)>,
+    /// Physical inner size, see [`scale`](Self::scale).
+    pub inner_size: (u32, u32),
+    pub maximized: bool,
+    pub minimized: bool,
+    /// Physical position, which is the same inside and outside, as there's no frame.
+    pub position: (i32, i32),
+    #[cfg(feature = "input")]
+    pub raw_mouse_motion: bool,
+    pub resizable: bool,
+    pub rtl_layout: bool,
+    pub scale: Scale,
+    #[cfg(feature = "input")]
+    pub text_input: bool,
+    pub title: String,
+    #[cfg(feature = "input")]
+    pub touch_emulates_mouse: bool,
+    pub visible: bool,
+}
+
+/// Headless specific extensions to the [`Window`](crate::window::Window) API.
+///
+/// The `inject_*` functions simulate something happening to the window from the outside,
+/// pushing the same events a native backend would push, which show up after the next
+/// [`swap_events`](crate::window::Window::swap_events).
+///
+/// # Example
+///
+/// ```
+/// use ramen::{event::Event, monitor::Size, platform::headless::WindowExt as _, window::Window};
+///
+/// let mut window = Window::builder().build().unwrap();
+/// window.inject_resize(Size::Physical(640, 480));
+/// window.swap_events();
+/// assert_eq!(window.events(), &[Event::Resize((Size::Logical(640.0, 480.0), 1.0))]);
+/// ```
+pub trait WindowExt {
+    /// Gets a snapshot of everything the window is keeping track of.
+    fn headless_state(&self) -> WindowState;
+
+    /// Simulates files being dragged onto the window, with a position like
+    /// [`inject_mouse_move`](Self::inject_mouse_move).
+    ///
+    /// This is dropped unless there are some files and the window
+    /// [accepts drops](crate::window::WindowBuilder::accept_drops).
+    fn inject_drag_enter(&self, paths: &[&Path], position: Point);
+
+    /// Simulates the dragged files moving over the window, which is dropped unless they've entered.
+    fn inject_drag_over(&self, position: Point);
+
+    /// Simulates the dragged files leaving the window, or the drag being cancelled.
+    fn inject_drag_leave(&self);
+
+    /// Simulates the dragged files being dropped on the window, which ends the drag.
+    fn inject_drop(&self, position: Point);
+
+    /// Simulates a character being typed, which is dropped if text input is disabled.
+    #[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "input")))]
+    #[cfg_attr(not(feature = "nightly-docs"), cfg(feature = "input"))]
+    fn inject_character(&self, character: char);
+
+    /// Simulates a close request, such as the user pressing the close button.
+    fn inject_close_request(&self, reason: CloseReason);
+
+    /// Simulates the window gaining (`true`) or losing (`false`) keyboard focus.
+    ///
+    /// Losing focus forgets which keys are held down, as no releases would be received.
+    /// Mouse buttons that are held down are released, like when a native window loses its mouse capture.
+    fn inject_focus(&self, focused: bool);
+
+    /// Simulates a key being pressed (`true`) or released (`false`).
+    ///
+    /// Pressing a scancode that's already held down is reported as an auto-repeat.
+    /// Modifier and lock keys update the [`Modifiers`] sent with input events.
+    #[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "input")))]
+    #[cfg_attr(not(feature = "nightly-docs"), cfg(feature = "input"))]
+    fn inject_key(&self, key: Key, scancode: ScanCode, pressed: bool);
+
+    /// Simulates the IME committing some text, which ends the composition if there was one.
+    ///
+    /// This is dropped if text input is disabled or the IME isn't allowed.
+    #[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "input")))]
+    #[cfg_attr(not(feature = "nightly-docs"), cfg(feature = "input"))]
+    fn inject_ime_commit(&self, text: &str);
+
+    /// Simulates the IME updating the preedit text, which starts a composition if there wasn't one.
+    ///
+    /// This is dropped if text input is disabled or the IME isn't allowed.
+    #[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "input")))]
+    #[cfg_attr(not(feature = "nightly-docs"), cfg(feature = "input"))]
+    fn inject_ime_preedit(&self, text: &str, cursor: Option<(usize, usize)>);
+
+    /// Simulates the window being maximized or restored by the window manager.
+    fn inject_maximize(&self, maximized: bool);
+
+    /// Simulates the window being minimized or restored by the window manager.
+    fn inject_minimize(&self, minimized: bool);
+
+    /// Simulates a mouse button being pressed (`true`) or released (`false`).
+    #[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "input")))]
+    #[cfg_attr(not(feature = "nightly-docs"), cfg(feature = "input"))]
+    fn inject_mouse_button(&self, button: MouseButton, pressed: bool);
+
+    /// Simulates the cursor moving to a position relative to the top-left of the inner area.
+    ///
+    /// Positions outside of the inner area are dropped, unless a mouse button is held down,
+    /// in which case they're reported like the native backends do while capturing the mouse.
+    /// A [`Logical`](Point::Logical) position is scaled by the current DPI scale factor.
+    #[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "input")))]
+    #[cfg_attr(not(feature = "nightly-docs"), cfg(feature = "input"))]
+    fn inject_mouse_move(&self, position: Point);
+
+    /// Simulates the mouse physically moving, which is dropped unless raw mouse motion
+    /// is enabled and the window is focused.
+    #[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "input")))]
+    #[cfg_attr(not(feature = "nightly-docs"), cfg(feature = "input"))]
+    fn inject_mouse_motion_raw(&self, dx: f64, dy: f64);
+
+    /// Simulates the user moving the window by dragging its title bar.
+    ///
+    /// A [`Logical`](Point::Logical) position is scaled by the current DPI scale factor.
+    fn inject_move(&self, position: Point);
+
+    /// Simulates scrolling over the window, such as with a mouse wheel or touchpad.
+    ///
+    /// Vertical scrolling in [`Lines`](ScrollDelta::Lines) is also reported as an
+    /// [`Event::MouseWheel`], the same as a wheel would be.
+    #[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "input")))]
+    #[cfg_attr(not(feature = "nightly-docs"), cfg(feature = "input"))]
+    fn inject_scroll(&self, delta: ScrollDelta, phase: Option<ScrollPhase>);
+
+    /// Simulates the user resizing the window by dragging its edges.
+    ///
+    /// A [`Logical`](Size::Logical) size is scaled by the current DPI scale factor.
+    fn inject_resize(&self, size: Size);
+
+    /// Simulates a finger touching the window, with a position like
+    /// [`inject_mouse_move`](Self::inject_mouse_move), but which is never dropped.
+    ///
+    /// The first finger is also reported as the left mouse button, unless that's been turned off with
+    /// [`WindowBuilder::touch_emulates_mouse`](crate::window::WindowBuilder::touch_emulates_mouse).
+    #[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "input")))]
+    #[cfg_attr(not(feature = "nightly-docs"), cfg(feature = "input"))]
+    fn inject_touch(&self, touch: Touch);
+
+    /// Simulates a change of the DPI scale factor, such as moving to a different monitor.
+    ///
+    /// If the window is in logical DPI mode, it's resized to keep the same logical size.
+    fn inject_scale(&self, scale: Scale);
+}
+
+impl WindowExt for window::Window {
+    fn headless_state(&self) -> WindowState {
+        let data = sync::mutex_lock(&self.0.user);
+        WindowState {
+            accept_drops: data.accept_drops,
+            borderless: data.style.borderless,
+            class_name: data.class_name.clone(),
+            controls: data.style.controls.clone(),
+            cursor: data.cursor.clone(),
+            cursor_grab: data.cursor_grab,
+            cursor_grabbed: data.cursor_grab != CursorGrab::None && data.is_focused && !data.is_minimized,
+            cursor_position: data.cursor_position,
+            focused: data.is_focused,
+            fullscreen: data.fullscreen.clone(),
+            icon: data.icon.clone(),
+            #[cfg(feature = "input")]
+            ime_allowed: data.ime_allowed,
+            #[cfg(feature = "input")]
+            ime_cursor_area: data.ime_cursor_area,
+            inner_size: data.client_area_size,
+            maximized: data.is_maximized,
+            minimized: data.is_minimized,
+            position: data.position,
+            #[cfg(feature = "input")]
+            raw_mouse_motion: data.raw_mouse_motion,
+            resizable: data.style.resizable,
+            rtl_layout: data.style.rtl_layout,
+            scale: data.current_scale,
+            #[cfg(feature = "input")]
+            text_input: data.text_input,
+            title: data.title.clone(),
+            #[cfg(feature = "input")]
+            touch_emulates_mouse: data.touch_emulates_mouse,
+            visible: data.style.visible,
+        }
+    }
+
+    fn inject_drag_enter(&self, paths: &[&Path], position: Point) {
+        let mut data = sync::mutex_lock(&self.0.user);
+        if data.accept_drops && !paths.is_empty() {
+            let paths = paths.iter().map(|&path| path.to_owned()).collect::<Vec<_>>();
+            let (position, scale) = (data.scale_point(position.as_physical(data.current_scale)), data.current_scale);
+            data.push_event_with_paths(&paths, |paths| Event::DragEnter((paths, position, scale)));
+            data.drag_paths = Some(paths);
+        }
+    }
+
+    fn inject_drag_over(&self, position: Point) {
+        let mut data = sync::mutex_lock(&self.0.user);
+        if data.drag_paths.is_some() {
+            let position = data.scale_point(position.as_physical(data.current_scale));
+            let event = Event::DragOver((position, data.current_scale));
+            data.push_event(event);
+        }
+    }
+
+    fn inject_drag_leave(&self) {
+        let mut data = sync::mutex_lock(&self.0.user);
+        if data.drag_paths.take().is_some() {
+            data.push_event(Event::DragLeave);
+        }
+    }
+
+    fn inject_drop(&self, position: Point) {
+        let mut data = sync::mutex_lock(&self.0.user);
+        if let Some(paths) = data.drag_paths.take() {
+            let (position, scale) = (data.scale_point(position.as_physical(data.current_scale)), data.current_scale);
+            data.push_event_with_paths(&paths, |paths| Event::Drop((paths, position, scale)));
+        }
+    }
+
+    #[cfg(feature = "input")]
+    fn inject_character(&self, character: char) {
+        let mut data = sync::mutex_lock(&self.0.user);
+        if data.text_input {
+            data.push_event(Event::Character(character));
+        }
+    }
+
+    fn inject_close_request(&self, reason: CloseReason) {
+        sync::mutex_lock(&self.0.user).push_event(Event::CloseRequest(reason));
+    }
+
+    fn inject_focus(&self, focused: bool) {
+        let mut data = sync::mutex_lock(&self.0.user);
+        if data.is_focused != focused {
+            data.is_focused = focused;
+            data.push_event(Event::Focus(focused));
+        }
+
+        // Key releases aren't sent to unfocused windows, so forget what's held down
+        #[cfg(feature = "input")]
+        if !focused {
+            let modifiers = data.modifiers;
+            for button in mem::take(&mut data.held_buttons) {
+                data.push_event(Event::MouseUp((button, modifiers)));
+            }
+            data.held_keys.clear();
+            data.modifiers.remove(Modifiers::SHIFT | Modifiers::CTRL | Modifiers::ALT | Modifiers::SUPER);
+        }
+    }
+
+    #[cfg(feature = "input")]
+    fn inject_key(&self, key: Key, scancode: ScanCode, pressed: bool) {
+        let mut data = sync::mutex_lock(&self.0.user);
+        let held = data.held_keys.iter().position(|&k| k == scancode);
+        let event = match (pressed, held) {
+            (true, Some(_)) => Event::KeyboardRepeat((key, scancode, data.modifiers)),
+            (true, None) => {
+                data.held_keys.push(scancode);
+                data.modifiers.update(key, true);
+                Event::KeyboardDown((key, scancode, data.modifiers))
+            },
+            (false, held) => {
+                if let Some(index) = held {
+                    let _ = data.held_keys.swap_remove(index);
+                }
+                data.modifiers.update(key, false);
+                Event::KeyboardUp((key, scancode, data.modifiers))
+            },
+        };
+        data.push_event(event);
+    }
+
+    #[cfg(feature = "input")]
+    fn inject_ime_commit(&self, text: &str) {
+        let mut data = sync::mutex_lock(&self.0.user);
+        if data.text_input && data.ime_allowed {
+            data.push_event_with_text(text, |text| Event::Ime(ImeEvent::Commit(text)));
+            if mem::replace(&mut data.is_composing, false) {
+                data.push_event(Event::Ime(ImeEvent::End));
+            }
+        }
+    }
+
+    #[cfg(feature = "input")]
+    fn inject_ime_preedit(&self, text: &str, cursor: Option<(usize, usize)>) {
+        let mut data = sync::mutex_lock(&self.0.user);
+        if data.text_input && data.ime_allowed {
+            if !mem::replace(&mut data.is_composing, true) {
+                data.push_event(Event::Ime(ImeEvent::Start));
+            }
+            data.push_event_with_text(text, |text| Event::Ime(ImeEvent::Preedit(text, cursor)));
+        }
+    }
+
+    fn inject_maximize(&self, maximized: bool) {
+        sync::mutex_lock(&self.0.user).set_maximized(maximized);
+    }
+
+    fn inject_minimize(&self, minimized: bool) {
+        let mut data = sync::mutex_lock(&self.0.user);
+        if data.is_minimized != minimized {
+            data.is_minimized = minimized;
+            data.push_event(Event::Minimize(minimized));
+        }
+    }
+
+    #[cfg(feature = "input")]
+    fn inject_mouse_button(&self, button: MouseButton, pressed: bool) {
+        let mut data = sync::mutex_lock(&self.0.user);
+        let modifiers = data.modifiers;
+        if !pressed {
+            data.held_buttons.retain(|&b| b != button);
+            data.push_event(Event::MouseUp((button, modifiers)));
+        } else {
+            if !data.held_buttons.contains(&button) {
+                data.held_buttons.push(button);
+            }
+            data.push_event(Event::MouseDown((button, modifiers)));
+        }
+    }
+
+    #[cfg(feature = "input")]
+    fn inject_mouse_move(&self, position: Point) {
+        let mut data = sync::mutex_lock(&self.0.user);
+        let (x, y) = position.as_physical(data.current_scale);
+        let (width, height) = data.client_area_size;
+        let is_inside = x >= 0 && (x as u32) < width && y >= 0 && (y as u32) < height;
+        if is_inside || !data.held_buttons.is_empty() {
+            let event = Event::MouseMove((data.scale_point((x, y)), data.current_scale));
+            data.push_event(event);
+        }
+    }
+
+    #[cfg(feature = "input")]
+    fn inject_mouse_motion_raw(&self, dx: f64, dy: f64) {
+        let mut data = sync::mutex_lock(&self.0.user);
+        if data.raw_mouse_motion && data.is_focused {
+            data.push_event(Event::MouseMotionRaw((dx, dy)));
+        }
+    }
+
+    fn inject_move(&self, position: Point) {
+        let mut data = sync::mutex_lock(&self.0.user);
+        let position = position.as_physical(data.current_scale);
+        data.move_to(position);
+    }
+
+    #[cfg(feature = "input")]
+    fn inject_scroll(&self, delta: ScrollDelta, phase: Option<ScrollPhase>) {
+        let mut data = sync::mutex_lock(&self.0.user);
+        let modifiers = data.modifiers;
+        if let ScrollDelta::Lines(_, y) = delta {
+            if let Some(wheel) = std::num::NonZeroI32::new((y * 120.0).round() as i32) {
+                data.push_event(Event::MouseWheel((wheel, modifiers)));
+            }
+        }
+        data.push_event(Event::MouseScroll((delta, phase, modifiers)));
+    }
+
+    fn inject_resize(&self, size: Size) {
+        let mut data = sync::mutex_lock(&self.0.user);
+        let size = size.as_physical(data.current_scale);
+        data.resize(size);
+    }
+
+    #[cfg(feature = "input")]
+    fn inject_touch(&self, touch: Touch) {
+        let mut data = sync::mutex_lock(&self.0.user);
+        let scale = data.current_scale;
+        let position = data.scale_point(touch.position.as_physical(scale));
+        let touch = Touch { position, ..touch };
+        data.push_event(Event::Touch((touch, scale)));
+        if data.touch_emulates_mouse {
+            let modifiers = data.modifiers;
+            for event in data.touch_mouse.emulate(&touch, scale, modifiers).iter().flatten() {
+                data.push_event(*event);
+            }
+        }
+    }
+
+    fn inject_scale(&self, scale: Scale) {
+        let mut data = sync::mutex_lock(&self.0.user);
+        if data.current_scale != scale {
+            let old_scale = mem::replace(&mut data.current_scale, scale);
+            data.push_event(Event::ScaleChanged(scale));
+            let (width, height) = data.client_area_size;
+            if data.is_dpi_logical {
+                let size = Size::Physical(width, height).to_logical(old_scale);
+                data.client_area_size = size.as_physical(scale);
+            }
+            let event = Event::Resize((data.scale_size(data.client_area_size), scale));
+            data.push_event(event);
+        }
+    }
+}
+
+/// The one and only monitor, which every window is on. Its scale follows the window's.
+fn virtual_monitor(scale: Scale) -> Monitor {
+    let mode = VideoMode { size: (1920, 1080), bit_depth: 24, refresh_rate: 60000 };
+    let rect = Rect { x: 0, y: 0, width: mode.size.0, height: mode.size.1 };
+    Monitor {
+        name: String::from("Headless"),
+        rect,
+        work_area: rect,
+        scale,
+        refresh_rate: mode.refresh_rate,
+        is_primary: true,
+        video_modes: vec![mode],
+    }
+}
+
+pub fn vulkan_instance_extensions() -> &'static [&'static CStr] {
+    // There's nothing to present to, so no surface extensions either
+    &[]
+}
+
+pub fn available_monitors() -> Vec<Monitor> {
+    vec![virtual_monitor(1.0)]
+}
+
+pub fn spawn_window(builder: &WindowBuilder) -> Result<WindowImpl, Error> {
+    // A freshly created window has a scale factor of 1.0 until told otherwise,
+    // and isn't focused, as nothing would have given it focus.
+    let current_scale = 1.0;
+    Ok(WindowImpl {
+        events: Vec::new(),
+        events_text: String::new(),
+        events_paths: Vec::new(),
+        user: Mutex::new(WindowImplData {
+            accept_drops: builder.accept_drops,
+            class_name: builder.class_name.as_ref().to_owned(),
+            clipboard_generation: sync::mutex_lock(&CLIPBOARD).0,
+            client_area_size: builder.inner_size.as_physical(current_scale),
+            current_scale,
+            cursor: builder.cursor.clone(),
+            cursor_grab: CursorGrab::None,
+            cursor_position: (0, 0),
+            fullscreen: builder.fullscreen.clone(),
+            icon: builder.icon.clone(),
+            #[cfg(feature = "input")]
+            ime_allowed: builder.ime_allowed,
+            #[cfg(feature = "input")]
+            ime_cursor_area: None,
+            is_dpi_logical: matches!(builder.inner_size, Size::Logical(..)),
+            position: builder.position.map(|p| p.as_physical(current_scale)).unwrap_or((0, 0)),
+            #[cfg(feature = "input")]
+            raw_mouse_motion: builder.raw_mouse_motion,
+            style: builder.style.clone(),
+            #[cfg(feature = "input")]
+            text_input: builder.text_input,
+            title: builder.title.as_ref().to_owned(),
+            #[cfg(feature = "input")]
+            touch_emulates_mouse: builder.touch_emulates_mouse,
+
+            event_queue: Vec::new(),
+            event_text: String::new(),
+            event_paths: Vec::new(),
+            drag_paths: None,
+            #[cfg(feature = "input")]
+            held_keys: Vec::new(),
+            #[cfg(feature = "input")]
+            held_buttons: Vec::new(),
+            #[cfg(feature = "input")]
+            touch_mouse: TouchMouse::default(),
+            #[cfg(feature = "input")]
+            is_composing: false,
+            is_focused: false,
+            is_maximized: false,
+            is_minimized: false,
+            #[cfg(feature = "input")]
+            modifiers: Modifiers::empty(),
+        }),
+    })
+}
+
+/// Implementation container for `gl::GlContext`, which can't exist without a display server.
+pub enum GlContextImpl {}
+
+impl GlContextImpl {
+    pub fn make_current(&self) -> bool {
+        match *self {}
+    }
+
+    pub fn make_not_current(&self) -> bool {
+        match *self {}
+    }
+
+    pub fn is_current(&self) -> bool {
+        match *self {}
+    }
+
+    pub fn swap_buffers(&self) -> bool {
+        match *self {}
+    }
+
+    pub fn set_swap_interval(&self, _interval: i32) -> bool {
+        match *self {}
+    }
+
+    pub fn get_proc_address(&self, _name: &CStr) -> *const c_void {
+        match *self {}
+    }
+
+    pub fn resize(&self, _width: u32, _height: u32) {
+        match *self {}
+    }
+}
+
+impl WindowImpl {
+    #[inline]
+    pub fn execute<F, T>(&self, f: F) -> T
+    where
+        F: Send + FnOnce() -> T,
+        T: Send,
+    {
+        // The calling thread is as good as a window thread here
+        f()
+    }
+
+    #[inline]
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+
+    #[inline]
+    pub fn events_text(&self) -> &str {
+        &self.events_text
+    }
+
+    #[inline]
+    pub fn events_paths(&self) -> &[PathBuf] {
+        &self.events_paths
+    }
+
+    pub fn clipboard_data(&self, format: &ClipboardFormat) -> Option<Vec<u8>> {
+        sync::mutex_lock(&CLIPBOARD).1.get(format).map(<[u8]>::to_vec)
+    }
+
+    pub fn clipboard_formats(&self) -> Vec<ClipboardFormat> {
+        sync::mutex_lock(&CLIPBOARD).1.formats()
+    }
+
+    #[inline]
+    pub unsafe fn create_gl_context(&self, _builder: &GlContextBuilder) -> Result<GlContextImpl, Error> {
+        Err(Error::Unsupported("OpenGL"))
+    }
+
+    #[inline]
+    pub unsafe fn create_vulkan_surface(
+        &self,
+        _instance: VkInstance,
+        _get_instance_proc_addr: Option<PFN_vkGetInstanceProcAddr>,
+    ) -> Result<VkSurfaceKHR, Error> {
+        Err(Error::Unsupported("Vulkan surfaces"))
+    }
+
+    #[inline]
+    pub fn current_monitor(&self) -> Option<Monitor> {
+        Some(virtual_monitor(sync::mutex_lock(&self.user).current_scale))
+    }
+
+    #[inline]
+    pub fn inner_position(&self) -> (Point, Scale) {
+        self.outer_position()
+    }
+
+    #[inline]
+    pub fn inner_size(&self) -> (Size, Scale) {
+        let data = sync::mutex_lock(&self.user);
+        (data.scale_size(data.client_area_size), data.current_scale)
+    }
+
+    #[inline]
+    pub fn is_dpi_logical(&self) -> bool {
+        sync::mutex_lock(&self.user).is_dpi_logical
+    }
+
+    #[inline]
+    pub fn outer_position(&self) -> (Point, Scale) {
+        let data = sync::mutex_lock(&self.user);
+        (data.scale_point(data.position), data.current_scale)
+    }
+
+    // There's no screen for the pixels to go to, which isn't something to fail over
+    #[inline]
+    pub fn present_pixels(&self, _pixels: &[u32], _size: (u32, u32), _damage: &[Rect]) -> Result<(), Error> {
+        Ok(())
+    }
+
+    // There's nothing to draw to without a display server, so there are no handles either
+    #[cfg(feature = "raw-window-handle")]
+    #[inline]
+    pub fn raw_display_handle(&self) -> Result<RawDisplayHandle, HandleError> {
+        Err(HandleError::NotSupported)
+    }
+
+    #[cfg(feature = "raw-window-handle")]
+    #[inline]
+    pub fn raw_window_handle(&self) -> Result<RawWindowHandle, HandleError> {
+        Err(HandleError::NotSupported)
+    }
+
+    #[inline]
+    pub fn set_borderless(&self, borderless: bool) {
+        sync::mutex_lock(&self.user).style.borderless = borderless;
+    }
+
+    #[inline]
+    pub fn set_borderless_async(&self, borderless: bool) {
+        self.set_borderless(borderless);
+    }
+
+    #[inline]
+    pub fn set_clipboard(&self, contents: &ClipboardContents) -> bool {
+        let mut clipboard = sync::mutex_lock(&CLIPBOARD);
+        *clipboard = (clipboard.0 + 1, contents.clone());
+        true
+    }
+
+    pub fn set_controls(&self, controls: Option<Controls>) {
+        sync::mutex_lock(&self.user).style.controls = controls;
+    }
+
+    #[inline]
+    pub fn set_controls_async(&self, controls: Option<Controls>) {
+        self.set_controls(controls);
+    }
+
+    #[inline]
+    pub fn set_cursor(&self, cursor: CursorIcon) {
+        sync::mutex_lock(&self.user).cursor = cursor;
+    }
+
+    #[inline]
+    pub fn set_cursor_async(&self, cursor: CursorIcon) {
+        self.set_cursor(cursor);
+    }
+
+    #[inline]
+    pub fn set_cursor_grab(&self, grab: CursorGrab) {
+        sync::mutex_lock(&self.user).cursor_grab = grab;
+    }
+
+    #[inline]
+    pub fn set_cursor_grab_async(&self, grab: CursorGrab) {
+        self.set_cursor_grab(grab);
+    }
+
+    #[inline]
+    pub fn set_cursor_position(&self, position: Point) {
+        let mut data = sync::mutex_lock(&self.user);
+        data.cursor_position = position.as_physical(data.current_scale);
+    }
+
+    #[inline]
+    pub fn set_cursor_position_async(&self, position: Point) {
+        self.set_cursor_position(position);
+    }
+
+    #[inline]
+    pub fn set_fullscreen(&self, fullscreen: Option<Fullscreen>) {
+        let mut data = sync::mutex_lock(&self.user);
+        let is_fullscreen = fullscreen.is_some();
+        let was_fullscreen = mem::replace(&mut data.fullscreen, fullscreen).is_some();
+        if was_fullscreen != is_fullscreen {
+            data.push_event(Event::Fullscreen(is_fullscreen));
+        }
+    }
+
+    #[inline]
+    pub fn set_fullscreen_async(&self, fullscreen: Option<Fullscreen>) {
+        self.set_fullscreen(fullscreen);
+    }
+
+    #[inline]
+    pub fn set_icon(&self, icon: Option<Icon>) {
+        sync::mutex_lock(&self.user).icon = icon;
+    }
+
+    #[inline]
+    pub fn set_icon_async(&self, icon: Option<Icon>) {
+        self.set_icon(icon);
+    }
+
+    #[inline]
+    pub fn set_inner_size(&self, size: Size) {
+        let mut data = sync::mutex_lock(&self.user);
+        // Like on Win32 & X11, the size is known beforehand, so asking for it isn't reported as a `Resize`
+        data.is_dpi_logical = matches!(size, Size::Logical(..));
+        data.client_area_size = size.as_physical(data.current_scale);
+    }
+
+    #[inline]
+    pub fn set_maximized(&self, maximized: bool) {
+        sync::mutex_lock(&self.user).set_maximized(maximized);
+    }
+
+    #[inline]
+    pub fn set_maximized_async(&self, maximized: bool) {
+        self.set_maximized(maximized);
+    }
+
+    #[inline]
+    pub fn set_position(&self, position: Point) {
+        let mut data = sync::mutex_lock(&self.user);
+        let position = position.as_physical(data.current_scale);
+        data.move_to(position);
+    }
+
+    #[inline]
+    pub fn set_position_async(&self, position: Point) {
+        self.set_position(position);
+    }
+
+    #[cfg(feature = "input")]
+    #[inline]
+    pub fn set_raw_mouse_motion(&self, enabled: bool) {
+        sync::mutex_lock(&self.user).raw_mouse_motion = enabled;
+    }
+
+    #[cfg(feature = "input")]
+    #[inline]
+    pub fn set_raw_mouse_motion_async(&self, enabled: bool) {
+        self.set_raw_mouse_motion(enabled);
+    }
+
+    #[inline]
+    pub fn set_resizable(&self, resizable: bool) {
+        sync::mutex_lock(&self.user).style.resizable = resizable;
+    }
+
+    #[inline]
+    pub fn set_resizable_async(&self, resizable: bool) {
+        self.set_resizable(resizable);
+    }
+
+    #[cfg(feature = "input")]
+    #[inline]
+    pub fn set_ime_allowed(&self, allowed: bool) {
+        let mut data = sync::mutex_lock(&self.user);
+        data.ime_allowed = allowed;
+        if !allowed {
+            data.end_composition();
+        }
+    }
+
+    #[cfg(feature = "input")]
+    #[inline]
+    pub fn set_ime_allowed_async(&self, allowed: bool) {
+        self.set_ime_allowed(allowed);
+    }
+
+    #[cfg(feature = "input")]
+    #[inline]
+    pub fn set_ime_cursor_area(&self, position: Point, size: Size) {
+        sync::mutex_lock(&self.user).ime_cursor_area = Some((position, size));
+    }
+
+    #[cfg(feature = "input")]
+    #[inline]
+    pub fn set_ime_cursor_area_async(&self, position: Point, size: Size) {
+        self.set_ime_cursor_area(position, size);
+    }
+
+    #[cfg(feature = "input")]
+    #[inline]
+    pub fn set_text_input(&self, enabled: bool) {
+        let mut data = sync::mutex_lock(&self.user);
+        data.text_input = enabled;
+        if !enabled {
+            data.end_composition();
+        }
+    }
+
+    #[cfg(feature = "input")]
+    #[inline]
+    pub fn set_text_input_async(&self, enabled: bool) {
+        self.set_text_input(enabled);
+    }
+
+    #[inline]
+    pub fn set_title(&self, title: &str) {
+        sync::mutex_lock(&self.user).title = title.to_owned();
+    }
+
+    #[inline]
+    pub fn set_title_async(&self, title: &str) {
+        self.set_title(title);
+    }
+
+    #[inline]
+    pub fn set_visible(&self, visible: bool) {
+        sync::mutex_lock(&self.user).style.visible = visible;
+    }
+
+    #[inline]
+    pub fn set_visible_async(&self, visible: bool) {
+        self.set_visible(visible);
+    }
+
+    pub fn swap_events(&mut self) {
+        // Recycle the old allocations as the new active buffer
+        self.events.clear();
+        self.events_text.clear();
+        self.events_paths.clear();
+        let mut data = sync::mutex_lock(&self.user);
+
+        // Every window sees clipboard changes, so they're caught up on here instead of being pushed
+        let clipboard_generation = sync::mutex_lock(&CLIPBOARD).0;
+        if mem::replace(&mut data.clipboard_generation, clipboard_generation) != clipboard_generation {
+            data.push_event(Event::ClipboardChanged);
+        }
+        mem::swap(&mut self.events, &mut data.event_queue);
+        mem::swap(&mut self.events_text, &mut data.event_text);
+        mem::swap(&mut self.events_paths, &mut data.event_paths);
+    }
+}
+
+impl WindowImplData {
+    #[inline]
+    fn push_event(&mut self, event: Event) {
+        self.event_queue.push(event);
+    }
+
+    #[cfg(feature = "input")]
+    fn push_event_with_text(&mut self, text: &str, f: impl FnOnce(EventText) -> Event) {
+        let offset = self.event_text.len();
+        self.event_text.push_str(text);
+        self.push_event(f(EventText { offset, len: text.len() }));
+    }
+
+    fn push_event_with_paths(&mut self, paths: &[PathBuf], f: impl FnOnce(EventPaths) -> Event) {
+        let offset = self.event_paths.len();
+        self.event_paths.extend_from_slice(paths);
+        self.push_event(f(EventPaths { offset, len: paths.len() }));
+    }
+
+    #[inline]
+    fn scale_point(&self, (x, y): (i32, i32)) -> Point {
+        let point = Point::Physical(x, y);
+        if self.is_dpi_logical {
+            point.to_logical(self.current_scale)
+        } else {
+            point
+        }
+    }
+
+    #[inline]
+    fn scale_size(&self, (width, height): (u32, u32)) -> Size {
+        let size = Size::Physical(width, height);
+        if self.is_dpi_logical {
+            size.to_logical(self.current_scale)
+        } else {
+            size
+        }
+    }
+
+    /// Cancels the IME composition, if there is one, as native IMEs do when they're turned off.
+    #[cfg(feature = "input")]
+    fn end_composition(&mut self) {
+        if mem::replace(&mut self.is_composing, false) {
+            self.push_event(Event::Ime(ImeEvent::End));
+        }
+    }
+
+    fn move_to(&mut self, position: (i32, i32)) {
+        if self.position != position {
+            self.position = position;
+            let event = Event::Move((self.scale_point(position), self.current_scale));
+            self.push_event(event);
+        }
+    }
+
+    fn resize(&mut self, size: (u32, u32)) {
+        if self.client_area_size != size {
+            self.client_area_size = size;
+            let event = Event::Resize((self.scale_size(size), self.current_scale));
+            self.push_event(event);
+        }
+    }
+
+    fn set_maximized(&mut self, maximized: bool) {
+        if self.is_maximized != maximized {
+            self.is_maximized = maximized;
+            self.push_event(Event::Maximize(maximized));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::window::Window;
+
+    #[test]
+    fn events_after_swap() {
+        let mut window = Window::builder().inner_size(Size::Physical(640, 480)).build().unwrap();
+        window.inject_focus(true);
+        window.inject_resize(Size::Physical(800, 600));
+        window.inject_close_request(CloseReason::SystemMenu);
+        assert!(window.events().is_empty());
+
+        window.swap_events();
+        assert_eq!(window.events(), &[
+            Event::Focus(true),
+            Event::Resize((Size::Physical(800, 600), 1.0)),
+            Event::CloseRequest(CloseReason::SystemMenu),
+        ]);
+
+        window.swap_events();
+        assert!(window.events().is_empty());
+    }
+
+    #[test]
+    fn programmatic_resize_is_silent() {
+        let mut window = Window::builder().inner_size(Size::Physical(640, 480)).build().unwrap();
+        window.set_inner_size(Size::Physical(800, 600));
+        assert_eq!(window.inner_size(), (Size::Physical(800, 600), 1.0));
+        window.inject_resize(Size::Physical(1024, 768));
+        window.swap_events();
+        assert_eq!(window.events(), &[Event::Resize((Size::Physical(1024, 768), 1.0))]);
+    }
+
+    #[test]
+    fn dropped_files() {
+        let (a, b) = (Path::new("/tmp/a.txt"), Path::new("/tmp/b c.png"));
+        let mut window = Window::builder().accept_drops(true).inner_size(Size::Physical(640, 480)).build().unwrap();
+        window.inject_drag_over(Point::Physical(1, 1));
+        window.inject_drag_enter(&[a, b], Point::Physical(10, 20));
+        window.inject_drag_over(Point::Physical(30, 40));
+        window.inject_drop(Point::Physical(30, 40));
+        window.inject_drag_leave();
+        window.swap_events();
+
+        let events = window.events();
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[1], Event::DragOver((Point::Physical(30, 40), 1.0)));
+        match (events[0], events[2]) {
+            (Event::DragEnter((enter, Point::Physical(10, 20), _)), Event::Drop((drop, Point::Physical(30, 40), _))) => {
+                assert_eq!(window.event_paths(enter), &[a, b]);
+                assert_eq!(window.event_paths(drop), &[a, b]);
+            },
+            _ => panic!("unexpected events: {:?}", events),
+        }
+
+        // Windows don't accept drops unless they say so
+        let mut window = Window::builder().build().unwrap();
+        window.inject_drag_enter(&[a], Point::Physical(10, 20));
+        window.inject_drop(Point::Physical(10, 20));
+        window.swap_events();
+        assert!(window.events().is_empty());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn dropped_files_not_unicode() {
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+        let path = Path::new(OsStr::from_bytes(b"/tmp/\xFFfile\xC0.txt"));
+        let mut window = Window::builder().accept_drops(true).build().unwrap();
+        window.inject_drag_enter(&[path], Point::Physical(10, 20));
+        window.inject_drop(Point::Physical(10, 20));
+        window.swap_events();
+        match window.events() {
+            &[Event::DragEnter((enter, ..)), Event::Drop((drop, ..))] => {
+                assert_eq!(window.event_paths(enter), &[path]);
+                assert_eq!(window.event_paths(drop)[0].as_os_str().as_bytes(), b"/tmp/\xFFfile\xC0.txt");
+            },
+            events => panic!("unexpected events: {:?}", events),
+        }
+    }
+
+    #[cfg(feature = "raw-window-handle")]
+    #[test]
+    fn no_raw_handles() {
+        use raw_window_handle::{HasDisplayHandle, HasWindowHandle};
+
+        let window = Window::builder().build().unwrap();
+        assert!(matches!(window.window_handle(), Err(HandleError::NotSupported)));
+        assert!(matches!(window.display_handle(), Err(HandleError::NotSupported)));
+    }
+
+    #[test]
+    fn no_vulkan_surfaces() {
+        let window = Window::builder().build().unwrap();
+        assert!(crate::vulkan::required_instance_extensions().is_empty());
+        let surface = unsafe { window.create_vulkan_surface(std::ptr::null_mut(), None) };
+        assert!(matches!(surface, Err(Error::Unsupported(_))));
+    }
+
+    #[test]
+    fn shared_clipboard() {
+        use crate::clipboard::{ClipboardContents, ClipboardImage};
+
+        let mut a = Window::builder().build().unwrap();
+        let mut b = Window::builder().build().unwrap();
+        let image = ClipboardImage::new(&[1, 2, 3, 4, 5, 6, 7, 8], (2, 1)).unwrap();
+        assert!(a.clipboard().set(ClipboardContents::new().text("hello").image(&image).data("text/x-ramen", b"\0")));
+        assert_eq!(b.clipboard().formats(), &[
+            ClipboardFormat::Text,
+            ClipboardFormat::Image,
+            ClipboardFormat::Custom(String::from("text/x-ramen")),
+        ]);
+        assert_eq!(b.clipboard().text().as_deref(), Some("hello"));
+        assert_eq!(b.clipboard().image(), Some(image));
+        assert_eq!(b.clipboard().data("text/x-ramen").as_deref(), Some(&b"\0"[..]));
+        assert_eq!(b.clipboard().data("text/x-other"), None);
+
+        // Both windows are told, but only once for however many changes
+        assert!(b.clipboard().set_text("bye"));
+        a.swap_events();
+        b.swap_events();
+        assert_eq!(a.events(), &[Event::ClipboardChanged]);
+        assert_eq!(b.events(), &[Event::ClipboardChanged]);
+        assert_eq!(a.clipboard().text().as_deref(), Some("bye"));
+        assert_eq!(a.clipboard().image(), None);
+
+        b.swap_events();
+        assert!(b.events().is_empty());
+    }
+
+    #[test]
+    fn logical_size_follows_scale() {
+        let mut window = Window::builder().inner_size(Size::Logical(400.0, 300.0)).build().unwrap();
+        window.inject_scale(2.0);
+        window.swap_events();
+        assert_eq!(window.events(), &[
+            Event::ScaleChanged(2.0),
+            Event::Resize((Size::Logical(400.0, 300.0), 2.0)),
+        ]);
+        assert_eq!(window.headless_state().inner_size, (800, 600));
+
+        window.set_inner_size(Size::Physical(100, 100));
+        window.inject_scale(1.0);
+        assert_eq!(window.inner_size(), (Size::Physical(100, 100), 1.0));
+    }
+
+    #[test]
+    fn physical_size_ignores_scale() {
+        let mut window = Window::builder().inner_size(Size::Physical(800, 600)).build().unwrap();
+        window.inject_scale(1.5);
+        window.inject_scale(1.5);
+        window.swap_events();
+        assert_eq!(window.events(), &[
+            Event::ScaleChanged(1.5),
+            Event::Resize((Size::Physical(800, 600), 1.5)),
+        ]);
+        assert_eq!(window.headless_state().inner_size, (800, 600));
+        assert_eq!(window.current_monitor().map(|monitor| monitor.scale), Some(1.5));
+    }
+
+    #[test]
+    fn logical_position_and_move() {
+        let mut window = Window::builder()
+            .inner_size(Size::Logical(400.0, 300.0))
+            .position(Point::Logical(-50.0, 20.0))
+            .build()
+            .unwrap();
+        window.inject_scale(2.0);
+        window.inject_move(Point::Physical(-100, 40));
+        window.set_position(Point::Logical(10.0, 10.0));
+        window.swap_events();
+        assert_eq!(window.events(), &[
+            Event::ScaleChanged(2.0),
+            Event::Resize((Size::Logical(400.0, 300.0), 2.0)),
+            Event::Move((Point::Logical(-50.0, 20.0), 2.0)),
+            Event::Move((Point::Logical(10.0, 10.0), 2.0)),
+        ]);
+        assert_eq!(window.headless_state().position, (20, 20));
+        assert_eq!(window.inner_position(), window.outer_position());
+    }
+
+    #[test]
+    fn fullscreen_state() {
+        let mut window = Window::builder()
+            .inner_size(Size::Physical(640, 480))
+            .fullscreen(Some(Fullscreen::Borderless(None)))
+            .build()
+            .unwrap();
+        assert_eq!(window.headless_state().fullscreen, Some(Fullscreen::Borderless(None)));
+
+        // Going between kinds of fullscreen doesn't leave it, so there's nothing to report
+        let monitor = window.current_monitor().unwrap();
+        let exclusive = Fullscreen::Exclusive(monitor.clone(), monitor.video_modes[0]);
+        window.set_fullscreen(Some(exclusive.clone()));
+        assert_eq!(window.headless_state().fullscreen, Some(exclusive));
+        window.set_fullscreen(None);
+        window.set_fullscreen(None);
+        window.set_fullscreen_async(Some(Fullscreen::Borderless(Some(monitor))));
+        window.swap_events();
+        assert_eq!(window.events(), &[Event::Fullscreen(false), Event::Fullscreen(true)]);
+        assert_eq!(window.inner_size(), (Size::Physical(640, 480), 1.0));
+    }
+
+    #[test]
+    fn borderless_keeps_size() {
+        let mut window = Window::builder().inner_size(Size::Logical(400.0, 300.0)).build().unwrap();
+        window.inject_scale(2.0);
+        window.swap_events();
+        window.set_borderless(true);
+        assert!(window.headless_state().borderless);
+        assert_eq!(window.inner_size(), (Size::Logical(400.0, 300.0), 2.0));
+        window.set_borderless_async(false);
+        assert!(!window.headless_state().borderless);
+        assert_eq!(window.headless_state().inner_size, (800, 600));
+
+        // The size doesn't change, so there's no resize to report
+        window.swap_events();
+        assert!(window.events().is_empty());
+    }
+
+    #[test]
+    fn cursor_grab_follows_focus() {
+        let window = Window::builder().build().unwrap();
+        window.set_cursor_grab(CursorGrab::Confined);
+        assert!(!window.headless_state().cursor_grabbed);
+        window.inject_focus(true);
+        assert!(window.headless_state().cursor_grabbed);
+        window.inject_minimize(true);
+        assert!(!window.headless_state().cursor_grabbed);
+        window.inject_minimize(false);
+        window.inject_focus(false);
+        assert!(!window.headless_state().cursor_grabbed);
+
+        window.inject_scale(2.0);
+        window.set_cursor_position(Point::Logical(10.0, 20.0));
+        assert_eq!(window.headless_state().cursor_position, (20, 40));
+    }
+
+    #[test]
+    fn custom_cursor_images() {
+        use crate::window::{Cursor, CustomCursor};
+
+        assert!(CustomCursor::new(&[0; 3 * 4], (2, 2), (0, 0)).is_err());
+        assert!(CustomCursor::new(&[0; 2 * 2 * 4], (2, 2), (2, 0)).is_err());
+        let cursor = CustomCursor::new(&[0; 2 * 2 * 4], (2, 2), (1, 1))
+            .and_then(|c| c.with_image(3.0, &[0; 6 * 6 * 4], (6, 6), (3, 3)))
+            .and_then(|c| c.with_image(2.0, &[0; 4 * 4 * 4], (4, 4), (2, 2)))
+            .unwrap();
+        assert_eq!(cursor.image(1.0).size, (2, 2));
+        assert_eq!(cursor.image(1.5).size, (4, 4));
+        assert_eq!(cursor.image(4.0).size, (6, 6));
+
+        let window = Window::builder().cursor(&cursor).build().unwrap();
+        assert_eq!(window.headless_state().cursor, CursorIcon::Custom(cursor));
+        window.set_cursor(Cursor::Hand);
+        assert_eq!(window.headless_state().cursor, CursorIcon::System(Cursor::Hand));
+    }
+
+    #[test]
+    fn icon_images() {
+        assert!(Icon::new(&[0; 15 * 16 * 4], (16, 16)).is_err());
+        assert!(Icon::new(&[], (0, 0)).is_err());
+        let icon = Icon::new(&[0; 48 * 48 * 4], (48, 48))
+            .and_then(|i| i.with_image(&[0; 16 * 16 * 4], (16, 16)))
+            .and_then(|i| i.with_image(&[0; 32 * 32 * 4], (32, 32)))
+            .unwrap();
+        assert_eq!(icon.image(16).size, (16, 16));
+        assert_eq!(icon.image(24).size, (32, 32));
+        assert_eq!(icon.image(256).size, (48, 48));
+
+        let window = Window::builder().icon(Some(icon.clone())).build().unwrap();
+        assert_eq!(window.headless_state().icon, Some(icon));
+        window.set_icon(None);
+        assert_eq!(window.headless_state().icon, None);
+    }
+
+    #[cfg(feature = "input")]
+    #[test]
+    fn key_repeat_and_focus_loss() {
+        let (shift, a) = (ScanCode(42), ScanCode(30));
+        let mut window = Window::builder().build().unwrap();
+        window.inject_focus(true);
+        window.inject_key(Key::LShift, shift, true);
+        window.inject_key(Key::A, a, true);
+        window.inject_key(Key::A, a, true);
+        window.inject_focus(false);
+        window.inject_focus(true);
+        window.inject_key(Key::A, a, true);
+        window.swap_events();
+        assert_eq!(window.events(), &[
+            Event::Focus(true),
+            Event::KeyboardDown((Key::LShift, shift, Modifiers::SHIFT)),
+            Event::KeyboardDown((Key::A, a, Modifiers::SHIFT)),
+            Event::KeyboardRepeat((Key::A, a, Modifiers::SHIFT)),
+            Event::Focus(false),
+            Event::Focus(true),
+            Event::KeyboardDown((Key::A, a, Modifiers::empty())),
+        ]);
+    }
+
+    #[cfg(feature = "input")]
+    #[test]
+    fn lock_and_unknown_keys() {
+        let (caps, ctrl, unknown) = (ScanCode(58), ScanCode(29), ScanCode(0x76));
+        let mut window = Window::builder().build().unwrap();
+        window.inject_key(Key::CapsLock, caps, true);
+        window.inject_key(Key::CapsLock, caps, false);
+        window.inject_key(Key::LControl, ctrl, true);
+        window.inject_mouse_button(MouseButton::Left, true);
+        window.inject_key(Key::Unknown(unknown), unknown, true);
+        window.inject_key(Key::Unknown(unknown), unknown, true);
+        window.inject_key(Key::Unknown(unknown), unknown, false);
+        window.inject_key(Key::LControl, ctrl, false);
+        window.inject_key(Key::CapsLock, caps, true);
+        window.swap_events();
+
+        let (locked, held) = (Modifiers::CAPS_LOCK, Modifiers::CAPS_LOCK | Modifiers::CTRL);
+        assert_eq!(window.events(), &[
+            Event::KeyboardDown((Key::CapsLock, caps, locked)),
+            Event::KeyboardUp((Key::CapsLock, caps, locked)),
+            Event::KeyboardDown((Key::LControl, ctrl, held)),
+            Event::MouseDown((MouseButton::Left, held)),
+            Event::KeyboardDown((Key::Unknown(unknown), unknown, held)),
+            Event::KeyboardRepeat((Key::Unknown(unknown), unknown, held)),
+            Event::KeyboardUp((Key::Unknown(unknown), unknown, held)),
+            Event::KeyboardUp((Key::LControl, ctrl, locked)),
+            Event::KeyboardDown((Key::CapsLock, caps, Modifiers::empty())),
+        ]);
+    }
+
+    #[cfg(feature = "input")]
+    #[test]
+    fn characters_whole_or_not_at_all() {
+        let mut window = Window::builder().build().unwrap();
+        // A dead key is only a key press, the character comes with the key after it
+        window.inject_key(Key::Oem7, ScanCode(40), true);
+        window.inject_key(Key::Oem7, ScanCode(40), false);
+        window.inject_key(Key::E, ScanCode(18), true);
+        window.inject_character('\u{E9}');
+        // Outside of the BMP, which is a surrogate pair in UTF-16
+        window.inject_character('\u{1F35C}');
+        window.set_text_input(false);
+        window.inject_character('x');
+        window.set_text_input_async(true);
+        window.inject_character('y');
+        window.swap_events();
+
+        let characters: Vec<_> = window.events().iter().filter_map(|event| match *event {
+            Event::Character(character) => Some(character),
+            _ => None,
+        }).collect();
+        assert_eq!(characters, &['\u{E9}', '\u{1F35C}', 'y']);
+    }
+
+    #[cfg(feature = "input")]
+    #[test]
+    fn raw_mouse_motion_opt_in() {
+        let mut window = Window::builder().build().unwrap();
+        window.inject_focus(true);
+        window.inject_mouse_motion_raw(1.0, 2.0);
+        window.set_raw_mouse_motion(true);
+        window.inject_mouse_motion_raw(3.0, -4.0);
+        window.inject_focus(false);
+        window.inject_mouse_motion_raw(5.0, 6.0);
+        window.swap_events();
+        assert_eq!(window.events(), &[
+            Event::Focus(true),
+            Event::MouseMotionRaw((3.0, -4.0)),
+            Event::Focus(false),
+        ]);
+    }
+
+    #[cfg(feature = "input")]
+    #[test]
+    fn held_buttons_report_outside_motion() {
+        let mut window = Window::builder().inner_size(Size::Physical(100, 100)).build().unwrap();
+        window.inject_focus(true);
+        window.inject_mouse_move(Point::Physical(-5, 50));
+        window.inject_mouse_button(MouseButton::Left, true);
+        window.inject_mouse_move(Point::Physical(-5, 150));
+        window.inject_focus(false);
+        window.inject_mouse_move(Point::Physical(-10, 150));
+        window.swap_events();
+
+        let modifiers = Modifiers::empty();
+        assert_eq!(window.events(), &[
+            Event::Focus(true),
+            Event::MouseDown((MouseButton::Left, modifiers)),
+            Event::MouseMove((Point::Physical(-5, 150), 1.0)),
+            Event::Focus(false),
+            Event::MouseUp((MouseButton::Left, modifiers)),
+        ]);
+    }
+
+    #[cfg(feature = "input")]
+    #[test]
+    fn first_touch_emulates_mouse() {
+        use crate::event::TouchPhase;
+
+        let touch = |id, phase, x| Touch { id, phase, position: Point::Physical(x, 10) };
+        let mut window = Window::builder().build().unwrap();
+        window.inject_touch(touch(1, TouchPhase::Start, 10));
+        window.inject_touch(touch(2, TouchPhase::Start, 20));
+        window.inject_touch(touch(2, TouchPhase::End, 20));
+        window.inject_touch(touch(1, TouchPhase::Cancel, 15));
+        window.swap_events();
+
+        let modifiers = Modifiers::empty();
+        let position = |x| Point::Logical(x as f64, 10.0);
+        let logical = |id, phase, x| Touch { id, phase, position: position(x) };
+        assert_eq!(window.events(), &[
+            Event::Touch((logical(1, TouchPhase::Start, 10), 1.0)),
+            Event::MouseMove((position(10), 1.0)),
+            Event::MouseDown((MouseButton::Left, modifiers)),
+            Event::Touch((logical(2, TouchPhase::Start, 20), 1.0)),
+            Event::Touch((logical(2, TouchPhase::End, 20), 1.0)),
+            Event::Touch((logical(1, TouchPhase::Cancel, 15), 1.0)),
+            Event::MouseMove((position(15), 1.0)),
+            Event::MouseUp((MouseButton::Left, modifiers)),
+        ]);
+    }
+
+    #[cfg(feature = "input")]
+    #[test]
+    fn scroll_reports_wheel_too() {
+        let mut window = Window::builder().build().unwrap();
+        window.inject_scroll(ScrollDelta::Lines(0.0, -0.5), None);
+        window.inject_scroll(ScrollDelta::Lines(1.0, 0.0), None);
+        window.inject_scroll(ScrollDelta::Pixels(0.0, 12.0), Some(ScrollPhase::Begin));
+        window.swap_events();
+
+        let modifiers = Modifiers::empty();
+        assert_eq!(window.events(), &[
+            Event::MouseWheel((std::num::NonZeroI32::new(-60).unwrap(), modifiers)),
+            Event::MouseScroll((ScrollDelta::Lines(0.0, -0.5), None, modifiers)),
+            Event::MouseScroll((ScrollDelta::Lines(1.0, 0.0), None, modifiers)),
+            Event::MouseScroll((ScrollDelta::Pixels(0.0, 12.0), Some(ScrollPhase::Begin), modifiers)),
+        ]);
+    }
+
+    #[cfg(feature = "input")]
+    #[test]
+    fn ime_composition_text() {
+        let mut window = Window::builder().build().unwrap();
+        window.inject_ime_preedit("ignored", None);
+        window.set_ime_allowed(true);
+        window.inject_ime_preedit("\u{304B}", Some((3, 3)));
+        window.inject_ime_commit("\u{6F22}\u{5B57}");
+        window.swap_events();
+
+        let texts: Vec<_> = window.events().iter().map(|event| match *event {
+            Event::Ime(ImeEvent::Preedit(text, cursor)) => (Some(window.event_text(text)), cursor),
+            Event::Ime(ImeEvent::Commit(text)) => (Some(window.event_text(text)), None),
+            _ => (None, None),
+        }).collect();
+        assert_eq!(texts, &[
+            (None, None),
+            (Some("\u{304B}"), Some((3, 3))),
+            (Some("\u{6F22}\u{5B57}"), None),
+            (None, None),
+        ]);
+        assert!(matches!(window.events()[0], Event::Ime(ImeEvent::Start)));
+        assert!(matches!(window.events()[3], Event::Ime(ImeEvent::End)));
+    }
+}
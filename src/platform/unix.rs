@@ -0,0 +1,9 @@
+//! Code shared by the backends of free Unix-likes (Linux, the BSDs), and picking between them.
+
+pub(crate) mod ffi;
+pub(crate) mod imp;
+
+// Required re-exports
+pub(crate) use imp::{available_monitors, spawn_window, vulkan_instance_extensions};
+pub(crate) type WindowRepr = imp::WindowImpl;
+pub(crate) type GlContextRepr = imp::GlContextImpl;
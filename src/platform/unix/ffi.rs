@@ -0,0 +1,89 @@
+// libc api goes here, shared by the backends of free Unix-likes
+
+#![allow(bad_style, dead_code)]
+
+use crate::vulkan::{PFN_vkVoidFunction, VkInstance};
+
+// Typedefs
+pub use core::ffi::c_void;
+pub type c_char = i8;
+pub type c_uchar = u8;
+pub type c_short = i16;
+pub type c_ushort = u16;
+pub type c_int = i32;
+pub type c_uint = u32;
+pub type c_long = isize; // LP64 & ILP32
+pub type c_ulong = usize;
+pub type off_t = i64;
+
+// Constants
+pub const EAGAIN: c_int = 11;
+pub const EINTR: c_int = 4;
+pub const IPC_CREAT: c_int = 0o1000;
+pub const IPC_PRIVATE: c_int = 0;
+pub const IPC_RMID: c_int = 0;
+pub const MAP_FAILED: *mut c_void = !0 as *mut c_void;
+pub const MAP_PRIVATE: c_int = 0x02;
+pub const MAP_SHARED: c_int = 0x01;
+pub const MFD_CLOEXEC: c_uint = 0x0001;
+pub const O_CLOEXEC: c_int = 0o2000000;
+pub const O_NONBLOCK: c_int = 0o4000;
+pub const POLLIN: c_short = 0x001;
+pub const PROT_READ: c_int = 0x1;
+pub const PROT_WRITE: c_int = 0x2;
+pub const RTLD_LOCAL: c_int = 0;
+pub const RTLD_NOW: c_int = 2;
+
+// Structs
+#[repr(C)]
+pub struct pollfd {
+    pub fd: c_int,
+    pub events: c_short,
+    pub revents: c_short,
+}
+
+// Static Linked Functions (libc, which `std` always links)
+extern "C" {
+    fn dlopen(filename: *const c_char, flag: c_int) -> *mut c_void;
+    pub fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void;
+
+    pub fn __errno_location() -> *mut c_int;
+    pub fn close(fd: c_int) -> c_int;
+    #[link_name = "ftruncate64"]
+    pub fn ftruncate(fd: c_int, length: off_t) -> c_int;
+    pub fn getpid() -> c_int;
+    pub fn memfd_create(name: *const c_char, flags: c_uint) -> c_int;
+    #[link_name = "mmap64"]
+    pub fn mmap(addr: *mut c_void, length: usize, prot: c_int, flags: c_int, fd: c_int, offset: off_t) -> *mut c_void;
+    pub fn munmap(addr: *mut c_void, length: usize) -> c_int;
+    pub fn pipe2(pipefd: *mut c_int, flags: c_int) -> c_int;
+    pub fn poll(fds: *mut pollfd, nfds: c_ulong, timeout: c_int) -> c_int;
+    pub fn read(fd: c_int, buf: *mut c_void, count: usize) -> isize;
+    pub fn shmat(shmid: c_int, shmaddr: *const c_void, shmflg: c_int) -> *mut c_void;
+    pub fn shmctl(shmid: c_int, cmd: c_int, buf: *mut c_void) -> c_int;
+    pub fn shmdt(shmaddr: *const c_void) -> c_int;
+    pub fn shmget(key: c_int, size: usize, shmflg: c_int) -> c_int;
+    pub fn write(fd: c_int, buf: *const c_void, count: usize) -> isize;
+}
+
+#[inline]
+pub unsafe fn dlopen_local(name: *const c_char) -> *mut c_void {
+    // Patch loading mechanism here, if you wish
+    dlopen(name, RTLD_NOW | RTLD_LOCAL)
+}
+
+// The Vulkan loader, which is only opened if a surface is made without being handed its entry point
+dyn_link! {
+    pub struct VulkanDL(dlopen_local => *mut c_void | dlsym) {
+        "libvulkan.so.1" | "libvulkan.so" {
+            fn vkGetInstanceProcAddr(instance: VkInstance, pName: *const c_char) -> Option<PFN_vkVoidFunction>;
+        },
+    }
+}
+
+impl VulkanDL {
+    #[inline]
+    pub unsafe fn link() -> Self {
+        Self::_link()
+    }
+}
@@ -0,0 +1,608 @@
+//! Backend selection and cross-thread messaging, shared by X11 and Wayland.
+
+use crate::{
+    clipboard::{ClipboardContents, ClipboardFormat},
+    error::Error,
+    event::Event,
+    monitor::{Monitor, Point, Rect, Scale, Size},
+    gl::GlContextBuilder,
+    platform::{wayland, x11},
+    util::{sync::{self, Condvar, Mutex}, LazyCell},
+    vulkan::{PFN_vkGetInstanceProcAddr, PFN_vkVoidFunction, VkInstance, VkSurfaceKHR},
+    window::{self, CursorGrab, CursorIcon, Icon, WindowBuilder},
+};
+use std::{
+    collections::VecDeque,
+    ffi::{CStr, OsString},
+    mem, ops,
+    os::{raw::c_void, unix::ffi::OsStringExt},
+    path::PathBuf,
+};
+
+#[cfg(feature = "raw-window-handle")]
+use raw_window_handle::{HandleError, RawDisplayHandle, RawWindowHandle};
+
+use crate::platform::unix::ffi::*;
+
+/// Implementation container for `window::Window`, dispatching to the backend in use.
+pub enum WindowImpl {
+    Wayland(wayland::WindowRepr),
+    X11(x11::WindowRepr),
+}
+
+/// Implementation container for `gl::GlContext`, made by the backend of its window.
+pub enum GlContextImpl {
+    Wayland(wayland::GlContextRepr),
+    X11(x11::GlContextRepr),
+}
+
+/// Forwards a method call to whichever backend the window (or context) was created with.
+macro_rules! dispatch {
+    ($self:ident.$method:ident($($arg:expr),* $(,)?)) => {
+        match $self {
+            Self::Wayland(window) => window.$method($($arg),*),
+            Self::X11(window) => window.$method($($arg),*),
+        }
+    };
+}
+
+pub fn spawn_window(builder: &WindowBuilder) -> Result<WindowImpl, Error> {
+    // Wayland is preferred if the session has a compositor we can work with,
+    // otherwise it's X11 (which also covers XWayland, if it comes to that).
+    if wayland::imp::is_available() {
+        wayland::spawn_window(builder).map(WindowImpl::Wayland)
+    } else {
+        x11::spawn_window(builder).map(WindowImpl::X11)
+    }
+}
+
+/// The Vulkan loader, for surfaces that are made without being handed `vkGetInstanceProcAddr`.
+static VULKAN: LazyCell<VulkanDL> = LazyCell::new(|| unsafe { VulkanDL::link() });
+
+pub fn vulkan_instance_extensions() -> &'static [&'static CStr] {
+    // Same deal as with monitors, these are for the backend windows would be created with
+    if wayland::imp::is_available() {
+        wayland::vulkan_instance_extensions()
+    } else {
+        x11::vulkan_instance_extensions()
+    }
+}
+
+/// Looks up a Vulkan instance function, going through the loader if `get_instance_proc_addr` is `None`.
+pub(crate) unsafe fn vulkan_instance_proc_addr(
+    instance: VkInstance,
+    get_instance_proc_addr: Option<PFN_vkGetInstanceProcAddr>,
+    name: &[u8],
+) -> Option<PFN_vkVoidFunction> {
+    match get_instance_proc_addr {
+        Some(get_instance_proc_addr) => get_instance_proc_addr(instance, name.as_ptr().cast()),
+        None => VULKAN.get().vkGetInstanceProcAddr(instance, name.as_ptr().cast()).flatten(),
+    }
+}
+
+pub fn available_monitors() -> Vec<Monitor> {
+    // Same deal as with windows, the monitors are the ones of the backend windows would be on
+    if wayland::imp::is_available() {
+        wayland::available_monitors()
+    } else {
+        x11::available_monitors()
+    }
+}
+
+/// Turns a `text/uri-list`, which is how both X11 & Wayland hand over dropped files, into event paths.
+///
+/// Only local files are kept. Their names are bytes like any other path here, so they're kept as such.
+pub fn parse_uri_list(list: &[u8]) -> Vec<PathBuf> {
+    let hex = |digit: Option<&u8>| digit.and_then(|&d| (d as char).to_digit(16));
+    let mut paths = Vec::new();
+    for line in list.split(|&b| b == b'\n') {
+        // Lines end with CRLF, and comments start with '#' (which doesn't match the scheme anyway)
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        let path = match line.strip_prefix(b"file://") {
+            // The host is usually empty, but it can be the hostname, so skip to the path
+            Some(rest) => match rest.iter().position(|&b| b == b'/') {
+                Some(start) => &rest[start..],
+                None => continue,
+            },
+            None => continue,
+        };
+
+        // Anything outside of plain ASCII is percent-encoded, which can make any byte but null
+        let mut decoded = Vec::with_capacity(path.len());
+        let mut bytes = path.iter();
+        while let Some(&byte) = bytes.next() {
+            let mut lookahead = bytes.clone();
+            match (byte, hex(lookahead.next()), hex(lookahead.next())) {
+                (b'%', Some(high), Some(low)) => {
+                    decoded.push((high << 4 | low) as u8);
+                    bytes = lookahead;
+                },
+                _ => decoded.push(byte),
+            }
+        }
+        if !decoded.contains(&0) {
+            paths.push(PathBuf::from(OsString::from_vec(decoded)));
+        }
+    }
+    paths
+}
+
+impl WindowImpl {
+    #[inline]
+    pub fn execute<F, T>(&self, f: F) -> T
+    where
+        F: Send + FnOnce() -> T,
+        T: Send,
+    {
+        dispatch!(self.execute(f))
+    }
+
+    #[inline]
+    pub fn events(&self) -> &[Event] {
+        dispatch!(self.events())
+    }
+
+    #[inline]
+    pub fn events_text(&self) -> &str {
+        dispatch!(self.events_text())
+    }
+
+    #[inline]
+    pub fn events_paths(&self) -> &[PathBuf] {
+        dispatch!(self.events_paths())
+    }
+
+    #[inline]
+    pub fn clipboard_data(&self, format: &ClipboardFormat) -> Option<Vec<u8>> {
+        dispatch!(self.clipboard_data(format))
+    }
+
+    #[inline]
+    pub fn clipboard_formats(&self) -> Vec<ClipboardFormat> {
+        dispatch!(self.clipboard_formats())
+    }
+
+    #[inline]
+    pub unsafe fn create_gl_context(&self, builder: &GlContextBuilder) -> Result<GlContextImpl, Error> {
+        match self {
+            Self::Wayland(window) => window.create_gl_context(builder).map(GlContextImpl::Wayland),
+            Self::X11(window) => window.create_gl_context(builder).map(GlContextImpl::X11),
+        }
+    }
+
+    #[inline]
+    pub unsafe fn create_vulkan_surface(
+        &self,
+        instance: VkInstance,
+        get_instance_proc_addr: Option<PFN_vkGetInstanceProcAddr>,
+    ) -> Result<VkSurfaceKHR, Error> {
+        dispatch!(self.create_vulkan_surface(instance, get_instance_proc_addr))
+    }
+
+    #[inline]
+    pub fn current_monitor(&self) -> Option<Monitor> {
+        dispatch!(self.current_monitor())
+    }
+
+    #[inline]
+    pub fn inner_position(&self) -> (Point, Scale) {
+        dispatch!(self.inner_position())
+    }
+
+    #[inline]
+    pub fn inner_size(&self) -> (Size, Scale) {
+        dispatch!(self.inner_size())
+    }
+
+    #[inline]
+    pub fn is_dpi_logical(&self) -> bool {
+        dispatch!(self.is_dpi_logical())
+    }
+
+    #[inline]
+    pub fn outer_position(&self) -> (Point, Scale) {
+        dispatch!(self.outer_position())
+    }
+
+    #[inline]
+    pub fn present_pixels(&self, pixels: &[u32], size: (u32, u32), damage: &[Rect]) -> Result<(), Error> {
+        dispatch!(self.present_pixels(pixels, size, damage))
+    }
+
+    #[cfg(feature = "raw-window-handle")]
+    #[inline]
+    pub fn raw_display_handle(&self) -> Result<RawDisplayHandle, HandleError> {
+        dispatch!(self.raw_display_handle())
+    }
+
+    #[cfg(feature = "raw-window-handle")]
+    #[inline]
+    pub fn raw_window_handle(&self) -> Result<RawWindowHandle, HandleError> {
+        dispatch!(self.raw_window_handle())
+    }
+
+    #[inline]
+    pub fn set_borderless(&self, borderless: bool) {
+        dispatch!(self.set_borderless(borderless))
+    }
+
+    #[inline]
+    pub fn set_borderless_async(&self, borderless: bool) {
+        dispatch!(self.set_borderless_async(borderless))
+    }
+
+    #[inline]
+    pub fn set_clipboard(&self, contents: &ClipboardContents) -> bool {
+        dispatch!(self.set_clipboard(contents))
+    }
+
+    #[inline]
+    pub fn set_controls(&self, controls: Option<window::Controls>) {
+        dispatch!(self.set_controls(controls))
+    }
+
+    #[inline]
+    pub fn set_controls_async(&self, controls: Option<window::Controls>) {
+        dispatch!(self.set_controls_async(controls))
+    }
+
+    #[inline]
+    pub fn set_cursor(&self, cursor: CursorIcon) {
+        dispatch!(self.set_cursor(cursor))
+    }
+
+    #[inline]
+    pub fn set_cursor_async(&self, cursor: CursorIcon) {
+        dispatch!(self.set_cursor_async(cursor))
+    }
+
+    #[inline]
+    pub fn set_cursor_grab(&self, grab: CursorGrab) {
+        dispatch!(self.set_cursor_grab(grab))
+    }
+
+    #[inline]
+    pub fn set_cursor_grab_async(&self, grab: CursorGrab) {
+        dispatch!(self.set_cursor_grab_async(grab))
+    }
+
+    #[inline]
+    pub fn set_cursor_position(&self, position: Point) {
+        dispatch!(self.set_cursor_position(position))
+    }
+
+    #[inline]
+    pub fn set_cursor_position_async(&self, position: Point) {
+        dispatch!(self.set_cursor_position_async(position))
+    }
+
+    #[inline]
+    pub fn set_fullscreen(&self, fullscreen: Option<window::Fullscreen>) {
+        dispatch!(self.set_fullscreen(fullscreen))
+    }
+
+    #[inline]
+    pub fn set_fullscreen_async(&self, fullscreen: Option<window::Fullscreen>) {
+        dispatch!(self.set_fullscreen_async(fullscreen))
+    }
+
+    #[inline]
+    pub fn set_icon(&self, icon: Option<Icon>) {
+        dispatch!(self.set_icon(icon))
+    }
+
+    #[inline]
+    pub fn set_icon_async(&self, icon: Option<Icon>) {
+        dispatch!(self.set_icon_async(icon))
+    }
+
+    #[inline]
+    pub fn set_inner_size(&self, size: Size) {
+        dispatch!(self.set_inner_size(size))
+    }
+
+    #[inline]
+    pub fn set_maximized(&self, maximized: bool) {
+        dispatch!(self.set_maximized(maximized))
+    }
+
+    #[inline]
+    pub fn set_maximized_async(&self, maximized: bool) {
+        dispatch!(self.set_maximized_async(maximized))
+    }
+
+    #[inline]
+    pub fn set_position(&self, position: Point) {
+        dispatch!(self.set_position(position))
+    }
+
+    #[inline]
+    pub fn set_position_async(&self, position: Point) {
+        dispatch!(self.set_position_async(position))
+    }
+
+    #[cfg(feature = "input")]
+    #[inline]
+    pub fn set_raw_mouse_motion(&self, enabled: bool) {
+        dispatch!(self.set_raw_mouse_motion(enabled))
+    }
+
+    #[cfg(feature = "input")]
+    #[inline]
+    pub fn set_raw_mouse_motion_async(&self, enabled: bool) {
+        dispatch!(self.set_raw_mouse_motion_async(enabled))
+    }
+
+    #[inline]
+    pub fn set_resizable(&self, resizable: bool) {
+        dispatch!(self.set_resizable(resizable))
+    }
+
+    #[inline]
+    pub fn set_resizable_async(&self, resizable: bool) {
+        dispatch!(self.set_resizable_async(resizable))
+    }
+
+    #[cfg(feature = "input")]
+    #[inline]
+    pub fn set_ime_allowed(&self, allowed: bool) {
+        dispatch!(self.set_ime_allowed(allowed))
+    }
+
+    #[cfg(feature = "input")]
+    #[inline]
+    pub fn set_ime_allowed_async(&self, allowed: bool) {
+        dispatch!(self.set_ime_allowed_async(allowed))
+    }
+
+    #[cfg(feature = "input")]
+    #[inline]
+    pub fn set_ime_cursor_area(&self, position: Point, size: Size) {
+        dispatch!(self.set_ime_cursor_area(position, size))
+    }
+
+    #[cfg(feature = "input")]
+    #[inline]
+    pub fn set_ime_cursor_area_async(&self, position: Point, size: Size) {
+        dispatch!(self.set_ime_cursor_area_async(position, size))
+    }
+
+    #[cfg(feature = "input")]
+    #[inline]
+    pub fn set_text_input(&self, enabled: bool) {
+        dispatch!(self.set_text_input(enabled))
+    }
+
+    #[cfg(feature = "input")]
+    #[inline]
+    pub fn set_text_input_async(&self, enabled: bool) {
+        dispatch!(self.set_text_input_async(enabled))
+    }
+
+    #[inline]
+    pub fn set_title(&self, title: &str) {
+        dispatch!(self.set_title(title))
+    }
+
+    #[inline]
+    pub fn set_title_async(&self, title: &str) {
+        dispatch!(self.set_title_async(title))
+    }
+
+    #[inline]
+    pub fn set_visible(&self, visible: bool) {
+        dispatch!(self.set_visible(visible))
+    }
+
+    #[inline]
+    pub fn set_visible_async(&self, visible: bool) {
+        dispatch!(self.set_visible_async(visible))
+    }
+
+    #[inline]
+    pub fn swap_events(&mut self) {
+        dispatch!(self.swap_events())
+    }
+}
+
+impl GlContextImpl {
+    #[inline]
+    pub fn make_current(&self) -> bool {
+        dispatch!(self.make_current())
+    }
+
+    #[inline]
+    pub fn make_not_current(&self) -> bool {
+        dispatch!(self.make_not_current())
+    }
+
+    #[inline]
+    pub fn is_current(&self) -> bool {
+        dispatch!(self.is_current())
+    }
+
+    #[inline]
+    pub fn swap_buffers(&self) -> bool {
+        dispatch!(self.swap_buffers())
+    }
+
+    #[inline]
+    pub fn set_swap_interval(&self, interval: i32) -> bool {
+        dispatch!(self.set_swap_interval(interval))
+    }
+
+    #[inline]
+    pub fn get_proc_address(&self, name: &CStr) -> *const c_void {
+        dispatch!(self.get_proc_address(name))
+    }
+
+    #[inline]
+    pub fn resize(&self, width: u32, height: u32) {
+        dispatch!(self.resize(width, height))
+    }
+}
+
+/// Cross-thread messages handled by the window thread.
+///
+/// These are the equivalent of the custom `RAMEN_WM_*` window messages on Win32.
+pub enum Message {
+    /// The "real" destroy signal, sent when the window is dropped.
+    Destroy,
+    /// Execute a closure inside the window thread.
+    Execute(*mut Option<Box<dyn FnOnce()>>),
+    /// Query the clipboard contents in a format (in & out pointers).
+    GetClipboard(*const ClipboardFormat, *mut Option<Vec<u8>>),
+    /// Query the formats of the clipboard contents (out pointer).
+    GetClipboardFormats(*mut Vec<ClipboardFormat>),
+    /// Query the monitor the window is on (out pointer).
+    GetCurrentMonitor(*mut Option<Monitor>),
+    /// Query the inner size (out pointers).
+    GetInnerSize(*mut Size, *mut Scale),
+    /// Query the inner (`true`) or outer (`false`) position (out pointers).
+    GetPosition(bool, *mut Point, *mut Scale),
+    /// Query whether we're in logical DPI mode (out pointer).
+    IsDpiLogical(*mut bool),
+    /// Copy an image into the window, reporting whether that worked (in & out pointers).
+    PresentPixels(*const [u32], (u32, u32), *const [Rect], *mut Result<(), Error>),
+    SetBorderless(bool),
+    /// Replace the clipboard contents, reporting whether that worked (in & out pointers).
+    SetClipboard(*const ClipboardContents, *mut bool),
+    SetControls(Option<window::Controls>),
+    SetCursor(CursorIcon),
+    SetCursorGrab(CursorGrab),
+    SetCursorPosition(Point),
+    /// Stop presenting anything, as OpenGL or Vulkan draws to the window now.
+    SetExternalSurface,
+    SetFullscreen(Option<window::Fullscreen>),
+    SetIcon(Option<Icon>),
+    #[cfg(feature = "input")]
+    SetImeAllowed(bool),
+    #[cfg(feature = "input")]
+    SetImeCursorArea(Point, Size),
+    SetInnerSize(Size),
+    SetMaximized(bool),
+    SetPosition(Point),
+    #[cfg(feature = "input")]
+    SetRawMouseMotion(bool),
+    SetResizable(bool),
+    #[cfg(feature = "input")]
+    SetTextInput(bool),
+    SetTitle(String),
+    SetVisible(bool),
+}
+
+// Pointers in messages are only sent by a thread blocking for the reply, so...
+unsafe impl Send for Message {}
+
+/// Message queue for a window thread, working similarly to the Win32 one.
+///
+/// Messages are pushed, then the window thread is woken up by writing to a pipe which it polls,
+/// alongside the display server connection. "Sent" messages wait until they've been handled.
+pub struct MessageQueue {
+    state: Mutex<MessageQueueState>,
+    ping: Condvar,
+    pub pipe_read: c_int,
+    pipe_write: c_int,
+}
+
+struct MessageQueueState {
+    messages: VecDeque<Message>,
+    posted: u64,
+    handled: u64,
+}
+
+impl MessageQueue {
+    pub fn new() -> Self {
+        let mut fds = [-1 as c_int; 2];
+        unsafe {
+            // A pipe can only fail to be created if we're out of file descriptors.
+            let result = pipe2(fds.as_mut_ptr(), O_CLOEXEC | O_NONBLOCK);
+            assert_eq!(result, 0, "failed to create a pipe for the window thread");
+        }
+        Self {
+            state: Mutex::new(MessageQueueState {
+                messages: VecDeque::new(),
+                posted: 0,
+                handled: 0,
+            }),
+            ping: Condvar::new(),
+            pipe_read: fds[0],
+            pipe_write: fds[1],
+        }
+    }
+
+    /// Pushes a message and wakes up the window thread, yielding the message's serial.
+    pub fn post(&self, message: Message) -> u64 {
+        let mut lock = sync::mutex_lock(&self.state);
+        lock.messages.push_back(message);
+        lock.posted += 1;
+        let serial = lock.posted;
+        mem::drop(lock);
+
+        // If the pipe is full, that's fine, it's going to be woken up regardless.
+        unsafe {
+            let _ = write(self.pipe_write, [0u8].as_ptr().cast(), 1);
+        }
+        serial
+    }
+
+    /// Pushes a message and blocks until the window thread has handled it.
+    pub fn send(&self, message: Message) {
+        let serial = self.post(message);
+        let mut lock = sync::mutex_lock(&self.state);
+        while lock.handled < serial {
+            sync::condvar_wait(&self.ping, &mut lock);
+        }
+    }
+
+    /// Takes every pending message out of the queue, for the window thread.
+    pub fn take(&self) -> VecDeque<Message> {
+        mem::take(&mut sync::mutex_lock(&self.state).messages)
+    }
+
+    /// Marks messages as handled, for the window thread.
+    pub fn mark_handled(&self, count: usize) {
+        let mut lock = sync::mutex_lock(&self.state);
+        lock.handled += count as u64;
+        sync::condvar_notify_all(&self.ping);
+    }
+
+    /// Drains the wake-up pipe, for the window thread.
+    pub fn drain_pipe(&self) {
+        let mut buf = [0u8; 64];
+        unsafe {
+            while read(self.pipe_read, buf.as_mut_ptr().cast(), buf.len()) > 0 {}
+        }
+    }
+}
+
+impl ops::Drop for MessageQueue {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = close(self.pipe_read);
+            let _ = close(self.pipe_write);
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::parse_uri_list;
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt, path::Path};
+
+    #[test]
+    fn uri_list() {
+        let list = b"# from a file manager\r\nfile:///home/user/a%20b.png\r\nfile://host/etc/%E2%9C%93\r\n";
+        assert_eq!(parse_uri_list(list), [Path::new("/home/user/a b.png"), Path::new("/etc/\u{2713}")]);
+        assert_eq!(parse_uri_list(b"https://example.com/\nfile:///100%\nfile:///%00"), [Path::new("/100%")]);
+        assert!(parse_uri_list(b"").is_empty());
+    }
+
+    #[test]
+    fn uri_list_not_unicode() {
+        // Names are only bytes, so one that isn't UTF-8 has to come back as it was to point at the same file
+        let paths = parse_uri_list(b"file:///tmp/%FFfile%C0.txt\r\n");
+        assert_eq!(paths, [Path::new(OsStr::from_bytes(b"/tmp/\xFFfile\xC0.txt"))]);
+    }
+}
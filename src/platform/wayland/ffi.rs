@@ -0,0 +1,723 @@
+// wayland-client api goes here, as well as the protocol bits that it doesn't ship with
+
+#![allow(bad_style, dead_code)]
+
+pub use crate::platform::unix::ffi::*;
+use crate::vulkan::{VkFlags, VkInstance, VkResult, VkStructureType, VkSurfaceKHR};
+use std::{ffi::CStr, ptr};
+
+// Opaque types
+/// Opaque connection to a Wayland compositor.
+pub enum wl_display {}
+/// Opaque client-side handle to a Wayland protocol object.
+pub enum wl_proxy {}
+#[doc(hidden)]
+pub enum wl_cursor_theme {}
+#[doc(hidden)]
+pub enum xkb_context {}
+#[doc(hidden)]
+pub enum xkb_keymap {}
+#[doc(hidden)]
+pub enum xkb_state {}
+#[doc(hidden)]
+pub enum xkb_compose_table {}
+#[doc(hidden)]
+pub enum xkb_compose_state {}
+
+// Typedefs
+pub type wl_fixed_t = i32;
+
+// Function typedefs
+pub type wl_dispatcher_func_t = unsafe extern "C" fn(
+    implementation: *const c_void,
+    target: *mut c_void,
+    opcode: u32,
+    message: *const wl_message,
+    args: *mut wl_argument,
+) -> c_int;
+
+// Constants
+pub const WL_MARSHAL_FLAG_DESTROY: u32 = 1 << 0;
+pub const WL_DATA_DEVICE_MANAGER_DND_ACTION_COPY: u32 = 1;
+pub const WL_OUTPUT_MODE_CURRENT: u32 = 0x1;
+pub const WL_KEYBOARD_KEY_STATE_PRESSED: u32 = 1;
+pub const WL_KEYBOARD_KEYMAP_FORMAT_XKB_V1: u32 = 1;
+pub const WL_POINTER_AXIS_VERTICAL_SCROLL: u32 = 0;
+pub const WL_POINTER_AXIS_HORIZONTAL_SCROLL: u32 = 1;
+pub const WL_POINTER_AXIS_SOURCE_WHEEL: u32 = 0;
+pub const WL_POINTER_AXIS_SOURCE_FINGER: u32 = 1;
+pub const WL_POINTER_AXIS_SOURCE_WHEEL_TILT: u32 = 3;
+pub const WL_POINTER_BUTTON_STATE_PRESSED: u32 = 1;
+pub const WL_SEAT_CAPABILITY_POINTER: u32 = 1;
+pub const WL_SEAT_CAPABILITY_KEYBOARD: u32 = 2;
+pub const WL_SEAT_CAPABILITY_TOUCH: u32 = 4;
+pub const WL_SHM_FORMAT_ARGB8888: u32 = 0;
+pub const WL_SHM_FORMAT_XRGB8888: u32 = 1;
+pub const XDG_TOPLEVEL_STATE_MAXIMIZED: u32 = 1;
+pub const XDG_TOPLEVEL_STATE_FULLSCREEN: u32 = 2;
+pub const XDG_TOPLEVEL_STATE_ACTIVATED: u32 = 4;
+pub const ZXDG_TOPLEVEL_DECORATION_V1_MODE_CLIENT_SIDE: u32 = 1;
+pub const ZXDG_TOPLEVEL_DECORATION_V1_MODE_SERVER_SIDE: u32 = 2;
+pub const ZWP_POINTER_CONSTRAINTS_V1_LIFETIME_PERSISTENT: u32 = 2;
+
+// Linux input event codes (mouse buttons)
+pub const BTN_LEFT: u32 = 0x110;
+pub const BTN_RIGHT: u32 = 0x111;
+pub const BTN_MIDDLE: u32 = 0x112;
+pub const BTN_SIDE: u32 = 0x113;
+pub const BTN_EXTRA: u32 = 0x114;
+
+// Request opcodes
+pub const WL_DISPLAY_GET_REGISTRY: u32 = 1;
+pub const WL_REGISTRY_BIND: u32 = 0;
+pub const WL_COMPOSITOR_CREATE_SURFACE: u32 = 0;
+pub const WL_SURFACE_DESTROY: u32 = 0;
+pub const WL_SURFACE_ATTACH: u32 = 1;
+pub const WL_SURFACE_DAMAGE: u32 = 2;
+pub const WL_SURFACE_COMMIT: u32 = 6;
+pub const WL_SURFACE_SET_BUFFER_SCALE: u32 = 8;
+pub const WL_SHM_CREATE_POOL: u32 = 0;
+pub const WL_SHM_POOL_CREATE_BUFFER: u32 = 0;
+pub const WL_SHM_POOL_DESTROY: u32 = 1;
+pub const WL_BUFFER_DESTROY: u32 = 0;
+pub const WL_DATA_DEVICE_MANAGER_CREATE_DATA_SOURCE: u32 = 0;
+pub const WL_DATA_DEVICE_MANAGER_GET_DATA_DEVICE: u32 = 1;
+pub const WL_DATA_DEVICE_SET_SELECTION: u32 = 1;
+pub const WL_DATA_DEVICE_RELEASE: u32 = 2;
+pub const WL_DATA_OFFER_ACCEPT: u32 = 0;
+pub const WL_DATA_OFFER_RECEIVE: u32 = 1;
+pub const WL_DATA_OFFER_DESTROY: u32 = 2;
+pub const WL_DATA_OFFER_FINISH: u32 = 3;
+pub const WL_DATA_OFFER_SET_ACTIONS: u32 = 4;
+pub const WL_DATA_SOURCE_OFFER: u32 = 0;
+pub const WL_DATA_SOURCE_DESTROY: u32 = 1;
+pub const WL_SEAT_GET_POINTER: u32 = 0;
+pub const WL_SEAT_GET_KEYBOARD: u32 = 1;
+pub const WL_SEAT_GET_TOUCH: u32 = 2;
+pub const WL_SEAT_RELEASE: u32 = 3;
+pub const WL_POINTER_SET_CURSOR: u32 = 0;
+pub const WL_POINTER_RELEASE: u32 = 1;
+pub const WL_KEYBOARD_RELEASE: u32 = 0;
+pub const WL_TOUCH_RELEASE: u32 = 0;
+pub const WL_OUTPUT_RELEASE: u32 = 0;
+pub const XDG_WM_BASE_DESTROY: u32 = 0;
+pub const XDG_WM_BASE_GET_XDG_SURFACE: u32 = 2;
+pub const XDG_WM_BASE_PONG: u32 = 3;
+pub const XDG_SURFACE_DESTROY: u32 = 0;
+pub const XDG_SURFACE_GET_TOPLEVEL: u32 = 1;
+pub const XDG_SURFACE_ACK_CONFIGURE: u32 = 4;
+pub const XDG_TOPLEVEL_DESTROY: u32 = 0;
+pub const XDG_TOPLEVEL_SET_TITLE: u32 = 2;
+pub const XDG_TOPLEVEL_SET_APP_ID: u32 = 3;
+pub const XDG_TOPLEVEL_SET_MAX_SIZE: u32 = 7;
+pub const XDG_TOPLEVEL_SET_MIN_SIZE: u32 = 8;
+pub const XDG_TOPLEVEL_SET_MAXIMIZED: u32 = 9;
+pub const XDG_TOPLEVEL_UNSET_MAXIMIZED: u32 = 10;
+pub const XDG_TOPLEVEL_SET_FULLSCREEN: u32 = 11;
+pub const XDG_TOPLEVEL_UNSET_FULLSCREEN: u32 = 12;
+pub const ZXDG_DECORATION_MANAGER_V1_DESTROY: u32 = 0;
+pub const ZXDG_DECORATION_MANAGER_V1_GET_TOPLEVEL_DECORATION: u32 = 1;
+pub const ZXDG_TOPLEVEL_DECORATION_V1_DESTROY: u32 = 0;
+pub const ZXDG_TOPLEVEL_DECORATION_V1_SET_MODE: u32 = 1;
+pub const ZWP_CONFINED_POINTER_V1_DESTROY: u32 = 0;
+pub const ZWP_LOCKED_POINTER_V1_DESTROY: u32 = 0;
+pub const ZWP_LOCKED_POINTER_V1_SET_CURSOR_POSITION_HINT: u32 = 1;
+pub const ZWP_POINTER_CONSTRAINTS_V1_DESTROY: u32 = 0;
+pub const ZWP_POINTER_CONSTRAINTS_V1_LOCK_POINTER: u32 = 1;
+pub const ZWP_POINTER_CONSTRAINTS_V1_CONFINE_POINTER: u32 = 2;
+pub const ZWP_RELATIVE_POINTER_MANAGER_V1_DESTROY: u32 = 0;
+pub const ZWP_RELATIVE_POINTER_MANAGER_V1_GET_RELATIVE_POINTER: u32 = 1;
+pub const ZWP_RELATIVE_POINTER_V1_DESTROY: u32 = 0;
+pub const ZWP_TEXT_INPUT_MANAGER_V3_DESTROY: u32 = 0;
+pub const ZWP_TEXT_INPUT_MANAGER_V3_GET_TEXT_INPUT: u32 = 1;
+pub const ZWP_TEXT_INPUT_V3_DESTROY: u32 = 0;
+pub const ZWP_TEXT_INPUT_V3_ENABLE: u32 = 1;
+pub const ZWP_TEXT_INPUT_V3_DISABLE: u32 = 2;
+pub const ZWP_TEXT_INPUT_V3_SET_CURSOR_RECTANGLE: u32 = 6;
+pub const ZWP_TEXT_INPUT_V3_COMMIT: u32 = 7;
+
+// Event opcodes
+pub const WL_REGISTRY_GLOBAL: u32 = 0;
+pub const WL_REGISTRY_GLOBAL_REMOVE: u32 = 1;
+pub const WL_SURFACE_ENTER: u32 = 0;
+pub const WL_SURFACE_LEAVE: u32 = 1;
+pub const WL_SEAT_CAPABILITIES: u32 = 0;
+pub const WL_DATA_DEVICE_DATA_OFFER: u32 = 0;
+pub const WL_DATA_DEVICE_ENTER: u32 = 1;
+pub const WL_DATA_DEVICE_LEAVE: u32 = 2;
+pub const WL_DATA_DEVICE_MOTION: u32 = 3;
+pub const WL_DATA_DEVICE_DROP: u32 = 4;
+pub const WL_DATA_DEVICE_SELECTION: u32 = 5;
+pub const WL_DATA_OFFER_OFFER: u32 = 0;
+pub const WL_DATA_SOURCE_SEND: u32 = 1;
+pub const WL_DATA_SOURCE_CANCELLED: u32 = 2;
+pub const WL_OUTPUT_GEOMETRY: u32 = 0;
+pub const WL_OUTPUT_MODE: u32 = 1;
+pub const WL_OUTPUT_SCALE: u32 = 3;
+pub const WL_OUTPUT_NAME: u32 = 4;
+pub const WL_POINTER_ENTER: u32 = 0;
+pub const WL_POINTER_LEAVE: u32 = 1;
+pub const WL_POINTER_MOTION: u32 = 2;
+pub const WL_POINTER_BUTTON: u32 = 3;
+pub const WL_POINTER_AXIS: u32 = 4;
+pub const WL_POINTER_FRAME: u32 = 5;
+pub const WL_POINTER_AXIS_SOURCE: u32 = 6;
+pub const WL_POINTER_AXIS_STOP: u32 = 7;
+pub const WL_POINTER_AXIS_DISCRETE: u32 = 8;
+pub const WL_KEYBOARD_KEYMAP: u32 = 0;
+pub const WL_KEYBOARD_ENTER: u32 = 1;
+pub const WL_KEYBOARD_LEAVE: u32 = 2;
+pub const WL_KEYBOARD_KEY: u32 = 3;
+pub const WL_KEYBOARD_MODIFIERS: u32 = 4;
+pub const WL_KEYBOARD_REPEAT_INFO: u32 = 5;
+pub const WL_TOUCH_DOWN: u32 = 0;
+pub const WL_TOUCH_UP: u32 = 1;
+pub const WL_TOUCH_MOTION: u32 = 2;
+pub const WL_TOUCH_CANCEL: u32 = 4;
+pub const XDG_WM_BASE_PING: u32 = 0;
+pub const XDG_SURFACE_CONFIGURE: u32 = 0;
+pub const XDG_TOPLEVEL_CONFIGURE: u32 = 0;
+pub const XDG_TOPLEVEL_CLOSE: u32 = 1;
+pub const ZWP_RELATIVE_POINTER_V1_RELATIVE_MOTION: u32 = 0;
+pub const ZWP_TEXT_INPUT_V3_ENTER: u32 = 0;
+pub const ZWP_TEXT_INPUT_V3_LEAVE: u32 = 1;
+pub const ZWP_TEXT_INPUT_V3_PREEDIT_STRING: u32 = 2;
+pub const ZWP_TEXT_INPUT_V3_COMMIT_STRING: u32 = 3;
+pub const ZWP_TEXT_INPUT_V3_DONE: u32 = 5;
+
+// Structs
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub union wl_argument {
+    pub i: i32,
+    pub u: u32,
+    pub f: wl_fixed_t,
+    pub s: *const c_char,
+    pub o: *mut wl_proxy,
+    pub n: u32,
+    pub a: *mut wl_array,
+    pub h: i32,
+}
+#[repr(C)]
+pub struct wl_array {
+    pub size: usize,
+    pub alloc: usize,
+    pub data: *mut c_void,
+}
+#[repr(C)]
+pub struct wl_message {
+    pub name: *const c_char,
+    pub signature: *const c_char,
+    pub types: *const *const wl_interface,
+}
+#[repr(C)]
+pub struct wl_interface {
+    pub name: *const c_char,
+    pub version: c_int,
+    pub method_count: c_int,
+    pub methods: *const wl_message,
+    pub event_count: c_int,
+    pub events: *const wl_message,
+}
+#[repr(C)]
+pub struct wl_cursor_image {
+    pub width: u32,
+    pub height: u32,
+    pub hotspot_x: u32,
+    pub hotspot_y: u32,
+    pub delay: u32,
+}
+#[repr(C)]
+pub struct wl_cursor {
+    pub image_count: c_uint,
+    pub images: *mut *mut wl_cursor_image,
+    pub name: *mut c_char,
+}
+
+// Interface descriptions are immutable, so...
+unsafe impl Sync for wl_message {}
+unsafe impl Sync for wl_interface {}
+
+dyn_link! {
+    pub struct WaylandDL(dlopen_local => *mut c_void | dlsym) {
+        "libwayland-client.so.0" | "libwayland-client.so" {
+            // Connection management
+            fn wl_display_connect(name: *const c_char) -> *mut wl_display;
+            fn wl_display_disconnect(display: *mut wl_display) -> ();
+            fn wl_display_get_fd(display: *mut wl_display) -> c_int;
+            fn wl_display_dispatch_pending(display: *mut wl_display) -> c_int;
+            fn wl_display_flush(display: *mut wl_display) -> c_int;
+            fn wl_display_prepare_read(display: *mut wl_display) -> c_int;
+            fn wl_display_read_events(display: *mut wl_display) -> c_int;
+            fn wl_display_cancel_read(display: *mut wl_display) -> ();
+            fn wl_display_roundtrip(display: *mut wl_display) -> c_int;
+
+            // Protocol objects
+            fn wl_proxy_marshal_array_flags(
+                proxy: *mut wl_proxy,
+                opcode: u32,
+                interface: *const wl_interface,
+                version: u32,
+                flags: u32,
+                args: *mut wl_argument,
+            ) -> *mut wl_proxy;
+            fn wl_proxy_add_dispatcher(
+                proxy: *mut wl_proxy,
+                dispatcher: wl_dispatcher_func_t,
+                implementation: *const c_void,
+                data: *mut c_void,
+            ) -> c_int;
+            fn wl_proxy_destroy(proxy: *mut wl_proxy) -> ();
+            fn wl_proxy_get_version(proxy: *mut wl_proxy) -> u32;
+        },
+
+        "libwayland-cursor.so.0" | "libwayland-cursor.so" {
+            /// Loads the user's cursor theme, since unlike X11 there's no server-side cursor.
+            fn wl_cursor_theme_load(name: *const c_char, size: c_int, shm: *mut wl_proxy) -> *mut wl_cursor_theme;
+            fn wl_cursor_theme_destroy(theme: *mut wl_cursor_theme) -> ();
+            fn wl_cursor_theme_get_cursor(theme: *mut wl_cursor_theme, name: *const c_char) -> *mut wl_cursor;
+            fn wl_cursor_image_get_buffer(image: *mut wl_cursor_image) -> *mut wl_proxy;
+        },
+    }
+}
+
+impl WaylandDL {
+    pub unsafe fn link() -> Option<Self> {
+        let dl = Self::_link();
+        let _ = dl.wl_proxy_marshal_array_flags?; // added in 1.20, the newest thing we use
+        Some(dl)
+    }
+}
+
+// Vulkan (vulkan/vulkan_wayland.h)
+pub const VK_KHR_WAYLAND_SURFACE_EXTENSION_NAME: &CStr =
+    unsafe { CStr::from_bytes_with_nul_unchecked(c_string!("VK_KHR_wayland_surface")) };
+pub const VK_STRUCTURE_TYPE_WAYLAND_SURFACE_CREATE_INFO_KHR: VkStructureType = 1000006000;
+
+#[repr(C)]
+pub struct VkWaylandSurfaceCreateInfoKHR {
+    pub sType: VkStructureType,
+    pub pNext: *const c_void,
+    pub flags: VkFlags,
+    pub display: *mut wl_display,
+    pub surface: *mut wl_proxy,
+}
+
+pub type PFN_vkCreateWaylandSurfaceKHR = unsafe extern "system" fn(
+    instance: VkInstance,
+    pCreateInfo: *const VkWaylandSurfaceCreateInfoKHR,
+    pAllocator: *const c_void,
+    pSurface: *mut VkSurfaceKHR,
+) -> VkResult;
+
+// OpenGL (EGL/egl.h, EGL/eglext.h & wayland-egl.h), with the libraries only opened once a context is made
+pub enum wl_egl_window {}
+pub type EGLBoolean = c_uint;
+pub type EGLConfig = *mut c_void;
+pub type EGLContext = *mut c_void;
+pub type EGLDisplay = *mut c_void;
+pub type EGLenum = c_uint;
+pub type EGLint = i32;
+pub type EGLSurface = *mut c_void;
+
+pub const EGL_TRUE: EGLBoolean = 1;
+pub const EGL_ALPHA_SIZE: EGLint = 0x3021;
+pub const EGL_BLUE_SIZE: EGLint = 0x3022;
+pub const EGL_CONTEXT_FLAGS_KHR: EGLint = 0x30FC;
+pub const EGL_CONTEXT_MAJOR_VERSION_KHR: EGLint = 0x3098;
+pub const EGL_CONTEXT_MINOR_VERSION_KHR: EGLint = 0x30FB;
+pub const EGL_CONTEXT_OPENGL_COMPATIBILITY_PROFILE_BIT_KHR: EGLint = 0x00000002;
+pub const EGL_CONTEXT_OPENGL_CORE_PROFILE_BIT_KHR: EGLint = 0x00000001;
+pub const EGL_CONTEXT_OPENGL_DEBUG_BIT_KHR: EGLint = 0x00000001;
+pub const EGL_CONTEXT_OPENGL_PROFILE_MASK_KHR: EGLint = 0x30FD;
+pub const EGL_DEPTH_SIZE: EGLint = 0x3025;
+pub const EGL_EXTENSIONS: EGLint = 0x3055;
+pub const EGL_GL_COLORSPACE_KHR: EGLint = 0x309D;
+pub const EGL_GL_COLORSPACE_SRGB_KHR: EGLint = 0x3089;
+pub const EGL_GREEN_SIZE: EGLint = 0x3023;
+pub const EGL_NONE: EGLint = 0x3038;
+pub const EGL_OPENGL_API: EGLenum = 0x30A2;
+pub const EGL_OPENGL_BIT: EGLint = 0x0008;
+pub const EGL_RED_SIZE: EGLint = 0x3024;
+pub const EGL_RENDERABLE_TYPE: EGLint = 0x3040;
+pub const EGL_SAMPLE_BUFFERS: EGLint = 0x3032;
+pub const EGL_SAMPLES: EGLint = 0x3031;
+pub const EGL_STENCIL_SIZE: EGLint = 0x3026;
+pub const EGL_SURFACE_TYPE: EGLint = 0x3033;
+pub const EGL_WINDOW_BIT: EGLint = 0x0004;
+
+dyn_link! {
+    pub struct EglDL(dlopen_local => *mut c_void | dlsym) {
+        "libEGL.so.1" | "libEGL.so" {
+            fn eglBindAPI(api: EGLenum) -> EGLBoolean;
+            fn eglChooseConfig(
+                dpy: EGLDisplay,
+                attrib_list: *const EGLint,
+                configs: *mut EGLConfig,
+                config_size: EGLint,
+                num_config: *mut EGLint,
+            ) -> EGLBoolean;
+            fn eglCreateContext(
+                dpy: EGLDisplay,
+                config: EGLConfig,
+                share_context: EGLContext,
+                attrib_list: *const EGLint,
+            ) -> EGLContext;
+            fn eglCreateWindowSurface(
+                dpy: EGLDisplay,
+                config: EGLConfig,
+                win: *mut wl_egl_window,
+                attrib_list: *const EGLint,
+            ) -> EGLSurface;
+            fn eglDestroyContext(dpy: EGLDisplay, ctx: EGLContext) -> EGLBoolean;
+            fn eglDestroySurface(dpy: EGLDisplay, surface: EGLSurface) -> EGLBoolean;
+            fn eglGetConfigAttrib(dpy: EGLDisplay, config: EGLConfig, attribute: EGLint, value: *mut EGLint) -> EGLBoolean;
+            fn eglGetCurrentContext() -> EGLContext;
+            fn eglGetDisplay(display_id: *mut wl_display) -> EGLDisplay;
+            fn eglGetError() -> EGLint;
+            fn eglGetProcAddress(procname: *const c_char) -> *mut c_void;
+            fn eglInitialize(dpy: EGLDisplay, major: *mut EGLint, minor: *mut EGLint) -> EGLBoolean;
+            fn eglMakeCurrent(dpy: EGLDisplay, draw: EGLSurface, read: EGLSurface, ctx: EGLContext) -> EGLBoolean;
+            fn eglQueryString(dpy: EGLDisplay, name: EGLint) -> *const c_char;
+            fn eglSwapBuffers(dpy: EGLDisplay, surface: EGLSurface) -> EGLBoolean;
+            fn eglSwapInterval(dpy: EGLDisplay, interval: EGLint) -> EGLBoolean;
+            fn eglTerminate(dpy: EGLDisplay) -> EGLBoolean;
+        },
+
+        "libwayland-egl.so.1" | "libwayland-egl.so" {
+            /// The native window EGL wants, which is a `wl_surface` with a size attached.
+            fn wl_egl_window_create(surface: *mut wl_proxy, width: c_int, height: c_int) -> *mut wl_egl_window;
+            fn wl_egl_window_destroy(egl_window: *mut wl_egl_window) -> ();
+            fn wl_egl_window_resize(egl_window: *mut wl_egl_window, width: c_int, height: c_int, dx: c_int, dy: c_int) -> ();
+        },
+    }
+}
+
+impl EglDL {
+    pub unsafe fn link() -> Option<Self> {
+        let dl = Self::_link();
+        let _ = dl.eglCreateContext?;
+        let _ = dl.wl_egl_window_create?;
+        Some(dl)
+    }
+}
+
+// xkbcommon, which turns key presses into text with the keymap the compositor sends
+pub const XKB_KEYMAP_FORMAT_TEXT_V1: c_int = 1;
+pub const XKB_LAYOUT_INVALID: u32 = 0xFFFFFFFF;
+pub const XKB_COMPOSE_FEED_ACCEPTED: c_int = 1;
+pub const XKB_COMPOSE_NOTHING: c_int = 0;
+pub const XKB_COMPOSE_COMPOSING: c_int = 1;
+pub const XKB_COMPOSE_COMPOSED: c_int = 2;
+pub const XKB_COMPOSE_CANCELLED: c_int = 3;
+
+dyn_link! {
+    pub struct XkbCommonDL(dlopen_local => *mut c_void | dlsym) {
+        "libxkbcommon.so.0" | "libxkbcommon.so" {
+            fn xkb_context_new(flags: c_int) -> *mut xkb_context;
+            fn xkb_context_unref(context: *mut xkb_context) -> ();
+            fn xkb_keymap_new_from_string(
+                context: *mut xkb_context,
+                string: *const c_char,
+                format: c_int,
+                flags: c_int,
+            ) -> *mut xkb_keymap;
+            fn xkb_keymap_unref(keymap: *mut xkb_keymap) -> ();
+            fn xkb_keymap_key_repeats(keymap: *mut xkb_keymap, key: u32) -> c_int;
+            fn xkb_keymap_key_get_syms_by_level(
+                keymap: *mut xkb_keymap,
+                key: u32,
+                layout: u32,
+                level: u32,
+                syms_out: *mut *const u32,
+            ) -> c_int;
+            fn xkb_state_new(keymap: *mut xkb_keymap) -> *mut xkb_state;
+            fn xkb_state_unref(state: *mut xkb_state) -> ();
+            fn xkb_state_update_mask(
+                state: *mut xkb_state,
+                depressed_mods: u32,
+                latched_mods: u32,
+                locked_mods: u32,
+                depressed_layout: u32,
+                latched_layout: u32,
+                locked_layout: u32,
+            ) -> c_int;
+            fn xkb_state_key_get_one_sym(state: *mut xkb_state, key: u32) -> u32;
+            fn xkb_state_key_get_layout(state: *mut xkb_state, key: u32) -> u32;
+            fn xkb_state_key_get_utf32(state: *mut xkb_state, key: u32) -> u32;
+
+            // Compose (dead keys & the compose key), which isn't in particularly old versions
+            fn xkb_compose_table_new_from_locale(
+                context: *mut xkb_context,
+                locale: *const c_char,
+                flags: c_int,
+            ) -> *mut xkb_compose_table;
+            fn xkb_compose_table_unref(table: *mut xkb_compose_table) -> ();
+            fn xkb_compose_state_new(table: *mut xkb_compose_table, flags: c_int) -> *mut xkb_compose_state;
+            fn xkb_compose_state_unref(state: *mut xkb_compose_state) -> ();
+            fn xkb_compose_state_feed(state: *mut xkb_compose_state, keysym: u32) -> c_int;
+            fn xkb_compose_state_reset(state: *mut xkb_compose_state) -> ();
+            fn xkb_compose_state_get_status(state: *mut xkb_compose_state) -> c_int;
+            fn xkb_compose_state_get_utf8(state: *mut xkb_compose_state, buffer: *mut c_char, size: usize) -> c_int;
+        },
+    }
+}
+
+impl XkbCommonDL {
+    pub unsafe fn link() -> Option<Self> {
+        let dl = Self::_link();
+        let _ = dl.xkb_context_new?;
+        Some(dl)
+    }
+}
+
+/// Core protocol interfaces, which are exported as data by `libwayland-client` itself.
+pub struct CoreInterfaces {
+    pub wl_buffer: &'static wl_interface,
+    pub wl_compositor: &'static wl_interface,
+    pub wl_data_device: &'static wl_interface,
+    pub wl_data_device_manager: &'static wl_interface,
+    pub wl_data_source: &'static wl_interface,
+    pub wl_keyboard: &'static wl_interface,
+    pub wl_output: &'static wl_interface,
+    pub wl_pointer: &'static wl_interface,
+    pub wl_registry: &'static wl_interface,
+    pub wl_seat: &'static wl_interface,
+    pub wl_shm: &'static wl_interface,
+    pub wl_shm_pool: &'static wl_interface,
+    pub wl_surface: &'static wl_interface,
+    pub wl_touch: &'static wl_interface,
+}
+
+impl CoreInterfaces {
+    pub unsafe fn load() -> Option<Self> {
+        let mut handle = ptr::null_mut();
+        for name in &[c_string!("libwayland-client.so.0"), c_string!("libwayland-client.so")] {
+            handle = dlopen_local(name.as_ptr().cast());
+            if !handle.is_null() {
+                break
+            }
+        }
+        if handle.is_null() {
+            return None
+        }
+        let symbol = |name: &[u8]| (dlsym(handle, name.as_ptr().cast()) as *const wl_interface).as_ref();
+        Some(Self {
+            wl_buffer: symbol(c_string!("wl_buffer_interface"))?,
+            wl_compositor: symbol(c_string!("wl_compositor_interface"))?,
+            wl_data_device: symbol(c_string!("wl_data_device_interface"))?,
+            wl_data_device_manager: symbol(c_string!("wl_data_device_manager_interface"))?,
+            wl_data_source: symbol(c_string!("wl_data_source_interface"))?,
+            wl_keyboard: symbol(c_string!("wl_keyboard_interface"))?,
+            wl_output: symbol(c_string!("wl_output_interface"))?,
+            wl_pointer: symbol(c_string!("wl_pointer_interface"))?,
+            wl_registry: symbol(c_string!("wl_registry_interface"))?,
+            wl_seat: symbol(c_string!("wl_seat_interface"))?,
+            wl_shm: symbol(c_string!("wl_shm_interface"))?,
+            wl_shm_pool: symbol(c_string!("wl_shm_pool_interface"))?,
+            wl_surface: symbol(c_string!("wl_surface_interface"))?,
+            wl_touch: symbol(c_string!("wl_touch_interface"))?,
+        })
+    }
+}
+
+// Protocol extensions, normally generated by `wayland-scanner` from their XML definitions.
+// None of the events we handle take object arguments, so the types are all left unspecified.
+
+/// Makes a `wl_message` out of a name and a signature.
+macro_rules! wl_message {
+    ($name:literal, $signature:literal) => {
+        wl_message {
+            name: c_string!($name).as_ptr().cast(),
+            signature: c_string!($signature).as_ptr().cast(),
+            types: NULL_TYPES.0.as_ptr(),
+        }
+    };
+}
+
+/// Makes a `wl_interface` out of a name, a version, and its requests & events.
+macro_rules! wl_interface {
+    ($name:literal, $version:literal, $requests:expr, $events:expr $(,)?) => {
+        wl_interface {
+            name: c_string!($name).as_ptr().cast(),
+            version: $version,
+            method_count: $requests.len() as c_int,
+            methods: $requests.as_ptr(),
+            event_count: $events.len() as c_int,
+            events: $events.as_ptr(),
+        }
+    };
+}
+
+#[repr(transparent)]
+struct NullTypes([*const wl_interface; 8]);
+unsafe impl Sync for NullTypes {}
+static NULL_TYPES: NullTypes = NullTypes([ptr::null(); 8]);
+static NO_MESSAGES: [wl_message; 0] = [];
+
+static XDG_WM_BASE_REQUESTS: [wl_message; 4] = [
+    wl_message!("destroy", ""),
+    wl_message!("create_positioner", "n"),
+    wl_message!("get_xdg_surface", "no"),
+    wl_message!("pong", "u"),
+];
+static XDG_WM_BASE_EVENTS: [wl_message; 1] = [wl_message!("ping", "u")];
+pub static xdg_wm_base_interface: wl_interface =
+    wl_interface!("xdg_wm_base", 1, XDG_WM_BASE_REQUESTS, XDG_WM_BASE_EVENTS);
+
+static XDG_SURFACE_REQUESTS: [wl_message; 5] = [
+    wl_message!("destroy", ""),
+    wl_message!("get_toplevel", "n"),
+    wl_message!("get_popup", "n?oo"),
+    wl_message!("set_window_geometry", "iiii"),
+    wl_message!("ack_configure", "u"),
+];
+static XDG_SURFACE_EVENTS: [wl_message; 1] = [wl_message!("configure", "u")];
+pub static xdg_surface_interface: wl_interface =
+    wl_interface!("xdg_surface", 1, XDG_SURFACE_REQUESTS, XDG_SURFACE_EVENTS);
+
+static XDG_TOPLEVEL_REQUESTS: [wl_message; 14] = [
+    wl_message!("destroy", ""),
+    wl_message!("set_parent", "?o"),
+    wl_message!("set_title", "s"),
+    wl_message!("set_app_id", "s"),
+    wl_message!("show_window_menu", "ouii"),
+    wl_message!("move", "ou"),
+    wl_message!("resize", "ouu"),
+    wl_message!("set_max_size", "ii"),
+    wl_message!("set_min_size", "ii"),
+    wl_message!("set_maximized", ""),
+    wl_message!("unset_maximized", ""),
+    wl_message!("set_fullscreen", "?o"),
+    wl_message!("unset_fullscreen", ""),
+    wl_message!("set_minimized", ""),
+];
+static XDG_TOPLEVEL_EVENTS: [wl_message; 2] = [
+    wl_message!("configure", "iia"),
+    wl_message!("close", ""),
+];
+pub static xdg_toplevel_interface: wl_interface =
+    wl_interface!("xdg_toplevel", 1, XDG_TOPLEVEL_REQUESTS, XDG_TOPLEVEL_EVENTS);
+
+static ZXDG_DECORATION_MANAGER_V1_REQUESTS: [wl_message; 2] = [
+    wl_message!("destroy", ""),
+    wl_message!("get_toplevel_decoration", "no"),
+];
+pub static zxdg_decoration_manager_v1_interface: wl_interface = wl_interface!(
+    "zxdg_decoration_manager_v1",
+    1,
+    ZXDG_DECORATION_MANAGER_V1_REQUESTS,
+    NO_MESSAGES,
+);
+
+static ZXDG_TOPLEVEL_DECORATION_V1_REQUESTS: [wl_message; 3] = [
+    wl_message!("destroy", ""),
+    wl_message!("set_mode", "u"),
+    wl_message!("unset_mode", ""),
+];
+static ZXDG_TOPLEVEL_DECORATION_V1_EVENTS: [wl_message; 1] = [wl_message!("configure", "u")];
+pub static zxdg_toplevel_decoration_v1_interface: wl_interface = wl_interface!(
+    "zxdg_toplevel_decoration_v1",
+    1,
+    ZXDG_TOPLEVEL_DECORATION_V1_REQUESTS,
+    ZXDG_TOPLEVEL_DECORATION_V1_EVENTS,
+);
+
+static ZWP_POINTER_CONSTRAINTS_V1_REQUESTS: [wl_message; 3] = [
+    wl_message!("destroy", ""),
+    wl_message!("lock_pointer", "noo?ou"),
+    wl_message!("confine_pointer", "noo?ou"),
+];
+pub static zwp_pointer_constraints_v1_interface: wl_interface = wl_interface!(
+    "zwp_pointer_constraints_v1",
+    1,
+    ZWP_POINTER_CONSTRAINTS_V1_REQUESTS,
+    NO_MESSAGES,
+);
+
+static ZWP_LOCKED_POINTER_V1_REQUESTS: [wl_message; 3] = [
+    wl_message!("destroy", ""),
+    wl_message!("set_cursor_position_hint", "ff"),
+    wl_message!("set_region", "?o"),
+];
+static ZWP_LOCKED_POINTER_V1_EVENTS: [wl_message; 2] = [
+    wl_message!("locked", ""),
+    wl_message!("unlocked", ""),
+];
+pub static zwp_locked_pointer_v1_interface: wl_interface = wl_interface!(
+    "zwp_locked_pointer_v1",
+    1,
+    ZWP_LOCKED_POINTER_V1_REQUESTS,
+    ZWP_LOCKED_POINTER_V1_EVENTS,
+);
+
+static ZWP_CONFINED_POINTER_V1_REQUESTS: [wl_message; 2] = [
+    wl_message!("destroy", ""),
+    wl_message!("set_region", "?o"),
+];
+static ZWP_CONFINED_POINTER_V1_EVENTS: [wl_message; 2] = [
+    wl_message!("confined", ""),
+    wl_message!("unconfined", ""),
+];
+pub static zwp_confined_pointer_v1_interface: wl_interface = wl_interface!(
+    "zwp_confined_pointer_v1",
+    1,
+    ZWP_CONFINED_POINTER_V1_REQUESTS,
+    ZWP_CONFINED_POINTER_V1_EVENTS,
+);
+
+static ZWP_RELATIVE_POINTER_MANAGER_V1_REQUESTS: [wl_message; 2] = [
+    wl_message!("destroy", ""),
+    wl_message!("get_relative_pointer", "no"),
+];
+pub static zwp_relative_pointer_manager_v1_interface: wl_interface = wl_interface!(
+    "zwp_relative_pointer_manager_v1",
+    1,
+    ZWP_RELATIVE_POINTER_MANAGER_V1_REQUESTS,
+    NO_MESSAGES,
+);
+
+static ZWP_RELATIVE_POINTER_V1_REQUESTS: [wl_message; 1] = [wl_message!("destroy", "")];
+static ZWP_RELATIVE_POINTER_V1_EVENTS: [wl_message; 1] = [wl_message!("relative_motion", "uuffff")];
+pub static zwp_relative_pointer_v1_interface: wl_interface = wl_interface!(
+    "zwp_relative_pointer_v1",
+    1,
+    ZWP_RELATIVE_POINTER_V1_REQUESTS,
+    ZWP_RELATIVE_POINTER_V1_EVENTS,
+);
+
+static ZWP_TEXT_INPUT_MANAGER_V3_REQUESTS: [wl_message; 2] = [
+    wl_message!("destroy", ""),
+    wl_message!("get_text_input", "no"),
+];
+pub static zwp_text_input_manager_v3_interface: wl_interface = wl_interface!(
+    "zwp_text_input_manager_v3",
+    1,
+    ZWP_TEXT_INPUT_MANAGER_V3_REQUESTS,
+    NO_MESSAGES,
+);
+
+static ZWP_TEXT_INPUT_V3_REQUESTS: [wl_message; 8] = [
+    wl_message!("destroy", ""),
+    wl_message!("enable", ""),
+    wl_message!("disable", ""),
+    wl_message!("set_surrounding_text", "sii"),
+    wl_message!("set_text_change_cause", "u"),
+    wl_message!("set_content_type", "uu"),
+    wl_message!("set_cursor_rectangle", "iiii"),
+    wl_message!("commit", ""),
+];
+static ZWP_TEXT_INPUT_V3_EVENTS: [wl_message; 6] = [
+    wl_message!("enter", "o"),
+    wl_message!("leave", "o"),
+    wl_message!("preedit_string", "?sii"),
+    wl_message!("commit_string", "?s"),
+    wl_message!("delete_surrounding_text", "uu"),
+    wl_message!("done", "u"),
+];
+pub static zwp_text_input_v3_interface: wl_interface = wl_interface!(
+    "zwp_text_input_v3",
+    1,
+    ZWP_TEXT_INPUT_V3_REQUESTS,
+    ZWP_TEXT_INPUT_V3_EVENTS,
+);
@@ -0,0 +1,3349 @@
+use crate::{
+    clipboard::{ClipboardContents, ClipboardFormat},
+    error::Error,
+    event::{CloseReason, Event, EventPaths},
+    gl::{self, GlContextBuilder, GlProfile},
+    monitor::{Monitor, Point, Rect, Scale, Size, VideoMode},
+    platform::unix::imp::{self as unix, Message, MessageQueue},
+    util::{self, sync::{Condvar, Mutex}, CreateSender, CreateSlot, EventQueue, LazyCell},
+    vulkan::{self, PFN_vkGetInstanceProcAddr, PFN_vkVoidFunction, VkInstance, VkSurfaceKHR},
+    window::{self, Cursor, CursorGrab, CursorIcon, CustomCursor, Icon, WindowBuilder},
+};
+use std::{
+    cell::UnsafeCell,
+    env,
+    ffi::{CStr, CString},
+    mem,
+    ops,
+    os::raw::c_void,
+    path::PathBuf,
+    ptr,
+    sync::Arc,
+    thread,
+    time::Duration,
+};
+
+#[cfg(feature = "raw-window-handle")]
+use raw_window_handle::{HandleError, RawDisplayHandle, RawWindowHandle, WaylandDisplayHandle, WaylandWindowHandle};
+
+#[cfg(feature = "input")]
+use crate::{
+    event::{EventText, ImeEvent, Key, Modifiers, MouseButton, ScanCode, ScrollDelta, ScrollPhase, Touch, TouchPhase},
+    platform::x11::{ffi::KeySym, imp::{translate_keysym, translate_modifiers}},
+    util::TouchMouse,
+};
+#[cfg(feature = "input")]
+use std::{os::unix::ffi::OsStringExt, time::Instant};
+
+// TODO: Maybe deglob
+use crate::platform::wayland::ffi::*;
+
+// Global immutable struct containing dynamically acquired API state
+static WAYLAND: LazyCell<Option<WaylandState>> = LazyCell::new(WaylandState::new);
+
+/// Whether the session has a compositor with everything we need, checked once per process.
+static IS_AVAILABLE: LazyCell<bool> = LazyCell::new(probe_compositor);
+
+/// EGL & libwayland-egl, which are only loaded once an OpenGL context is made.
+static EGL: LazyCell<Option<EglDL>> = LazyCell::new(|| unsafe { EglDL::link() });
+
+/// TODO: Measure this
+const MAX_EVENTS_PER_SWAP: usize = 4096;
+/// How long another client has to send the next bit of data it's offered us before we give up on it.
+const RECEIVE_TIMEOUT: Duration = Duration::from_secs(1);
+
+struct WaylandState {
+    /// Dynamically linked libwayland functions.
+    dl: WaylandDL,
+
+    /// Core protocol interfaces, exported by libwayland-client.
+    interfaces: CoreInterfaces,
+
+    /// Dynamically linked xkbcommon functions, for text input. Optional.
+    #[cfg(feature = "input")]
+    xkb: Option<XkbCommonDL>,
+}
+
+impl WaylandState {
+    fn new() -> Option<Self> {
+        unsafe {
+            Some(Self {
+                dl: WaylandDL::link()?,
+                interfaces: CoreInterfaces::load()?,
+                #[cfg(feature = "input")]
+                xkb: XkbCommonDL::link(),
+            })
+        }
+    }
+}
+
+/// Checks whether windows should be created with the Wayland backend.
+pub(crate) fn is_available() -> bool {
+    *IS_AVAILABLE.get()
+}
+
+/// Connects to the compositor to see if it has `wl_compositor`, `wl_shm` and `xdg_wm_base`.
+fn probe_compositor() -> bool {
+    // Without this, `wl_display_connect` would try `wayland-0` which might be someone else's
+    if env::var_os("WAYLAND_DISPLAY").is_none() {
+        return false
+    }
+    let wl = match WAYLAND.get() {
+        Some(wl) => wl,
+        None => return false,
+    };
+
+    unsafe extern "C" fn registry_dispatch(
+        found: *const c_void,
+        _proxy: *mut c_void,
+        opcode: u32,
+        _message: *const wl_message,
+        args: *mut wl_argument,
+    ) -> c_int {
+        let found = &mut *(found as *mut [bool; 3]);
+        if opcode == WL_REGISTRY_GLOBAL {
+            match CStr::from_ptr((*args.add(1)).s).to_bytes() {
+                b"wl_compositor" => found[0] = true,
+                b"wl_shm" => found[1] = true,
+                b"xdg_wm_base" => found[2] = true,
+                _ => (),
+            }
+        }
+        0
+    }
+
+    unsafe {
+        let display = wl.dl.wl_display_connect(ptr::null()).unwrap_or(ptr::null_mut());
+        if display.is_null() {
+            return false
+        }
+        let mut found = [false; 3];
+        let registry = constructor(wl, display.cast(), WL_DISPLAY_GET_REGISTRY, wl.interfaces.wl_registry, &mut [
+            NEW_ID,
+        ]);
+        let _ = wl.dl.wl_proxy_add_dispatcher(registry, registry_dispatch, (&mut found as *mut [bool; 3]).cast(), ptr::null_mut());
+        let _ = wl.dl.wl_display_roundtrip(display);
+        let _ = wl.dl.wl_proxy_destroy(registry);
+        let _ = wl.dl.wl_display_disconnect(display);
+        found == [true; 3]
+    }
+}
+
+/// A `wl_output` global we've bound, to figure out what scale the window should be drawn at,
+/// and to describe it as a [`Monitor`].
+struct Output {
+    proxy: *mut wl_proxy,
+    name: u32,
+    scale: i32,
+
+    // Filled in by the output's events
+    position: (i32, i32),
+    transform: i32,
+    model: String,
+    output_name: Option<String>,
+    current_mode: Option<VideoMode>,
+    video_modes: Vec<VideoMode>,
+}
+
+impl Output {
+    fn new(proxy: *mut wl_proxy, name: u32) -> Self {
+        Self {
+            proxy,
+            name,
+            scale: 1,
+            position: (0, 0),
+            transform: 0,
+            model: String::new(),
+            output_name: None,
+            current_mode: None,
+            video_modes: Vec::new(),
+        }
+    }
+
+    /// Handles the events of the `wl_output`, which describe it.
+    unsafe fn event(&mut self, opcode: u32, arg: impl Fn(usize) -> wl_argument) {
+        let string = |i: usize| CStr::from_ptr(arg(i).s).to_string_lossy().into_owned();
+        match opcode {
+            WL_OUTPUT_GEOMETRY => {
+                self.position = (arg(0).i, arg(1).i);
+                self.model = format!("{} {}", string(5), string(6));
+                self.transform = arg(7).i;
+            },
+            WL_OUTPUT_MODE => {
+                // Bit depth isn't a thing here, but buffers are 32-bit XRGB, so it's 24 bits of colour
+                let (width, height) = (arg(1).i.max(0) as u32, arg(2).i.max(0) as u32);
+                let mode = VideoMode { size: (width, height), bit_depth: 24, refresh_rate: arg(3).i.max(0) as u32 };
+                if arg(0).u & WL_OUTPUT_MODE_CURRENT != 0 {
+                    self.current_mode = Some(mode);
+                }
+                if !self.video_modes.contains(&mode) {
+                    self.video_modes.push(mode);
+                }
+            },
+            WL_OUTPUT_SCALE => self.scale = arg(0).i.max(1),
+            WL_OUTPUT_NAME => self.output_name = Some(string(0)),
+            _ => (),
+        }
+    }
+
+    fn monitor(&self) -> Monitor {
+        let (mut width, mut height) = self.current_mode.map(|mode| mode.size).unwrap_or((0, 0));
+        if self.transform % 2 != 0 {
+            // Rotated by 90 or 270 degrees, which the mode doesn't account for
+            mem::swap(&mut width, &mut height);
+        }
+        let rect = Rect { x: self.position.0, y: self.position.1, width, height };
+        Monitor {
+            name: self.output_name.clone().unwrap_or_else(|| self.model.clone()),
+            rect,
+            work_area: rect,
+            scale: self.scale as Scale,
+            refresh_rate: self.current_mode.map(|mode| mode.refresh_rate).unwrap_or(0),
+            is_primary: false,
+            video_modes: self.video_modes.clone(),
+        }
+    }
+}
+
+pub fn vulkan_instance_extensions() -> &'static [&'static CStr] {
+    &[vulkan::VK_KHR_SURFACE_EXTENSION_NAME, VK_KHR_WAYLAND_SURFACE_EXTENSION_NAME]
+}
+
+pub fn available_monitors() -> Vec<Monitor> {
+    let wl = match WAYLAND.get() {
+        Some(wl) => wl,
+        None => return Vec::new(),
+    };
+
+    /// The outputs of a connection of our own, as there's no window to ask.
+    struct OutputList {
+        wl: &'static WaylandState,
+        registry: *mut wl_proxy,
+        outputs: Vec<Output>,
+    }
+
+    unsafe extern "C" fn dispatch_outputs(
+        list: *const c_void,
+        proxy: *mut c_void,
+        opcode: u32,
+        _message: *const wl_message,
+        args: *mut wl_argument,
+    ) -> c_int {
+        let list_ptr = list as *mut OutputList;
+        let list = &mut *list_ptr;
+        let (wl, proxy) = (list.wl, proxy as *mut wl_proxy);
+        let arg = |i: usize| *args.add(i);
+        if proxy == list.registry {
+            if opcode == WL_REGISTRY_GLOBAL && CStr::from_ptr(arg(1).s).to_bytes() == b"wl_output" {
+                let (name, version) = (arg(0).u, output_version(wl, arg(2).u));
+                let proxy = bind_global(wl, list.registry, name, wl.interfaces.wl_output, version);
+                let _ = wl.dl.wl_proxy_add_dispatcher(proxy, dispatch_outputs, list_ptr.cast(), ptr::null_mut());
+                list.outputs.push(Output::new(proxy, name));
+            }
+        } else if let Some(output) = list.outputs.iter_mut().find(|output| output.proxy == proxy) {
+            output.event(opcode, arg);
+        }
+        0
+    }
+
+    unsafe {
+        let display = wl.dl.wl_display_connect(ptr::null()).unwrap_or(ptr::null_mut());
+        if display.is_null() {
+            return Vec::new()
+        }
+
+        // Bind the outputs (first roundtrip), then get their descriptions (second)
+        let registry = constructor(wl, display.cast(), WL_DISPLAY_GET_REGISTRY, wl.interfaces.wl_registry, &mut [
+            NEW_ID,
+        ]);
+        let mut list = OutputList { wl, registry, outputs: Vec::new() };
+        let list_ptr = &mut list as *mut OutputList;
+        let _ = wl.dl.wl_proxy_add_dispatcher(registry, dispatch_outputs, list_ptr.cast(), ptr::null_mut());
+        let _ = wl.dl.wl_display_roundtrip(display);
+        let _ = wl.dl.wl_display_roundtrip(display);
+
+        let monitors = list.outputs.iter().map(Output::monitor).collect();
+        for output in list.outputs.drain(..) {
+            let _ = wl.dl.wl_proxy_destroy(output.proxy);
+        }
+        let _ = wl.dl.wl_proxy_destroy(registry);
+        let _ = wl.dl.wl_display_disconnect(display);
+        monitors
+    }
+}
+
+/// A drag & drop over the window, from `wl_data_device.enter` until it leaves or is dropped.
+struct Drag {
+    /// The `wl_data_offer` of the drag, which is destroyed once it's over.
+    offer: *mut wl_proxy,
+
+    /// Where the drag was last at, which is also where the files are dropped.
+    position: Point,
+
+    /// The dragged files, if the drag has any.
+    paths: Option<Vec<PathBuf>>,
+}
+
+/// Implementation container for `window::Window`
+pub struct WindowImpl {
+    display: *mut wl_display,
+    queue: Arc<MessageQueue>,
+    surface: *mut wl_proxy,
+    thread: Option<thread::JoinHandle<()>>,
+    user: *mut WindowImplData, // 'thread
+}
+
+// Pointers automatically lose Send and Sync, so...
+unsafe impl Send for WindowImpl {}
+unsafe impl Sync for WindowImpl {}
+
+/// Wayland specific extensions to the [`Window`](crate::window::Window) API.
+pub trait WindowExt {
+    /// Gets the [`wl_display`] connection owned by the window thread.
+    ///
+    /// Each window has its own connection, which is closed when the window is dropped.
+    ///
+    /// Returns `None` if the window wasn't created with the Wayland backend.
+    fn wayland_display(&self) -> Option<*mut wl_display>;
+
+    /// Gets the `wl_surface` of the window.
+    ///
+    /// Returns `None` if the window wasn't created with the Wayland backend.
+    fn wayland_surface(&self) -> Option<*mut wl_proxy>;
+}
+
+impl WindowExt for window::Window {
+    #[inline]
+    fn wayland_display(&self) -> Option<*mut wl_display> {
+        match &self.0 {
+            unix::WindowImpl::Wayland(window) => Some(window.display),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn wayland_surface(&self) -> Option<*mut wl_proxy> {
+        match &self.0 {
+            unix::WindowImpl::Wayland(window) => Some(window.surface),
+            _ => None,
+        }
+    }
+}
+
+/// User data structure.
+///
+/// Mostly unsynchronized (and is therefore for the window thread only), handle with care.
+pub struct WindowImplData {
+    /// libwayland function table & interfaces (lives as long as the process)
+    wl: &'static WaylandState,
+
+    /// The connection, which has to be flushed before waiting on other clients.
+    display: *mut wl_display,
+
+    // Globals
+    registry: *mut wl_proxy,
+    compositor: *mut wl_proxy,
+    shm: *mut wl_proxy,
+    wm_base: *mut wl_proxy,
+    decoration_manager: *mut wl_proxy,
+    seat: *mut wl_proxy,
+    outputs: Vec<Output>,
+    pointer_constraints: *mut wl_proxy,
+    data_device_manager: *mut wl_proxy,
+    #[cfg(feature = "input")]
+    text_input_manager: *mut wl_proxy,
+    #[cfg(feature = "input")]
+    relative_pointer_manager: *mut wl_proxy,
+
+    // Input devices of the seat
+    pointer: *mut wl_proxy,
+    keyboard: *mut wl_proxy,
+    touch: *mut wl_proxy,
+
+    /// The pointer's `zwp_locked_pointer_v1` or `zwp_confined_pointer_v1`, depending on `cursor_grab`.
+    /// It's persistent, so the compositor takes care of it only applying while the window has focus.
+    pointer_constraint: *mut wl_proxy,
+
+    /// The pointer's `zwp_relative_pointer_v1`, which only exists while raw mouse motion is enabled.
+    #[cfg(feature = "input")]
+    relative_pointer: *mut wl_proxy,
+
+    /// The seat's `zwp_text_input_v3`, which is how input methods talk to the window.
+    #[cfg(feature = "input")]
+    text_input_v3: *mut wl_proxy,
+
+    /// The seat's `wl_data_device`, which drag & drop and the clipboard go through.
+    data_device: *mut wl_proxy,
+
+    /// Offers from the data device, and the mime types they're in, until they're destroyed.
+    data_offers: Vec<(*mut wl_proxy, Vec<String>)>,
+
+    /// Whether drags are accepted, see `WindowBuilder::accept_drops`.
+    accept_drops: bool,
+
+    /// The drag & drop over the window, if there is one.
+    drag: Option<Drag>,
+
+    /// The offer of the clipboard contents, which is null if it's empty, or until the window is first focused.
+    selection_offer: *mut wl_proxy,
+
+    /// The `wl_data_source` of the clipboard while the window owns it, and the contents it sends.
+    clipboard_source: *mut wl_proxy,
+    clipboard: Option<ClipboardContents>,
+
+    /// The serial of the last input event, which setting the clipboard has to come with.
+    input_serial: u32,
+
+    /// The surface that is the window, which exists for as long as the window does.
+    surface: *mut wl_proxy,
+
+    // The role of the surface, which only exists while the window is visible.
+    // Destroying these is how the window gets hidden, as there's no such thing as unmapping it.
+    xdg_surface: *mut wl_proxy,
+    xdg_toplevel: *mut wl_proxy,
+    decoration: *mut wl_proxy,
+
+    /// The (blank) shared memory buffer attached to the surface, and its size in pixels.
+    buffer: *mut wl_proxy,
+    buffer_size: (u32, u32),
+
+    // Cursors are drawn by the client, on a surface of their own.
+    cursor: CursorIcon,
+    cursor_surface: *mut wl_proxy,
+    /// The buffer of a custom cursor, made for the current buffer scale (null until it's shown).
+    cursor_buffer: *mut wl_proxy,
+    cursor_theme: *mut wl_cursor_theme,
+    cursor_grab: CursorGrab,
+    pointer_serial: u32,
+
+    /// The outputs that the surface is on, which decide its scale.
+    entered_outputs: Vec<*mut wl_proxy>,
+
+    /// Size of the surface in surface-local coordinates (pixels divided by the buffer scale).
+    surface_size: (i32, i32),
+
+    /// Current size of the client area (inner area) in pixels
+    client_area_size: (u32, u32),
+
+    /// The integer scale of the buffer, which is the scale of the densest output the window is on.
+    buffer_scale: i32,
+
+    /// Size, maximized & fullscreen state from the last `xdg_toplevel.configure`,
+    /// applied once it's acknowledged.
+    pending_configure: (i32, i32, bool, bool),
+
+    /// Whether the surface has received its first configure, and can have a buffer attached.
+    is_configured: bool,
+
+    /// Whether OpenGL or Vulkan presents to the surface, in which case our buffer stays out of the way.
+    has_external_surface: bool,
+
+    // Kept around as roles get recreated when the window is shown again
+    class_name: CString,
+    title: CString,
+
+    /// The fullscreen mode that was asked for, which is asked for again when the role is recreated.
+    fullscreen: Option<window::Fullscreen>,
+
+    /// Whether things should be scaled according to DPI.
+    is_dpi_logical: bool,
+
+    /// Indicates whether the message loop should stop and the window be destroyed.
+    destroy_flag: bool,
+
+    /// The current window style which the toplevel state is built out of.
+    style: window::Style,
+
+    // Very lightweight event-swap system...
+    // Read `EventQueue::push_many` for more info
+    event_queue: EventQueue<Event, MAX_EVENTS_PER_SWAP>,
+
+    /// Keymap & modifier state from `wl_keyboard`, if xkbcommon is available.
+    #[cfg(feature = "input")]
+    xkb: Option<Xkb>,
+    /// Modifiers from `wl_keyboard.modifiers`, which is sent after the key press that changed them.
+    #[cfg(feature = "input")]
+    modifiers: Modifiers,
+    /// Whether key presses are turned into text for `Event::Character`.
+    #[cfg(feature = "input")]
+    text_input: bool,
+    /// Key repeat is done by clients on Wayland, so this is the key being repeated and when it's next due.
+    #[cfg(feature = "input")]
+    repeat: Option<(u32, Key, Instant)>,
+    /// Key repeat delay & interval, from `wl_keyboard.repeat_info`.
+    #[cfg(feature = "input")]
+    repeat_info: (Duration, Option<Duration>),
+    /// Scrolling from the `wl_pointer.axis*` events, which is gathered until the `frame` ending them.
+    #[cfg(feature = "input")]
+    pending_scroll: PendingScroll,
+    /// Whether a touchpad scroll is ongoing, so `Event::MouseScroll` knows the phase.
+    #[cfg(feature = "input")]
+    is_scroll_gesture: bool,
+    /// Mouse buttons that are down, which the compositor keeps the pointer on us for until they're released.
+    #[cfg(feature = "input")]
+    held_buttons: Vec<MouseButton>,
+    /// Fingers on the window and where they last were, as `wl_touch.up` & `cancel` don't say.
+    #[cfg(feature = "input")]
+    touches: Vec<(i32, Point)>,
+    /// Whether touch is reported as the mouse too, which is up to clients on Wayland.
+    #[cfg(feature = "input")]
+    touch_emulates_mouse: bool,
+    #[cfg(feature = "input")]
+    touch_mouse: TouchMouse,
+    /// Whether `relative_pointer` should exist, see `Window::set_raw_mouse_motion`.
+    #[cfg(feature = "input")]
+    raw_mouse_motion: bool,
+    /// Whether the input method is enabled, see `Window::set_ime_allowed`.
+    #[cfg(feature = "input")]
+    ime_allowed: bool,
+    /// The text area that the input method should avoid covering, relative to the window.
+    #[cfg(feature = "input")]
+    ime_cursor_area: Option<(Point, Size)>,
+    /// Preedit & committed text from `zwp_text_input_v3`, applied on its `done` event.
+    #[cfg(feature = "input")]
+    pending_preedit: Option<(String, Option<(usize, usize)>)>,
+    #[cfg(feature = "input")]
+    pending_commit: Option<String>,
+
+    // State flag dump
+    is_focused: bool,
+    is_fullscreen: bool,
+    is_maximized: bool,
+    is_mouse_inside: bool,
+    #[cfg(feature = "input")]
+    is_composing: bool,
+    /// Whether the text input has entered the surface, which is when it can be enabled.
+    #[cfg(feature = "input")]
+    is_text_input_entered: bool,
+}
+
+pub fn spawn_window(builder: &WindowBuilder) -> Result<WindowImpl, Error> {
+    let builder = builder.clone();
+
+    let wl = WAYLAND.get().as_ref().ok_or(Error::Unavailable("failed to load libwayland-client"))?;
+    let display = unsafe { wl.dl.wl_display_connect(ptr::null()) }.unwrap_or(ptr::null_mut());
+    if display.is_null() {
+        return Err(Error::Unavailable("failed to connect to the Wayland compositor"))
+    }
+
+    let queue = Arc::new(MessageQueue::new());
+    let queue2 = Arc::clone(&queue); // remote thread's handle object
+    let recv: Arc<CreateSlot<WindowImpl>> = Arc::new((Mutex::new(None), Condvar::new()));
+    let sender = CreateSender(Arc::clone(&recv)); // remote thread's handle object
+
+    // HACK: Since Rust doesn't trust us to share pointers, we move a `usize`
+    let display = display as usize;
+    let thread = thread::spawn(move || unsafe {
+        let display = display as *mut wl_display;
+        let wl = WAYLAND.get().as_ref().unwrap();
+
+        // There's no knowing what the scale is until the surface is on an output, so it's 1 for now
+        let (width, height) = builder.inner_size.as_physical(1.0);
+
+        let user_data: UnsafeCell<WindowImplData> = UnsafeCell::new(WindowImplData {
+            wl,
+            display,
+
+            registry: ptr::null_mut(),
+            compositor: ptr::null_mut(),
+            shm: ptr::null_mut(),
+            wm_base: ptr::null_mut(),
+            decoration_manager: ptr::null_mut(),
+            seat: ptr::null_mut(),
+            outputs: Vec::new(),
+            pointer_constraints: ptr::null_mut(),
+            data_device_manager: ptr::null_mut(),
+            #[cfg(feature = "input")]
+            text_input_manager: ptr::null_mut(),
+            #[cfg(feature = "input")]
+            relative_pointer_manager: ptr::null_mut(),
+
+            pointer: ptr::null_mut(),
+            keyboard: ptr::null_mut(),
+            touch: ptr::null_mut(),
+            pointer_constraint: ptr::null_mut(),
+            #[cfg(feature = "input")]
+            relative_pointer: ptr::null_mut(),
+            #[cfg(feature = "input")]
+            text_input_v3: ptr::null_mut(),
+            data_device: ptr::null_mut(),
+            data_offers: Vec::new(),
+            accept_drops: builder.accept_drops,
+            drag: None,
+            selection_offer: ptr::null_mut(),
+            clipboard_source: ptr::null_mut(),
+            clipboard: None,
+            input_serial: 0,
+
+            surface: ptr::null_mut(),
+            xdg_surface: ptr::null_mut(),
+            xdg_toplevel: ptr::null_mut(),
+            decoration: ptr::null_mut(),
+
+            buffer: ptr::null_mut(),
+            buffer_size: (0, 0),
+
+            cursor: builder.cursor.clone(),
+            cursor_surface: ptr::null_mut(),
+            cursor_buffer: ptr::null_mut(),
+            cursor_theme: ptr::null_mut(),
+            cursor_grab: CursorGrab::None,
+            pointer_serial: 0,
+
+            entered_outputs: Vec::new(),
+            surface_size: (width.max(1) as i32, height.max(1) as i32),
+            client_area_size: (width.max(1), height.max(1)),
+            buffer_scale: 1,
+            pending_configure: (0, 0, false, false),
+            is_configured: false,
+            has_external_surface: false,
+
+            class_name: CString::new(builder.class_name.as_ref()).unwrap_or_default(),
+            title: CString::new(builder.title.as_ref()).unwrap_or_default(),
+            fullscreen: builder.fullscreen.clone(),
+            is_dpi_logical: matches!(builder.inner_size, Size::Logical(..)),
+            destroy_flag: false,
+            style: builder.style.clone(),
+
+            event_queue: EventQueue::new(),
+
+            #[cfg(feature = "input")]
+            xkb: None,
+            #[cfg(feature = "input")]
+            modifiers: Modifiers::empty(),
+            #[cfg(feature = "input")]
+            text_input: builder.text_input,
+            #[cfg(feature = "input")]
+            repeat: None,
+            // Defaults are the same as Weston's, for compositors that don't send `repeat_info`
+            #[cfg(feature = "input")]
+            repeat_info: (Duration::from_millis(400), Some(Duration::from_millis(1000 / 40))),
+            #[cfg(feature = "input")]
+            pending_scroll: PendingScroll::default(),
+            #[cfg(feature = "input")]
+            is_scroll_gesture: false,
+            #[cfg(feature = "input")]
+            held_buttons: Vec::new(),
+            #[cfg(feature = "input")]
+            touches: Vec::new(),
+            #[cfg(feature = "input")]
+            touch_emulates_mouse: builder.touch_emulates_mouse,
+            #[cfg(feature = "input")]
+            touch_mouse: TouchMouse::default(),
+            #[cfg(feature = "input")]
+            raw_mouse_motion: builder.raw_mouse_motion,
+            #[cfg(feature = "input")]
+            ime_allowed: builder.ime_allowed,
+            #[cfg(feature = "input")]
+            ime_cursor_area: None,
+            #[cfg(feature = "input")]
+            pending_preedit: None,
+            #[cfg(feature = "input")]
+            pending_commit: None,
+
+            is_focused: false,
+            is_fullscreen: false,
+            is_maximized: false,
+            is_mouse_inside: false,
+            #[cfg(feature = "input")]
+            is_composing: false,
+            #[cfg(feature = "input")]
+            is_text_input_entered: false,
+        });
+        let data = &mut *user_data.get();
+
+        // Bind globals (first roundtrip), then get the seat's capabilities & outputs' scales (second)
+        data.registry = constructor(wl, display.cast(), WL_DISPLAY_GET_REGISTRY, wl.interfaces.wl_registry, &mut [
+            NEW_ID,
+        ]);
+        add_dispatcher(data, data.registry);
+        let _ = wl.dl.wl_display_roundtrip(display);
+        let _ = wl.dl.wl_display_roundtrip(display);
+
+        // The compositor has been checked beforehand, so this is unlikely, but it's not a creation failure either
+        if data.compositor.is_null() || data.shm.is_null() || data.wm_base.is_null() {
+            destroy_window(data, display);
+            sender.send(Err(Error::Unavailable("the Wayland compositor lacks wl_compositor, wl_shm or xdg_wm_base")));
+            return
+        }
+
+        // Input methods (and relative pointers) are optional, as not every compositor supports them
+        #[cfg(feature = "input")]
+        update_relative_pointer(data);
+        #[cfg(feature = "input")]
+        if !data.text_input_manager.is_null() && !data.seat.is_null() {
+            data.text_input_v3 = constructor(
+                wl,
+                data.text_input_manager,
+                ZWP_TEXT_INPUT_MANAGER_V3_GET_TEXT_INPUT,
+                &zwp_text_input_v3_interface,
+                &mut [NEW_ID, object(data.seat)],
+            );
+            add_dispatcher(data, data.text_input_v3);
+        }
+        if !data.data_device_manager.is_null() && !data.seat.is_null() {
+            data.data_device = constructor(
+                wl,
+                data.data_device_manager,
+                WL_DATA_DEVICE_MANAGER_GET_DATA_DEVICE,
+                wl.interfaces.wl_data_device,
+                &mut [NEW_ID, object(data.seat)],
+            );
+            add_dispatcher(data, data.data_device);
+        }
+
+        data.surface = constructor(wl, data.compositor, WL_COMPOSITOR_CREATE_SURFACE, wl.interfaces.wl_surface, &mut [
+            NEW_ID,
+        ]);
+        add_dispatcher(data, data.surface);
+
+        // The cursor theme is optional, as it's a separate library which might not be around
+        if let Some(theme) = wl.dl.wl_cursor_theme_load(
+            env::var("XCURSOR_THEME").ok().and_then(|s| CString::new(s).ok()).as_deref().map_or(ptr::null(), CStr::as_ptr),
+            env::var("XCURSOR_SIZE").ok().and_then(|s| s.parse().ok()).unwrap_or(24),
+            data.shm,
+        ) {
+            data.cursor_theme = theme;
+        }
+        data.cursor_surface =
+            constructor(wl, data.compositor, WL_COMPOSITOR_CREATE_SURFACE, wl.interfaces.wl_surface, &mut [NEW_ID]);
+
+        // Windows are only mapped once they have a role, have been configured, and have a buffer
+        if data.style.visible {
+            create_role(data);
+            let _ = wl.dl.wl_display_roundtrip(display);
+        }
+        let _ = wl.dl.wl_display_flush(display);
+
+        sender.send(Ok(WindowImpl {
+            display,
+            queue: Arc::clone(&queue2),
+            surface: data.surface,
+            thread: None, // filled in by caller
+            user: user_data.get(),
+        }));
+
+        // No longer needed, free memory
+        mem::drop(builder);
+        mem::drop(sender);
+
+        // Run message loop until the window is dropped
+        let mut fds = [
+            pollfd { fd: wl.dl.wl_display_get_fd(display).unwrap_or(-1), events: POLLIN, revents: 0 },
+            pollfd { fd: queue2.pipe_read, events: POLLIN, revents: 0 },
+        ];
+        'message_loop: loop {
+            // Process everything the owning thread has sent so far
+            let messages = queue2.take();
+            let count = messages.len();
+            for message in messages {
+                process_message(data, message);
+            }
+            queue2.mark_handled(count);
+            if data.destroy_flag {
+                break 'message_loop
+            }
+
+            #[cfg(feature = "input")]
+            let timeout = repeat_keys(data);
+            #[cfg(not(feature = "input"))]
+            let timeout = -1;
+
+            // Announce we're going to read, which fails if there's still events queued up
+            while wl.dl.wl_display_prepare_read(display).unwrap_or(0) != 0 {
+                let _ = wl.dl.wl_display_dispatch_pending(display);
+            }
+            let _ = wl.dl.wl_display_flush(display);
+
+            if poll(fds.as_mut_ptr(), fds.len() as c_ulong, timeout) < 0 && *__errno_location() != EINTR {
+                panic!("Hard error {} in poll loop!", *__errno_location());
+            }
+            if fds[0].revents != 0 {
+                let _ = wl.dl.wl_display_read_events(display);
+            } else {
+                let _ = wl.dl.wl_display_cancel_read(display);
+            }
+            if fds[1].revents != 0 {
+                queue2.drain_pipe();
+            }
+            let _ = wl.dl.wl_display_dispatch_pending(display);
+        }
+
+        destroy_window(data, display);
+    });
+
+    // Wait until the thread is done creating the window or notifying us why it couldn't do that
+    match util::create_wait(&recv) {
+        Ok(mut window) => {
+            window.thread = Some(thread);
+            Ok(window)
+        },
+        Err(err) => {
+            let _ = thread.join();
+            Err(err)
+        },
+    }
+}
+
+impl WindowImpl {
+    /// Handles a message synchronously, like `SendMessageW` on Win32.
+    fn send(&self, message: Message) {
+        // Sending a message from the window thread (ex. inside `execute`) would wait forever,
+        // so in that case it's handled right away, which is also what Win32 does.
+        if self.thread.as_ref().map(|t| t.thread().id()) == Some(thread::current().id()) {
+            unsafe { process_message(&mut *self.user, message) }
+        } else {
+            self.queue.send(message)
+        }
+    }
+
+    /// Handles a message asynchronously, like `PostMessageW` on Win32.
+    #[inline]
+    fn post(&self, message: Message) {
+        let _ = self.queue.post(message);
+    }
+
+    #[inline]
+    pub fn execute<F, T>(&self, f: F) -> T
+    where
+        F: Send + FnOnce() -> T,
+        T: Send,
+    {
+        let mut result = mem::MaybeUninit::<T>::uninit();
+
+        // SAFETY: `send` blocks until the window thread has responded.
+        let out_ptr = result.as_mut_ptr();
+        let mut f = Some(Box::new(move || unsafe {
+            *out_ptr = f();
+        }) as Box<dyn FnOnce() + '_>);
+
+        // The message can't carry a lifetime (just like an `LPARAM` can't), so it's erased here.
+        let f_ptr = unsafe {
+            mem::transmute::<*mut Option<Box<dyn FnOnce() + '_>>, *mut Option<Box<dyn FnOnce()>>>(&mut f)
+        };
+        self.send(Message::Execute(f_ptr));
+        unsafe { result.assume_init() }
+    }
+
+    pub fn events(&self) -> &[Event] {
+        // SAFETY: The event buffer isn't swapped until `swap_events` is called (takes &mut self)
+        unsafe { (&*self.user).event_queue.backbuffer() }
+    }
+
+    #[inline]
+    pub fn events_text(&self) -> &str {
+        // SAFETY: Same as `events`
+        unsafe { (&*self.user).event_queue.backbuffer_text() }
+    }
+
+    #[inline]
+    pub fn events_paths(&self) -> &[PathBuf] {
+        // SAFETY: Same as `events`
+        unsafe { (&*self.user).event_queue.backbuffer_paths() }
+    }
+
+    #[inline]
+    pub fn clipboard_data(&self, format: &ClipboardFormat) -> Option<Vec<u8>> {
+        let mut bytes = None;
+        self.send(Message::GetClipboard(format, &mut bytes));
+        bytes
+    }
+
+    #[inline]
+    pub fn clipboard_formats(&self) -> Vec<ClipboardFormat> {
+        let mut formats = Vec::new();
+        self.send(Message::GetClipboardFormats(&mut formats));
+        formats
+    }
+
+    pub unsafe fn create_gl_context(&self, builder: &GlContextBuilder) -> Result<GlContextImpl, Error> {
+        let egl = EGL.get().as_ref().ok_or(Error::Unsupported("OpenGL"))?;
+        let (size, scale) = self.inner_size();
+        let context = GlContextImpl::new(egl, self.display, self.surface, size.as_physical(scale), builder)?;
+        self.send(Message::SetExternalSurface);
+        Ok(context)
+    }
+
+    pub unsafe fn create_vulkan_surface(
+        &self,
+        instance: VkInstance,
+        get_instance_proc_addr: Option<PFN_vkGetInstanceProcAddr>,
+    ) -> Result<VkSurfaceKHR, Error> {
+        let create_surface = mem::transmute::<PFN_vkVoidFunction, PFN_vkCreateWaylandSurfaceKHR>(
+            unix::vulkan_instance_proc_addr(instance, get_instance_proc_addr, c_string!("vkCreateWaylandSurfaceKHR"))
+                .ok_or(Error::Unsupported("VK_KHR_wayland_surface"))?,
+        );
+        let create_info = VkWaylandSurfaceCreateInfoKHR {
+            sType: VK_STRUCTURE_TYPE_WAYLAND_SURFACE_CREATE_INFO_KHR,
+            pNext: ptr::null(),
+            flags: 0,
+            display: self.display,
+            surface: self.surface,
+        };
+        let mut surface = 0;
+        match create_surface(instance, &create_info, ptr::null(), &mut surface) {
+            vulkan::VK_SUCCESS => {
+                self.send(Message::SetExternalSurface);
+                Ok(surface)
+            },
+            result => Err(Error::SurfaceCreation(result)),
+        }
+    }
+
+    #[inline]
+    pub fn current_monitor(&self) -> Option<Monitor> {
+        let mut monitor = None;
+        self.send(Message::GetCurrentMonitor(&mut monitor));
+        monitor
+    }
+
+    #[inline]
+    pub fn inner_position(&self) -> (Point, Scale) {
+        let mut position = mem::MaybeUninit::<Point>::uninit();
+        let mut scale = mem::MaybeUninit::<Scale>::uninit();
+        self.send(Message::GetPosition(true, position.as_mut_ptr(), scale.as_mut_ptr()));
+        unsafe { (position.assume_init(), scale.assume_init()) }
+    }
+
+    #[inline]
+    pub fn inner_size(&self) -> (Size, Scale) {
+        let mut size = mem::MaybeUninit::<Size>::uninit();
+        let mut scale = mem::MaybeUninit::<Scale>::uninit();
+        self.send(Message::GetInnerSize(size.as_mut_ptr(), scale.as_mut_ptr()));
+        unsafe { (size.assume_init(), scale.assume_init()) }
+    }
+
+    #[inline]
+    pub fn is_dpi_logical(&self) -> bool {
+        let mut logical = false;
+        self.send(Message::IsDpiLogical(&mut logical));
+        logical
+    }
+
+    #[inline]
+    pub fn outer_position(&self) -> (Point, Scale) {
+        let mut position = mem::MaybeUninit::<Point>::uninit();
+        let mut scale = mem::MaybeUninit::<Scale>::uninit();
+        self.send(Message::GetPosition(false, position.as_mut_ptr(), scale.as_mut_ptr()));
+        unsafe { (position.assume_init(), scale.assume_init()) }
+    }
+
+    #[inline]
+    pub fn present_pixels(&self, pixels: &[u32], size: (u32, u32), damage: &[Rect]) -> Result<(), Error> {
+        let mut result = Ok(());
+        self.send(Message::PresentPixels(pixels, size, damage, &mut result));
+        result
+    }
+
+    #[cfg(feature = "raw-window-handle")]
+    #[inline]
+    pub fn raw_display_handle(&self) -> Result<RawDisplayHandle, HandleError> {
+        let display = ptr::NonNull::new(self.display.cast()).ok_or(HandleError::Unavailable)?;
+        Ok(WaylandDisplayHandle::new(display).into())
+    }
+
+    #[cfg(feature = "raw-window-handle")]
+    #[inline]
+    pub fn raw_window_handle(&self) -> Result<RawWindowHandle, HandleError> {
+        let surface = ptr::NonNull::new(self.surface.cast()).ok_or(HandleError::Unavailable)?;
+        Ok(WaylandWindowHandle::new(surface).into())
+    }
+
+    #[inline]
+    pub fn set_borderless(&self, borderless: bool) {
+        self.send(Message::SetBorderless(borderless));
+    }
+
+    #[inline]
+    pub fn set_borderless_async(&self, borderless: bool) {
+        self.post(Message::SetBorderless(borderless));
+    }
+
+    #[inline]
+    pub fn set_clipboard(&self, contents: &ClipboardContents) -> bool {
+        let mut success = false;
+        self.send(Message::SetClipboard(contents, &mut success));
+        success
+    }
+
+    #[inline]
+    pub fn set_controls(&self, controls: Option<window::Controls>) {
+        self.send(Message::SetControls(controls));
+    }
+
+    #[inline]
+    pub fn set_controls_async(&self, controls: Option<window::Controls>) {
+        self.post(Message::SetControls(controls));
+    }
+
+    #[inline]
+    pub fn set_cursor(&self, cursor: CursorIcon) {
+        self.send(Message::SetCursor(cursor));
+    }
+
+    #[inline]
+    pub fn set_cursor_async(&self, cursor: CursorIcon) {
+        self.post(Message::SetCursor(cursor));
+    }
+
+    #[inline]
+    pub fn set_cursor_grab(&self, grab: CursorGrab) {
+        self.send(Message::SetCursorGrab(grab));
+    }
+
+    #[inline]
+    pub fn set_cursor_grab_async(&self, grab: CursorGrab) {
+        self.post(Message::SetCursorGrab(grab));
+    }
+
+    #[inline]
+    pub fn set_cursor_position(&self, position: Point) {
+        self.send(Message::SetCursorPosition(position));
+    }
+
+    #[inline]
+    pub fn set_cursor_position_async(&self, position: Point) {
+        self.post(Message::SetCursorPosition(position));
+    }
+
+    #[inline]
+    pub fn set_fullscreen(&self, fullscreen: Option<window::Fullscreen>) {
+        self.send(Message::SetFullscreen(fullscreen));
+    }
+
+    #[inline]
+    pub fn set_fullscreen_async(&self, fullscreen: Option<window::Fullscreen>) {
+        self.post(Message::SetFullscreen(fullscreen));
+    }
+
+    #[inline]
+    pub fn set_icon(&self, icon: Option<Icon>) {
+        self.send(Message::SetIcon(icon));
+    }
+
+    #[inline]
+    pub fn set_icon_async(&self, icon: Option<Icon>) {
+        self.post(Message::SetIcon(icon));
+    }
+
+    #[cfg(feature = "input")]
+    #[inline]
+    pub fn set_ime_allowed(&self, allowed: bool) {
+        self.send(Message::SetImeAllowed(allowed));
+    }
+
+    #[cfg(feature = "input")]
+    #[inline]
+    pub fn set_ime_allowed_async(&self, allowed: bool) {
+        self.post(Message::SetImeAllowed(allowed));
+    }
+
+    #[cfg(feature = "input")]
+    #[inline]
+    pub fn set_ime_cursor_area(&self, position: Point, size: Size) {
+        self.send(Message::SetImeCursorArea(position, size));
+    }
+
+    #[cfg(feature = "input")]
+    #[inline]
+    pub fn set_ime_cursor_area_async(&self, position: Point, size: Size) {
+        self.post(Message::SetImeCursorArea(position, size));
+    }
+
+    #[inline]
+    pub fn set_inner_size(&self, size: Size) {
+        self.send(Message::SetInnerSize(size));
+    }
+
+    #[inline]
+    pub fn set_maximized(&self, maximized: bool) {
+        self.send(Message::SetMaximized(maximized));
+    }
+
+    #[inline]
+    pub fn set_maximized_async(&self, maximized: bool) {
+        self.post(Message::SetMaximized(maximized));
+    }
+
+    #[inline]
+    pub fn set_position(&self, position: Point) {
+        self.send(Message::SetPosition(position));
+    }
+
+    #[inline]
+    pub fn set_position_async(&self, position: Point) {
+        self.post(Message::SetPosition(position));
+    }
+
+    #[cfg(feature = "input")]
+    #[inline]
+    pub fn set_raw_mouse_motion(&self, enabled: bool) {
+        self.send(Message::SetRawMouseMotion(enabled));
+    }
+
+    #[cfg(feature = "input")]
+    #[inline]
+    pub fn set_raw_mouse_motion_async(&self, enabled: bool) {
+        self.post(Message::SetRawMouseMotion(enabled));
+    }
+
+    #[inline]
+    pub fn set_resizable(&self, resizable: bool) {
+        self.send(Message::SetResizable(resizable));
+    }
+
+    #[inline]
+    pub fn set_resizable_async(&self, resizable: bool) {
+        self.post(Message::SetResizable(resizable));
+    }
+
+    #[cfg(feature = "input")]
+    #[inline]
+    pub fn set_text_input(&self, enabled: bool) {
+        self.send(Message::SetTextInput(enabled));
+    }
+
+    #[cfg(feature = "input")]
+    #[inline]
+    pub fn set_text_input_async(&self, enabled: bool) {
+        self.post(Message::SetTextInput(enabled));
+    }
+
+    #[inline]
+    pub fn set_title(&self, title: &str) {
+        self.send(Message::SetTitle(title.to_owned()));
+    }
+
+    #[inline]
+    pub fn set_title_async(&self, title: &str) {
+        self.post(Message::SetTitle(title.to_owned()));
+    }
+
+    #[inline]
+    pub fn set_visible(&self, visible: bool) {
+        self.send(Message::SetVisible(visible));
+    }
+
+    #[inline]
+    pub fn set_visible_async(&self, visible: bool) {
+        self.post(Message::SetVisible(visible));
+    }
+
+    pub fn swap_events(&mut self) {
+        // SAFETY: `&mut self` guarantees no slices from `events` are alive
+        unsafe { (&*self.user).event_queue.swap() }
+    }
+}
+
+impl WindowImplData {
+    #[inline]
+    pub fn push_event(&mut self, event: Event) {
+        self.push_events(&[event]);
+    }
+
+    #[inline]
+    pub fn push_events(&mut self, events: &[Event]) {
+        self.event_queue.push_many(events);
+    }
+
+    #[inline]
+    #[cfg(feature = "input")]
+    pub fn push_event_with_text(&mut self, text: &str, f: impl FnOnce(EventText) -> Event) {
+        self.event_queue.push_with_text(text, |offset, len| f(EventText { offset, len }));
+    }
+
+    #[inline]
+    pub fn push_event_with_paths(&mut self, paths: &[PathBuf], f: impl FnOnce(EventPaths) -> Event) {
+        self.event_queue.push_with_paths(paths, |offset, len| f(EventPaths { offset, len }));
+    }
+
+    #[inline]
+    fn scale_size(&self, size: Size) -> Size {
+        if self.is_dpi_logical {
+            size.to_logical(self.buffer_scale as Scale)
+        } else {
+            size
+        }
+    }
+}
+
+impl ops::Drop for WindowImpl {
+    fn drop(&mut self) {
+        // Signal the window it's OK to close, and wait for the thread to naturally return
+        self.post(Message::Destroy);
+        let _ = self.thread.take().map(thread::JoinHandle::join);
+    }
+}
+
+/// Implementation container for `gl::GlContext`.
+///
+/// EGL reads events on a queue of its own, so it shares the connection with the window thread just fine.
+/// A `wl_surface` can only have one `wl_egl_window`, so this is also the only context of its window,
+/// which is why it owns (and terminates) the `EGLDisplay` too.
+pub struct GlContextImpl {
+    egl: &'static EglDL,
+    display: EGLDisplay,
+    egl_window: *mut wl_egl_window,
+    surface: EGLSurface,
+    context: EGLContext,
+}
+
+// Contexts can move between threads, as long as they're not current on any
+unsafe impl Send for GlContextImpl {}
+
+impl GlContextImpl {
+    unsafe fn new(
+        egl: &'static EglDL,
+        wl_display: *mut wl_display,
+        wl_surface: *mut wl_proxy,
+        (width, height): (u32, u32),
+        builder: &GlContextBuilder,
+    ) -> Result<Self, Error> {
+        let display = egl.eglGetDisplay(wl_display).unwrap_or(ptr::null_mut());
+        let (mut major, mut minor) = (0, 0);
+        if display.is_null() || egl.eglInitialize(display, &mut major, &mut minor) != Some(EGL_TRUE) {
+            return Err(Error::Unsupported("OpenGL"))
+        }
+        let mut context = Self {
+            egl,
+            display,
+            egl_window: ptr::null_mut(),
+            surface: ptr::null_mut(),
+            context: ptr::null_mut(),
+        };
+        let extensions = match egl.eglQueryString(display, EGL_EXTENSIONS) {
+            Some(extensions) if !extensions.is_null() => CStr::from_ptr(extensions).to_bytes(),
+            _ => &[],
+        };
+        let _ = egl.eglBindAPI(EGL_OPENGL_API);
+        let config = choose_egl_config(egl, display, builder)?;
+        let error = |egl: &EglDL| Error::ContextCreation(egl.eglGetError().unwrap_or(0) as u32);
+
+        // Versions & such came with EGL 1.5, where they're the same as the extension
+        let mut attributes = Vec::new();
+        if (major, minor) >= (1, 5) || gl::has_extension(extensions, "EGL_KHR_create_context") {
+            let profile = match builder.profile {
+                GlProfile::Core => EGL_CONTEXT_OPENGL_CORE_PROFILE_BIT_KHR,
+                GlProfile::Compatibility => EGL_CONTEXT_OPENGL_COMPATIBILITY_PROFILE_BIT_KHR,
+            };
+            let flags = if builder.debug { EGL_CONTEXT_OPENGL_DEBUG_BIT_KHR } else { 0 };
+            attributes.extend_from_slice(&[
+                EGL_CONTEXT_MAJOR_VERSION_KHR, builder.version.0.into(),
+                EGL_CONTEXT_MINOR_VERSION_KHR, builder.version.1.into(),
+                EGL_CONTEXT_OPENGL_PROFILE_MASK_KHR, profile,
+                EGL_CONTEXT_FLAGS_KHR, flags,
+            ]);
+        } else if builder.version >= (3, 0) || builder.debug {
+            return Err(Error::Unsupported("EGL_KHR_create_context"))
+        }
+        attributes.push(EGL_NONE);
+        context.context = egl.eglCreateContext(display, config, ptr::null_mut(), attributes.as_ptr()).unwrap_or(ptr::null_mut());
+        if context.context.is_null() {
+            return Err(error(egl))
+        }
+
+        let mut attributes = Vec::new();
+        if builder.srgb {
+            if !gl::has_extension(extensions, "EGL_KHR_gl_colorspace") {
+                return Err(Error::Unsupported("EGL_KHR_gl_colorspace"))
+            }
+            attributes.extend_from_slice(&[EGL_GL_COLORSPACE_KHR, EGL_GL_COLORSPACE_SRGB_KHR]);
+        }
+        attributes.push(EGL_NONE);
+        context.egl_window =
+            egl.wl_egl_window_create(wl_surface, width.max(1) as c_int, height.max(1) as c_int).unwrap_or(ptr::null_mut());
+        if context.egl_window.is_null() {
+            return Err(Error::ContextCreation(0))
+        }
+        context.surface =
+            egl.eglCreateWindowSurface(display, config, context.egl_window, attributes.as_ptr()).unwrap_or(ptr::null_mut());
+        if context.surface.is_null() {
+            return Err(error(egl))
+        }
+        Ok(context)
+    }
+
+    #[inline]
+    pub fn make_current(&self) -> bool {
+        unsafe {
+            // The API is per thread, not per context
+            let _ = self.egl.eglBindAPI(EGL_OPENGL_API);
+            self.egl.eglMakeCurrent(self.display, self.surface, self.surface, self.context) == Some(EGL_TRUE)
+        }
+    }
+
+    #[inline]
+    pub fn make_not_current(&self) -> bool {
+        let (no_surface, no_context) = (ptr::null_mut(), ptr::null_mut());
+        self.is_current()
+            && unsafe { self.egl.eglMakeCurrent(self.display, no_surface, no_surface, no_context) == Some(EGL_TRUE) }
+    }
+
+    #[inline]
+    pub fn is_current(&self) -> bool {
+        unsafe { self.egl.eglGetCurrentContext() == Some(self.context) }
+    }
+
+    #[inline]
+    pub fn swap_buffers(&self) -> bool {
+        unsafe { self.egl.eglSwapBuffers(self.display, self.surface) == Some(EGL_TRUE) }
+    }
+
+    #[inline]
+    pub fn set_swap_interval(&self, interval: i32) -> bool {
+        // EGL clamps the interval itself, and there's no late swap tearing on Wayland
+        unsafe { self.egl.eglSwapInterval(self.display, interval) == Some(EGL_TRUE) }
+    }
+
+    #[inline]
+    pub fn get_proc_address(&self, name: &CStr) -> *const c_void {
+        unsafe { self.egl.eglGetProcAddress(name.as_ptr()).unwrap_or(ptr::null_mut()) }
+    }
+
+    #[inline]
+    pub fn resize(&self, width: u32, height: u32) {
+        unsafe {
+            let _ = self.egl.wl_egl_window_resize(self.egl_window, width.max(1) as c_int, height.max(1) as c_int, 0, 0);
+        }
+    }
+}
+
+impl ops::Drop for GlContextImpl {
+    fn drop(&mut self) {
+        unsafe {
+            let egl = self.egl;
+            let _ = self.make_not_current();
+            if !self.surface.is_null() {
+                let _ = egl.eglDestroySurface(self.display, self.surface);
+            }
+            if !self.egl_window.is_null() {
+                let _ = egl.wl_egl_window_destroy(self.egl_window);
+            }
+            if !self.context.is_null() {
+                let _ = egl.eglDestroyContext(self.display, self.context);
+            }
+            let _ = egl.eglTerminate(self.display);
+        }
+    }
+}
+
+/// Picks the first config that fits, preferring ones without alpha, as that'd make the window see-through.
+unsafe fn choose_egl_config(egl: &EglDL, display: EGLDisplay, builder: &GlContextBuilder) -> Result<EGLConfig, Error> {
+    let mut attributes = vec![
+        EGL_SURFACE_TYPE, EGL_WINDOW_BIT,
+        EGL_RENDERABLE_TYPE, EGL_OPENGL_BIT,
+        EGL_RED_SIZE, 8,
+        EGL_GREEN_SIZE, 8,
+        EGL_BLUE_SIZE, 8,
+        EGL_DEPTH_SIZE, builder.depth_bits.into(),
+        EGL_STENCIL_SIZE, builder.stencil_bits.into(),
+    ];
+    if builder.samples > 0 {
+        attributes.extend_from_slice(&[EGL_SAMPLE_BUFFERS, 1, EGL_SAMPLES, builder.samples.into()]);
+    }
+    attributes.push(EGL_NONE);
+    let mut configs = [ptr::null_mut(); 64];
+    let mut count = 0;
+    let _ = egl.eglChooseConfig(display, attributes.as_ptr(), configs.as_mut_ptr(), configs.len() as EGLint, &mut count);
+    let configs = &configs[..count.clamp(0, configs.len() as EGLint) as usize];
+    let opaque = configs.iter().copied().find(|&config| {
+        let mut alpha = 0;
+        egl.eglGetConfigAttrib(display, config, EGL_ALPHA_SIZE, &mut alpha) == Some(EGL_TRUE) && alpha == 0
+    });
+    opaque.or_else(|| configs.first().copied()).ok_or(Error::Unsupported("pixel format"))
+}
+
+// Arguments for marshalling requests
+const NEW_ID: wl_argument = wl_argument { n: 0 };
+#[inline]
+fn int(i: i32) -> wl_argument {
+    wl_argument { i }
+}
+#[inline]
+fn uint(u: u32) -> wl_argument {
+    wl_argument { u }
+}
+#[inline]
+fn object(o: *mut wl_proxy) -> wl_argument {
+    wl_argument { o }
+}
+#[inline]
+fn string(s: *const c_char) -> wl_argument {
+    wl_argument { s }
+}
+#[inline]
+fn fixed(f: f64) -> wl_argument {
+    wl_argument { f: (f * 256.0) as wl_fixed_t }
+}
+#[inline]
+fn fd(h: c_int) -> wl_argument {
+    wl_argument { h }
+}
+
+/// Sends a request to a protocol object.
+unsafe fn request(wl: &WaylandState, proxy: *mut wl_proxy, opcode: u32, args: &mut [wl_argument]) {
+    let version = wl.dl.wl_proxy_get_version(proxy).unwrap_or(0);
+    let _ = wl.dl.wl_proxy_marshal_array_flags(proxy, opcode, ptr::null(), version, 0, args.as_mut_ptr());
+}
+
+/// Sends a request which creates a protocol object, which has the same version as its parent.
+unsafe fn constructor(
+    wl: &WaylandState,
+    proxy: *mut wl_proxy,
+    opcode: u32,
+    interface: &wl_interface,
+    args: &mut [wl_argument],
+) -> *mut wl_proxy {
+    let version = wl.dl.wl_proxy_get_version(proxy).unwrap_or(0);
+    wl.dl
+        .wl_proxy_marshal_array_flags(proxy, opcode, interface, version, 0, args.as_mut_ptr())
+        .unwrap_or(ptr::null_mut())
+}
+
+/// Sends a destructor request, which also destroys the proxy.
+unsafe fn destructor(wl: &WaylandState, proxy: *mut wl_proxy, opcode: u32) {
+    let version = wl.dl.wl_proxy_get_version(proxy).unwrap_or(0);
+    let _ = wl.dl.wl_proxy_marshal_array_flags(
+        proxy,
+        opcode,
+        ptr::null(),
+        version,
+        WL_MARSHAL_FLAG_DESTROY,
+        [NEW_ID].as_mut_ptr(), // unused, there's no arguments
+    );
+}
+
+/// Binds a global from the window's registry.
+unsafe fn bind(data: &WindowImplData, name: u32, interface: &wl_interface, version: u32) -> *mut wl_proxy {
+    bind_global(data.wl, data.registry, name, interface, version)
+}
+
+/// Binds a global from a registry.
+unsafe fn bind_global(
+    wl: &WaylandState,
+    registry: *mut wl_proxy,
+    name: u32,
+    interface: &wl_interface,
+    version: u32,
+) -> *mut wl_proxy {
+    wl.dl
+        .wl_proxy_marshal_array_flags(registry, WL_REGISTRY_BIND, interface, version, 0, [
+            uint(name),
+            string(interface.name),
+            uint(version),
+            NEW_ID,
+        ].as_mut_ptr())
+        .unwrap_or(ptr::null_mut())
+}
+
+/// Picks the `wl_output` version to bind, as the name (version 4) is nice to have.
+///
+/// Binding a newer version than libwayland knows of would have it choke on the new events.
+unsafe fn output_version(wl: &WaylandState, advertised: u32) -> u32 {
+    advertised.min(4).min(wl.interfaces.wl_output.version.max(1) as u32)
+}
+
+/// Routes the events of a protocol object to `dispatch`.
+unsafe fn add_dispatcher(data: &mut WindowImplData, proxy: *mut wl_proxy) {
+    let data_ptr = data as *mut WindowImplData;
+    let _ = data.wl.dl.wl_proxy_add_dispatcher(proxy, dispatch, data_ptr.cast(), ptr::null_mut());
+}
+
+/// Creates the `xdg_toplevel` role for the surface, which is how it gets shown.
+unsafe fn create_role(data: &mut WindowImplData) {
+    let wl = data.wl;
+    data.xdg_surface = constructor(wl, data.wm_base, XDG_WM_BASE_GET_XDG_SURFACE, &xdg_surface_interface, &mut [
+        NEW_ID,
+        object(data.surface),
+    ]);
+    add_dispatcher(data, data.xdg_surface);
+    data.xdg_toplevel = constructor(wl, data.xdg_surface, XDG_SURFACE_GET_TOPLEVEL, &xdg_toplevel_interface, &mut [
+        NEW_ID,
+    ]);
+    add_dispatcher(data, data.xdg_toplevel);
+    if !data.decoration_manager.is_null() {
+        data.decoration = constructor(
+            wl,
+            data.decoration_manager,
+            ZXDG_DECORATION_MANAGER_V1_GET_TOPLEVEL_DECORATION,
+            &zxdg_toplevel_decoration_v1_interface,
+            &mut [NEW_ID, object(data.xdg_toplevel)],
+        );
+    }
+    request(wl, data.xdg_toplevel, XDG_TOPLEVEL_SET_TITLE, &mut [string(data.title.as_ptr())]);
+    request(wl, data.xdg_toplevel, XDG_TOPLEVEL_SET_APP_ID, &mut [string(data.class_name.as_ptr())]);
+    if data.fullscreen.is_some() {
+        let output = fullscreen_output(data);
+        request(wl, data.xdg_toplevel, XDG_TOPLEVEL_SET_FULLSCREEN, &mut [object(output)]);
+    }
+
+    // This commits the surface with no buffer, which asks the compositor for the first configure
+    data.is_configured = false;
+    update_window_style(data);
+}
+
+/// Finds the `wl_output` of the monitor that fullscreen was asked for on, or null to let the compositor pick.
+///
+/// Outputs are bound by every connection on its own, so the monitor is matched by its name & position.
+fn fullscreen_output(data: &WindowImplData) -> *mut wl_proxy {
+    let monitor = match data.fullscreen.as_ref().and_then(window::Fullscreen::monitor) {
+        Some(monitor) => monitor,
+        None => return ptr::null_mut(),
+    };
+    data
+        .outputs
+        .iter()
+        .find(|output| {
+            let other = output.monitor();
+            other.name == monitor.name && (other.rect.x, other.rect.y) == (monitor.rect.x, monitor.rect.y)
+        })
+        .map(|output| output.proxy)
+        .unwrap_or(ptr::null_mut())
+}
+
+/// Destroys the role of the surface, which unmaps it.
+unsafe fn destroy_role(data: &mut WindowImplData) {
+    let wl = data.wl;
+    if data.xdg_toplevel.is_null() {
+        return
+    }
+    if !data.decoration.is_null() {
+        destructor(wl, data.decoration, ZXDG_TOPLEVEL_DECORATION_V1_DESTROY);
+        data.decoration = ptr::null_mut();
+    }
+    destructor(wl, data.xdg_toplevel, XDG_TOPLEVEL_DESTROY);
+    destructor(wl, data.xdg_surface, XDG_SURFACE_DESTROY);
+    data.xdg_toplevel = ptr::null_mut();
+    data.xdg_surface = ptr::null_mut();
+    data.is_configured = false;
+
+    // The surface needs to be without a buffer for it to get a role again
+    request(wl, data.surface, WL_SURFACE_ATTACH, &mut [object(ptr::null_mut()), int(0), int(0)]);
+    request(wl, data.surface, WL_SURFACE_COMMIT, &mut []);
+}
+
+/// Destroys everything the window thread created, in reverse order, and disconnects.
+unsafe fn destroy_window(data: &mut WindowImplData, display: *mut wl_display) {
+    let wl = data.wl;
+    destroy_role(data);
+    for proxy in [data.buffer, data.cursor_buffer, data.cursor_surface, data.surface] {
+        if !proxy.is_null() {
+            let is_buffer = proxy == data.buffer || proxy == data.cursor_buffer;
+            destructor(wl, proxy, if is_buffer { WL_BUFFER_DESTROY } else { WL_SURFACE_DESTROY });
+        }
+    }
+    if !data.cursor_theme.is_null() {
+        let _ = wl.dl.wl_cursor_theme_destroy(data.cursor_theme);
+    }
+    #[cfg(feature = "input")]
+    {
+        if !data.text_input_v3.is_null() {
+            destructor(wl, data.text_input_v3, ZWP_TEXT_INPUT_V3_DESTROY);
+        }
+        if !data.text_input_manager.is_null() {
+            destructor(wl, data.text_input_manager, ZWP_TEXT_INPUT_MANAGER_V3_DESTROY);
+        }
+    }
+    if let Some(drag) = data.drag.take() {
+        destroy_data_offer(data, drag.offer);
+    }
+    for (offer, _) in mem::take(&mut data.data_offers) {
+        destructor(wl, offer, WL_DATA_OFFER_DESTROY);
+    }
+    if !data.clipboard_source.is_null() {
+        destructor(wl, data.clipboard_source, WL_DATA_SOURCE_DESTROY);
+    }
+    if !data.data_device.is_null() {
+        // `release` is new in version 2, before that there was only the client-side destroy
+        if wl.dl.wl_proxy_get_version(data.data_device).unwrap_or(0) >= 2 {
+            destructor(wl, data.data_device, WL_DATA_DEVICE_RELEASE);
+        } else {
+            let _ = wl.dl.wl_proxy_destroy(data.data_device);
+        }
+    }
+    release_input_devices(data, 0);
+    if !data.pointer_constraints.is_null() {
+        destructor(wl, data.pointer_constraints, ZWP_POINTER_CONSTRAINTS_V1_DESTROY);
+    }
+    #[cfg(feature = "input")]
+    if !data.relative_pointer_manager.is_null() {
+        destructor(wl, data.relative_pointer_manager, ZWP_RELATIVE_POINTER_MANAGER_V1_DESTROY);
+    }
+    for output in data.outputs.drain(..) {
+        let _ = wl.dl.wl_proxy_destroy(output.proxy);
+    }
+    if !data.decoration_manager.is_null() {
+        destructor(wl, data.decoration_manager, ZXDG_DECORATION_MANAGER_V1_DESTROY);
+    }
+    if !data.wm_base.is_null() {
+        destructor(wl, data.wm_base, XDG_WM_BASE_DESTROY);
+    }
+    for proxy in [data.data_device_manager, data.seat, data.shm, data.compositor, data.registry] {
+        if !proxy.is_null() {
+            let _ = wl.dl.wl_proxy_destroy(proxy);
+        }
+    }
+    let _ = wl.dl.wl_display_flush(display);
+    let _ = wl.dl.wl_display_disconnect(display);
+}
+
+/// Convenience function to take a `window::Style` and apply it to the toplevel.
+///
+/// There's no notion of individual window buttons in xdg-shell, so window controls map onto
+/// the decoration mode: server-side decorations (title bar and all) are only asked for if
+/// the window has controls and isn't borderless.
+unsafe fn update_window_style(data: &mut WindowImplData) {
+    let wl = data.wl;
+    if data.xdg_toplevel.is_null() {
+        return
+    }
+    if !data.decoration.is_null() {
+        let mode = if !data.style.borderless && data.style.controls.is_some() {
+            ZXDG_TOPLEVEL_DECORATION_V1_MODE_SERVER_SIDE
+        } else {
+            ZXDG_TOPLEVEL_DECORATION_V1_MODE_CLIENT_SIDE
+        };
+        request(wl, data.decoration, ZXDG_TOPLEVEL_DECORATION_V1_SET_MODE, &mut [uint(mode)]);
+    }
+
+    // Non-resizable windows have their minimum and maximum size set to the current size
+    let (width, height) = if data.style.resizable { (0, 0) } else { data.surface_size };
+    request(wl, data.xdg_toplevel, XDG_TOPLEVEL_SET_MIN_SIZE, &mut [int(width), int(height)]);
+    request(wl, data.xdg_toplevel, XDG_TOPLEVEL_SET_MAX_SIZE, &mut [int(width), int(height)]);
+
+    // Min & max size are double-buffered state, and only apply on commit
+    request(wl, data.surface, WL_SURFACE_COMMIT, &mut []);
+}
+
+/// Creates a buffer in shared memory with a `wl_shm` format, which is always little-endian.
+/// Without pixels it's blank, which is all black (zeroed memory) in XRGB.
+unsafe fn create_buffer(
+    data: &WindowImplData,
+    width: u32,
+    height: u32,
+    pixels: Option<&[u32]>,
+    format: u32,
+) -> *mut wl_proxy {
+    let wl = data.wl;
+    let stride = width * 4;
+    let size = stride as usize * height as usize;
+    let fd = memfd_create(c_string!("ramen-buffer").as_ptr().cast(), MFD_CLOEXEC);
+    if fd < 0 {
+        return ptr::null_mut()
+    }
+    if ftruncate(fd, size as off_t) != 0 {
+        let _ = close(fd);
+        return ptr::null_mut()
+    }
+    if let Some(pixels) = pixels {
+        let map = mmap(ptr::null_mut(), size, PROT_READ | PROT_WRITE, MAP_SHARED, fd, 0);
+        if map == MAP_FAILED {
+            let _ = close(fd);
+            return ptr::null_mut()
+        }
+        ptr::copy_nonoverlapping(pixels.as_ptr(), map.cast(), size / 4);
+        let _ = munmap(map, size);
+    }
+    let pool = constructor(wl, data.shm, WL_SHM_CREATE_POOL, wl.interfaces.wl_shm_pool, &mut [
+        NEW_ID,
+        wl_argument { h: fd },
+        int(size as i32),
+    ]);
+    let buffer = constructor(wl, pool, WL_SHM_POOL_CREATE_BUFFER, wl.interfaces.wl_buffer, &mut [
+        NEW_ID,
+        int(0),
+        int(width as i32),
+        int(height as i32),
+        int(stride as i32),
+        uint(format),
+    ]);
+    destructor(wl, pool, WL_SHM_POOL_DESTROY);
+    let _ = close(fd);
+    buffer
+}
+
+/// Brings the client area size in line with the surface size & scale, then redraws.
+unsafe fn update_size(data: &mut WindowImplData) {
+    let wl = data.wl;
+    let scale = data.buffer_scale;
+    let (width, height) = data.surface_size;
+    let size = ((width * scale) as u32, (height * scale) as u32);
+    if data.client_area_size != size {
+        data.client_area_size = size;
+        let event = Event::Resize((data.scale_size(Size::Physical(size.0, size.1)), scale as Scale));
+        data.push_event(event);
+    }
+
+    // Attaching a buffer before the first configure is a protocol error
+    if !data.is_configured {
+        return
+    }
+    if data.has_external_surface {
+        request(wl, data.surface, WL_SURFACE_COMMIT, &mut []);
+        return
+    }
+    if data.buffer_size != size || data.buffer.is_null() {
+        if !data.buffer.is_null() {
+            destructor(wl, data.buffer, WL_BUFFER_DESTROY);
+        }
+        data.buffer = create_buffer(data, size.0, size.1, None, WL_SHM_FORMAT_XRGB8888);
+        data.buffer_size = size;
+    }
+    request(wl, data.surface, WL_SURFACE_ATTACH, &mut [object(data.buffer), int(0), int(0)]);
+    request(wl, data.surface, WL_SURFACE_DAMAGE, &mut [int(0), int(0), int(i32::MAX), int(i32::MAX)]);
+    request(wl, data.surface, WL_SURFACE_COMMIT, &mut []);
+}
+
+/// Copies an image into a new buffer, stretched to the client area, which replaces the blank one.
+///
+/// The buffer stays attached until the size changes, as the compositor keeps it around for redrawing.
+unsafe fn present_pixels(data: &mut WindowImplData, pixels: &[u32], size: (u32, u32), damage: &[Rect]) -> Result<(), Error> {
+    let wl = data.wl;
+    let client_size = data.client_area_size;
+    if !data.is_configured || client_size.0 == 0 || client_size.1 == 0 {
+        return Ok(())
+    }
+
+    // Buffers can't be written to while the compositor holds them, so every frame gets a new one
+    let stretched;
+    let pixels = if size == client_size {
+        pixels
+    } else {
+        let mut buffer = vec![0; client_size.0 as usize * client_size.1 as usize];
+        let whole = Rect { x: 0, y: 0, width: client_size.0, height: client_size.1 };
+        util::stretch_pixels(pixels, size, &mut buffer, client_size, whole);
+        stretched = buffer;
+        &stretched[..]
+    };
+    let buffer = create_buffer(data, client_size.0, client_size.1, Some(pixels), WL_SHM_FORMAT_XRGB8888);
+    if buffer.is_null() {
+        return Err(Error::Unavailable("failed to create a shared memory buffer"))
+    }
+    if !data.buffer.is_null() {
+        destructor(wl, data.buffer, WL_BUFFER_DESTROY);
+    }
+    data.buffer = buffer;
+    data.buffer_size = client_size;
+    request(wl, data.surface, WL_SURFACE_ATTACH, &mut [object(buffer), int(0), int(0)]);
+
+    // `wl_surface.damage` is in surface coordinates, so the buffer scale is divided out (rounding outwards)
+    let scale = data.buffer_scale;
+    for &rect in damage {
+        let area = util::scale_rect(rect, size, client_size);
+        let (left, top) = (area.x / scale, area.y / scale);
+        let right = (area.x + area.width as i32 + scale - 1) / scale;
+        let bottom = (area.y + area.height as i32 + scale - 1) / scale;
+        request(wl, data.surface, WL_SURFACE_DAMAGE, &mut [int(left), int(top), int(right - left), int(bottom - top)]);
+    }
+    request(wl, data.surface, WL_SURFACE_COMMIT, &mut []);
+    Ok(())
+}
+
+/// Recalculates the buffer scale after the window moved between outputs, or an output changed.
+unsafe fn update_scale(data: &mut WindowImplData) {
+    let wl = data.wl;
+    let scale = data
+        .outputs
+        .iter()
+        .filter(|output| data.entered_outputs.contains(&output.proxy))
+        .map(|output| output.scale)
+        .max()
+        .unwrap_or(data.buffer_scale);
+
+    // `wl_surface.set_buffer_scale` is new in version 3
+    if scale == data.buffer_scale || wl.dl.wl_proxy_get_version(data.surface).unwrap_or(0) < 3 {
+        return
+    }
+    if !data.is_dpi_logical {
+        // Physical sizes stay the same in pixels, so the surface gets smaller or bigger
+        let (width, height) = data.client_area_size;
+        data.surface_size = ((width as i32 / scale).max(1), (height as i32 / scale).max(1));
+    }
+    data.buffer_scale = scale;
+    data.push_event(Event::ScaleChanged(scale as Scale));
+    request(wl, data.surface, WL_SURFACE_SET_BUFFER_SCALE, &mut [int(scale)]);
+    update_size(data);
+
+    // Custom cursors may have a better image for the new scale
+    if matches!(data.cursor, CursorIcon::Custom(_)) {
+        destroy_cursor_buffer(data);
+        update_cursor(data);
+    }
+}
+
+/// Sets the cursor shown over the surface, which is only possible while the pointer is over it.
+unsafe fn update_cursor(data: &mut WindowImplData) {
+    let wl = data.wl;
+    if data.pointer.is_null() || !data.is_mouse_inside {
+        return
+    }
+    let cursor = match &data.cursor {
+        CursorIcon::System(cursor) => *cursor,
+        CursorIcon::Custom(cursor) => {
+            let cursor = cursor.clone();
+            set_custom_cursor(data, &cursor);
+            return
+        },
+    };
+    let name: &[u8] = match cursor {
+        Cursor::Arrow => c_string!("left_ptr"),
+        Cursor::Blank => {
+            request(wl, data.pointer, WL_POINTER_SET_CURSOR, &mut [
+                uint(data.pointer_serial),
+                object(ptr::null_mut()),
+                int(0),
+                int(0),
+            ]);
+            return
+        },
+        Cursor::Cross => c_string!("crosshair"),
+        Cursor::Hand => c_string!("hand2"),
+        Cursor::Help => c_string!("question_arrow"),
+        Cursor::IBeam => c_string!("xterm"),
+        Cursor::Progress => c_string!("left_ptr_watch"),
+        Cursor::ResizeNESW => c_string!("fd_double_arrow"),
+        Cursor::ResizeNS => c_string!("sb_v_double_arrow"),
+        Cursor::ResizeNWSE => c_string!("bd_double_arrow"),
+        Cursor::ResizeWE => c_string!("sb_h_double_arrow"),
+        Cursor::ResizeAll => c_string!("fleur"),
+        Cursor::Unavailable => c_string!("crossed_circle"),
+        Cursor::Wait => c_string!("watch"),
+    };
+
+    // Without a theme, the compositor's cursor is left as it is
+    if data.cursor_theme.is_null() {
+        return
+    }
+    let cursor = wl.dl.wl_cursor_theme_get_cursor(data.cursor_theme, name.as_ptr().cast()).unwrap_or(ptr::null_mut());
+    if cursor.is_null() || (*cursor).image_count == 0 {
+        return
+    }
+    let image = *(*cursor).images;
+    let buffer = wl.dl.wl_cursor_image_get_buffer(image).unwrap_or(ptr::null_mut());
+    if buffer.is_null() {
+        return
+    }
+    let hotspot = ((*image).hotspot_x as i32, (*image).hotspot_y as i32);
+    attach_cursor(data, buffer, 1, hotspot);
+}
+
+/// Shows a custom cursor, with the image for the current buffer scale.
+unsafe fn set_custom_cursor(data: &mut WindowImplData, cursor: &CustomCursor) {
+    let image = cursor.image(data.buffer_scale as Scale);
+    let (width, height) = image.size;
+    if data.cursor_buffer.is_null() {
+        let pixels: Vec<u32> = image
+            .rgba
+            .chunks_exact(4)
+            .map(|rgba| {
+                let alpha = rgba[3] as u32;
+                let premultiply = |channel: u8| (channel as u32 * alpha + 127) / 255;
+                let argb = alpha << 24 | premultiply(rgba[0]) << 16 | premultiply(rgba[1]) << 8 | premultiply(rgba[2]);
+                argb.to_le()
+            })
+            .collect();
+        data.cursor_buffer = create_buffer(data, width, height, Some(&pixels), WL_SHM_FORMAT_ARGB8888);
+        if data.cursor_buffer.is_null() {
+            return
+        }
+    }
+
+    // The buffer scale has to divide the size, otherwise it's a protocol error, so it's shown unscaled then
+    let scale = (image.scale.round() as i32).max(1);
+    let scale = if width % scale as u32 == 0 && height % scale as u32 == 0 { scale } else { 1 };
+    let hotspot = (image.hotspot.0 as i32 / scale, image.hotspot.1 as i32 / scale);
+    attach_cursor(data, data.cursor_buffer, scale, hotspot);
+}
+
+/// Puts a buffer on the cursor surface and makes it the pointer's cursor.
+/// The hotspot is in surface-local coordinates, which are pixels divided by the buffer scale.
+unsafe fn attach_cursor(data: &WindowImplData, buffer: *mut wl_proxy, scale: i32, (x, y): (i32, i32)) {
+    let wl = data.wl;
+
+    // `wl_surface.set_buffer_scale` is new in version 3
+    if wl.dl.wl_proxy_get_version(data.cursor_surface).unwrap_or(0) >= 3 {
+        request(wl, data.cursor_surface, WL_SURFACE_SET_BUFFER_SCALE, &mut [int(scale)]);
+    }
+    request(wl, data.cursor_surface, WL_SURFACE_ATTACH, &mut [object(buffer), int(0), int(0)]);
+    request(wl, data.cursor_surface, WL_SURFACE_DAMAGE, &mut [int(0), int(0), int(i32::MAX), int(i32::MAX)]);
+    request(wl, data.cursor_surface, WL_SURFACE_COMMIT, &mut []);
+    request(wl, data.pointer, WL_POINTER_SET_CURSOR, &mut [
+        uint(data.pointer_serial),
+        object(data.cursor_surface),
+        int(x),
+        int(y),
+    ]);
+}
+
+unsafe fn destroy_cursor_buffer(data: &mut WindowImplData) {
+    if !data.cursor_buffer.is_null() {
+        destructor(data.wl, data.cursor_buffer, WL_BUFFER_DESTROY);
+        data.cursor_buffer = ptr::null_mut();
+    }
+}
+
+/// Releases the pointer, keyboard and touch, if the seat no longer has the given capabilities.
+unsafe fn release_input_devices(data: &mut WindowImplData, capabilities: u32) {
+    let wl = data.wl;
+    if capabilities & WL_SEAT_CAPABILITY_POINTER == 0 && !data.pointer.is_null() {
+        destroy_pointer_constraint(data);
+        #[cfg(feature = "input")]
+        if !data.relative_pointer.is_null() {
+            destructor(wl, data.relative_pointer, ZWP_RELATIVE_POINTER_V1_DESTROY);
+            data.relative_pointer = ptr::null_mut();
+        }
+
+        // `release` is new in version 3, before that there was only the client-side destroy
+        if wl.dl.wl_proxy_get_version(data.pointer).unwrap_or(0) >= 3 {
+            destructor(wl, data.pointer, WL_POINTER_RELEASE);
+        } else {
+            let _ = wl.dl.wl_proxy_destroy(data.pointer);
+        }
+        data.pointer = ptr::null_mut();
+    }
+    if capabilities & WL_SEAT_CAPABILITY_KEYBOARD == 0 && !data.keyboard.is_null() {
+        if wl.dl.wl_proxy_get_version(data.keyboard).unwrap_or(0) >= 3 {
+            destructor(wl, data.keyboard, WL_KEYBOARD_RELEASE);
+        } else {
+            let _ = wl.dl.wl_proxy_destroy(data.keyboard);
+        }
+        data.keyboard = ptr::null_mut();
+    }
+    if capabilities & WL_SEAT_CAPABILITY_TOUCH == 0 && !data.touch.is_null() {
+        if wl.dl.wl_proxy_get_version(data.touch).unwrap_or(0) >= 3 {
+            destructor(wl, data.touch, WL_TOUCH_RELEASE);
+        } else {
+            let _ = wl.dl.wl_proxy_destroy(data.touch);
+        }
+        data.touch = ptr::null_mut();
+    }
+}
+
+/// Emits the pointer position in the window, given surface-local coordinates.
+#[cfg(feature = "input")]
+fn mouse_move(data: &mut WindowImplData, x: wl_fixed_t, y: wl_fixed_t) {
+    let scale = data.buffer_scale as f64;
+    let x = (x as f64 / 256.0 * scale).floor();
+    let y = (y as f64 / 256.0 * scale).floor();
+    // While buttons are held, the compositor keeps sending motion from outside of the surface
+    let (cw, ch) = data.client_area_size;
+    let is_inside = x >= 0.0 && x < cw as f64 && y >= 0.0 && y < ch as f64;
+    if is_inside || !data.held_buttons.is_empty() {
+        let point = Point::Physical(x as i32, y as i32);
+        let event = if data.is_dpi_logical {
+            Event::MouseMove((point.to_logical(scale), scale))
+        } else {
+            Event::MouseMove((point, scale))
+        };
+        data.push_event(event);
+    }
+}
+
+/// Handles `wl_touch` events, which are reported as they come rather than gathered until each `frame`.
+#[cfg(feature = "input")]
+fn touch_event(data: &mut WindowImplData, opcode: u32, arg: impl Fn(usize) -> wl_argument) {
+    let (scale, is_dpi_logical) = (data.buffer_scale as f64, data.is_dpi_logical);
+    let position = |x: wl_fixed_t, y: wl_fixed_t| {
+        let x = (x as f64 / 256.0 * scale).floor();
+        let y = (y as f64 / 256.0 * scale).floor();
+        let point = Point::Physical(x as i32, y as i32);
+        if is_dpi_logical { point.to_logical(scale) } else { point }
+    };
+    let (id, phase, position) = match opcode {
+        // Received when a finger touches the window, which the rest of its events then belong to.
+        WL_TOUCH_DOWN => unsafe {
+            let (id, position) = (arg(3).i, position(arg(4).f, arg(5).f));
+            data.touches.push((id, position));
+            (id, TouchPhase::Start, position)
+        },
+        WL_TOUCH_MOTION => unsafe {
+            let (id, position) = (arg(1).i, position(arg(2).f, arg(3).f));
+            match data.touches.iter_mut().find(|(touch, _)| *touch == id) {
+                Some(touch) => touch.1 = position,
+                None => return,
+            }
+            (id, TouchPhase::Move, position)
+        },
+        WL_TOUCH_UP => unsafe {
+            let id = arg(2).i;
+            match data.touches.iter().position(|&(touch, _)| touch == id) {
+                Some(index) => (id, TouchPhase::End, data.touches.swap_remove(index).1),
+                None => return,
+            }
+        },
+
+        // Received when the compositor takes over every finger, such as for a gesture.
+        WL_TOUCH_CANCEL => {
+            for (id, position) in mem::take(&mut data.touches) {
+                push_touch(data, id, TouchPhase::Cancel, position);
+            }
+            return
+        },
+        _ => return,
+    };
+    push_touch(data, id, phase, position);
+}
+
+/// Emits a touch, and the mouse events it emulates if enabled.
+#[cfg(feature = "input")]
+fn push_touch(data: &mut WindowImplData, id: i32, phase: TouchPhase, position: Point) {
+    let scale = data.buffer_scale as f64;
+    let touch = Touch { id: id as u32 as u64, phase, position };
+    data.push_event(Event::Touch((touch, scale)));
+    if data.touch_emulates_mouse {
+        let modifiers = data.modifiers;
+        for event in data.touch_mouse.emulate(&touch, scale, modifiers).iter().flatten() {
+            data.push_event(*event);
+        }
+    }
+}
+
+/// Scrolling from a `wl_pointer` frame, indexed by axis (vertical, then horizontal) where positive is down & right.
+#[cfg(feature = "input")]
+#[derive(Default)]
+struct PendingScroll {
+    /// What's scrolling, from `axis_source`.
+    source: Option<u32>,
+    /// Surface-local distance, from `axis`.
+    delta: [f64; 2],
+    /// Wheel notches, from `axis_discrete`.
+    discrete: [i32; 2],
+    /// Whether a touchpad scroll has ended, from `axis_stop`.
+    stop: bool,
+}
+
+/// Reports the scrolling gathered since the last frame.
+#[cfg(feature = "input")]
+fn flush_scroll(data: &mut WindowImplData) {
+    let PendingScroll { source, delta: [dy, dx], discrete: [notches_y, notches_x], stop } =
+        mem::take(&mut data.pending_scroll);
+    if dx == 0.0 && dy == 0.0 && !(stop && data.is_scroll_gesture) {
+        return
+    }
+    let modifiers = data.modifiers;
+
+    // Positive is down on Wayland, but positive is up on Win32 (in units of 120)
+    let wheel = if notches_y != 0 { -notches_y * 120 } else { (dy * -12.0).round() as i32 };
+    if let Some(wheel) = std::num::NonZeroI32::new(wheel) {
+        data.push_event(Event::MouseWheel((wheel, modifiers)));
+    }
+
+    // Without a source (before version 5) it's not known what's scrolling, so it's treated as a wheel
+    let delta = match source {
+        Some(WL_POINTER_AXIS_SOURCE_WHEEL | WL_POINTER_AXIS_SOURCE_WHEEL_TILT) | None => {
+            if notches_x != 0 || notches_y != 0 {
+                ScrollDelta::Lines(notches_x.into(), (-notches_y).into())
+            } else {
+                ScrollDelta::Lines(dx / 10.0, dy / -10.0)
+            }
+        },
+        Some(_) => {
+            let scale = data.buffer_scale as f64;
+            ScrollDelta::Pixels(dx * scale, -dy * scale)
+        },
+    };
+    let phase = if source == Some(WL_POINTER_AXIS_SOURCE_FINGER) || data.is_scroll_gesture {
+        let was_scrolling = mem::replace(&mut data.is_scroll_gesture, !stop);
+        Some(if stop {
+            ScrollPhase::End
+        } else if was_scrolling {
+            ScrollPhase::Update
+        } else {
+            ScrollPhase::Begin
+        })
+    } else {
+        None
+    };
+    data.push_event(Event::MouseScroll((delta, phase, modifiers)));
+}
+
+/// Keymap, modifier state & compose state from xkbcommon, which turns key presses into text.
+#[cfg(feature = "input")]
+struct Xkb {
+    dl: &'static XkbCommonDL,
+    context: *mut xkb_context,
+    keymap: *mut xkb_keymap,
+    state: *mut xkb_state,
+    /// Null if there's no compose table for the user's locale
+    compose: *mut xkb_compose_state,
+}
+
+#[cfg(feature = "input")]
+impl Xkb {
+    /// Compiles the keymap that `wl_keyboard.keymap` sent as a file descriptor.
+    unsafe fn new(dl: &'static XkbCommonDL, fd: c_int, size: usize) -> Option<Self> {
+        // The mapping has to be private since version 7, and is a null-terminated string
+        let map = mmap(ptr::null_mut(), size, PROT_READ, MAP_PRIVATE, fd, 0);
+        if map == MAP_FAILED {
+            return None
+        }
+        let context = dl.xkb_context_new(0).unwrap_or(ptr::null_mut());
+        let keymap = if context.is_null() {
+            ptr::null_mut()
+        } else {
+            dl.xkb_keymap_new_from_string(context, map.cast(), XKB_KEYMAP_FORMAT_TEXT_V1, 0).unwrap_or(ptr::null_mut())
+        };
+        let _ = munmap(map, size);
+        let state = if keymap.is_null() { ptr::null_mut() } else { dl.xkb_state_new(keymap).unwrap_or(ptr::null_mut()) };
+        let mut xkb = Self { dl, context, keymap, state, compose: ptr::null_mut() };
+        if state.is_null() {
+            return None // dropping cleans up what was created
+        }
+
+        // Compose sequences depend on the locale, the same way they do in libX11
+        let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+            .iter()
+            .filter_map(env::var_os)
+            .find(|value| !value.is_empty())
+            .and_then(|value| CString::new(value.into_vec()).ok())
+            .unwrap_or_else(|| CString::new("C").unwrap());
+        let table = dl.xkb_compose_table_new_from_locale(context, locale.as_ptr(), 0).unwrap_or(ptr::null_mut());
+        if !table.is_null() {
+            xkb.compose = dl.xkb_compose_state_new(table, 0).unwrap_or(ptr::null_mut());
+            let _ = dl.xkb_compose_table_unref(table);
+        }
+        Some(xkb)
+    }
+
+    /// Applies `wl_keyboard.modifiers`.
+    unsafe fn update_mask(&mut self, depressed: u32, latched: u32, locked: u32, group: u32) {
+        let _ = self.dl.xkb_state_update_mask(self.state, depressed, latched, locked, 0, 0, group);
+    }
+
+    /// Gets the text typed by pressing a key, which is nothing if it's part of a compose sequence.
+    unsafe fn key_text(&mut self, code: u32, text: &mut String) {
+        // Linux input event codes are offset by 8 in XKB, for X11 reasons
+        let keycode = code + 8;
+        if !self.compose.is_null() {
+            let keysym = self.dl.xkb_state_key_get_one_sym(self.state, keycode).unwrap_or(0);
+            if self.dl.xkb_compose_state_feed(self.compose, keysym) == Some(XKB_COMPOSE_FEED_ACCEPTED) {
+                match self.dl.xkb_compose_state_get_status(self.compose).unwrap_or(XKB_COMPOSE_NOTHING) {
+                    XKB_COMPOSE_COMPOSING => return,
+                    XKB_COMPOSE_COMPOSED => {
+                        let mut buffer = [0u8; 64];
+                        let len = self
+                            .dl
+                            .xkb_compose_state_get_utf8(self.compose, buffer.as_mut_ptr().cast(), buffer.len())
+                            .unwrap_or(0);
+                        let len = (len.max(0) as usize).min(buffer.len() - 1);
+                        text.push_str(&String::from_utf8_lossy(&buffer[..len]));
+                        let _ = self.dl.xkb_compose_state_reset(self.compose);
+                        return
+                    },
+                    XKB_COMPOSE_CANCELLED => {
+                        let _ = self.dl.xkb_compose_state_reset(self.compose);
+                        return
+                    },
+                    _ => (),
+                }
+            }
+        }
+        self.repeat_text(code, text);
+    }
+
+    /// Gets the text typed by a key being repeated, which skips composing.
+    unsafe fn repeat_text(&self, code: u32, text: &mut String) {
+        let utf32 = self.dl.xkb_state_key_get_utf32(self.state, code + 8).unwrap_or(0);
+        if let Some(character) = char::from_u32(utf32).filter(|&c| c != '\0') {
+            text.push(character);
+        }
+    }
+
+    /// Gets the keysym a key has in the active layout, ignoring modifiers (or 0 if it has none).
+    unsafe fn key_sym(&self, code: u32) -> u32 {
+        let layout = self.dl.xkb_state_key_get_layout(self.state, code + 8).unwrap_or(XKB_LAYOUT_INVALID);
+        let mut syms = ptr::null();
+        match self.dl.xkb_keymap_key_get_syms_by_level(self.keymap, code + 8, layout, 0, &mut syms) {
+            Some(count) if count > 0 && !syms.is_null() => *syms,
+            _ => 0,
+        }
+    }
+
+    /// Whether a key should be repeated when held down, which isn't the case for things like modifiers.
+    unsafe fn key_repeats(&self, code: u32) -> bool {
+        self.dl.xkb_keymap_key_repeats(self.keymap, code + 8).unwrap_or(1) != 0
+    }
+
+    /// Drops any half-typed compose sequence.
+    unsafe fn reset_compose(&mut self) {
+        if !self.compose.is_null() {
+            let _ = self.dl.xkb_compose_state_reset(self.compose);
+        }
+    }
+}
+
+#[cfg(feature = "input")]
+impl ops::Drop for Xkb {
+    fn drop(&mut self) {
+        unsafe {
+            if !self.compose.is_null() {
+                let _ = self.dl.xkb_compose_state_unref(self.compose);
+            }
+            if !self.state.is_null() {
+                let _ = self.dl.xkb_state_unref(self.state);
+            }
+            if !self.keymap.is_null() {
+                let _ = self.dl.xkb_keymap_unref(self.keymap);
+            }
+            if !self.context.is_null() {
+                let _ = self.dl.xkb_context_unref(self.context);
+            }
+        }
+    }
+}
+
+/// Emits key repeats that are due, and returns how long until the next one (for `poll`).
+#[cfg(feature = "input")]
+fn repeat_keys(data: &mut WindowImplData) -> c_int {
+    let (_, interval) = data.repeat_info;
+    if let (Some((code, key, next)), Some(interval)) = (data.repeat.as_mut(), interval) {
+        let now = Instant::now();
+        let mut repeats = 0;
+        while *next <= now {
+            *next += interval;
+            repeats += 1;
+        }
+        let (code, key) = (*code, *key);
+        let timeout = next.saturating_duration_since(now).as_millis() as c_int + 1;
+        let mut text = String::new();
+        if let (true, Some(xkb)) = (data.text_input, data.xkb.as_ref()) {
+            unsafe { xkb.repeat_text(code, &mut text) };
+        }
+        for _ in 0..repeats {
+            data.push_event(Event::KeyboardRepeat((key, ScanCode(code), data.modifiers)));
+            for character in text.chars() {
+                data.push_event(Event::Character(character));
+            }
+        }
+        timeout
+    } else {
+        -1
+    }
+}
+
+/// Handles events from all of the window's protocol objects, the equivalent of `window_proc` on Win32.
+unsafe extern "C" fn dispatch(
+    data: *const c_void,
+    proxy: *mut c_void,
+    opcode: u32,
+    _message: *const wl_message,
+    args: *mut wl_argument,
+) -> c_int {
+    let data = &mut *(data as *mut WindowImplData);
+    let wl = data.wl;
+    let proxy = proxy as *mut wl_proxy;
+    let arg = |i: usize| *args.add(i);
+
+    #[cfg(feature = "input")]
+    if proxy == data.text_input_v3 {
+        text_input_event(data, opcode, arg);
+        return 0
+    }
+
+    // Received when the mouse moves, even where the cursor can't (dx, dy, then the same without acceleration).
+    #[cfg(feature = "input")]
+    if proxy == data.relative_pointer {
+        if opcode == ZWP_RELATIVE_POINTER_V1_RELATIVE_MOTION && data.is_focused {
+            let (dx, dy) = (arg(4).f as f64 / 256.0, arg(5).f as f64 / 256.0);
+            data.push_event(Event::MouseMotionRaw((dx, dy)));
+        }
+        return 0
+    }
+
+    if proxy == data.registry {
+        match opcode {
+            // Received for every global on creation, and whenever one is added later on.
+            WL_REGISTRY_GLOBAL => {
+                let (name, version) = (arg(0).u, arg(2).u);
+                match CStr::from_ptr(arg(1).s).to_bytes() {
+                    b"wl_compositor" if data.compositor.is_null() => {
+                        data.compositor = bind(data, name, wl.interfaces.wl_compositor, version.min(3));
+                    },
+                    b"wl_shm" if data.shm.is_null() => {
+                        data.shm = bind(data, name, wl.interfaces.wl_shm, 1);
+                    },
+                    b"wl_seat" if data.seat.is_null() => {
+                        data.seat = bind(data, name, wl.interfaces.wl_seat, version.min(5));
+                        add_dispatcher(data, data.seat);
+                    },
+                    b"wl_output" => {
+                        let proxy = bind(data, name, wl.interfaces.wl_output, output_version(wl, version));
+                        add_dispatcher(data, proxy);
+                        data.outputs.push(Output::new(proxy, name));
+                    },
+                    b"xdg_wm_base" if data.wm_base.is_null() => {
+                        data.wm_base = bind(data, name, &xdg_wm_base_interface, 1);
+                        add_dispatcher(data, data.wm_base);
+                    },
+                    b"zxdg_decoration_manager_v1" if data.decoration_manager.is_null() => {
+                        data.decoration_manager = bind(data, name, &zxdg_decoration_manager_v1_interface, 1);
+                    },
+                    b"zwp_pointer_constraints_v1" if data.pointer_constraints.is_null() => {
+                        data.pointer_constraints = bind(data, name, &zwp_pointer_constraints_v1_interface, 1);
+                    },
+                    b"wl_data_device_manager" if data.data_device_manager.is_null() => {
+                        let interface = wl.interfaces.wl_data_device_manager;
+                        data.data_device_manager = bind(data, name, interface, version.min(3));
+                    },
+                    #[cfg(feature = "input")]
+                    b"zwp_text_input_manager_v3" if data.text_input_manager.is_null() => {
+                        data.text_input_manager = bind(data, name, &zwp_text_input_manager_v3_interface, 1);
+                    },
+                    #[cfg(feature = "input")]
+                    b"zwp_relative_pointer_manager_v1" if data.relative_pointer_manager.is_null() => {
+                        data.relative_pointer_manager =
+                            bind(data, name, &zwp_relative_pointer_manager_v1_interface, 1);
+                    },
+                    _ => (),
+                }
+            },
+
+            // Received when a global goes away, of which only outputs can reasonably do so.
+            WL_REGISTRY_GLOBAL_REMOVE => {
+                if let Some(index) = data.outputs.iter().position(|output| output.name == arg(0).u) {
+                    let output = data.outputs.remove(index);
+                    data.entered_outputs.retain(|&proxy| proxy != output.proxy);
+                    let _ = wl.dl.wl_proxy_destroy(output.proxy);
+                    update_scale(data);
+                }
+            },
+            _ => (),
+        }
+    } else if proxy == data.wm_base {
+        // The compositor checks whether we're responding, so bounce it back.
+        if opcode == XDG_WM_BASE_PING {
+            request(wl, data.wm_base, XDG_WM_BASE_PONG, &mut [uint(arg(0).u)]);
+        }
+    } else if proxy == data.xdg_toplevel {
+        match opcode {
+            // Received with the state the compositor wants the window to be in.
+            // It's applied once the `xdg_surface.configure` that follows arrives.
+            XDG_TOPLEVEL_CONFIGURE => {
+                let states = &*arg(2).a;
+                let states = std::slice::from_raw_parts(states.data as *const u32, states.size / 4);
+                data.pending_configure = (
+                    arg(0).i,
+                    arg(1).i,
+                    states.contains(&XDG_TOPLEVEL_STATE_MAXIMIZED),
+                    states.contains(&XDG_TOPLEVEL_STATE_FULLSCREEN),
+                );
+            },
+
+            // The compositor doesn't tell us why, but this is what the "X button" does.
+            XDG_TOPLEVEL_CLOSE => data.push_event(Event::CloseRequest(CloseReason::SystemMenu)),
+            _ => (),
+        }
+    } else if proxy == data.xdg_surface {
+        if opcode == XDG_SURFACE_CONFIGURE {
+            request(wl, data.xdg_surface, XDG_SURFACE_ACK_CONFIGURE, &mut [uint(arg(0).u)]);
+            let (width, height, maximized, fullscreen) = data.pending_configure;
+            if data.is_maximized != maximized {
+                data.is_maximized = maximized;
+                data.push_event(Event::Maximize(maximized));
+            }
+            if data.is_fullscreen != fullscreen {
+                data.is_fullscreen = fullscreen;
+                data.push_event(Event::Fullscreen(fullscreen));
+            }
+
+            // A size of zero means that the client can decide
+            if width > 0 && height > 0 {
+                data.surface_size = (width, height);
+            }
+            data.is_configured = true;
+            update_size(data);
+        }
+    } else if proxy == data.surface {
+        match opcode {
+            // Received when the window has moved onto or off of an output, which changes its scale.
+            WL_SURFACE_ENTER => {
+                data.entered_outputs.push(arg(0).o);
+                update_scale(data);
+            },
+            WL_SURFACE_LEAVE => {
+                data.entered_outputs.retain(|&output| output != arg(0).o);
+                update_scale(data);
+            },
+            _ => (),
+        }
+    } else if proxy == data.seat {
+        // Received when input devices come and go, such as a keyboard being plugged in.
+        if opcode == WL_SEAT_CAPABILITIES {
+            let capabilities = arg(0).u;
+            if capabilities & WL_SEAT_CAPABILITY_POINTER != 0 && data.pointer.is_null() {
+                data.pointer = constructor(wl, data.seat, WL_SEAT_GET_POINTER, wl.interfaces.wl_pointer, &mut [NEW_ID]);
+                add_dispatcher(data, data.pointer);
+                update_pointer_constraint(data);
+                #[cfg(feature = "input")]
+                update_relative_pointer(data);
+            }
+            if capabilities & WL_SEAT_CAPABILITY_KEYBOARD != 0 && data.keyboard.is_null() {
+                data.keyboard =
+                    constructor(wl, data.seat, WL_SEAT_GET_KEYBOARD, wl.interfaces.wl_keyboard, &mut [NEW_ID]);
+                add_dispatcher(data, data.keyboard);
+            }
+            #[cfg(feature = "input")]
+            if capabilities & WL_SEAT_CAPABILITY_TOUCH != 0 && data.touch.is_null() {
+                data.touch = constructor(wl, data.seat, WL_SEAT_GET_TOUCH, wl.interfaces.wl_touch, &mut [NEW_ID]);
+                add_dispatcher(data, data.touch);
+            }
+            release_input_devices(data, capabilities);
+        }
+    } else if proxy == data.keyboard {
+        keyboard_event(data, opcode, arg);
+    } else if proxy == data.pointer {
+        pointer_event(data, opcode, arg);
+    } else if proxy == data.touch {
+        #[cfg(feature = "input")]
+        touch_event(data, opcode, arg);
+    } else if proxy == data.data_device {
+        data_device_event(data, opcode, arg);
+    } else if proxy == data.clipboard_source {
+        data_source_event(data, opcode, arg);
+    } else if let Some(index) = data.data_offers.iter().position(|&(offer, _)| offer == proxy) {
+        // Received for each mime type of an offer, right after it's introduced.
+        if opcode == WL_DATA_OFFER_OFFER {
+            data.data_offers[index].1.push(CStr::from_ptr(arg(0).s).to_string_lossy().into_owned());
+        }
+    } else if let Some(output) = data.outputs.iter_mut().find(|output| output.proxy == proxy) {
+        output.event(opcode, arg);
+
+        // Received when the output's scale is set, or changed (version 2).
+        if opcode == WL_OUTPUT_SCALE {
+            update_scale(data);
+        }
+    }
+    0
+}
+
+/// Handles `wl_data_device` events, which is how files are dragged onto the window from other clients,
+/// and how the clipboard is offered to it.
+unsafe fn data_device_event(data: &mut WindowImplData, opcode: u32, arg: impl Fn(usize) -> wl_argument) {
+    let wl = data.wl;
+    match opcode {
+        // Received with a new offer, which is followed by its mime types, then the `enter` or `selection` it's for.
+        WL_DATA_DEVICE_DATA_OFFER => {
+            data.data_offers.push((arg(0).o, Vec::new()));
+            add_dispatcher(data, arg(0).o);
+        },
+
+        // Received when a drag enters a surface (serial, surface, x, y, offer).
+        WL_DATA_DEVICE_ENTER => {
+            let (serial, offer) = (arg(0).u, arg(4).o);
+            if let Some(drag) = data.drag.take() {
+                destroy_data_offer(data, drag.offer);
+            }
+            if offer.is_null() {
+                return // dragged within the source client, which isn't us
+            }
+            let has_files = data.accept_drops && data.data_offers.iter().any(|(proxy, mime_types)| {
+                *proxy == offer && mime_types.iter().any(|mime_type| mime_type == "text/uri-list")
+            });
+            let paths = Some(offer).filter(|_| has_files).map(|offer| receive_paths(data, offer));
+            let paths = paths.filter(|paths| !paths.is_empty());
+
+            // Accepting no mime type is how a drag is refused, and copying is the only action we do
+            let mime_type = if paths.is_some() { c_string!("text/uri-list").as_ptr().cast() } else { ptr::null() };
+            request(wl, offer, WL_DATA_OFFER_ACCEPT, &mut [uint(serial), string(mime_type)]);
+            if paths.is_some() && wl.dl.wl_proxy_get_version(offer).unwrap_or(0) >= 3 {
+                let copy = WL_DATA_DEVICE_MANAGER_DND_ACTION_COPY;
+                request(wl, offer, WL_DATA_OFFER_SET_ACTIONS, &mut [uint(copy), uint(copy)]);
+            }
+
+            let position = drag_position(data, arg(2).f, arg(3).f);
+            if let Some(paths) = &paths {
+                let scale = data.buffer_scale as f64;
+                data.push_event_with_paths(paths, |paths| Event::DragEnter((paths, position, scale)));
+            }
+            data.drag = Some(Drag { offer, position, paths });
+        },
+
+        // Received when a drag moves within the surface (time, x, y).
+        WL_DATA_DEVICE_MOTION => {
+            let position = drag_position(data, arg(1).f, arg(2).f);
+            if let Some(drag) = data.drag.as_mut().filter(|drag| drag.paths.is_some()) {
+                drag.position = position;
+                data.push_event(Event::DragOver((position, data.buffer_scale as f64)));
+            }
+        },
+
+        // Received when a drag leaves the surface, including after a drop.
+        WL_DATA_DEVICE_LEAVE => {
+            if let Some(drag) = data.drag.take() {
+                if drag.paths.is_some() {
+                    data.push_event(Event::DragLeave);
+                }
+                destroy_data_offer(data, drag.offer);
+            }
+        },
+
+        // Received when a drag is dropped on the surface, at where it last was.
+        WL_DATA_DEVICE_DROP => {
+            if let Some(drag) = data.drag.take() {
+                if let Some(paths) = &drag.paths {
+                    let (position, scale) = (drag.position, data.buffer_scale as f64);
+                    data.push_event_with_paths(paths, |paths| Event::Drop((paths, position, scale)));
+                    if wl.dl.wl_proxy_get_version(drag.offer).unwrap_or(0) >= 3 {
+                        request(wl, drag.offer, WL_DATA_OFFER_FINISH, &mut []);
+                    }
+                }
+                destroy_data_offer(data, drag.offer);
+            }
+        },
+
+        // Received with the clipboard's offer whenever it changes, and when keyboard focus is gained
+        // (the offer is null if the clipboard is empty). The previous one isn't needed anymore.
+        WL_DATA_DEVICE_SELECTION => {
+            let offer = arg(0).o;
+            if !data.selection_offer.is_null() && data.selection_offer != offer {
+                destroy_data_offer(data, data.selection_offer);
+            }
+            data.selection_offer = offer;
+            data.push_event(Event::ClipboardChanged);
+        },
+
+        _ => (),
+    }
+}
+
+/// Handles `wl_data_source` events, which is how other clients get the clipboard contents from us.
+unsafe fn data_source_event(data: &mut WindowImplData, opcode: u32, arg: impl Fn(usize) -> wl_argument) {
+    match opcode {
+        // Received when a client wants the contents as a mime type, which are written to the fd, then it's closed.
+        WL_DATA_SOURCE_SEND => {
+            let (mime_type, fd) = (CStr::from_ptr(arg(0).s).to_string_lossy(), arg(1).h);
+            let bytes = data.clipboard.as_ref().and_then(|contents| {
+                contents.data.iter().find(|(format, _)| clipboard_mime_types(format).contains(&&*mime_type))
+            });
+            if let Some((_, bytes)) = bytes {
+                let mut written = 0;
+                while written < bytes.len() {
+                    match write(fd, bytes[written..].as_ptr().cast(), bytes.len() - written) {
+                        count if count > 0 => written += count as usize,
+                        count if count < 0 && *__errno_location() == EINTR => continue,
+                        _ => break,
+                    }
+                }
+            }
+            let _ = close(fd);
+        },
+
+        // Received when another client has set the clipboard, replacing ours.
+        WL_DATA_SOURCE_CANCELLED => {
+            destructor(data.wl, data.clipboard_source, WL_DATA_SOURCE_DESTROY);
+            data.clipboard_source = ptr::null_mut();
+            data.clipboard = None;
+        },
+
+        _ => (),
+    }
+}
+
+/// Gets the mime types that clipboard data in a format is exchanged as, the first one being preferred.
+fn clipboard_mime_types(format: &ClipboardFormat) -> Vec<&str> {
+    match format {
+        ClipboardFormat::Text => vec!["text/plain;charset=utf-8", "UTF8_STRING"],
+        ClipboardFormat::Image => vec!["image/bmp"],
+        ClipboardFormat::Custom(mime_type) => vec![mime_type],
+    }
+}
+
+/// Gets the formats the clipboard is in, from the mime types of its offer.
+fn clipboard_formats(data: &WindowImplData) -> Vec<ClipboardFormat> {
+    if let Some(contents) = &data.clipboard {
+        return contents.formats()
+    }
+    let mut formats = Vec::new();
+    if let Some((_, mime_types)) = data.data_offers.iter().find(|&&(offer, _)| offer == data.selection_offer) {
+        for mime_type in mime_types {
+            let format = match mime_type.as_str() {
+                "text/plain;charset=utf-8" | "UTF8_STRING" => ClipboardFormat::Text,
+                "image/bmp" => ClipboardFormat::Image,
+                _ => ClipboardFormat::Custom(mime_type.clone()),
+            };
+            if !formats.contains(&format) {
+                formats.push(format);
+            }
+        }
+    }
+    formats
+}
+
+/// Gets the clipboard data in a format, as the first of its mime types that the offer has.
+unsafe fn clipboard_data(data: &WindowImplData, format: &ClipboardFormat) -> Option<Vec<u8>> {
+    if let Some(contents) = &data.clipboard {
+        return contents.get(format).map(<[u8]>::to_vec)
+    }
+    let (offer, mime_types) = data.data_offers.iter().find(|&&(offer, _)| offer == data.selection_offer)?;
+    let mime_type = clipboard_mime_types(format).into_iter().find(|&m| mime_types.iter().any(|offered| offered == m))?;
+    receive_offer(data, *offer, &CString::new(mime_type).ok()?)
+}
+
+/// Offers new clipboard contents with a `wl_data_source`, which sends them to whoever asks for them from now on.
+unsafe fn set_clipboard(data: &mut WindowImplData, contents: &ClipboardContents) -> bool {
+    // Compositors only let the focused client set the clipboard, with the serial of an input event
+    if data.data_device.is_null() || !data.is_focused {
+        return false
+    }
+    let wl = data.wl;
+    let source = constructor(
+        wl,
+        data.data_device_manager,
+        WL_DATA_DEVICE_MANAGER_CREATE_DATA_SOURCE,
+        wl.interfaces.wl_data_source,
+        &mut [NEW_ID],
+    );
+    add_dispatcher(data, source);
+    for (format, _) in &contents.data {
+        for mime_type in clipboard_mime_types(format).into_iter().filter_map(|m| CString::new(m).ok()) {
+            request(wl, source, WL_DATA_SOURCE_OFFER, &mut [string(mime_type.as_ptr())]);
+        }
+    }
+    request(wl, data.data_device, WL_DATA_DEVICE_SET_SELECTION, &mut [object(source), uint(data.input_serial)]);
+    if !data.clipboard_source.is_null() {
+        destructor(wl, data.clipboard_source, WL_DATA_SOURCE_DESTROY);
+    }
+    data.clipboard_source = source;
+    data.clipboard = Some(contents.clone());
+    let _ = wl.dl.wl_display_flush(data.display);
+    true
+}
+
+/// Destroys a `wl_data_offer`, forgetting about its mime types.
+unsafe fn destroy_data_offer(data: &mut WindowImplData, offer: *mut wl_proxy) {
+    data.data_offers.retain(|&(proxy, _)| proxy != offer);
+    destructor(data.wl, offer, WL_DATA_OFFER_DESTROY);
+}
+
+/// Reads the files out of an offer.
+unsafe fn receive_paths(data: &WindowImplData, offer: *mut wl_proxy) -> Vec<PathBuf> {
+    let list = receive_offer(data, offer, CStr::from_bytes_with_nul_unchecked(c_string!("text/uri-list")));
+    unix::parse_uri_list(&list.unwrap_or_default())
+}
+
+/// Reads the data of an offer as a mime type, which waits until the source has sent it all,
+/// giving up if it stops sending for `RECEIVE_TIMEOUT`.
+unsafe fn receive_offer(data: &WindowImplData, offer: *mut wl_proxy, mime_type: &CStr) -> Option<Vec<u8>> {
+    let mut fds = [0; 2];
+    if pipe2(fds.as_mut_ptr(), O_CLOEXEC) != 0 {
+        return None
+    }
+
+    // libwayland sends a duplicate of the write end, so ours is closed for the read to end when the source's is
+    request(data.wl, offer, WL_DATA_OFFER_RECEIVE, &mut [string(mime_type.as_ptr()), fd(fds[1])]);
+    let _ = close(fds[1]);
+    let _ = data.wl.dl.wl_display_flush(data.display);
+
+    let mut bytes = Vec::new();
+    let mut buffer = [0u8; 4096];
+    let mut poll_fd = pollfd { fd: fds[0], events: POLLIN, revents: 0 };
+    let result = loop {
+        match poll(&mut poll_fd, 1, RECEIVE_TIMEOUT.as_millis() as c_int) {
+            0 => break None,
+            count if count < 0 && *__errno_location() == EINTR => continue,
+            count if count < 0 => break None,
+            _ => (),
+        }
+        match read(fds[0], buffer.as_mut_ptr().cast(), buffer.len()) {
+            count if count > 0 => bytes.extend_from_slice(&buffer[..count as usize]),
+            count if count < 0 && *__errno_location() == EINTR => continue,
+            0 => break Some(bytes),
+            _ => break None,
+        }
+    };
+    let _ = close(fds[0]);
+    result
+}
+
+/// Converts a surface-local drag position to pixels, and back to logical if the window is DPI logical.
+fn drag_position(data: &WindowImplData, x: wl_fixed_t, y: wl_fixed_t) -> Point {
+    let scale = data.buffer_scale as f64;
+    let x = (x as f64 / 256.0 * scale).floor();
+    let y = (y as f64 / 256.0 * scale).floor();
+    let point = Point::Physical(x as i32, y as i32);
+    if data.is_dpi_logical {
+        point.to_logical(scale)
+    } else {
+        point
+    }
+}
+
+/// Handles `wl_keyboard` events, which is also where keyboard focus comes from.
+unsafe fn keyboard_event(data: &mut WindowImplData, opcode: u32, arg: impl Fn(usize) -> wl_argument) {
+    match opcode {
+        // Received with the keymap, which is only needed for text input.
+        WL_KEYBOARD_KEYMAP => {
+            let (format, fd, size) = (arg(0).u, arg(1).h, arg(2).u);
+            #[cfg(feature = "input")]
+            {
+                data.xkb = None;
+                if let (WL_KEYBOARD_KEYMAP_FORMAT_XKB_V1, Some(dl)) = (format, data.wl.xkb.as_ref()) {
+                    data.xkb = Xkb::new(dl, fd, size as usize);
+                }
+            }
+            #[cfg(not(feature = "input"))]
+            let _ = (format, size);
+            let _ = close(fd);
+        },
+
+        // Received when the modifier state has changed, which decides what text keys type.
+        #[cfg(feature = "input")]
+        WL_KEYBOARD_MODIFIERS => {
+            data.modifiers = translate_modifiers(arg(1).u | arg(2).u | arg(3).u);
+            if let Some(xkb) = data.xkb.as_mut() {
+                xkb.update_mask(arg(1).u, arg(2).u, arg(3).u, arg(4).u);
+            }
+        },
+
+        // Received when keyboard focus is gained or lost.
+        WL_KEYBOARD_ENTER | WL_KEYBOARD_LEAVE => {
+            let focused = opcode == WL_KEYBOARD_ENTER;
+            if focused {
+                data.input_serial = arg(0).u;
+            }
+            if data.is_focused != focused {
+                data.is_focused = focused;
+                data.push_event(Event::Focus(focused));
+            }
+
+            // Key releases aren't sent to unfocused windows, so stop repeating
+            #[cfg(feature = "input")]
+            if !focused {
+                data.repeat = None;
+                data.modifiers.remove(Modifiers::SHIFT | Modifiers::CTRL | Modifiers::ALT | Modifiers::SUPER);
+            }
+        },
+
+        // Received when a key is pressed or released, as a Linux input event code (scancode).
+        #[cfg(feature = "input")]
+        WL_KEYBOARD_KEY => {
+            data.input_serial = arg(0).u;
+            let (code, pressed) = (arg(2).u, arg(3).u == WL_KEYBOARD_KEY_STATE_PRESSED);
+            let scancode = ScanCode(code);
+
+            // The key is what the keymap says it is, or where it is if there's no keymap
+            let key = match data.xkb.as_ref() {
+                Some(xkb) => translate_keysym(xkb.key_sym(code) as KeySym),
+                None => translate_keycode(code),
+            };
+            let key = key.unwrap_or(Key::Unknown(scancode));
+
+            // `wl_keyboard.modifiers` comes after this, so the key itself is applied on top
+            let mut modifiers = data.modifiers;
+            modifiers.update(key, pressed);
+            if pressed {
+                data.push_event(Event::KeyboardDown((key, scancode, modifiers)));
+                let mut text = String::new();
+                if let (true, Some(xkb)) = (data.text_input, data.xkb.as_mut()) {
+                    xkb.key_text(code, &mut text);
+                }
+                for character in text.chars() {
+                    data.push_event(Event::Character(character));
+                }
+
+                // Keys are repeated like on other platforms, and unknown ones as the keymap says
+                let repeats = match (key, data.xkb.as_ref()) {
+                    (Key::Unknown(_), Some(xkb)) => xkb.key_repeats(code),
+                    (Key::Unknown(_), None) => false,
+                    _ => true,
+                };
+                let (delay, interval) = data.repeat_info;
+                if repeats && interval.is_some() {
+                    data.repeat = Some((code, key, Instant::now() + delay));
+                }
+            } else {
+                data.push_event(Event::KeyboardUp((key, scancode, modifiers)));
+                if matches!(data.repeat, Some((repeating, ..)) if repeating == code) {
+                    data.repeat = None;
+                }
+            }
+        },
+
+        // Received with the user's key repeat settings (version 4).
+        #[cfg(feature = "input")]
+        WL_KEYBOARD_REPEAT_INFO => {
+            let (rate, delay) = (arg(0).i, arg(1).i);
+            let interval = if rate > 0 { Some(Duration::from_millis(1000 / rate as u64)) } else { None };
+            data.repeat_info = (Duration::from_millis(delay.max(0) as u64), interval);
+        },
+
+        _ => (),
+    }
+}
+
+/// Replaces the pointer constraint with one for the current cursor grab, if there's a pointer to constrain.
+unsafe fn update_pointer_constraint(data: &mut WindowImplData) {
+    destroy_pointer_constraint(data);
+    if data.pointer_constraints.is_null() || data.pointer.is_null() {
+        return
+    }
+    let (opcode, interface) = match data.cursor_grab {
+        CursorGrab::None => return,
+        CursorGrab::Confined => (ZWP_POINTER_CONSTRAINTS_V1_CONFINE_POINTER, &zwp_confined_pointer_v1_interface),
+        CursorGrab::Locked => (ZWP_POINTER_CONSTRAINTS_V1_LOCK_POINTER, &zwp_locked_pointer_v1_interface),
+    };
+    // A null region is the whole surface
+    data.pointer_constraint = constructor(data.wl, data.pointer_constraints, opcode, interface, &mut [
+        NEW_ID,
+        object(data.surface),
+        object(data.pointer),
+        object(ptr::null_mut()),
+        uint(ZWP_POINTER_CONSTRAINTS_V1_LIFETIME_PERSISTENT),
+    ]);
+}
+
+unsafe fn destroy_pointer_constraint(data: &mut WindowImplData) {
+    if !data.pointer_constraint.is_null() {
+        // Both kinds of constraint have `destroy` as their first request
+        destructor(data.wl, data.pointer_constraint, ZWP_LOCKED_POINTER_V1_DESTROY);
+        data.pointer_constraint = ptr::null_mut();
+    }
+}
+
+/// Creates or destroys the relative pointer, depending on whether raw mouse motion is enabled.
+#[cfg(feature = "input")]
+unsafe fn update_relative_pointer(data: &mut WindowImplData) {
+    let wl = data.wl;
+    if data.raw_mouse_motion {
+        if data.relative_pointer.is_null() && !data.relative_pointer_manager.is_null() && !data.pointer.is_null() {
+            data.relative_pointer = constructor(
+                wl,
+                data.relative_pointer_manager,
+                ZWP_RELATIVE_POINTER_MANAGER_V1_GET_RELATIVE_POINTER,
+                &zwp_relative_pointer_v1_interface,
+                &mut [NEW_ID, object(data.pointer)],
+            );
+            add_dispatcher(data, data.relative_pointer);
+        }
+    } else if !data.relative_pointer.is_null() {
+        destructor(wl, data.relative_pointer, ZWP_RELATIVE_POINTER_V1_DESTROY);
+        data.relative_pointer = ptr::null_mut();
+    }
+}
+
+/// Handles `zwp_text_input_v3` events, which is how input methods send their compositions.
+#[cfg(feature = "input")]
+unsafe fn text_input_event(data: &mut WindowImplData, opcode: u32, arg: impl Fn(usize) -> wl_argument) {
+    let nullable_string = |s: *const c_char| {
+        if s.is_null() { String::new() } else { CStr::from_ptr(s).to_string_lossy().into_owned() }
+    };
+    match opcode {
+        // Received when the surface gets keyboard focus, which is when the input method can be enabled.
+        ZWP_TEXT_INPUT_V3_ENTER | ZWP_TEXT_INPUT_V3_LEAVE => {
+            data.is_text_input_entered = opcode == ZWP_TEXT_INPUT_V3_ENTER;
+            update_text_input(data);
+        },
+
+        // Received with the new preedit text, and the byte range of the cursor (both -1 if hidden).
+        ZWP_TEXT_INPUT_V3_PREEDIT_STRING => {
+            let (begin, end) = (arg(1).i, arg(2).i);
+            let cursor = if begin >= 0 && end >= 0 { Some((begin as usize, end as usize)) } else { None };
+            data.pending_preedit = Some((nullable_string(arg(0).s), cursor));
+        },
+
+        // Received with text to insert.
+        ZWP_TEXT_INPUT_V3_COMMIT_STRING => data.pending_commit = Some(nullable_string(arg(0).s)),
+
+        // Received after the events above, which are applied all at once, in the order the protocol says.
+        // Any preedit text which isn't sent again is cleared.
+        ZWP_TEXT_INPUT_V3_DONE => {
+            let commit = data.pending_commit.take();
+            let preedit = data.pending_preedit.take().filter(|(text, _)| !text.is_empty());
+            if let Some(text) = commit {
+                data.push_event_with_text(&text, |text| Event::Ime(ImeEvent::Commit(text)));
+            }
+            match preedit {
+                Some((text, cursor)) => {
+                    if !mem::replace(&mut data.is_composing, true) {
+                        data.push_event(Event::Ime(ImeEvent::Start));
+                    }
+                    data.push_event_with_text(&text, |text| Event::Ime(ImeEvent::Preedit(text, cursor)));
+                },
+                None => end_composition(data),
+            }
+        },
+
+        _ => (),
+    }
+}
+
+/// Ends the composition in progress, if there is one.
+#[cfg(feature = "input")]
+fn end_composition(data: &mut WindowImplData) {
+    if mem::replace(&mut data.is_composing, false) {
+        data.push_event(Event::Ime(ImeEvent::End));
+    }
+}
+
+/// Enables the text input while it's entered, allowed and text input is on, and disables it otherwise.
+///
+/// Each enable starts over from a clean slate, so the cursor area is sent again too.
+#[cfg(feature = "input")]
+unsafe fn update_text_input(data: &mut WindowImplData) {
+    let wl = data.wl;
+    if data.text_input_v3.is_null() {
+        return
+    }
+    if is_text_input_enabled(data) {
+        request(wl, data.text_input_v3, ZWP_TEXT_INPUT_V3_ENABLE, &mut []);
+        set_cursor_rectangle(data);
+    } else {
+        request(wl, data.text_input_v3, ZWP_TEXT_INPUT_V3_DISABLE, &mut []);
+        data.pending_preedit = None;
+        data.pending_commit = None;
+        end_composition(data);
+    }
+    request(wl, data.text_input_v3, ZWP_TEXT_INPUT_V3_COMMIT, &mut []);
+}
+
+#[cfg(feature = "input")]
+fn is_text_input_enabled(data: &WindowImplData) -> bool {
+    data.is_text_input_entered && data.ime_allowed && data.text_input
+}
+
+/// Tells the input method where the cursor area is, for it to put its candidate window next to.
+#[cfg(feature = "input")]
+unsafe fn set_cursor_rectangle(data: &mut WindowImplData) {
+    if let Some((position, size)) = data.ime_cursor_area {
+        // This is in surface-local coordinates, which are pixels divided by the buffer scale
+        let scale = data.buffer_scale;
+        let (x, y) = position.as_physical(scale as Scale);
+        let (width, height) = size.as_physical(scale as Scale);
+        request(data.wl, data.text_input_v3, ZWP_TEXT_INPUT_V3_SET_CURSOR_RECTANGLE, &mut [
+            int(x / scale),
+            int(y / scale),
+            int(width as i32 / scale),
+            int(height as i32 / scale),
+        ]);
+    }
+}
+
+/// Handles `wl_pointer` events, which is also where the cursor gets set.
+unsafe fn pointer_event(data: &mut WindowImplData, opcode: u32, arg: impl Fn(usize) -> wl_argument) {
+    match opcode {
+        // Received when the mouse enters or leaves the window.
+        // The cursor has to be set on every enter, with its serial.
+        WL_POINTER_ENTER => {
+            data.pointer_serial = arg(0).u;
+            if !data.is_mouse_inside {
+                data.is_mouse_inside = true;
+                #[cfg(feature = "input")]
+                data.push_event(Event::MouseFocus(true));
+            }
+            update_cursor(data);
+            #[cfg(feature = "input")]
+            mouse_move(data, arg(2).f, arg(3).f);
+        },
+        WL_POINTER_LEAVE if data.is_mouse_inside => {
+            data.is_mouse_inside = false;
+            #[cfg(feature = "input")]
+            {
+                data.push_event(Event::MouseFocus(false));
+
+                // Leaving with buttons held means the compositor took the pointer away, such as for a
+                // window switcher, and their releases will go elsewhere, so they're released now
+                let modifiers = data.modifiers;
+                for button in mem::take(&mut data.held_buttons) {
+                    data.push_event(Event::MouseUp((button, modifiers)));
+                }
+            }
+        },
+
+        // Received when the mouse cursor has moved around in the window.
+        #[cfg(feature = "input")]
+        WL_POINTER_MOTION => mouse_move(data, arg(1).f, arg(2).f),
+
+        // Received when a mouse button is pressed or released.
+        #[cfg(feature = "input")]
+        WL_POINTER_BUTTON => {
+            data.input_serial = arg(0).u;
+            let button = match arg(2).u {
+                BTN_LEFT => MouseButton::Left,
+                BTN_RIGHT => MouseButton::Right,
+                BTN_MIDDLE => MouseButton::Middle,
+                BTN_SIDE => MouseButton::Mouse4,
+                BTN_EXTRA => MouseButton::Mouse5,
+                _ => return,
+            };
+            let pressed = arg(3).u == WL_POINTER_BUTTON_STATE_PRESSED;
+            if !pressed {
+                data.held_buttons.retain(|&b| b != button);
+            } else if !data.held_buttons.contains(&button) {
+                data.held_buttons.push(button);
+            }
+            let modifiers = data.modifiers;
+            let event = if pressed {
+                Event::MouseDown((button, modifiers))
+            } else {
+                Event::MouseUp((button, modifiers))
+            };
+            data.push_event(event);
+        },
+
+        // Received when scrolling, in surface-local units where a wheel notch is usually 10.
+        // Since version 5, what's scrolling, wheel notches and the end of touchpad scrolls come
+        // in their own events, and `frame` says when they've all been sent.
+        #[cfg(feature = "input")]
+        WL_POINTER_AXIS if arg(1).u <= WL_POINTER_AXIS_HORIZONTAL_SCROLL => {
+            data.pending_scroll.delta[arg(1).u as usize] += arg(2).f as f64 / 256.0;
+            if data.wl.dl.wl_proxy_get_version(data.pointer).unwrap_or(0) < 5 {
+                flush_scroll(data);
+            }
+        },
+        #[cfg(feature = "input")]
+        WL_POINTER_AXIS_SOURCE => data.pending_scroll.source = Some(arg(0).u),
+        #[cfg(feature = "input")]
+        WL_POINTER_AXIS_STOP => data.pending_scroll.stop = true,
+        #[cfg(feature = "input")]
+        WL_POINTER_AXIS_DISCRETE if arg(0).u <= WL_POINTER_AXIS_HORIZONTAL_SCROLL => {
+            data.pending_scroll.discrete[arg(0).u as usize] += arg(1).i;
+        },
+        #[cfg(feature = "input")]
+        WL_POINTER_FRAME => flush_scroll(data),
+
+        _ => (),
+    }
+}
+
+/// Handles a message from the owning thread, the equivalent of the `RAMEN_WM_*` handlers on Win32.
+unsafe fn process_message(data: &mut WindowImplData, message: Message) {
+    let wl = data.wl;
+    match message {
+        Message::Destroy => data.destroy_flag = true,
+
+        Message::Execute(f) => {
+            // `FnOnce` requires the closure to consume itself, so it's done like this!
+            if let Some(f) = (&mut *f).take() {
+                f();
+            }
+        },
+
+        Message::GetInnerSize(out_size, out_scale) => {
+            let (width, height) = data.client_area_size;
+            *out_size = data.scale_size(Size::Physical(width, height));
+            *out_scale = data.buffer_scale as Scale;
+        },
+
+        Message::GetClipboard(format, out) => *out = clipboard_data(data, &*format),
+
+        Message::GetClipboardFormats(out) => *out = clipboard_formats(data),
+
+        // There's no knowing where the window is, only which outputs it's on, so it's the first of those
+        Message::GetCurrentMonitor(out) => {
+            *out = data
+                .entered_outputs
+                .first()
+                .and_then(|&proxy| data.outputs.iter().find(|output| output.proxy == proxy))
+                .map(Output::monitor);
+        },
+
+        // Window positions are kept from clients entirely, so everything is at the origin
+        Message::GetPosition(_, out_position, out_scale) => {
+            *out_position = if data.is_dpi_logical { Point::Logical(0.0, 0.0) } else { Point::Physical(0, 0) };
+            *out_scale = data.buffer_scale as Scale;
+        },
+
+        Message::IsDpiLogical(out) => *out = data.is_dpi_logical,
+
+        Message::PresentPixels(pixels, size, damage, out) => *out = present_pixels(data, &*pixels, size, &*damage),
+
+        // The frame is outside of the inner area either way, so the inner size stays the same
+        Message::SetBorderless(borderless) => {
+            if data.style.borderless != borderless {
+                data.style.borderless = borderless;
+                update_window_style(data);
+            }
+        },
+
+        Message::SetClipboard(contents, out) => *out = set_clipboard(data, &*contents),
+
+        Message::SetControls(controls) => {
+            if data.style.controls != controls {
+                data.style.controls = controls;
+                update_window_style(data);
+            }
+        },
+
+        Message::SetCursor(cursor) => {
+            data.cursor = cursor;
+            destroy_cursor_buffer(data);
+            update_cursor(data);
+        },
+
+        Message::SetCursorGrab(grab) => {
+            if data.cursor_grab != grab {
+                data.cursor_grab = grab;
+                update_pointer_constraint(data);
+            }
+        },
+
+        // The compositor decides where the pointer goes, but a locked pointer can say where it'd like to be.
+        // This is in surface-local coordinates, and is applied on the next commit like the rest of the surface.
+        Message::SetCursorPosition(position) => {
+            if data.cursor_grab == CursorGrab::Locked && !data.pointer_constraint.is_null() {
+                let scale = data.buffer_scale;
+                let (x, y) = position.as_physical(scale as Scale);
+                let opcode = ZWP_LOCKED_POINTER_V1_SET_CURSOR_POSITION_HINT;
+                request(wl, data.pointer_constraint, opcode, &mut [
+                    fixed(x as f64 / scale as f64),
+                    fixed(y as f64 / scale as f64),
+                ]);
+                request(wl, data.surface, WL_SURFACE_COMMIT, &mut []);
+            }
+        },
+
+        // Whatever's attached stays up until the first frame from OpenGL or Vulkan replaces it
+        Message::SetExternalSurface => data.has_external_surface = true,
+
+        // There's no changing video modes on Wayland, so all fullscreen is "borderless".
+        // The compositor picks the output if we don't, and puts the window back where it was afterwards.
+        Message::SetFullscreen(fullscreen) => {
+            if data.fullscreen != fullscreen {
+                data.fullscreen = fullscreen;
+                if !data.xdg_toplevel.is_null() {
+                    if data.fullscreen.is_some() {
+                        let output = fullscreen_output(data);
+                        request(wl, data.xdg_toplevel, XDG_TOPLEVEL_SET_FULLSCREEN, &mut [object(output)]);
+                    } else {
+                        request(wl, data.xdg_toplevel, XDG_TOPLEVEL_UNSET_FULLSCREEN, &mut []);
+                    }
+                }
+            }
+        },
+
+        Message::SetIcon(_) => {
+            // There's no stable protocol for this, icons come from the desktop entry matching the app ID
+        },
+
+        Message::SetInnerSize(size) => {
+            let scale = data.buffer_scale;
+            let (width, height) = size.as_physical(scale as Scale);
+            data.is_dpi_logical = matches!(size, Size::Logical(..));
+            data.surface_size = ((width as i32 / scale).max(1), (height as i32 / scale).max(1));
+            update_window_style(data);
+            update_size(data);
+        },
+
+        Message::SetMaximized(maximized) => {
+            if data.is_maximized != maximized && !data.xdg_toplevel.is_null() {
+                let opcode = if maximized { XDG_TOPLEVEL_SET_MAXIMIZED } else { XDG_TOPLEVEL_UNSET_MAXIMIZED };
+                request(wl, data.xdg_toplevel, opcode, &mut []);
+            }
+        },
+
+        // The compositor decides where windows go, and there's no protocol to ask it otherwise
+        Message::SetPosition(_) => (),
+
+        #[cfg(feature = "input")]
+        Message::SetRawMouseMotion(enabled) => {
+            data.raw_mouse_motion = enabled;
+            update_relative_pointer(data);
+        },
+
+        Message::SetResizable(resizable) => {
+            if data.style.resizable != resizable {
+                data.style.resizable = resizable;
+                update_window_style(data);
+            }
+        },
+
+        #[cfg(feature = "input")]
+        Message::SetImeAllowed(allowed) => {
+            if data.ime_allowed != allowed {
+                data.ime_allowed = allowed;
+                update_text_input(data);
+            }
+        },
+
+        #[cfg(feature = "input")]
+        Message::SetImeCursorArea(position, size) => {
+            data.ime_cursor_area = Some((position, size));
+            if !data.text_input_v3.is_null() && is_text_input_enabled(data) {
+                set_cursor_rectangle(data);
+                request(wl, data.text_input_v3, ZWP_TEXT_INPUT_V3_COMMIT, &mut []);
+            }
+        },
+
+        #[cfg(feature = "input")]
+        Message::SetTextInput(enabled) => {
+            let changed = mem::replace(&mut data.text_input, enabled) != enabled;
+            if let Some(xkb) = data.xkb.as_mut() {
+                xkb.reset_compose();
+            }
+            if changed {
+                update_text_input(data);
+            }
+        },
+
+        Message::SetTitle(mut title) => {
+            util::str_sweep_nulls(&mut title);
+            data.title = CString::new(title).unwrap_or_default();
+            if !data.xdg_toplevel.is_null() {
+                request(wl, data.xdg_toplevel, XDG_TOPLEVEL_SET_TITLE, &mut [string(data.title.as_ptr())]);
+            }
+        },
+
+        Message::SetVisible(visible) => {
+            if data.style.visible != visible {
+                data.style.visible = visible;
+                if visible {
+                    create_role(data);
+                } else {
+                    destroy_role(data);
+                }
+            }
+        },
+    }
+}
+
+/// Translates a Linux input event code, which is what `wl_keyboard.key` sends, into a key.
+///
+/// Keys are translated by where they are, assuming a US layout, as it's only used without a keymap.
+#[cfg(feature = "input")]
+fn translate_keycode(code: u32) -> Option<Key> {
+    Some(match code {
+        1 => Key::Escape,
+        2 => Key::Num1,
+        3 => Key::Num2,
+        4 => Key::Num3,
+        5 => Key::Num4,
+        6 => Key::Num5,
+        7 => Key::Num6,
+        8 => Key::Num7,
+        9 => Key::Num8,
+        10 => Key::Num9,
+        11 => Key::Num0,
+        12 => Key::Minus,
+        13 => Key::Plus,
+        14 => Key::Backspace,
+        15 => Key::Tab,
+        16 => Key::Q,
+        17 => Key::W,
+        18 => Key::E,
+        19 => Key::R,
+        20 => Key::T,
+        21 => Key::Y,
+        22 => Key::U,
+        23 => Key::I,
+        24 => Key::O,
+        25 => Key::P,
+        26 => Key::Oem4,
+        27 => Key::Oem6,
+        28 => Key::Enter,
+        29 => Key::LControl,
+        30 => Key::A,
+        31 => Key::S,
+        32 => Key::D,
+        33 => Key::F,
+        34 => Key::G,
+        35 => Key::H,
+        36 => Key::J,
+        37 => Key::K,
+        38 => Key::L,
+        39 => Key::Oem1,
+        40 => Key::Oem7,
+        41 => Key::Oem3,
+        42 => Key::LShift,
+        43 => Key::Oem5,
+        44 => Key::Z,
+        45 => Key::X,
+        46 => Key::C,
+        47 => Key::V,
+        48 => Key::B,
+        49 => Key::N,
+        50 => Key::M,
+        51 => Key::Comma,
+        52 => Key::Period,
+        53 => Key::Oem2,
+        54 => Key::RShift,
+        55 => Key::Multiply,
+        56 => Key::LAlt,
+        57 => Key::Space,
+        58 => Key::CapsLock,
+        59 => Key::F1,
+        60 => Key::F2,
+        61 => Key::F3,
+        62 => Key::F4,
+        63 => Key::F5,
+        64 => Key::F6,
+        65 => Key::F7,
+        66 => Key::F8,
+        67 => Key::F9,
+        68 => Key::F10,
+        69 => Key::NumLock,
+        70 => Key::ScrollLock,
+        71 => Key::Numpad7,
+        72 => Key::Numpad8,
+        73 => Key::Numpad9,
+        74 => Key::Subtract,
+        75 => Key::Numpad4,
+        76 => Key::Numpad5,
+        77 => Key::Numpad6,
+        78 => Key::Add,
+        79 => Key::Numpad1,
+        80 => Key::Numpad2,
+        81 => Key::Numpad3,
+        82 => Key::Numpad0,
+        83 => Key::Decimal,
+        85 => Key::ImeKanji, // KEY_ZENKAKUHANKAKU
+        86 => Key::Oem102,
+        87 => Key::F11,
+        88 => Key::F12,
+        92 => Key::ImeConvert,
+        93 => Key::ImeKana,
+        94 => Key::ImeNonConvert,
+        96 => Key::Enter, // KEY_KPENTER
+        97 => Key::RControl,
+        98 => Key::Divide,
+        99 => Key::PrintScreen,
+        100 => Key::RAlt,
+        102 => Key::Home,
+        103 => Key::Up,
+        104 => Key::PageUp,
+        105 => Key::Left,
+        106 => Key::Right,
+        107 => Key::End,
+        108 => Key::Down,
+        109 => Key::PageDown,
+        110 => Key::Insert,
+        111 => Key::Delete,
+        113 => Key::VolumeMute,
+        114 => Key::VolumeDown,
+        115 => Key::VolumeUp,
+        119 => Key::Pause,
+        121 => Key::Separator,
+        125 => Key::LSuper,
+        126 => Key::RSuper,
+        127 => Key::Applications,
+        128 => Key::BrowserStop,
+        138 => Key::Help,
+        140 => Key::LaunchApplication2,
+        142 => Key::Sleep,
+        144 => Key::LaunchApplication1,
+        155 => Key::LaunchMail,
+        156 => Key::BrowserFavourites,
+        158 => Key::BrowserBack,
+        159 => Key::BrowserForward,
+        163 => Key::MediaNextTrack,
+        164 => Key::MediaPlayPause,
+        165 => Key::MediaPreviousTrack,
+        166 => Key::MediaStop,
+        172 => Key::BrowserHome,
+        173 => Key::BrowserRefresh,
+        183 => Key::F13,
+        184 => Key::F14,
+        185 => Key::F15,
+        186 => Key::F16,
+        187 => Key::F17,
+        188 => Key::F18,
+        189 => Key::F19,
+        190 => Key::F20,
+        191 => Key::F21,
+        192 => Key::F22,
+        193 => Key::F23,
+        194 => Key::F24,
+        217 => Key::BrowserSearch,
+        226 => Key::LaunchMediaSelect,
+        _ => return None,
+    })
+}
@@ -4,8 +4,9 @@ pub(crate) mod ffi;
 pub(crate) mod imp;
 
 // Required re-exports
-pub(crate) use imp::spawn_window;
+pub(crate) use imp::{available_monitors, spawn_window, vulkan_instance_extensions};
 pub(crate) type WindowRepr = imp::WindowImpl;
+pub(crate) type GlContextRepr = imp::GlContextImpl;
 
 // Bonus
 pub use ffi::{HINSTANCE, HMONITOR, HWND};
@@ -3,6 +3,9 @@
 // TODO: Remove `dead_code` when all is done
 #![allow(bad_style, dead_code, overflowing_literals, clippy::upper_case_acronyms)]
 
+use crate::vulkan::{PFN_vkVoidFunction, VkFlags, VkInstance, VkResult, VkStructureType, VkSurfaceKHR};
+use std::{ffi::CStr, mem};
+
 // Opaque handles
 macro_rules! def_handle {
     // documented, exported
@@ -24,12 +27,18 @@ def_handle!("Opaque handle to a monitor.", HMONITOR, HMONITOR__);
 def_handle!("Opaque handle to a window.", HWND, HWND__);
 def_handle!(DPI_AWARENESS_CONTEXT, DPI_AWARENESS_CONTEXT__);
 def_handle!(FARPROC, __some_function);
+def_handle!(HBITMAP, HBITMAP__);
 def_handle!(HBRUSH, HBRUSH__);
 def_handle!(HDC, HDC__);
+def_handle!(HDROP, HDROP__);
+def_handle!(HGLRC, HGLRC__);
 def_handle!(HHOOK, HHOOK__);
 def_handle!(HICON, HICON__);
+def_handle!(HIMC, HIMC__);
 def_handle!(HMENU, HMENU__);
 def_handle!(HMODULE, HMODULE__);
+def_handle!(HRAWINPUT, HRAWINPUT__);
+def_handle!(HRGN, HRGN__);
 pub type HCURSOR = HICON;
 
 // Typedefs
@@ -50,8 +59,10 @@ pub type ATOM = WORD;
 pub type BOOL = c_int;
 pub type BYTE = c_uchar;
 pub type CHAR = c_char;
+pub type CLIPFORMAT = WORD;
 pub type DWORD = c_ulong;
 pub type HANDLE = *mut c_void;
+pub type HGLOBAL = HANDLE;
 pub type HLOCAL = HANDLE;
 pub type HRESULT = c_long;
 pub type INT = c_int;
@@ -61,10 +72,13 @@ pub type LONG_PTR = isize;
 pub type LPARAM = LONG_PTR;
 pub type LRESULT = LONG_PTR;
 pub type NTSTATUS = LONG;
+pub type POINTER_INPUT_TYPE = DWORD;
 pub type PROCESS_DPI_AWARENESS = u32;
 pub type SHORT = c_short;
+pub type SIZE_T = ULONG_PTR;
 pub type UINT = c_uint;
 pub type UINT_PTR = usize;
+pub type ULONG = c_ulong;
 pub type ULONG_PTR = usize;
 pub type USHORT = c_ushort;
 pub type WCHAR = wchar_t;
@@ -73,24 +87,54 @@ pub type WPARAM = UINT_PTR;
 
 // Function typedefs
 pub type HOOKPROC = unsafe extern "system" fn(c_int, WPARAM, LPARAM) -> LRESULT;
+pub type MONITORENUMPROC = unsafe extern "system" fn(HMONITOR, HDC, *mut RECT, LPARAM) -> BOOL;
 pub type WNDPROC = unsafe extern "system" fn(HWND, UINT, WPARAM, LPARAM) -> LRESULT;
 
 // Constants
 pub const _WIN32_WINNT_VISTA: WORD = 0x0600;
 pub const _WIN32_WINNT_WINBLUE: WORD = 0x0603;
+pub const BI_RGB: DWORD = 0;
 pub const CCHILDREN_TITLEBAR: usize = 5;
+pub const CCHDEVICENAME: usize = 32;
+pub const CDS_FULLSCREEN: DWORD = 0x00000004;
+pub const CF_DIB: CLIPFORMAT = 8;
+pub const CF_DIBV5: CLIPFORMAT = 17;
+pub const CF_HDROP: CLIPFORMAT = 15;
+pub const CF_UNICODETEXT: CLIPFORMAT = 13;
+pub const CFS_EXCLUDE: DWORD = 0x0080;
+pub const CFS_POINT: DWORD = 0x0002;
+pub const COLORONCOLOR: c_int = 3;
 pub const CP_UTF8: DWORD = 65001;
 pub const CS_OWNDC: UINT = 0x0020;
 pub const CW_USEDEFAULT: c_int = 0x80000000;
+pub const DIB_RGB_COLORS: UINT = 0;
+pub const DISP_CHANGE_SUCCESSFUL: LONG = 0;
+pub const DM_BITSPERPEL: DWORD = 0x00040000;
+pub const DM_DISPLAYFREQUENCY: DWORD = 0x00400000;
+pub const DM_PELSHEIGHT: DWORD = 0x00100000;
+pub const DM_PELSWIDTH: DWORD = 0x00080000;
+pub const ENUM_CURRENT_SETTINGS: DWORD = 0xFFFFFFFF;
 pub const DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2: DPI_AWARENESS_CONTEXT = -4isize as _;
+pub const DROPEFFECT_COPY: DWORD = 1;
+pub const DROPEFFECT_NONE: DWORD = 0;
+pub const DVASPECT_CONTENT: DWORD = 1;
+pub const E_NOINTERFACE: HRESULT = 0x80004002;
 pub const ERROR_SUCCESS: DWORD = 0; // lol
 pub const FALSE: BOOL = 0;
 pub const GCL_CBCLSEXTRA: c_int = -20;
+pub const GCS_COMPSTR: DWORD = 0x0008;
+pub const GCS_CURSORPOS: DWORD = 0x0080;
+pub const GCS_RESULTSTR: DWORD = 0x0800;
+pub const GMEM_MOVEABLE: UINT = 0x0002;
 pub const GWL_EXSTYLE: c_int = -20;
 pub const GWL_STYLE: c_int = -16;
 pub const GWL_USERDATA: c_int = -21;
 pub const HCBT_DESTROYWND: c_int = 4;
+pub const HID_USAGE_GENERIC_MOUSE: USHORT = 0x02;
+pub const HID_USAGE_PAGE_GENERIC: USHORT = 0x01;
 pub const HTCAPTION: LRESULT = 2;
+pub const HWND_TOP: HWND = 0 as HWND;
+pub const IACE_DEFAULT: DWORD = 0x0010;
 pub const IDC_APPSTARTING: *const WCHAR = 32650 as *const WCHAR;
 pub const IDC_ARROW: *const WCHAR = 32512 as *const WCHAR;
 pub const IDC_CROSS: *const WCHAR = 32515 as *const WCHAR;
@@ -107,26 +151,54 @@ pub const IDC_SIZENWSE: *const WCHAR = 32642 as *const WCHAR;
 pub const IDC_SIZEWE: *const WCHAR = 32644 as *const WCHAR;
 pub const IDC_UPARROW: *const WCHAR = 32516 as *const WCHAR;
 pub const IDC_WAIT: *const WCHAR = 32514 as *const WCHAR;
+pub const ICON_BIG: WPARAM = 1;
+pub const ICON_SMALL: WPARAM = 0;
 pub const IMAGE_CURSOR: UINT = 2;
+pub const ISC_SHOWUICOMPOSITIONWINDOW: LPARAM = 0x80000000;
 pub const HTCLIENT: LRESULT = 1;
 pub const LR_DEFAULTSIZE: UINT = 0x00000040;
 pub const LR_SHARED: UINT = 0x00008000;
+pub const MDT_EFFECTIVE_DPI: u32 = 0;
 pub const MF_BYCOMMAND: UINT = 0x00000000;
 pub const MF_DISABLED: UINT = 0x00000002;
 pub const MF_ENABLED: UINT = 0x00000000;
 pub const MF_GRAYED: UINT = 0x00000001;
+pub const MONITOR_DEFAULTTONEAREST: DWORD = 0x00000002;
+pub const MONITOR_DEFAULTTONULL: DWORD = 0x00000000;
+pub const MONITOR_DEFAULTTOPRIMARY: DWORD = 0x00000001;
+pub const MONITORINFOF_PRIMARY: DWORD = 0x00000001;
+pub const MOUSE_MOVE_ABSOLUTE: USHORT = 0x01;
+pub const PEN_FLAG_BARREL: UINT = 0x00000001;
+pub const PEN_FLAG_ERASER: UINT = 0x00000004;
+pub const PEN_FLAG_INVERTED: UINT = 0x00000002;
+pub const PEN_MASK_PRESSURE: UINT = 0x00000001;
+pub const PEN_MASK_TILT_X: UINT = 0x00000004;
+pub const PEN_MASK_TILT_Y: UINT = 0x00000008;
+pub const POINTER_FLAG_CANCELED: UINT = 0x00008000;
+pub const POINTER_FLAG_INCONTACT: UINT = 0x00000004;
 pub const PROCESS_PER_MONITOR_DPI_AWARE: PROCESS_DPI_AWARENESS = 2;
 pub const PROCESS_SYSTEM_DPI_AWARE: PROCESS_DPI_AWARENESS = 1;
+pub const PT_PEN: POINTER_INPUT_TYPE = 3;
+pub const PT_TOUCH: POINTER_INPUT_TYPE = 2;
+pub const RID_INPUT: UINT = 0x10000003;
+pub const RIDEV_REMOVE: DWORD = 0x00000001;
+pub const RIM_TYPEMOUSE: DWORD = 0;
+pub const RGN_OR: c_int = 2;
 pub const SC_CLOSE: WPARAM = 0xF060;
 pub const SC_MAXIMIZE: WPARAM = 0xF030;
 pub const SC_RESTORE: WPARAM = 0xF120;
+pub const SM_CXICON: c_int = 11;
+pub const SM_CXSMICON: c_int = 49;
 pub const SIZE_RESTORED: WPARAM = 0;
 pub const SIZE_MINIMIZED: WPARAM = 1;
 pub const SIZE_MAXIMIZED: WPARAM = 2;
 pub const SIZE_MAXSHOW: WPARAM = 3;
 pub const SIZE_MAXHIDE: WPARAM = 4;
+pub const SRCCOPY: DWORD = 0x00CC0020;
 pub const SW_HIDE: c_int = 0;
 pub const SW_SHOW: c_int = 5;
+pub const SW_SHOWMAXIMIZED: UINT = 3;
+pub const SW_SHOWNORMAL: UINT = 1;
 pub const SWP_ASYNCWINDOWPOS: UINT = 0x4000;
 pub const SWP_DEFERERASE: UINT = 0x2000;
 pub const SWP_DRAWFRAME: UINT = SWP_FRAMECHANGED;
@@ -142,7 +214,9 @@ pub const SWP_NOSENDCHANGING: UINT = 0x0400;
 pub const SWP_NOSIZE: UINT = 0x0001;
 pub const SWP_NOZORDER: UINT = 0x0004;
 pub const SWP_SHOWWINDOW: UINT = 0x0040;
+pub const S_OK: HRESULT = 0;
 pub const TRUE: BOOL = 1;
+pub const TYMED_HGLOBAL: DWORD = 1;
 pub const VER_BUILDNUMBER: DWORD = 0x0000004;
 pub const VER_GREATER_EQUAL: BYTE = 3;
 pub const VER_MAJORVERSION: DWORD = 0x0000002;
@@ -150,6 +224,7 @@ pub const VER_MINORVERSION: DWORD = 0x0000001;
 pub const VER_SERVICEPACKMAJOR: DWORD = 0x0000020;
 pub const VER_SERVICEPACKMINOR: DWORD = 0x0000010;
 pub const WH_CBT: c_int = 5;
+pub const WHEEL_DELTA: c_short = 120;
 pub const WM_NULL: UINT = 0x0000;
 pub const WM_CREATE: UINT = 0x0001;
 pub const WM_DESTROY: UINT = 0x0002;
@@ -167,13 +242,22 @@ pub const WM_ERASEBKGND: UINT = 0x0014;
 pub const WM_SHOWWINDOW: UINT = 0x0018;
 pub const WM_ACTIVATEAPP: UINT = 0x001C;
 pub const WM_SETCURSOR: UINT = 0x0020;
+pub const WM_SETICON: UINT = 0x0080;
 pub const WM_NCCREATE: UINT = 0x0081;
 pub const WM_NCDESTROY: UINT = 0x0082;
 pub const WM_NCLBUTTONDOWN: UINT = 0x00A1;
+pub const WM_INPUT: UINT = 0x00FF;
 pub const WM_KEYDOWN: UINT = 0x0100;
 pub const WM_KEYUP: UINT = 0x0101;
+pub const WM_CHAR: UINT = 0x0102;
 pub const WM_SYSKEYDOWN: UINT = 0x0104;
 pub const WM_SYSKEYUP: UINT = 0x0105;
+pub const WM_SYSCHAR: UINT = 0x0106;
+pub const WM_UNICHAR: UINT = 0x0109;
+pub const UNICODE_NOCHAR: WPARAM = 0xFFFF;
+pub const WM_IME_STARTCOMPOSITION: UINT = 0x010D;
+pub const WM_IME_ENDCOMPOSITION: UINT = 0x010E;
+pub const WM_IME_COMPOSITION: UINT = 0x010F;
 pub const WM_SYSCOMMAND: UINT = 0x0112;
 pub const WM_MOUSEMOVE: UINT = 0x0200;
 pub const WM_LBUTTONDOWN: UINT = 0x0201;
@@ -185,8 +269,17 @@ pub const WM_MBUTTONUP: UINT = 0x0208;
 pub const WM_MOUSEWHEEL: UINT = 0x020A;
 pub const WM_XBUTTONDOWN: UINT = 0x020B;
 pub const WM_XBUTTONUP: UINT = 0x020C;
+pub const WM_MOUSEHWHEEL: UINT = 0x020E;
+pub const WM_CAPTURECHANGED: UINT = 0x0215;
 pub const WM_MOVING: UINT = 0x0216;
 pub const WM_EXITSIZEMOVE: UINT = 0x0232;
+pub const WM_POINTERUPDATE: UINT = 0x0245;
+pub const WM_POINTERDOWN: UINT = 0x0246;
+pub const WM_POINTERUP: UINT = 0x0247;
+pub const WM_POINTERCAPTURECHANGED: UINT = 0x024C;
+pub const WM_IME_SETCONTEXT: UINT = 0x0281;
+pub const WM_DPICHANGED: UINT = 0x02E0;
+pub const WM_CLIPBOARDUPDATE: UINT = 0x031D;
 pub const WM_USER: UINT = 0x0400;
 pub const WS_BORDER: DWORD = 0x00800000;
 pub const WS_CAPTION: DWORD = 0x00C00000;
@@ -219,8 +312,42 @@ pub const WS_VSCROLL: DWORD = 0x00200000;
 pub const XBUTTON1: WORD = 0x0001;
 pub const XBUTTON2: WORD = 0x0002;
 
+// Interface IDs
+pub const IID_IDropTarget: GUID =
+    GUID { Data1: 0x00000122, Data2: 0x0000, Data3: 0x0000, Data4: [0xC0, 0, 0, 0, 0, 0, 0, 0x46] };
+pub const IID_IUnknown: GUID =
+    GUID { Data1: 0x00000000, Data2: 0x0000, Data3: 0x0000, Data4: [0xC0, 0, 0, 0, 0, 0, 0, 0x46] };
+
 // Structs
 #[repr(C)]
+#[derive(PartialEq, Eq)]
+pub struct GUID {
+    pub Data1: c_ulong,
+    pub Data2: c_ushort,
+    pub Data3: c_ushort,
+    pub Data4: [c_uchar; 8],
+}
+#[repr(C)]
+pub struct FORMATETC {
+    pub cfFormat: CLIPFORMAT,
+    pub ptd: *mut c_void,
+    pub dwAspect: DWORD,
+    pub lindex: LONG,
+    pub tymed: DWORD,
+}
+#[repr(C)]
+pub struct STGMEDIUM {
+    pub tymed: DWORD,
+    /// A union of handles and interfaces, all pointers, which is an `HGLOBAL` for `TYMED_HGLOBAL`.
+    pub hGlobal: HANDLE,
+    pub pUnkForRelease: *mut c_void,
+}
+#[repr(C)]
+pub struct POINTL {
+    pub x: LONG,
+    pub y: LONG,
+}
+#[repr(C)]
 pub struct POINT {
     pub x: LONG,
     pub y: LONG,
@@ -243,6 +370,128 @@ pub struct MSG {
     pub pt: POINT,
 }
 #[repr(C)]
+pub struct CANDIDATEFORM {
+    pub dwIndex: DWORD,
+    pub dwStyle: DWORD,
+    pub ptCurrentPos: POINT,
+    pub rcArea: RECT,
+}
+#[repr(C)]
+pub struct COMPOSITIONFORM {
+    pub dwStyle: DWORD,
+    pub ptCurrentPos: POINT,
+    pub rcArea: RECT,
+}
+#[repr(C)]
+pub struct RAWINPUTDEVICE {
+    pub usUsagePage: USHORT,
+    pub usUsage: USHORT,
+    pub dwFlags: DWORD,
+    pub hwndTarget: HWND,
+}
+#[repr(C)]
+pub struct RAWINPUTHEADER {
+    pub dwType: DWORD,
+    pub dwSize: DWORD,
+    pub hDevice: HANDLE,
+    pub wParam: WPARAM,
+}
+#[repr(C)]
+pub struct RAWMOUSE {
+    pub usFlags: USHORT,
+    /// Union with `usButtonFlags` & `usButtonData` (both `USHORT`).
+    pub ulButtons: ULONG,
+    pub ulRawButtons: ULONG,
+    pub lLastX: LONG,
+    pub lLastY: LONG,
+    pub ulExtraInformation: ULONG,
+}
+#[repr(C)]
+pub struct POINTER_INFO {
+    pub pointerType: POINTER_INPUT_TYPE,
+    pub pointerId: UINT,
+    pub frameId: UINT,
+    pub pointerFlags: UINT,
+    pub sourceDevice: HANDLE,
+    pub hwndTarget: HWND,
+    pub ptPixelLocation: POINT,
+    pub ptHimetricLocation: POINT,
+    pub ptPixelLocationRaw: POINT,
+    pub ptHimetricLocationRaw: POINT,
+    pub dwTime: DWORD,
+    pub historyCount: UINT,
+    pub InputData: INT,
+    pub dwKeyStates: DWORD,
+    pub PerformanceCount: c_ulonglong,
+    pub ButtonChangeType: c_int,
+}
+#[repr(C)]
+pub struct POINTER_PEN_INFO {
+    pub pointerInfo: POINTER_INFO,
+    pub penFlags: UINT,
+    pub penMask: UINT,
+    pub pressure: UINT,
+    pub rotation: UINT,
+    pub tiltX: INT,
+    pub tiltY: INT,
+}
+/// The `data` union is only declared as `RAWMOUSE`, as that's the only device we register for.
+#[repr(C)]
+pub struct RAWINPUT {
+    pub header: RAWINPUTHEADER,
+    pub mouse: RAWMOUSE,
+}
+#[repr(C)]
+pub struct DEVMODEW {
+    pub dmDeviceName: [WCHAR; CCHDEVICENAME],
+    pub dmSpecVersion: WORD,
+    pub dmDriverVersion: WORD,
+    pub dmSize: WORD,
+    pub dmDriverExtra: WORD,
+    pub dmFields: DWORD,
+    // (the display half of a union with printer fields)
+    pub dmPosition: POINT,
+    pub dmDisplayOrientation: DWORD,
+    pub dmDisplayFixedOutput: DWORD,
+    pub dmColor: c_short,
+    pub dmDuplex: c_short,
+    pub dmYResolution: c_short,
+    pub dmTTOption: c_short,
+    pub dmCollate: c_short,
+    pub dmFormName: [WCHAR; CCHDEVICENAME],
+    pub dmLogPixels: WORD,
+    pub dmBitsPerPel: DWORD,
+    pub dmPelsWidth: DWORD,
+    pub dmPelsHeight: DWORD,
+    pub dmDisplayFlags: DWORD,
+    pub dmDisplayFrequency: DWORD,
+    pub dmICMMethod: DWORD,
+    pub dmICMIntent: DWORD,
+    pub dmMediaType: DWORD,
+    pub dmDitherType: DWORD,
+    pub dmReserved1: DWORD,
+    pub dmReserved2: DWORD,
+    pub dmPanningWidth: DWORD,
+    pub dmPanningHeight: DWORD,
+}
+#[repr(C)]
+pub struct DISPLAY_DEVICEW {
+    pub cb: DWORD,
+    pub DeviceName: [WCHAR; 32],
+    pub DeviceString: [WCHAR; 128],
+    pub StateFlags: DWORD,
+    pub DeviceID: [WCHAR; 128],
+    pub DeviceKey: [WCHAR; 128],
+}
+#[repr(C)]
+pub struct MONITORINFOEXW {
+    pub cbSize: DWORD,
+    pub rcMonitor: RECT,
+    pub rcWork: RECT,
+    pub dwFlags: DWORD,
+    pub szDevice: [WCHAR; CCHDEVICENAME],
+}
+#[repr(C)]
 pub struct OSVERSIONINFOEXW {
     pub dwOSVersionInfoSize: DWORD,
     pub dwMajorVersion: DWORD,
@@ -257,6 +506,49 @@ pub struct OSVERSIONINFOEXW {
     pub wReserved: BYTE,
 }
 #[repr(C)]
+pub struct BITMAPINFOHEADER {
+    pub biSize: DWORD,
+    pub biWidth: LONG,
+    pub biHeight: LONG,
+    pub biPlanes: WORD,
+    pub biBitCount: WORD,
+    pub biCompression: DWORD,
+    pub biSizeImage: DWORD,
+    pub biXPelsPerMeter: LONG,
+    pub biYPelsPerMeter: LONG,
+    pub biClrUsed: DWORD,
+    pub biClrImportant: DWORD,
+}
+#[repr(C)]
+pub struct RGBQUAD {
+    pub rgbBlue: BYTE,
+    pub rgbGreen: BYTE,
+    pub rgbRed: BYTE,
+    pub rgbReserved: BYTE,
+}
+#[repr(C)]
+pub struct BITMAPINFO {
+    pub bmiHeader: BITMAPINFOHEADER,
+    pub bmiColors: [RGBQUAD; 1],
+}
+#[repr(C)]
+pub struct PAINTSTRUCT {
+    pub hdc: HDC,
+    pub fErase: BOOL,
+    pub rcPaint: RECT,
+    pub fRestore: BOOL,
+    pub fIncUpdate: BOOL,
+    pub rgbReserved: [BYTE; 32],
+}
+#[repr(C)]
+pub struct ICONINFO {
+    pub fIcon: BOOL,
+    pub xHotspot: DWORD,
+    pub yHotspot: DWORD,
+    pub hbmMask: HBITMAP,
+    pub hbmColor: HBITMAP,
+}
+#[repr(C)]
 pub struct TITLEBARINFO {
     pub cbSize: DWORD,
     pub rcTitleBar: RECT,
@@ -278,6 +570,15 @@ pub struct WNDCLASSEXW {
     pub hIconSm: HICON,
 }
 #[repr(C)]
+pub struct WINDOWPLACEMENT {
+    pub length: UINT,
+    pub flags: UINT,
+    pub showCmd: UINT,
+    pub ptMinPosition: POINT,
+    pub ptMaxPosition: POINT,
+    pub rcNormalPosition: RECT,
+}
+#[repr(C)]
 pub struct IMAGE_DOS_HEADER {
     e_magic: WORD,
     e_cblp: WORD,
@@ -300,6 +601,57 @@ pub struct IMAGE_DOS_HEADER {
     e_lfanew: LONG,
 }
 
+// COM interfaces, as a pointer to their table of methods
+#[repr(C)]
+pub struct IDataObject {
+    pub lpVtbl: *const IDataObjectVtbl,
+}
+/// Only the methods up to `GetData` are declared, as that's the only one used.
+#[repr(C)]
+pub struct IDataObjectVtbl {
+    pub QueryInterface:
+        unsafe extern "system" fn(This: *mut IDataObject, riid: *const GUID, ppvObject: *mut *mut c_void) -> HRESULT,
+    pub AddRef: unsafe extern "system" fn(This: *mut IDataObject) -> ULONG,
+    pub Release: unsafe extern "system" fn(This: *mut IDataObject) -> ULONG,
+    pub GetData: unsafe extern "system" fn(
+        This: *mut IDataObject,
+        pformatetcIn: *const FORMATETC,
+        pmedium: *mut STGMEDIUM,
+    ) -> HRESULT,
+}
+#[repr(C)]
+pub struct IDropTarget {
+    pub lpVtbl: *const IDropTargetVtbl,
+}
+#[repr(C)]
+pub struct IDropTargetVtbl {
+    pub QueryInterface:
+        unsafe extern "system" fn(This: *mut IDropTarget, riid: *const GUID, ppvObject: *mut *mut c_void) -> HRESULT,
+    pub AddRef: unsafe extern "system" fn(This: *mut IDropTarget) -> ULONG,
+    pub Release: unsafe extern "system" fn(This: *mut IDropTarget) -> ULONG,
+    pub DragEnter: unsafe extern "system" fn(
+        This: *mut IDropTarget,
+        pDataObj: *mut IDataObject,
+        grfKeyState: DWORD,
+        pt: POINTL,
+        pdwEffect: *mut DWORD,
+    ) -> HRESULT,
+    pub DragOver: unsafe extern "system" fn(
+        This: *mut IDropTarget,
+        grfKeyState: DWORD,
+        pt: POINTL,
+        pdwEffect: *mut DWORD,
+    ) -> HRESULT,
+    pub DragLeave: unsafe extern "system" fn(This: *mut IDropTarget) -> HRESULT,
+    pub Drop: unsafe extern "system" fn(
+        This: *mut IDropTarget,
+        pDataObj: *mut IDataObject,
+        grfKeyState: DWORD,
+        pt: POINTL,
+        pdwEffect: *mut DWORD,
+    ) -> HRESULT,
+}
+
 // Static Linked Functions
 #[link(name = "Kernel32")]
 extern "system" {
@@ -318,6 +670,83 @@ extern "system" {
     pub fn GetProcAddress(hModule: HMODULE, lpProcName: *const CHAR) -> FARPROC;
     pub fn LoadLibraryExA(lpLibFileName: *const CHAR, hFile: HANDLE, dwFlags: DWORD) -> HMODULE;
     pub fn VerSetConditionMask(ConditionMask: c_ulonglong, TypeMask: DWORD, Condition: BYTE) -> c_ulonglong;
+    pub fn GlobalAlloc(uFlags: UINT, dwBytes: SIZE_T) -> HGLOBAL;
+    pub fn GlobalFree(hMem: HGLOBAL) -> HGLOBAL;
+    pub fn GlobalLock(hMem: HGLOBAL) -> *mut c_void;
+    pub fn GlobalSize(hMem: HGLOBAL) -> SIZE_T;
+    pub fn GlobalUnlock(hMem: HGLOBAL) -> BOOL;
+}
+#[link(name = "Gdi32")]
+extern "system" {
+    pub fn CreateBitmap(
+        nWidth: c_int,
+        nHeight: c_int,
+        nPlanes: UINT,
+        nBitCount: UINT,
+        lpBits: *const c_void,
+    ) -> HBITMAP;
+    pub fn DeleteObject(ho: HANDLE) -> BOOL;
+    pub fn ChoosePixelFormat(hdc: HDC, ppfd: *const PIXELFORMATDESCRIPTOR) -> c_int;
+    pub fn DescribePixelFormat(hdc: HDC, iPixelFormat: c_int, nBytes: UINT, ppfd: *mut PIXELFORMATDESCRIPTOR) -> c_int;
+    pub fn GetPixelFormat(hdc: HDC) -> c_int;
+    pub fn SetPixelFormat(hdc: HDC, format: c_int, ppfd: *const PIXELFORMATDESCRIPTOR) -> BOOL;
+    pub fn SwapBuffers(hdc: HDC) -> BOOL;
+
+    // Drawing pixels for `present_pixels`
+    pub fn CombineRgn(hrgnDst: HRGN, hrgnSrc1: HRGN, hrgnSrc2: HRGN, iMode: c_int) -> c_int;
+    pub fn CreateRectRgn(x1: c_int, y1: c_int, x2: c_int, y2: c_int) -> HRGN;
+    pub fn SelectClipRgn(hdc: HDC, hrgn: HRGN) -> c_int;
+    pub fn SetDIBitsToDevice(
+        hdc: HDC,
+        xDest: c_int,
+        yDest: c_int,
+        w: DWORD,
+        h: DWORD,
+        xSrc: c_int,
+        ySrc: c_int,
+        StartScan: UINT,
+        cLines: UINT,
+        lpvBits: *const c_void,
+        lpbmi: *const BITMAPINFO,
+        ColorUse: UINT,
+    ) -> c_int;
+    pub fn SetStretchBltMode(hdc: HDC, mode: c_int) -> c_int;
+    pub fn StretchDIBits(
+        hdc: HDC,
+        xDest: c_int,
+        yDest: c_int,
+        DestWidth: c_int,
+        DestHeight: c_int,
+        xSrc: c_int,
+        ySrc: c_int,
+        SrcWidth: c_int,
+        SrcHeight: c_int,
+        lpBits: *const c_void,
+        lpbmi: *const BITMAPINFO,
+        iUsage: UINT,
+        rop: DWORD,
+    ) -> c_int;
+}
+#[link(name = "Imm32")]
+extern "system" {
+    pub fn ImmAssociateContextEx(hWnd: HWND, hIMC: HIMC, dwFlags: DWORD) -> BOOL;
+    pub fn ImmGetContext(hWnd: HWND) -> HIMC;
+    pub fn ImmReleaseContext(hWnd: HWND, hIMC: HIMC) -> BOOL;
+    pub fn ImmGetCompositionStringW(hIMC: HIMC, dwIndex: DWORD, lpBuf: *mut c_void, dwBufLen: DWORD) -> LONG;
+    pub fn ImmSetCandidateWindow(hIMC: HIMC, lpCandidate: *const CANDIDATEFORM) -> BOOL;
+    pub fn ImmSetCompositionWindow(hIMC: HIMC, lpCompForm: *const COMPOSITIONFORM) -> BOOL;
+}
+#[link(name = "Ole32")]
+extern "system" {
+    pub fn OleInitialize(pvReserved: *mut c_void) -> HRESULT;
+    pub fn OleUninitialize();
+    pub fn RegisterDragDrop(hwnd: HWND, pDropTarget: *mut IDropTarget) -> HRESULT;
+    pub fn RevokeDragDrop(hwnd: HWND) -> HRESULT;
+    pub fn ReleaseStgMedium(pmedium: *mut STGMEDIUM);
+}
+#[link(name = "Shell32")]
+extern "system" {
+    pub fn DragQueryFileW(hDrop: HDROP, iFile: UINT, lpszFile: *mut WCHAR, cch: UINT) -> UINT;
 }
 #[link(name = "User32")]
 extern "system" {
@@ -343,11 +772,40 @@ extern "system" {
     pub fn AdjustWindowRectEx(lpRect: *mut RECT, dwStyle: DWORD, bMenu: BOOL, dwExStyle: DWORD) -> BOOL;
     pub fn ClientToScreen(hWnd: HWND, lpPoint: *mut POINT) -> BOOL;
     pub fn GetClientRect(hWnd: HWND, lpRect: *mut RECT) -> BOOL;
+    pub fn ScreenToClient(hWnd: HWND, lpPoint: *mut POINT) -> BOOL;
     pub fn GetWindowRect(hWnd: HWND, lpRect: *mut RECT) -> BOOL;
+    pub fn IsIconic(hWnd: HWND) -> BOOL;
+    pub fn GetWindowPlacement(hWnd: HWND, lpwndpl: *mut WINDOWPLACEMENT) -> BOOL;
+    pub fn SetWindowPlacement(hWnd: HWND, lpwndpl: *const WINDOWPLACEMENT) -> BOOL;
     pub fn GetTitleBarInfo(hwnd: HWND, pti: *mut TITLEBARINFO) -> BOOL;
     pub fn SetWindowPos(hWnd: HWND, hWndInsertAfter: HWND, X: c_int, Y: c_int, cx: c_int, cy: c_int, uFlags: UINT) -> BOOL;
     pub fn WindowFromPoint(Point: POINT) -> HWND;
     pub fn DestroyWindow(hWnd: HWND) -> BOOL;
+    pub fn GetDC(hWnd: HWND) -> HDC;
+    pub fn ReleaseDC(hWnd: HWND, hDC: HDC) -> c_int;
+    pub fn BeginPaint(hWnd: HWND, lpPaint: *mut PAINTSTRUCT) -> HDC;
+    pub fn EndPaint(hWnd: HWND, lpPaint: *const PAINTSTRUCT) -> BOOL;
+
+    // Monitors & display settings
+    pub fn MonitorFromPoint(pt: POINT, dwFlags: DWORD) -> HMONITOR;
+    pub fn MonitorFromRect(lprc: *const RECT, dwFlags: DWORD) -> HMONITOR;
+    pub fn MonitorFromWindow(hwnd: HWND, dwFlags: DWORD) -> HMONITOR;
+    pub fn GetMonitorInfoW(hMonitor: HMONITOR, lpmi: *mut MONITORINFOEXW) -> BOOL;
+    pub fn EnumDisplayMonitors(hdc: HDC, lprcClip: *const RECT, lpfnEnum: MONITORENUMPROC, dwData: LPARAM) -> BOOL;
+    pub fn EnumDisplayDevicesW(
+        lpDevice: *const WCHAR,
+        iDevNum: DWORD,
+        lpDisplayDevice: *mut DISPLAY_DEVICEW,
+        dwFlags: DWORD,
+    ) -> BOOL;
+    pub fn EnumDisplaySettingsW(lpszDeviceName: *const WCHAR, iModeNum: DWORD, lpDevMode: *mut DEVMODEW) -> BOOL;
+    pub fn ChangeDisplaySettingsExW(
+        lpszDeviceName: *const WCHAR,
+        lpDevMode: *mut DEVMODEW,
+        hwnd: HWND,
+        dwflags: DWORD,
+        lParam: *mut c_void,
+    ) -> LONG;
 
     // Hooking API
     pub fn CallNextHookEx(hhk: HHOOK, nCode: c_int, wParam: WPARAM, lParam: LPARAM) -> LRESULT;
@@ -359,6 +817,7 @@ extern "system" {
     pub fn GetMessageW(lpMsg: *mut MSG, hWnd: HWND, wMsgFilterMin: UINT, wMsgFilterMax: UINT) -> BOOL;
     pub fn PostMessageW(hWnd: HWND, Msg: UINT, wParam: WPARAM, lParam: LPARAM) -> BOOL;
     pub fn SendMessageW(hWnd: HWND, Msg: UINT, wParam: WPARAM, lParam: LPARAM) -> LRESULT;
+    pub fn TranslateMessage(lpMsg: *const MSG) -> BOOL;
     pub fn DispatchMessageW(lpmsg: *const MSG) -> LRESULT;
     pub fn PostQuitMessage(nExitCode: c_int);
 
@@ -368,14 +827,35 @@ extern "system" {
 
     // Keyboard & mouse related
     pub fn ClipCursor(lpRect: *const RECT) -> BOOL;
+    pub fn GetRawInputData(
+        hRawInput: HRAWINPUT,
+        uiCommand: UINT,
+        pData: *mut c_void,
+        pcbSize: *mut UINT,
+        cbSizeHeader: UINT,
+    ) -> UINT;
+    pub fn RegisterRawInputDevices(pRawInputDevices: *const RAWINPUTDEVICE, uiNumDevices: UINT, cbSize: UINT) -> BOOL;
     pub fn SetCursorPos(X: c_int, Y: c_int) -> BOOL;
     pub fn GetCursorPos(lpPoint: *mut POINT) -> BOOL;
     pub fn SetCapture(hWnd: HWND) -> HWND;
     pub fn GetCapture() -> HWND;
     pub fn ReleaseCapture() -> BOOL;
     pub fn GetAsyncKeyState(vKey: c_int) -> SHORT;
+    pub fn GetKeyState(nVirtKey: c_int) -> SHORT;
     pub fn GetSystemMetrics(nIndex: c_int) -> c_int;
     pub fn SetCursor(hCursor: HCURSOR) -> HCURSOR;
+    pub fn CreateIconIndirect(piconinfo: *const ICONINFO) -> HICON;
+    pub fn DestroyIcon(hIcon: HICON) -> BOOL;
+
+    // Clipboard
+    pub fn OpenClipboard(hWndNewOwner: HWND) -> BOOL;
+    pub fn CloseClipboard() -> BOOL;
+    pub fn EmptyClipboard() -> BOOL;
+    pub fn EnumClipboardFormats(format: UINT) -> UINT;
+    pub fn GetClipboardData(uFormat: UINT) -> HANDLE;
+    pub fn SetClipboardData(uFormat: UINT, hMem: HANDLE) -> HANDLE;
+    pub fn GetClipboardFormatNameW(format: UINT, lpszFormatName: *mut WCHAR, cchMaxCount: c_int) -> c_int;
+    pub fn RegisterClipboardFormatW(lpszFormat: *const WCHAR) -> UINT;
 
     // Misc legacy garbage
     pub fn EnableMenuItem(hMenu: HMENU, uIDEnableItem: UINT, uEnable: UINT) -> BOOL;
@@ -450,6 +930,14 @@ dyn_link! {
         },
 
         "User32.dll" {
+            /// (Vista+)
+            /// Signs a window up for `WM_CLIPBOARDUPDATE`, which is sent whenever the clipboard changes.
+            fn AddClipboardFormatListener(hwnd: HWND) -> BOOL;
+
+            /// (Vista+)
+            /// Undoes `AddClipboardFormatListener`.
+            fn RemoveClipboardFormatListener(hwnd: HWND) -> BOOL;
+
             // (Win10 1607+)
             // It's a version of AdjustWindowRectEx with DPI, but they added it 7 years late.
             // The DPI parameter accounts for scaled non-client areas, not to scale client areas.
@@ -465,6 +953,18 @@ dyn_link! {
             /// Enables automatic scaling of the non-client area as a hack for PMv1 DPI mode.
             fn EnableNonClientDpiScaling(hwnd: HWND) -> BOOL;
 
+            /// (Win8+)
+            /// Gets the type, position and state of a touch or pen contact from a `WM_POINTER*` message.
+            fn GetPointerInfo(pointerId: UINT, pointerInfo: *mut POINTER_INFO) -> BOOL;
+
+            /// (Win8+)
+            /// Like `GetPointerInfo`, but with the pressure, tilt and buttons of a pen.
+            fn GetPointerPenInfo(pointerId: UINT, penInfo: *mut POINTER_PEN_INFO) -> BOOL;
+
+            /// (Win10 1607+)
+            /// Version of GetSystemMetrics for a specific DPI, as the original only knows the system DPI.
+            fn GetSystemMetricsForDpi(nIndex: c_int, dpi: UINT) -> c_int;
+
             /// (Vista+)
             /// First introduction of DPI awareness, this function enables System-Aware DPI.
             fn SetProcessDPIAware() -> BOOL;
@@ -492,6 +992,177 @@ impl Win32DL {
     }
 }
 
+// OpenGL (WGL), with `opengl32.dll` only opened once a context is made
+pub const PFD_DOUBLEBUFFER: DWORD = 0x00000001;
+pub const PFD_DRAW_TO_WINDOW: DWORD = 0x00000004;
+pub const PFD_MAIN_PLANE: BYTE = 0;
+pub const PFD_SUPPORT_OPENGL: DWORD = 0x00000020;
+pub const PFD_TYPE_RGBA: BYTE = 0;
+pub const WGL_ALPHA_BITS_ARB: c_int = 0x201B;
+pub const WGL_COLOR_BITS_ARB: c_int = 0x2014;
+pub const WGL_CONTEXT_COMPATIBILITY_PROFILE_BIT_ARB: c_int = 0x00000002;
+pub const WGL_CONTEXT_CORE_PROFILE_BIT_ARB: c_int = 0x00000001;
+pub const WGL_CONTEXT_DEBUG_BIT_ARB: c_int = 0x00000001;
+pub const WGL_CONTEXT_FLAGS_ARB: c_int = 0x2094;
+pub const WGL_CONTEXT_MAJOR_VERSION_ARB: c_int = 0x2091;
+pub const WGL_CONTEXT_MINOR_VERSION_ARB: c_int = 0x2092;
+pub const WGL_CONTEXT_PROFILE_MASK_ARB: c_int = 0x9126;
+pub const WGL_DEPTH_BITS_ARB: c_int = 0x2022;
+pub const WGL_DOUBLE_BUFFER_ARB: c_int = 0x2011;
+pub const WGL_DRAW_TO_WINDOW_ARB: c_int = 0x2001;
+pub const WGL_FRAMEBUFFER_SRGB_CAPABLE_ARB: c_int = 0x20A9;
+pub const WGL_PIXEL_TYPE_ARB: c_int = 0x2013;
+pub const WGL_SAMPLE_BUFFERS_ARB: c_int = 0x2041;
+pub const WGL_SAMPLES_ARB: c_int = 0x2042;
+pub const WGL_STENCIL_BITS_ARB: c_int = 0x2023;
+pub const WGL_SUPPORT_OPENGL_ARB: c_int = 0x2010;
+pub const WGL_TYPE_RGBA_ARB: c_int = 0x202B;
+
+#[repr(C)]
+pub struct PIXELFORMATDESCRIPTOR {
+    pub nSize: WORD,
+    pub nVersion: WORD,
+    pub dwFlags: DWORD,
+    pub iPixelType: BYTE,
+    pub cColorBits: BYTE,
+    pub cRedBits: BYTE,
+    pub cRedShift: BYTE,
+    pub cGreenBits: BYTE,
+    pub cGreenShift: BYTE,
+    pub cBlueBits: BYTE,
+    pub cBlueShift: BYTE,
+    pub cAlphaBits: BYTE,
+    pub cAlphaShift: BYTE,
+    pub cAccumBits: BYTE,
+    pub cAccumRedBits: BYTE,
+    pub cAccumGreenBits: BYTE,
+    pub cAccumBlueBits: BYTE,
+    pub cAccumAlphaBits: BYTE,
+    pub cDepthBits: BYTE,
+    pub cStencilBits: BYTE,
+    pub cAuxBuffers: BYTE,
+    pub iLayerType: BYTE,
+    pub bReserved: BYTE,
+    pub dwLayerMask: DWORD,
+    pub dwVisibleMask: DWORD,
+    pub dwDamageMask: DWORD,
+}
+
+dyn_link! {
+    pub struct WglDL(dlopen => HMODULE | GetProcAddress) {
+        "opengl32.dll" {
+            fn wglCreateContext(hdc: HDC) -> HGLRC;
+            fn wglDeleteContext(hglrc: HGLRC) -> BOOL;
+            fn wglGetCurrentContext() -> HGLRC;
+            fn wglGetCurrentDC() -> HDC;
+            fn wglMakeCurrent(hdc: HDC, hglrc: HGLRC) -> BOOL;
+        },
+    }
+}
+
+impl WglDL {
+    pub unsafe fn link() -> Option<Self> {
+        let prev_error = GetLastError();
+        let instance = Self::_link();
+        SetLastError(prev_error);
+        let _ = instance.wglCreateContext?;
+        Some(instance)
+    }
+}
+
+// The WGL extensions, which can only be looked up while a context is current.
+dyn_link! {
+    pub struct WglExtDL(dlopen => HMODULE | wgl_get_proc_address) {
+        "opengl32.dll" {
+            /// (WGL_ARB_pixel_format)
+            fn wglChoosePixelFormatARB(
+                hdc: HDC,
+                piAttribIList: *const c_int,
+                pfAttribFList: *const f32,
+                nMaxFormats: UINT,
+                piFormats: *mut c_int,
+                nNumFormats: *mut UINT,
+            ) -> BOOL;
+
+            /// (WGL_ARB_create_context)
+            fn wglCreateContextAttribsARB(hDC: HDC, hShareContext: HGLRC, attribList: *const c_int) -> HGLRC;
+
+            /// (WGL_EXT_swap_control)
+            fn wglSwapIntervalEXT(interval: c_int) -> BOOL;
+        },
+    }
+}
+
+impl WglExtDL {
+    pub unsafe fn link() -> Self {
+        let prev_error = GetLastError();
+        let instance = Self::_link();
+        SetLastError(prev_error);
+        instance
+    }
+}
+
+/// Opens `opengl32.dll`, or rather increases its reference count, as it's already open with `WglDL`.
+#[inline]
+pub unsafe fn opengl32() -> HMODULE {
+    dlopen(c_string!("opengl32.dll").as_ptr().cast())
+}
+
+/// Looks up an OpenGL function or WGL extension, which `wglGetProcAddress` has for everything past
+/// OpenGL 1.1, and `opengl32.dll` exports for the rest. Only works while a context is current.
+pub unsafe fn wgl_get_proc_address(opengl32: HMODULE, name: *const CHAR) -> FARPROC {
+    let wgl_get_proc_address = mem::transmute::<FARPROC, Option<unsafe extern "system" fn(*const CHAR) -> FARPROC>>(
+        GetProcAddress(opengl32, c_string!("wglGetProcAddress").as_ptr().cast()),
+    );
+    if let Some(wgl_get_proc_address) = wgl_get_proc_address {
+        // Some drivers return a small number or -1 instead of null, which are just as invalid
+        let address = wgl_get_proc_address(name);
+        if !matches!(address as isize, -1..=3) {
+            return address
+        }
+    }
+    GetProcAddress(opengl32, name)
+}
+
+// Vulkan (vulkan/vulkan_win32.h), with the loader only opened if a surface is made without it
+pub const VK_KHR_WIN32_SURFACE_EXTENSION_NAME: &CStr =
+    unsafe { CStr::from_bytes_with_nul_unchecked(c_string!("VK_KHR_win32_surface")) };
+pub const VK_STRUCTURE_TYPE_WIN32_SURFACE_CREATE_INFO_KHR: VkStructureType = 1000009000;
+
+#[repr(C)]
+pub struct VkWin32SurfaceCreateInfoKHR {
+    pub sType: VkStructureType,
+    pub pNext: *const c_void,
+    pub flags: VkFlags,
+    pub hinstance: HINSTANCE,
+    pub hwnd: HWND,
+}
+
+pub type PFN_vkCreateWin32SurfaceKHR = unsafe extern "system" fn(
+    instance: VkInstance,
+    pCreateInfo: *const VkWin32SurfaceCreateInfoKHR,
+    pAllocator: *const c_void,
+    pSurface: *mut VkSurfaceKHR,
+) -> VkResult;
+
+dyn_link! {
+    pub struct VulkanDL(dlopen => HMODULE | GetProcAddress) {
+        "vulkan-1.dll" {
+            fn vkGetInstanceProcAddr(instance: VkInstance, pName: *const c_char) -> Option<PFN_vkVoidFunction>;
+        },
+    }
+}
+
+impl VulkanDL {
+    pub unsafe fn link() -> Self {
+        // Same as with `Win32DL`, not having Vulkan installed isn't an error worth leaving around
+        let prev_error = GetLastError();
+        let instance = Self::_link();
+        SetLastError(prev_error);
+        instance
+    }
+}
+
 // (Get/Set)(Class/Window)Long(A/W) all took LONG, a 32-bit type.
 // When MS went from 32 to 64 bit, they realized how big of a mistake this was,
 // seeing as some of those values need to be as big as a pointer is (like size_t).
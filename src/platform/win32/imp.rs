@@ -8,16 +8,37 @@
 )]
 
 use crate::{
+    clipboard::{self, ClipboardContents, ClipboardFormat},
     error::Error,
-    event::{CloseReason, Event},
-    monitor::{Scale, Size},
-    util::{sync::{self, Condvar, Mutex}, FixedVec, LazyCell},
-    window::{self, Cursor, WindowBuilder},
+    gl::{GlContextBuilder, GlProfile},
+    event::{CloseReason, Event, EventPaths},
+    monitor::{Monitor, Point, Rect, Scale, Size, VideoMode},
+    util::{self, sync::{self, Condvar, Mutex}, CreateSender, CreateSlot, EventQueue, FixedVec, LazyCell},
+    vulkan::{self, PFN_vkGetInstanceProcAddr, PFN_vkVoidFunction, VkInstance, VkSurfaceKHR},
+    window::{self, Cursor, CursorGrab, CursorIcon, CursorImage, Icon, WindowBuilder},
 };
-use std::{cell::UnsafeCell, mem, num::NonZeroI32, ops, ptr, sync::{atomic, Arc}, thread};
+use std::{
+    cell::UnsafeCell,
+    ffi::{CStr, OsString},
+    mem,
+    num::NonZeroI32,
+    ops,
+    os::windows::ffi::OsStringExt,
+    path::PathBuf,
+    ptr,
+    slice,
+    sync::{atomic, Arc},
+    thread,
+    time::Duration,
+};
+
+#[cfg(feature = "raw-window-handle")]
+use raw_window_handle::{HandleError, RawDisplayHandle, RawWindowHandle, Win32WindowHandle, WindowsDisplayHandle};
+#[cfg(feature = "raw-window-handle")]
+use std::num::NonZeroIsize;
 
 #[cfg(feature = "input")]
-use crate::{event::{Key, MouseButton}, monitor::Point};
+use crate::event::{EventText, ImeEvent, Key, Modifiers, MouseButton, Pen, ScanCode, ScrollDelta, Touch, TouchPhase};
 
 // TODO: Maybe deglob
 use crate::platform::win32::ffi::*;
@@ -29,6 +50,12 @@ static CLASS_REGISTRY_LOCK: LazyCell<Mutex<()>> = LazyCell::new(|| Mutex::new(()
 // Global immutable struct containing dynamically acquired API state
 static WIN32: LazyCell<Win32State> = LazyCell::new(Win32State::new);
 
+/// The Vulkan loader, for surfaces that are made without being handed `vkGetInstanceProcAddr`.
+static VULKAN: LazyCell<VulkanDL> = LazyCell::new(|| unsafe { VulkanDL::link() });
+
+/// The base WGL functions, from `opengl32.dll`, which is only loaded once an OpenGL context is made.
+static WGL: LazyCell<Option<WglDL>> = LazyCell::new(|| unsafe { WglDL::link() });
+
 /// TODO: yeah
 const BASE_DPI: UINT = 96;
 /// TODO: Measure this
@@ -39,16 +66,27 @@ const HOOKPROC_MARKER: &[u8; 4] = b"viri";
 // Custom window messages (see `window_proc` for docs)
 const RAMEN_WM_DROP:          UINT = WM_USER + 0;
 const RAMEN_WM_EXECUTE:       UINT = WM_USER + 1; // TODO:
-// const RAMEN_WM_SETBORDERLESS: UINT = WM_USER + 2; // TODO:
+const RAMEN_WM_SETBORDERLESS: UINT = WM_USER + 2;
 const RAMEN_WM_SETCONTROLS:   UINT = WM_USER + 3;
 const RAMEN_WM_SETCURSOR:     UINT = WM_USER + 4;
-// const RAMEN_WM_SETFULLSCREEN: UINT = WM_USER + 5; // TODO:
+const RAMEN_WM_SETFULLSCREEN: UINT = WM_USER + 5;
 const RAMEN_WM_SETTEXT_ASYNC: UINT = WM_USER + 6;
 const RAMEN_WM_SETTHICKFRAME: UINT = WM_USER + 7;
 const RAMEN_WM_SETINNERSIZE:  UINT = WM_USER + 8;
 const RAMEN_WM_GETINNERSIZE:  UINT = WM_USER + 9;
 const RAMEN_WM_ISDPILOGICAL:  UINT = WM_USER + 10;
 const RAMEN_WM_SETMAXIMIZED:  UINT = WM_USER + 11;
+const RAMEN_WM_SETPOSITION:   UINT = WM_USER + 12;
+const RAMEN_WM_GETOUTERPOS:   UINT = WM_USER + 13;
+const RAMEN_WM_GETINNERPOS:   UINT = WM_USER + 14;
+const RAMEN_WM_SETTEXTINPUT:  UINT = WM_USER + 15;
+const RAMEN_WM_SETIMEALLOWED: UINT = WM_USER + 16;
+const RAMEN_WM_SETIMEAREA:    UINT = WM_USER + 17;
+const RAMEN_WM_SETRAWMOUSE:   UINT = WM_USER + 18;
+const RAMEN_WM_SETCURSORGRAB: UINT = WM_USER + 19;
+const RAMEN_WM_SETCURSORPOS:  UINT = WM_USER + 20;
+const RAMEN_WM_SETICON:       UINT = WM_USER + 21;
+const RAMEN_WM_PRESENT:       UINT = WM_USER + 22;
 
 /// Retrieves the base module [`HINSTANCE`].
 #[inline]
@@ -196,6 +234,60 @@ fn cursor_to_int_resource(cursor: Cursor) -> *const WCHAR {
     }
 }
 
+/// Makes the `HCURSOR` for a cursor at a DPI. System cursors are shared, but custom ones are made here,
+/// and have to be destroyed with `DestroyIcon`.
+unsafe fn load_cursor(cursor: &CursorIcon, dpi: UINT) -> HCURSOR {
+    match cursor {
+        CursorIcon::System(cursor) => {
+            let rsrc = cursor_to_int_resource(*cursor);
+
+            // `LoadImageW` is not only superseding `LoadCursorW` but it's ~20µs faster. Wow, use this!
+            if !rsrc.is_null() {
+                LoadImageW(ptr::null_mut(), rsrc, IMAGE_CURSOR, 0, 0, LR_DEFAULTSIZE | LR_SHARED).cast()
+            } else {
+                ptr::null_mut()
+            }
+        },
+        CursorIcon::Custom(cursor) => create_cursor(cursor.image(dpi as f64 / BASE_DPI as f64)),
+    }
+}
+
+/// Creates a cursor out of an RGBA image, which is null if that fails.
+unsafe fn create_cursor(image: &CursorImage) -> HCURSOR {
+    create_icon(&image.rgba, image.size, Some(image.hotspot))
+}
+
+/// Creates an icon out of an RGBA image, or a cursor if it has a hotspot, which is null if that fails.
+unsafe fn create_icon(rgba: &[u8], (width, height): (u32, u32), hotspot: Option<(u32, u32)>) -> HICON {
+    // The colour bitmap is BGRA, and having an alpha channel means the mask is unused, but it's still required.
+    // Monochrome bitmap rows are padded to 16 bits.
+    let bgra: Vec<u8> = rgba.chunks_exact(4).flat_map(|p| [p[2], p[1], p[0], p[3]]).collect();
+    let mask = vec![0u8; (width as usize).div_ceil(16) * 2 * height as usize];
+    let color = CreateBitmap(width as c_int, height as c_int, 1, 32, bgra.as_ptr().cast());
+    let mask = CreateBitmap(width as c_int, height as c_int, 1, 1, mask.as_ptr().cast());
+    let icon = if !color.is_null() && !mask.is_null() {
+        let (x_hotspot, y_hotspot) = hotspot.unwrap_or((0, 0));
+        let info = ICONINFO {
+            fIcon: if hotspot.is_none() { TRUE } else { FALSE },
+            xHotspot: x_hotspot,
+            yHotspot: y_hotspot,
+            hbmMask: mask,
+            hbmColor: color,
+        };
+        CreateIconIndirect(&info)
+    } else {
+        ptr::null_mut()
+    };
+
+    // The icon has its own copies of the bitmaps
+    for bitmap in [color, mask] {
+        if !bitmap.is_null() {
+            let _ = DeleteObject(bitmap.cast());
+        }
+    }
+    icon
+}
+
 #[inline]
 fn rect_to_size2d(rect: &RECT) -> (LONG, LONG) {
     (rect.right - rect.left, rect.bottom - rect.top)
@@ -207,14 +299,14 @@ fn style_as_win32(style: &window::Style) -> DWORD {
     let mut dword = 0;
 
     if style.borderless {
-        // TODO: Why does this just not work without THICKFRAME? Borderless is dumb.
-        dword |= WS_POPUP | WS_THICKFRAME;
+        // Any of `WS_BORDER`, `WS_DLGFRAME` (`WS_CAPTION` is both) or `WS_THICKFRAME` draw a frame,
+        // so borderless windows can't be resized by dragging the edges, only programmatically.
+        dword |= WS_POPUP;
     } else {
         dword |= WS_OVERLAPPED | WS_BORDER | WS_CAPTION;
-    }
-
-    if style.resizable {
-        dword |= WS_THICKFRAME;
+        if style.resizable {
+            dword |= WS_THICKFRAME;
+        }
     }
 
     if style.visible {
@@ -234,6 +326,25 @@ fn style_as_win32(style: &window::Style) -> DWORD {
     dword
 }
 
+/// Gets the style bits used while fullscreen, which leave out the entire window frame.
+/// The system menu is kept around, as it's still reachable from the taskbar and Alt+Space.
+fn fullscreen_style_as_win32(style: &window::Style) -> DWORD {
+    let mut dword = WS_POPUP;
+
+    if style.visible {
+        dword |= WS_VISIBLE;
+    }
+
+    if let Some(controls) = &style.controls {
+        if controls.minimize {
+            dword |= WS_MINIMIZEBOX;
+        }
+        dword |= WS_SYSMENU;
+    }
+
+    dword
+}
+
 /// Gets the extended window style bits.
 fn style_as_win32_ex(style: &window::Style) -> DWORD {
     let mut dword = 0;
@@ -257,10 +368,21 @@ unsafe fn ping_window_frame(hwnd: HWND) {
     let _ = SetWindowPos(hwnd, ptr::null_mut(), 0, 0, 0, 0, MASK);
 }
 
-/// Convenience function to take a `window::Style` and slap it on a HWND.
-fn update_window_style(hwnd: HWND, style: &window::Style) {
-    let dword = style_as_win32(&style);
-    let dword_ex = style_as_win32_ex(&style);
+/// Gets the size the window frame adds onto the client area with a given style.
+unsafe fn frame_size(style: &window::Style, dpi: UINT) -> (LONG, LONG) {
+    let (dword, dword_ex) = (style_as_win32(style), style_as_win32_ex(style));
+    adjust_window_for_dpi(WIN32.get(), Size::Physical(0, 0), dword, dword_ex, dpi)
+}
+
+/// Convenience function to take a `window::Style` and slap it on a HWND, minding fullscreen.
+fn update_window_style(hwnd: HWND, user_data: &WindowImplData) {
+    let style = &user_data.style;
+    let dword = if user_data.fullscreen.is_some() {
+        fullscreen_style_as_win32(style)
+    } else {
+        style_as_win32(style)
+    };
+    let dword_ex = style_as_win32_ex(style);
     unsafe {
         let _ = set_window_data(hwnd, GWL_STYLE, dword as usize);
         let _ = set_window_data(hwnd, GWL_EXSTYLE, dword_ex as usize);
@@ -268,6 +390,17 @@ fn update_window_style(hwnd: HWND, style: &window::Style) {
     }
 }
 
+/// Checks whether a window class name belongs to one of the system classes.
+///
+/// Source: https://docs.microsoft.com/en-us/windows/win32/winmsg/about-window-classes#system-classes
+fn is_reserved_class_name(class_name: &str) -> bool {
+    const RESERVED: &[&str] = &[
+        "Button", "ComboBox", "Edit", "ListBox", "MDIClient", "ScrollBar", "Static",
+        "ComboLBox", "DDEMLEvent", "Message", "#32768", "#32769", "#32770", "#32771", "#32772",
+    ];
+    RESERVED.iter().any(|name| name.eq_ignore_ascii_case(class_name))
+}
+
 /// Converts a &str to an LPCWSTR-compatible wide string.
 ///
 /// If the length is 0 (aka `*retv == 0x00`) then no allocation was made (it points to a static NULL).
@@ -396,6 +529,40 @@ pub struct WindowImplData {
     /// The cursor sent to `WM_SETCURSOR`
     cursor: HCURSOR,
 
+    /// What `cursor` was made from, which is owned by us if it's custom.
+    cursor_icon: CursorIcon,
+
+    /// How the cursor is held inside the window while it's focused.
+    cursor_grab: CursorGrab,
+
+    /// Where the cursor is held while locked, relative to the client area.
+    cursor_lock_position: (LONG, LONG),
+
+    /// Whether `ClipCursor` was called by us, so it's only undone if it was ours.
+    is_cursor_clipped: bool,
+
+    /// The icon shown in the title bar and taskbar, if not the default one.
+    icon: Option<Icon>,
+
+    /// Small and big `HICON`s made from `icon` for the current DPI, which are owned by us.
+    icon_handles: [HICON; 2],
+
+    /// The last image given to `present_pixels` and its size, which is redrawn on `WM_PAINT`.
+    frame: Vec<u32>,
+    frame_size: (u32, u32),
+
+    /// Last known position of the window frame, which isn't updated while minimized.
+    outer_position: (LONG, LONG),
+
+    /// The fullscreen mode the window is in, if it is.
+    fullscreen: Option<window::Fullscreen>,
+
+    /// Device name of the monitor which had its video mode changed for exclusive fullscreen.
+    fullscreen_device: Option<[WCHAR; CCHDEVICENAME]>,
+
+    /// Where the window was (and if it was maximized) before becoming fullscreen.
+    restore_placement: WINDOWPLACEMENT,
+
     /// Whether things should be scaled according to DPI.
     is_dpi_logical: bool,
 
@@ -407,12 +574,39 @@ pub struct WindowImplData {
     style: window::Style,
 
     // Very lightweight event-swap system...
-    // Read `Self::push_event` for more info
-    ev_buf_sync: Mutex<bool>,
-    ev_buf_ping: Condvar,
-    ev_buf_is_primary: bool,
-    ev_buf_primary: FixedVec<Event, MAX_EVENTS_PER_SWAP>,
-    ev_buf_secondary: FixedVec<Event, MAX_EVENTS_PER_SWAP>,
+    // Read `EventQueue::push_many` for more info
+    event_queue: EventQueue<Event, MAX_EVENTS_PER_SWAP>,
+
+    /// Whether key messages are translated into `WM_CHAR` in the message loop.
+    #[cfg(feature = "input")]
+    text_input: bool,
+
+    /// The first half of a UTF-16 surrogate pair, which `WM_CHAR` sends one at a time.
+    #[cfg(feature = "input")]
+    high_surrogate: Option<u16>,
+
+    /// Whether the IME may be used, which it only is while text input is enabled as well.
+    #[cfg(feature = "input")]
+    ime_allowed: bool,
+
+    /// The text area that the IME should avoid covering, relative to the client area.
+    #[cfg(feature = "input")]
+    ime_cursor_area: Option<(Point, Size)>,
+
+    /// Whether the mouse is registered for raw input, see `Window::set_raw_mouse_motion`.
+    #[cfg(feature = "input")]
+    raw_mouse_motion: bool,
+
+    /// Mouse buttons that are down, which the mouse is captured for until they're all released.
+    #[cfg(feature = "input")]
+    held_buttons: Vec<MouseButton>,
+
+    /// Whether touch is left to the system to also send as mouse messages.
+    #[cfg(feature = "input")]
+    touch_emulates_mouse: bool,
+
+    /// Whether the window is registered as a drop target, which has to be revoked before it's destroyed.
+    accept_drops: bool,
 
     // State flag dump
     is_focused: bool,
@@ -423,14 +617,24 @@ pub struct WindowImplData {
 pub fn spawn_window(builder: &WindowBuilder) -> Result<WindowImpl, Error> {
     let builder = builder.clone();
 
+    // Registering a system class name would "succeed" by finding the existing class,
+    // which has its own window procedure that knows nothing about us
+    let class_name = builder.class_name.as_ref();
+    if class_name.is_empty() {
+        return Err(Error::InvalidParameter("class name is empty"))
+    }
+    if is_reserved_class_name(class_name) {
+        return Err(Error::InvalidParameter("class name is reserved by the system"))
+    }
+
     // Convert class name & title to `WCHAR` string for Win32
     // This and the `recv` Arc are the only allocations, none in the thread
     let mut class_name_buf = Vec::new();
     let mut title_buf = Vec::new();
     let class_name = str_to_wstr(builder.class_name.as_ref(), &mut class_name_buf) as usize;
     let title = str_to_wstr(builder.title.as_ref(), &mut title_buf) as usize;
-    let recv = Arc::new((Mutex::new(Option::<Result<WindowImpl, Error>>::None), Condvar::new()));
-    let recv2 = Arc::clone(&recv); // remote thread's handle object
+    let recv: Arc<CreateSlot<WindowImpl>> = Arc::new((Mutex::new(None), Condvar::new()));
+    let sender = CreateSender(Arc::clone(&recv)); // remote thread's handle object
 
     let thread = thread::spawn(move || unsafe {
         // HACK: Since Rust doesn't trust us to share pointers, we move a `usize`
@@ -462,44 +666,71 @@ pub fn spawn_window(builder: &WindowBuilder) -> Result<WindowImpl, Error> {
             class.hCursor = ptr::null_mut();
             class.hbrBackground = ptr::null_mut();
             class.lpszMenuName = ptr::null_mut();
-            // TODO: Filter reserved class names
             class.lpszClassName = class_name;
             class.hIconSm = ptr::null_mut();
 
-            // _: The fields on `WNDCLASSEXW` are known to be valid
-            let _ = RegisterClassExW(class);
+            if RegisterClassExW(class) == 0 {
+                sender.send(Err(Error::ClassRegistration(GetLastError())));
+                return
+            }
         }
         mem::drop(class_registry_lock);
 
-        let dpi = BASE_DPI; // TODO:
+        // Windows without a set position are placed on the primary monitor, and logical positions
+        // are relative to its DPI, so that's where the window's initial DPI comes from either way
+        let win32 = WIN32.get();
+        let primary_dpi = monitor_dpi(win32, MonitorFromPoint(POINT { x: 0, y: 0 }, MONITOR_DEFAULTTOPRIMARY));
+        let ((pos_x, pos_y), dpi) = match builder.position {
+            Some(position) => {
+                let (x, y) = position.as_physical(primary_dpi as f64 / BASE_DPI as f64);
+                ((x, y), monitor_dpi(win32, MonitorFromPoint(POINT { x, y }, MONITOR_DEFAULTTONEAREST)))
+            },
+            None => ((CW_USEDEFAULT, CW_USEDEFAULT), primary_dpi),
+        };
         let style = style_as_win32(&builder.style);
         let style_ex = style_as_win32_ex(&builder.style);
 
-        let (width, height) = adjust_window_for_dpi(WIN32.get(), builder.inner_size, style, style_ex, dpi);
-        let (pos_x, pos_y) = (CW_USEDEFAULT, CW_USEDEFAULT);
+        let (width, height) = adjust_window_for_dpi(win32, builder.inner_size, style, style_ex, dpi);
 
         // Special
         let user_data: UnsafeCell<WindowImplData> = UnsafeCell::new(WindowImplData {
             client_area_size: builder.inner_size.as_physical(dpi as f64 / BASE_DPI as f64),
             close_reason: None, // unknown
             current_dpi: dpi,
-            cursor: {
-                let rsrc = cursor_to_int_resource(builder.cursor);
-                if !rsrc.is_null() {
-                    LoadImageW(ptr::null_mut(), rsrc, IMAGE_CURSOR, 0, 0, LR_DEFAULTSIZE | LR_SHARED).cast()
-                } else {
-                    ptr::null_mut()
-                }
-            },
+            cursor: load_cursor(&builder.cursor, dpi),
+            cursor_icon: builder.cursor.clone(),
+            cursor_grab: CursorGrab::None,
+            cursor_lock_position: (0, 0),
+            is_cursor_clipped: false,
+            icon: None, // set after creation
+            icon_handles: [ptr::null_mut(); 2],
+            frame: Vec::new(),
+            frame_size: (0, 0),
+            fullscreen: None, // set after creation
+            fullscreen_device: None,
+            restore_placement: mem::zeroed(),
+            outer_position: (0, 0), // set in `WM_CREATE`
             is_dpi_logical: matches!(builder.inner_size, Size::Logical(..)),
             destroy_flag: atomic::AtomicBool::new(false),
             style: builder.style.clone(),
 
-            ev_buf_sync: Mutex::new(false),
-            ev_buf_ping: Condvar::new(),
-            ev_buf_is_primary: true,
-            ev_buf_primary: FixedVec::new(),
-            ev_buf_secondary: FixedVec::new(),
+            event_queue: EventQueue::new(),
+
+            #[cfg(feature = "input")]
+            text_input: builder.text_input,
+            #[cfg(feature = "input")]
+            high_surrogate: None,
+            #[cfg(feature = "input")]
+            ime_allowed: builder.ime_allowed,
+            #[cfg(feature = "input")]
+            ime_cursor_area: None,
+            #[cfg(feature = "input")]
+            raw_mouse_motion: false,
+            #[cfg(feature = "input")]
+            held_buttons: Vec::new(),
+            #[cfg(feature = "input")]
+            touch_emulates_mouse: builder.touch_emulates_mouse,
+            accept_drops: false, // set after creation
 
             is_focused: false,
             is_maximized: false,
@@ -527,27 +758,44 @@ pub fn spawn_window(builder: &WindowBuilder) -> Result<WindowImpl, Error> {
         );
 
         if hwnd.is_null() && create_params.error.is_none() {
-            // TODO: Push create failure
+            create_params.error = Some(Error::WindowCreation(GetLastError()));
         }
 
-        let (mutex, condvar) = &*recv2;
-        let mut lock = sync::mutex_lock(&mutex);
+        // Kept here (and not in `user_data`) as OLE calls into it by pointer, outliving the window
+        let mut drop_target = None;
+
         if let Some(err) = create_params.error.take() {
-            *lock = Some(Err(err));
-            return // early return (dropped by caller)
+            sender.send(Err(err));
+            return // early return (joined by caller)
         } else {
-            *lock = Some(Ok(WindowImpl {
+            if builder.fullscreen.is_some() {
+                set_fullscreen(hwnd, &mut *user_data.get(), builder.fullscreen.clone());
+            }
+            if builder.icon.is_some() {
+                set_icon(hwnd, &mut *user_data.get(), builder.icon.clone());
+            }
+            #[cfg(feature = "input")]
+            {
+                update_ime_context(hwnd, &*user_data.get());
+                if builder.raw_mouse_motion {
+                    set_raw_mouse_motion(hwnd, &mut *user_data.get(), true);
+                }
+            }
+            if builder.accept_drops {
+                drop_target = register_drop_target(hwnd);
+                (*user_data.get()).accept_drops = drop_target.is_some();
+            }
+            let _ = WIN32.dl.AddClipboardFormatListener(hwnd);
+            sender.send(Ok(WindowImpl {
                 hwnd,
                 thread: None, // filled in by caller
                 user: user_data.get(),
             }));
         }
-        sync::condvar_notify1(&condvar);
-        mem::drop(lock);
 
         // No longer needed, free memory
         mem::drop(builder);
-        mem::drop(recv2);
+        mem::drop(sender);
 
         // Set marker to identify our windows in HOOKPROC functions
         if class_created_this_thread {
@@ -571,6 +819,12 @@ pub fn spawn_window(builder: &WindowBuilder) -> Result<WindowImpl, Error> {
                     break 'message_loop
                 },
                 _ => {
+                    // Generate `WM_CHAR` and friends from key messages, composing dead keys too
+                    #[cfg(feature = "input")]
+                    if (*user_data.get()).text_input {
+                        let _ = TranslateMessage(&msg);
+                    }
+
                     // Dispatch message to `window_proc`
                     // NOTE: Some events call `window_proc` directly instead of through here
                     let _ = DispatchMessageW(&msg);
@@ -581,21 +835,24 @@ pub fn spawn_window(builder: &WindowBuilder) -> Result<WindowImpl, Error> {
         // Free `HCBT_DESTROYWND` hook (thread global)
         let _ = UnhookWindowsHookEx(hhook);
 
+        // The drop target was revoked with the window, so OLE is done with it
+        if drop_target.take().is_some() {
+            OleUninitialize();
+        }
+
         // No need to unregister classes, that's done on exit
     });
 
     // Wait until the thread is done creating the window or notifying us why it couldn't do that
-    let (mutex, condvar) = &*recv;
-    let mut lock = sync::mutex_lock(&mutex);
-    loop {
-        if let Some(result) = (&mut *lock).take() {
-            break result.map(|mut window| {
-                window.thread = Some(thread);
-                window
-            })
-        } else {
-            sync::condvar_wait(&condvar, &mut lock);
-        }
+    match util::create_wait(&recv) {
+        Ok(mut window) => {
+            window.thread = Some(thread);
+            Ok(window)
+        },
+        Err(err) => {
+            let _ = thread.join();
+            Err(err)
+        },
     }
 }
 
@@ -622,13 +879,82 @@ impl WindowImpl {
 
     pub fn events(&self) -> &[Event] {
         // SAFETY: The event buffer isn't swapped until `swap_events` is called (takes &mut self)
+        unsafe { (&*self.user).event_queue.backbuffer() }
+    }
 
-        // The backbuffer contains the "last" events, so use the opposite the active one
-        let user_data = unsafe { &*self.user };
-        if user_data.ev_buf_is_primary {
-            user_data.ev_buf_secondary.slice()
-        } else {
-            user_data.ev_buf_primary.slice()
+    #[inline]
+    pub fn events_text(&self) -> &str {
+        // SAFETY: Same as `events`
+        unsafe { (&*self.user).event_queue.backbuffer_text() }
+    }
+
+    #[inline]
+    pub fn events_paths(&self) -> &[PathBuf] {
+        // SAFETY: Same as `events`
+        unsafe { (&*self.user).event_queue.backbuffer_paths() }
+    }
+
+    #[inline]
+    pub fn clipboard_data(&self, format: &ClipboardFormat) -> Option<Vec<u8>> {
+        // The clipboard can be opened from any thread, so there's no need to bother the window thread
+        unsafe { clipboard_data(self.hwnd, format) }
+    }
+
+    #[inline]
+    pub fn clipboard_formats(&self) -> Vec<ClipboardFormat> {
+        unsafe { clipboard_formats(self.hwnd) }
+    }
+
+    pub unsafe fn create_vulkan_surface(
+        &self,
+        instance: VkInstance,
+        get_instance_proc_addr: Option<PFN_vkGetInstanceProcAddr>,
+    ) -> Result<VkSurfaceKHR, Error> {
+        let name = c_string!("vkCreateWin32SurfaceKHR").as_ptr().cast();
+        let create_surface = match get_instance_proc_addr {
+            Some(get_instance_proc_addr) => get_instance_proc_addr(instance, name),
+            None => VULKAN.get().vkGetInstanceProcAddr(instance, name).flatten(),
+        };
+        let create_surface = mem::transmute::<PFN_vkVoidFunction, PFN_vkCreateWin32SurfaceKHR>(
+            create_surface.ok_or(Error::Unsupported("VK_KHR_win32_surface"))?,
+        );
+        let create_info = VkWin32SurfaceCreateInfoKHR {
+            sType: VK_STRUCTURE_TYPE_WIN32_SURFACE_CREATE_INFO_KHR,
+            pNext: ptr::null(),
+            flags: 0,
+            hinstance: this_hinstance(),
+            hwnd: self.hwnd,
+        };
+        let mut surface = 0;
+        match create_surface(instance, &create_info, ptr::null(), &mut surface) {
+            vulkan::VK_SUCCESS => Ok(surface),
+            result => Err(Error::SurfaceCreation(result)),
+        }
+    }
+
+    #[inline]
+    pub unsafe fn create_gl_context(&self, builder: &GlContextBuilder) -> Result<GlContextImpl, Error> {
+        create_gl_context(self.hwnd, builder)
+    }
+
+    #[inline]
+    pub fn current_monitor(&self) -> Option<Monitor> {
+        // No need to bother the window thread, as it's all thread-safe
+        unsafe { monitor_info(WIN32.get(), MonitorFromWindow(self.hwnd, MONITOR_DEFAULTTONEAREST)) }
+    }
+
+    #[inline]
+    pub fn inner_position(&self) -> (Point, Scale) {
+        let mut position = mem::MaybeUninit::<Point>::uninit();
+        let mut scale = mem::MaybeUninit::<Scale>::uninit();
+        unsafe {
+            let _ = SendMessageW(
+                self.hwnd,
+                RAMEN_WM_GETINNERPOS,
+                position.as_mut_ptr() as WPARAM,
+                scale.as_mut_ptr() as LPARAM,
+            );
+            (position.assume_init(), scale.assume_init())
         }
     }
 
@@ -654,6 +980,39 @@ impl WindowImpl {
         }
     }
 
+    #[cfg(feature = "raw-window-handle")]
+    #[inline]
+    pub fn raw_display_handle(&self) -> Result<RawDisplayHandle, HandleError> {
+        Ok(WindowsDisplayHandle::new().into())
+    }
+
+    #[cfg(feature = "raw-window-handle")]
+    pub fn raw_window_handle(&self) -> Result<RawWindowHandle, HandleError> {
+        let hwnd = NonZeroIsize::new(self.hwnd as isize).ok_or(HandleError::Unavailable)?;
+        let mut handle = Win32WindowHandle::new(hwnd);
+        handle.hinstance = NonZeroIsize::new(this_hinstance() as isize);
+        Ok(handle.into())
+    }
+
+    #[inline]
+    pub fn set_borderless(&self, borderless: bool) {
+        unsafe {
+            let _ = SendMessageW(self.hwnd, RAMEN_WM_SETBORDERLESS, borderless as WPARAM, 0);
+        }
+    }
+
+    #[inline]
+    pub fn set_borderless_async(&self, borderless: bool) {
+        unsafe {
+            let _ = PostMessageW(self.hwnd, RAMEN_WM_SETBORDERLESS, borderless as WPARAM, 0);
+        }
+    }
+
+    #[inline]
+    pub fn set_clipboard(&self, contents: &ClipboardContents) -> bool {
+        unsafe { set_clipboard(self.hwnd, contents) }
+    }
+
     #[inline]
     pub fn set_controls(&self, controls: Option<window::Controls>) {
         let controls = controls.map(|c| c.to_bits()).unwrap_or(!0);
@@ -663,179 +1022,741 @@ impl WindowImpl {
     }
 
     #[inline]
-    pub fn set_cursor(&self, cursor: Cursor) {
+    pub fn set_cursor(&self, cursor: CursorIcon) {
         unsafe {
-            let _ = SendMessageW(self.hwnd, RAMEN_WM_SETCURSOR, cursor as u32 as WPARAM, 0);
+            let _ = SendMessageW(self.hwnd, RAMEN_WM_SETCURSOR, 0, (&cursor) as *const CursorIcon as LPARAM);
         }
     }
 
     #[inline]
-    pub fn set_cursor_async(&self, cursor: Cursor) {
+    pub fn set_cursor_async(&self, cursor: CursorIcon) {
+        let cursor = Box::into_raw(Box::new(cursor));
         unsafe {
-            let _ = PostMessageW(self.hwnd, RAMEN_WM_SETCURSOR, cursor as u32 as WPARAM, 0);
+            if PostMessageW(self.hwnd, RAMEN_WM_SETCURSOR, 1, cursor as LPARAM) == 0 {
+                // The message was never posted, so `window_proc` won't be cleaning it up
+                mem::drop(Box::from_raw(cursor));
+            }
         }
     }
 
     #[inline]
-    pub fn set_controls_async(&self, controls: Option<window::Controls>) {
-        let controls = controls.map(|c| c.to_bits()).unwrap_or(!0);
+    pub fn set_cursor_grab(&self, grab: CursorGrab) {
         unsafe {
-            let _ = PostMessageW(self.hwnd, RAMEN_WM_SETCONTROLS, controls as WPARAM, 0);
+            let _ = SendMessageW(self.hwnd, RAMEN_WM_SETCURSORGRAB, grab as WPARAM, 0);
         }
     }
 
     #[inline]
-    pub fn set_inner_size(&self, size: Size) {
+    pub fn set_cursor_grab_async(&self, grab: CursorGrab) {
         unsafe {
-            let _ = SendMessageW(self.hwnd, RAMEN_WM_SETINNERSIZE, 0, (&size) as *const Size as LPARAM);
+            let _ = PostMessageW(self.hwnd, RAMEN_WM_SETCURSORGRAB, grab as WPARAM, 0);
         }
     }
 
     #[inline]
-    pub fn set_maximized(&self, maximized: bool) {
+    pub fn set_cursor_position(&self, position: Point) {
         unsafe {
-            let _ = SendMessageW(self.hwnd, RAMEN_WM_SETMAXIMIZED, maximized as WPARAM, 0);
+            let _ = SendMessageW(self.hwnd, RAMEN_WM_SETCURSORPOS, 0, (&position) as *const Point as LPARAM);
         }
     }
 
     #[inline]
-    pub fn set_maximized_async(&self, maximized: bool) {
+    pub fn set_cursor_position_async(&self, position: Point) {
+        let position = Box::into_raw(Box::new(position));
         unsafe {
-            let _ = PostMessageW(self.hwnd, RAMEN_WM_SETMAXIMIZED, maximized as WPARAM, 0);
+            if PostMessageW(self.hwnd, RAMEN_WM_SETCURSORPOS, 1, position as LPARAM) == 0 {
+                // The message was never posted, so `window_proc` won't be cleaning it up
+                mem::drop(Box::from_raw(position));
+            }
         }
     }
 
     #[inline]
-    pub fn set_resizable(&self, resizable: bool) {
+    pub fn set_controls_async(&self, controls: Option<window::Controls>) {
+        let controls = controls.map(|c| c.to_bits()).unwrap_or(!0);
         unsafe {
-            let _ = SendMessageW(self.hwnd, RAMEN_WM_SETTHICKFRAME, resizable as WPARAM, 0);
+            let _ = PostMessageW(self.hwnd, RAMEN_WM_SETCONTROLS, controls as WPARAM, 0);
         }
     }
 
     #[inline]
-    pub fn set_resizable_async(&self, resizable: bool) {
+    pub fn set_fullscreen(&self, fullscreen: Option<window::Fullscreen>) {
         unsafe {
-            let _ = PostMessageW(self.hwnd, RAMEN_WM_SETTHICKFRAME, resizable as WPARAM, 0);
+            let _ = SendMessageW(self.hwnd, RAMEN_WM_SETFULLSCREEN, 0, (&fullscreen) as *const _ as LPARAM);
         }
     }
 
     #[inline]
-    pub fn set_title(&self, title: &str) {
-        let mut wstr = Vec::new();
-        let lpcwstr = str_to_wstr(title, &mut wstr);
+    pub fn set_fullscreen_async(&self, fullscreen: Option<window::Fullscreen>) {
+        let fullscreen = Box::into_raw(Box::new(fullscreen));
         unsafe {
-            // TODO: explicit pass on settext in windowproc
-            let _ = SendMessageW(self.hwnd, WM_SETTEXT, 0, lpcwstr as LPARAM);
+            if PostMessageW(self.hwnd, RAMEN_WM_SETFULLSCREEN, 1, fullscreen as LPARAM) == 0 {
+                // The message was never posted, so `window_proc` won't be cleaning it up
+                mem::drop(Box::from_raw(fullscreen));
+            }
         }
     }
 
-    pub fn set_title_async(&self, title: &str) {
-        // Win32 has special behaviour on WM_SETTEXT, since it takes a pointer to a buffer.
-        // You can't actually call it asynchronously, *in case* it's being sent from a different process.
-        // Only if they had Rust back then, this poorly documented stupid detail would not exist,
-        // as trying to use PostMessageW with WM_SETTEXT silently fails because it's scared of lifetimes.
-        // As a workaround, we just define our own event, WM_SETTEXT_ASYNC, and still support WM_SETTEXT.
-        // This is far better than using the "unused parameter" in WM_SETTEXT anyways.
-        // More info on this: https://devblogs.microsoft.com/oldnewthing/20110916-00/?p=9623
-        let mut wstr: Vec<WCHAR> = Vec::new();
-        let lpcwstr = str_to_wstr(title, &mut wstr);
+    #[inline]
+    pub fn set_icon(&self, icon: Option<Icon>) {
         unsafe {
-            // TODO: okay maybe do filter null in str_to_wstr..ugh
-            if *lpcwstr == 0x00 {
-                // If the string is empty, nothing is allocated and nothing needs to be passed
-                // lParam == NULL indicates that it should be empty
-                let _ = PostMessageW(self.hwnd, RAMEN_WM_SETTEXT_ASYNC, 0, 0);
-            } else {
-                let _ = PostMessageW(
-                    self.hwnd,
-                    RAMEN_WM_SETTEXT_ASYNC,
-                    wstr.len() as WPARAM,
-                    lpcwstr as LPARAM,
-                );
-                mem::forget(wstr); // "leak" the memory as `window_proc` will clean it up
-            }
+            let _ = SendMessageW(self.hwnd, RAMEN_WM_SETICON, 0, (&icon) as *const Option<Icon> as LPARAM);
         }
     }
 
     #[inline]
-    pub fn set_visible(&self, visible: bool) {
+    pub fn set_icon_async(&self, icon: Option<Icon>) {
+        let icon = Box::into_raw(Box::new(icon));
         unsafe {
-            let _ = ShowWindow(self.hwnd, if visible { SW_SHOW } else { SW_HIDE });
+            if PostMessageW(self.hwnd, RAMEN_WM_SETICON, 1, icon as LPARAM) == 0 {
+                // The message was never posted, so `window_proc` won't be cleaning it up
+                mem::drop(Box::from_raw(icon));
+            }
         }
     }
 
+    #[cfg(feature = "input")]
     #[inline]
-    pub fn set_visible_async(&self, visible: bool) {
+    pub fn set_ime_allowed(&self, allowed: bool) {
         unsafe {
-            // They provide a function to do this asynchonously, how handy!
-            // The difference being that it does `PostMessage`, not `SendMessage`.
-            // It's implemented as `WM_SHOWWINDOW` and is handled by `DefWindowProcW` (or you).
-            let _ = ShowWindowAsync(self.hwnd, if visible { SW_SHOW } else { SW_HIDE });
+            let _ = SendMessageW(self.hwnd, RAMEN_WM_SETIMEALLOWED, allowed as WPARAM, 0);
         }
     }
 
-    pub fn swap_events(&mut self) {
-        let user_data = unsafe { &mut *self.user };
-        let mut lock = sync::mutex_lock(&user_data.ev_buf_sync);
+    #[cfg(feature = "input")]
+    #[inline]
+    pub fn set_ime_allowed_async(&self, allowed: bool) {
+        unsafe {
+            let _ = PostMessageW(self.hwnd, RAMEN_WM_SETIMEALLOWED, allowed as WPARAM, 0);
+        }
+    }
 
-        // clear backbuffer, switch to it
-        if user_data.ev_buf_is_primary {
-            user_data.ev_buf_secondary.clear();
-        } else {
-            user_data.ev_buf_primary.clear();
+    #[cfg(feature = "input")]
+    #[inline]
+    pub fn set_ime_cursor_area(&self, position: Point, size: Size) {
+        let area = (position, size);
+        unsafe {
+            let _ = SendMessageW(self.hwnd, RAMEN_WM_SETIMEAREA, 0, (&area) as *const (Point, Size) as LPARAM);
         }
-        user_data.ev_buf_is_primary = !user_data.ev_buf_is_primary;
+    }
 
-        // deal with potential lockup (see `WindowImplData::push_event`)
-        if *lock {
-            *lock = false; // "the request to ping the condvar is processed"
-            sync::condvar_notify1(&user_data.ev_buf_ping);
+    #[cfg(feature = "input")]
+    #[inline]
+    pub fn set_ime_cursor_area_async(&self, position: Point, size: Size) {
+        let area = Box::into_raw(Box::new((position, size)));
+        unsafe {
+            if PostMessageW(self.hwnd, RAMEN_WM_SETIMEAREA, 1, area as LPARAM) == 0 {
+                // The message was never posted, so `window_proc` won't be cleaning it up
+                mem::drop(Box::from_raw(area));
+            }
         }
+    }
 
-        mem::drop(lock);
+    #[inline]
+    pub fn set_inner_size(&self, size: Size) {
+        unsafe {
+            let _ = SendMessageW(self.hwnd, RAMEN_WM_SETINNERSIZE, 0, (&size) as *const Size as LPARAM);
+        }
     }
-}
 
-impl WindowImplData {
     #[inline]
-    pub fn push_event(&mut self, event: Event) {
-        self.push_events(&[event]);
+    pub fn outer_position(&self) -> (Point, Scale) {
+        let mut position = mem::MaybeUninit::<Point>::uninit();
+        let mut scale = mem::MaybeUninit::<Scale>::uninit();
+        unsafe {
+            let _ = SendMessageW(
+                self.hwnd,
+                RAMEN_WM_GETOUTERPOS,
+                position.as_mut_ptr() as WPARAM,
+                scale.as_mut_ptr() as LPARAM,
+            );
+            (position.assume_init(), scale.assume_init())
+        }
     }
 
-    pub fn push_events(&mut self, events: &[Event]) {
-        // If the window thread locks up, the window should too, eventually.
-        // This quirk of the event swap system stores a "is cvar waiting" in the mutex,
-        // making it so that if swap never occurs, this eventually will indefinitely block,
-        // and thus "Not Responding" will occur and a crash will snowball into the window too.
-        // This is similar to what Win32 does with its event buffer.
-        let mut lock = sync::mutex_lock(&self.ev_buf_sync);
-        loop {
-            let ev_buf = if self.ev_buf_is_primary {
-                &mut self.ev_buf_primary
-            } else {
-                &mut self.ev_buf_secondary
-            };
-            if *lock || !ev_buf.push_many(events) {
-                *lock = true; // "the condvar should be pinged"
-                sync::condvar_wait(&self.ev_buf_ping, &mut lock);
+    #[inline]
+    pub fn present_pixels(&self, pixels: &[u32], size: (u32, u32), damage: &[Rect]) -> Result<(), Error> {
+        let frame = (pixels, size, damage);
+        unsafe {
+            let lparam = (&frame) as *const (&[u32], (u32, u32), &[Rect]) as LPARAM;
+            if SendMessageW(self.hwnd, RAMEN_WM_PRESENT, 0, lparam) != 0 {
+                Ok(())
             } else {
-                break
+                Err(Error::Unavailable("failed to draw to the window"))
             }
         }
     }
-}
 
-/// Due to legacy reasons, the close button is a system menu item and not a window style.
-/// This function is for turning it on and off (enabled and disabled, rather).
-unsafe fn set_close_button(hwnd: HWND, enabled: bool) {
-    let menu: HMENU = GetSystemMenu(hwnd, FALSE);
-    let flag = if enabled {
-        MF_BYCOMMAND | MF_ENABLED
-    } else {
-        MF_BYCOMMAND | MF_DISABLED | MF_GRAYED
-    };
-    let _ = EnableMenuItem(menu, SC_CLOSE as UINT, flag);
-}
+    #[inline]
+    pub fn set_maximized(&self, maximized: bool) {
+        unsafe {
+            let _ = SendMessageW(self.hwnd, RAMEN_WM_SETMAXIMIZED, maximized as WPARAM, 0);
+        }
+    }
+
+    #[inline]
+    pub fn set_maximized_async(&self, maximized: bool) {
+        unsafe {
+            let _ = PostMessageW(self.hwnd, RAMEN_WM_SETMAXIMIZED, maximized as WPARAM, 0);
+        }
+    }
+
+    #[inline]
+    pub fn set_position(&self, position: Point) {
+        unsafe {
+            let _ = SendMessageW(self.hwnd, RAMEN_WM_SETPOSITION, 0, (&position) as *const Point as LPARAM);
+        }
+    }
+
+    #[inline]
+    pub fn set_position_async(&self, position: Point) {
+        let position = Box::into_raw(Box::new(position));
+        unsafe {
+            if PostMessageW(self.hwnd, RAMEN_WM_SETPOSITION, 1, position as LPARAM) == 0 {
+                // The message was never posted, so `window_proc` won't be cleaning it up
+                mem::drop(Box::from_raw(position));
+            }
+        }
+    }
+
+    #[cfg(feature = "input")]
+    #[inline]
+    pub fn set_raw_mouse_motion(&self, enabled: bool) {
+        unsafe {
+            let _ = SendMessageW(self.hwnd, RAMEN_WM_SETRAWMOUSE, enabled as WPARAM, 0);
+        }
+    }
+
+    #[cfg(feature = "input")]
+    #[inline]
+    pub fn set_raw_mouse_motion_async(&self, enabled: bool) {
+        unsafe {
+            let _ = PostMessageW(self.hwnd, RAMEN_WM_SETRAWMOUSE, enabled as WPARAM, 0);
+        }
+    }
+
+    #[inline]
+    pub fn set_resizable(&self, resizable: bool) {
+        unsafe {
+            let _ = SendMessageW(self.hwnd, RAMEN_WM_SETTHICKFRAME, resizable as WPARAM, 0);
+        }
+    }
+
+    #[inline]
+    pub fn set_resizable_async(&self, resizable: bool) {
+        unsafe {
+            let _ = PostMessageW(self.hwnd, RAMEN_WM_SETTHICKFRAME, resizable as WPARAM, 0);
+        }
+    }
+
+    #[cfg(feature = "input")]
+    #[inline]
+    pub fn set_text_input(&self, enabled: bool) {
+        unsafe {
+            let _ = SendMessageW(self.hwnd, RAMEN_WM_SETTEXTINPUT, enabled as WPARAM, 0);
+        }
+    }
+
+    #[cfg(feature = "input")]
+    #[inline]
+    pub fn set_text_input_async(&self, enabled: bool) {
+        unsafe {
+            let _ = PostMessageW(self.hwnd, RAMEN_WM_SETTEXTINPUT, enabled as WPARAM, 0);
+        }
+    }
+
+    #[inline]
+    pub fn set_title(&self, title: &str) {
+        let mut wstr = Vec::new();
+        let lpcwstr = str_to_wstr(title, &mut wstr);
+        unsafe {
+            // TODO: explicit pass on settext in windowproc
+            let _ = SendMessageW(self.hwnd, WM_SETTEXT, 0, lpcwstr as LPARAM);
+        }
+    }
+
+    pub fn set_title_async(&self, title: &str) {
+        // Win32 has special behaviour on WM_SETTEXT, since it takes a pointer to a buffer.
+        // You can't actually call it asynchronously, *in case* it's being sent from a different process.
+        // Only if they had Rust back then, this poorly documented stupid detail would not exist,
+        // as trying to use PostMessageW with WM_SETTEXT silently fails because it's scared of lifetimes.
+        // As a workaround, we just define our own event, WM_SETTEXT_ASYNC, and still support WM_SETTEXT.
+        // This is far better than using the "unused parameter" in WM_SETTEXT anyways.
+        // More info on this: https://devblogs.microsoft.com/oldnewthing/20110916-00/?p=9623
+        let mut wstr: Vec<WCHAR> = Vec::new();
+        let lpcwstr = str_to_wstr(title, &mut wstr);
+        unsafe {
+            // TODO: okay maybe do filter null in str_to_wstr..ugh
+            if *lpcwstr == 0x00 {
+                // If the string is empty, nothing is allocated and nothing needs to be passed
+                // lParam == NULL indicates that it should be empty
+                let _ = PostMessageW(self.hwnd, RAMEN_WM_SETTEXT_ASYNC, 0, 0);
+            } else {
+                let _ = PostMessageW(
+                    self.hwnd,
+                    RAMEN_WM_SETTEXT_ASYNC,
+                    wstr.len() as WPARAM,
+                    lpcwstr as LPARAM,
+                );
+                mem::forget(wstr); // "leak" the memory as `window_proc` will clean it up
+            }
+        }
+    }
+
+    #[inline]
+    pub fn set_visible(&self, visible: bool) {
+        unsafe {
+            let _ = ShowWindow(self.hwnd, if visible { SW_SHOW } else { SW_HIDE });
+        }
+    }
+
+    #[inline]
+    pub fn set_visible_async(&self, visible: bool) {
+        unsafe {
+            // They provide a function to do this asynchonously, how handy!
+            // The difference being that it does `PostMessage`, not `SendMessage`.
+            // It's implemented as `WM_SHOWWINDOW` and is handled by `DefWindowProcW` (or you).
+            let _ = ShowWindowAsync(self.hwnd, if visible { SW_SHOW } else { SW_HIDE });
+        }
+    }
+
+    pub fn swap_events(&mut self) {
+        // SAFETY: `&mut self` guarantees no slices from `events` are alive
+        unsafe { (&*self.user).event_queue.swap() }
+    }
+}
+
+impl WindowImplData {
+    #[inline]
+    pub fn push_event(&mut self, event: Event) {
+        self.push_events(&[event]);
+    }
+
+    #[inline]
+    pub fn push_events(&mut self, events: &[Event]) {
+        self.event_queue.push_many(events);
+    }
+
+    #[inline]
+    #[cfg(feature = "input")]
+    pub fn push_event_with_text(&mut self, text: &str, f: impl FnOnce(EventText) -> Event) {
+        self.event_queue.push_with_text(text, |offset, len| f(EventText { offset, len }));
+    }
+
+    #[inline]
+    pub fn push_event_with_paths(&mut self, paths: &[PathBuf], f: impl FnOnce(EventPaths) -> Event) {
+        self.event_queue.push_with_paths(paths, |offset, len| f(EventPaths { offset, len }));
+    }
+}
+
+/// Turns the IME on or off for the window, by (dis)associating it with the thread's input context.
+///
+/// It's only on while both text input and the IME are enabled, so that no composition events are sent otherwise.
+#[cfg(feature = "input")]
+unsafe fn update_ime_context(hwnd: HWND, user_data: &WindowImplData) {
+    let flags = if user_data.text_input && user_data.ime_allowed { IACE_DEFAULT } else { 0 };
+    let _ = ImmAssociateContextEx(hwnd, ptr::null_mut(), flags);
+}
+
+/// Registers (or unregisters) the window for raw mouse input, which is sent as `WM_INPUT`.
+///
+/// Raw input is registered per process, so only the last window to register receives it.
+#[cfg(feature = "input")]
+unsafe fn set_raw_mouse_motion(hwnd: HWND, user_data: &mut WindowImplData, enabled: bool) {
+    if user_data.raw_mouse_motion != enabled {
+        let device = RAWINPUTDEVICE {
+            usUsagePage: HID_USAGE_PAGE_GENERIC,
+            usUsage: HID_USAGE_GENERIC_MOUSE,
+            dwFlags: if enabled { 0 } else { RIDEV_REMOVE },
+            hwndTarget: if enabled { hwnd } else { ptr::null_mut() },
+        };
+        let size = mem::size_of::<RAWINPUTDEVICE>() as UINT;
+        if RegisterRawInputDevices(&device, 1, size) != FALSE {
+            user_data.raw_mouse_motion = enabled;
+        }
+    }
+}
+
+/// Reports a mouse button, capturing the mouse while any are held down.
+///
+/// Capturing keeps `WM_MOUSEMOVE` coming when dragging out of the window, and makes sure the release is seen.
+#[cfg(feature = "input")]
+unsafe fn mouse_button(hwnd: HWND, user_data: &mut WindowImplData, button: MouseButton, pressed: bool) {
+    let modifiers = modifier_state();
+    if pressed {
+        if user_data.held_buttons.is_empty() {
+            let _ = SetCapture(hwnd);
+        }
+        if !user_data.held_buttons.contains(&button) {
+            user_data.held_buttons.push(button);
+        }
+        user_data.push_event(Event::MouseDown((button, modifiers)));
+    } else {
+        let was_held = user_data.held_buttons.contains(&button);
+        user_data.held_buttons.retain(|&b| b != button);
+        user_data.push_event(Event::MouseUp((button, modifiers)));
+        if was_held && user_data.held_buttons.is_empty() {
+            let _ = ReleaseCapture();
+        }
+    }
+}
+
+/// Reports a `WM_POINTER*` message from touch or a pen, returning whether it shouldn't be passed on.
+///
+/// Passing it on to `DefWindowProcW` is what makes the system send the mouse messages for it too.
+#[cfg(feature = "input")]
+unsafe fn pointer_event(hwnd: HWND, user_data: &mut WindowImplData, msg: UINT, wparam: WPARAM) -> bool {
+    let id = (wparam & 0xFFFF) as UINT;
+    let mut pen = mem::MaybeUninit::<POINTER_PEN_INFO>::zeroed();
+    if WIN32.dl.GetPointerInfo(id, &mut (*pen.as_mut_ptr()).pointerInfo) != Some(TRUE) {
+        return false
+    }
+    let info = &(*pen.as_ptr()).pointerInfo;
+    let (pointer_type, flags) = (info.pointerType, info.pointerFlags);
+
+    let phase = match msg {
+        _ if msg == WM_POINTERCAPTURECHANGED || flags & POINTER_FLAG_CANCELED != 0 => TouchPhase::Cancel,
+        WM_POINTERDOWN => TouchPhase::Start,
+        WM_POINTERUP => TouchPhase::End,
+        _ => TouchPhase::Move,
+    };
+    let mut point = POINT { x: info.ptPixelLocation.x, y: info.ptPixelLocation.y };
+    let _ = ScreenToClient(hwnd, &mut point);
+    let dpi_scale = user_data.current_dpi as f64 / BASE_DPI as f64;
+    let mut position = Point::Physical(point.x, point.y);
+    if user_data.is_dpi_logical {
+        position = position.to_logical(dpi_scale);
+    }
+
+    match pointer_type {
+        // Touchscreens that can sense hovering fingers send updates without contact, which aren't touches
+        PT_TOUCH if phase != TouchPhase::Move || flags & POINTER_FLAG_INCONTACT != 0 => {
+            let touch = Touch { id: id.into(), phase, position };
+            user_data.push_event(Event::Touch((touch, dpi_scale)));
+            !user_data.touch_emulates_mouse
+        },
+        PT_PEN if WIN32.dl.GetPointerPenInfo(id, pen.as_mut_ptr()) == Some(TRUE) => {
+            let pen = &*pen.as_ptr();
+            let has = |mask| pen.penMask & mask != 0;
+            let pen = Pen {
+                phase,
+                position,
+                // Pressure is from 0 to 1024, and tilt is already in degrees
+                pressure: if has(PEN_MASK_PRESSURE) { pen.pressure as f64 / 1024.0 } else { 0.0 },
+                tilt: (
+                    if has(PEN_MASK_TILT_X) { pen.tiltX.into() } else { 0.0 },
+                    if has(PEN_MASK_TILT_Y) { pen.tiltY.into() } else { 0.0 },
+                ),
+                eraser: pen.penFlags & (PEN_FLAG_ERASER | PEN_FLAG_INVERTED) != 0,
+                barrel_button: pen.penFlags & PEN_FLAG_BARREL != 0,
+            };
+            user_data.push_event(Event::Pen((pen, dpi_scale)));
+            false
+        },
+        _ => false,
+    }
+}
+
+/// Moves the IME candidate window out of the way of the cursor area, if one was set.
+#[cfg(feature = "input")]
+unsafe fn update_ime_position(hwnd: HWND, user_data: &WindowImplData) {
+    if let Some((position, size)) = user_data.ime_cursor_area {
+        let dpi_factor = user_data.current_dpi as f64 / BASE_DPI as f64;
+        let (x, y) = position.as_physical(dpi_factor);
+        let (width, height) = size.as_physical(dpi_factor);
+        let area = RECT { left: x, top: y, right: x + width as LONG, bottom: y + height as LONG };
+
+        let himc = ImmGetContext(hwnd);
+        if !himc.is_null() {
+            // The composition window isn't shown (see `WM_IME_SETCONTEXT`), but some IMEs still go by it
+            let composition = COMPOSITIONFORM {
+                dwStyle: CFS_POINT,
+                ptCurrentPos: POINT { x, y },
+                rcArea: RECT { left: 0, top: 0, right: 0, bottom: 0 },
+            };
+            let candidate = CANDIDATEFORM {
+                dwIndex: 0,
+                dwStyle: CFS_EXCLUDE,
+                ptCurrentPos: POINT { x, y },
+                rcArea: area,
+            };
+            let _ = ImmSetCompositionWindow(himc, &composition);
+            let _ = ImmSetCandidateWindow(himc, &candidate);
+            let _ = ImmReleaseContext(hwnd, himc);
+        }
+    }
+}
+
+/// Gets one of the strings of the IME's composition, such as `GCS_COMPSTR`.
+#[cfg(feature = "input")]
+unsafe fn composition_string(himc: HIMC, index: DWORD) -> Vec<WCHAR> {
+    // The size is in bytes, not characters
+    let size = ImmGetCompositionStringW(himc, index, ptr::null_mut(), 0).max(0);
+    let mut buf = vec![0 as WCHAR; size as usize / mem::size_of::<WCHAR>()];
+    let _ = ImmGetCompositionStringW(himc, index, buf.as_mut_ptr().cast(), size as DWORD);
+    buf
+}
+
+/// Due to legacy reasons, the close button is a system menu item and not a window style.
+/// This function is for turning it on and off (enabled and disabled, rather).
+unsafe fn set_close_button(hwnd: HWND, enabled: bool) {
+    let menu: HMENU = GetSystemMenu(hwnd, FALSE);
+    let flag = if enabled {
+        MF_BYCOMMAND | MF_ENABLED
+    } else {
+        MF_BYCOMMAND | MF_DISABLED | MF_GRAYED
+    };
+    let _ = EnableMenuItem(menu, SC_CLOSE as UINT, flag);
+}
+
+/// Enters, switches, or leaves fullscreen, keeping track of what to restore afterwards.
+unsafe fn set_fullscreen(hwnd: HWND, user_data: &mut WindowImplData, fullscreen: Option<window::Fullscreen>) {
+    let was_fullscreen = user_data.fullscreen.is_some();
+
+    // Put the monitor back the way it was, even if just switching to another video mode
+    if let Some(device) = user_data.fullscreen_device.take() {
+        let _ = ChangeDisplaySettingsExW(device.as_ptr(), ptr::null_mut(), ptr::null_mut(), 0, ptr::null_mut());
+    }
+
+    if let Some(mut fullscreen) = fullscreen {
+        if !was_fullscreen {
+            user_data.restore_placement.length = mem::size_of::<WINDOWPLACEMENT>() as UINT;
+            let _ = GetWindowPlacement(hwnd, &mut user_data.restore_placement);
+
+            // Maximized windows are restored first, otherwise the leftover maximized state
+            // gets the window stuck in a limbo where it can't be restored properly.
+            // It's maximized again on leaving, as that's part of the placement.
+            if user_data.is_maximized {
+                let _ = DefWindowProcW(hwnd, WM_SYSCOMMAND, SC_RESTORE, 0);
+            }
+        }
+
+        // The monitor that was asked for is found by its area, or it's the one the window is on if it's gone
+        let mut monitor = ptr::null_mut();
+        if let Some(target) = fullscreen.monitor() {
+            let rect = RECT {
+                left: target.rect.x,
+                top: target.rect.y,
+                right: target.rect.x + target.rect.width as LONG,
+                bottom: target.rect.y + target.rect.height as LONG,
+            };
+            monitor = MonitorFromRect(&rect, MONITOR_DEFAULTTONULL);
+        }
+        if monitor.is_null() {
+            monitor = MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST);
+        }
+        let mut info: MONITORINFOEXW = mem::zeroed();
+        info.cbSize = mem::size_of_val(&info) as DWORD;
+        let _ = GetMonitorInfoW(monitor, &mut info);
+
+        if let window::Fullscreen::Exclusive(target, mode) = &fullscreen {
+            let mut devmode: DEVMODEW = mem::zeroed();
+            devmode.dmSize = mem::size_of_val(&devmode) as WORD;
+            devmode.dmFields = DM_PELSWIDTH | DM_PELSHEIGHT | DM_BITSPERPEL | DM_DISPLAYFREQUENCY;
+            devmode.dmPelsWidth = mode.size.0;
+            devmode.dmPelsHeight = mode.size.1;
+            devmode.dmBitsPerPel = mode.bit_depth.into();
+            // Win32 only deals in whole hertz, rounded down (59.94Hz is 59Hz)
+            devmode.dmDisplayFrequency = mode.refresh_rate / 1000;
+
+            let device = info.szDevice.as_ptr();
+            let flags = CDS_FULLSCREEN; // "temporary", so it's reset if we crash
+            if ChangeDisplaySettingsExW(device, &mut devmode, ptr::null_mut(), flags, ptr::null_mut())
+                == DISP_CHANGE_SUCCESSFUL
+            {
+                user_data.fullscreen_device = Some(info.szDevice);
+
+                // The monitor area changes along with the resolution
+                let _ = GetMonitorInfoW(monitor, &mut info);
+            } else {
+                fullscreen = window::Fullscreen::Borderless(Some(target.clone()));
+            }
+        }
+
+        user_data.fullscreen = Some(fullscreen);
+        update_window_style(hwnd, user_data);
+        let rect = &info.rcMonitor;
+        let (width, height) = rect_to_size2d(rect);
+        const MASK: UINT = SWP_NOOWNERZORDER | SWP_FRAMECHANGED;
+        let _ = SetWindowPos(hwnd, HWND_TOP, rect.left, rect.top, width, height, MASK);
+    } else if was_fullscreen {
+        user_data.fullscreen = None;
+        update_window_style(hwnd, user_data);
+
+        // Hidden windows should stay hidden, the placement would show them
+        let placement = &mut user_data.restore_placement;
+        let show_cmd = placement.showCmd;
+        if !user_data.style.visible {
+            placement.showCmd = SW_HIDE as UINT;
+        }
+        let _ = SetWindowPlacement(hwnd, placement);
+        placement.showCmd = show_cmd;
+    }
+
+    if was_fullscreen != user_data.fullscreen.is_some() {
+        user_data.push_event(Event::Fullscreen(!was_fullscreen));
+    }
+}
+
+pub fn vulkan_instance_extensions() -> &'static [&'static CStr] {
+    &[vulkan::VK_KHR_SURFACE_EXTENSION_NAME, VK_KHR_WIN32_SURFACE_EXTENSION_NAME]
+}
+
+pub fn available_monitors() -> Vec<Monitor> {
+    unsafe extern "system" fn enum_monitor(hmonitor: HMONITOR, _hdc: HDC, _rect: *mut RECT, lparam: LPARAM) -> BOOL {
+        (*(lparam as *mut Vec<HMONITOR>)).push(hmonitor);
+        TRUE
+    }
+
+    // The DPI mode has to be set before anything's measured, otherwise everything is virtualized
+    let win32 = WIN32.get();
+    let mut hmonitors: Vec<HMONITOR> = Vec::new();
+    unsafe {
+        let _ = EnumDisplayMonitors(ptr::null_mut(), ptr::null(), enum_monitor, (&mut hmonitors) as *mut _ as LPARAM);
+        hmonitors.into_iter().filter_map(|hmonitor| monitor_info(win32, hmonitor)).collect()
+    }
+}
+
+/// Gets the DPI of a monitor, which is only different per monitor with PMv1 and above.
+unsafe fn monitor_dpi(win32: &Win32State, hmonitor: HMONITOR) -> UINT {
+    let (mut dpi_x, mut dpi_y) = (BASE_DPI, BASE_DPI);
+    if matches!(win32.dpi_mode, Win32DpiMode::PerMonitorV1 | Win32DpiMode::PerMonitorV2) {
+        let _ = win32.dl.GetDpiForMonitor(hmonitor, MDT_EFFECTIVE_DPI, &mut dpi_x, &mut dpi_y);
+    }
+    dpi_x
+}
+
+/// Describes a monitor, which is `None` if it's since been disconnected.
+unsafe fn monitor_info(win32: &Win32State, hmonitor: HMONITOR) -> Option<Monitor> {
+    let mut info: MONITORINFOEXW = mem::zeroed();
+    info.cbSize = mem::size_of_val(&info) as DWORD;
+    if GetMonitorInfoW(hmonitor, &mut info) == FALSE {
+        return None
+    }
+    let device = info.szDevice.as_ptr();
+
+    // That's the adapter's name, along the lines of `\\.\DISPLAY1`, the monitor on it has a nicer one
+    let mut display_device: DISPLAY_DEVICEW = mem::zeroed();
+    display_device.cb = mem::size_of_val(&display_device) as DWORD;
+    let name = if EnumDisplayDevicesW(device, 0, &mut display_device, 0) != FALSE {
+        wstr_to_string(&display_device.DeviceString)
+    } else {
+        wstr_to_string(&info.szDevice)
+    };
+
+    let dpi = monitor_dpi(win32, hmonitor);
+
+    let mut devmode: DEVMODEW = mem::zeroed();
+    devmode.dmSize = mem::size_of_val(&devmode) as WORD;
+    let refresh_rate = if EnumDisplaySettingsW(device, ENUM_CURRENT_SETTINGS, &mut devmode) != FALSE {
+        devmode_refresh_rate(&devmode)
+    } else {
+        0
+    };
+    let mut video_modes = Vec::new();
+    let mut index = 0;
+    while EnumDisplaySettingsW(device, index, &mut devmode) != FALSE {
+        let mode = VideoMode {
+            size: (devmode.dmPelsWidth, devmode.dmPelsHeight),
+            bit_depth: devmode.dmBitsPerPel as u16,
+            refresh_rate: devmode_refresh_rate(&devmode),
+        };
+        // Modes only differing in things like scaling and orientation show up more than once
+        if !video_modes.contains(&mode) {
+            video_modes.push(mode);
+        }
+        index += 1;
+    }
+
+    Some(Monitor {
+        name,
+        rect: rect_to_monitor_rect(&info.rcMonitor),
+        work_area: rect_to_monitor_rect(&info.rcWork),
+        scale: dpi as f64 / BASE_DPI as f64,
+        refresh_rate,
+        is_primary: info.dwFlags & MONITORINFOF_PRIMARY != 0,
+        video_modes,
+    })
+}
+
+/// Gets the refresh rate of a display mode in millihertz, which Win32 only knows in whole hertz.
+fn devmode_refresh_rate(devmode: &DEVMODEW) -> u32 {
+    match devmode.dmDisplayFrequency {
+        0 | 1 => 0, // "hardware default"
+        hertz => hertz * 1000,
+    }
+}
+
+fn rect_to_monitor_rect(rect: &RECT) -> Rect {
+    let (width, height) = rect_to_size2d(rect);
+    Rect { x: rect.left, y: rect.top, width: width as u32, height: height as u32 }
+}
+
+/// Converts a null-terminated wide string to a `String`, replacing invalid UTF-16.
+fn wstr_to_string(wstr: &[WCHAR]) -> String {
+    let len = wstr.iter().position(|&c| c == 0x00).unwrap_or(wstr.len());
+    String::from_utf16_lossy(&wstr[..len])
+}
+
+/// Draws the last presented image, stretched over the client area (nearest neighbour, like everywhere else).
+///
+/// With damage, only the areas it covers are drawn, otherwise what's drawn is up to the clipping region of `dc`.
+unsafe fn draw_frame(dc: HDC, user_data: &WindowImplData, damage: Option<&[Rect]>) -> bool {
+    let (width, height) = user_data.frame_size;
+    let (client_width, client_height) = user_data.client_area_size;
+    if user_data.frame.is_empty() || client_width == 0 || client_height == 0 {
+        return true
+    }
+
+    let mut region: HRGN = ptr::null_mut();
+    if let Some(damage) = damage {
+        region = CreateRectRgn(0, 0, 0, 0);
+        for &rect in damage {
+            let area = util::scale_rect(rect, user_data.frame_size, user_data.client_area_size);
+            let (right, bottom) = (area.x + area.width as c_int, area.y + area.height as c_int);
+            let part = CreateRectRgn(area.x, area.y, right, bottom);
+            let _ = CombineRgn(region, region, part, RGN_OR);
+            let _ = DeleteObject(part.cast());
+        }
+        let _ = SelectClipRgn(dc, region);
+    }
+
+    // A negative height makes the DIB top-down, and 32-bit `BI_RGB` is 0x00RRGGBB already
+    let mut info: BITMAPINFO = mem::zeroed();
+    info.bmiHeader.biSize = mem::size_of::<BITMAPINFOHEADER>() as DWORD;
+    info.bmiHeader.biWidth = width as LONG;
+    info.bmiHeader.biHeight = -(height as LONG);
+    info.bmiHeader.biPlanes = 1;
+    info.bmiHeader.biBitCount = 32;
+    info.bmiHeader.biCompression = BI_RGB;
+    let bits = user_data.frame.as_ptr().cast();
+    let lines = if user_data.frame_size == user_data.client_area_size {
+        SetDIBitsToDevice(dc, 0, 0, width, height, 0, 0, 0, height, bits, &info, DIB_RGB_COLORS)
+    } else {
+        let _ = SetStretchBltMode(dc, COLORONCOLOR);
+        StretchDIBits(
+            dc,
+            0,
+            0,
+            client_width as c_int,
+            client_height as c_int,
+            0,
+            0,
+            width as c_int,
+            height as c_int,
+            bits,
+            &info,
+            DIB_RGB_COLORS,
+            SRCCOPY,
+        )
+    };
+
+    if !region.is_null() {
+        let _ = SelectClipRgn(dc, ptr::null_mut());
+        let _ = DeleteObject(region.cast());
+    }
+    lines != 0
+}
 
 /// Client area -> Screen space
 unsafe fn client_area_screen_space(hwnd: HWND) -> RECT {
@@ -846,6 +1767,578 @@ unsafe fn client_area_screen_space(hwnd: HWND) -> RECT {
     client_area
 }
 
+/// Replaces the title bar and taskbar icons, going back to the default ones for `None`.
+unsafe fn set_icon(hwnd: HWND, user_data: &mut WindowImplData, icon: Option<Icon>) {
+    let win32 = WIN32.get();
+    let dpi = user_data.current_dpi;
+    let mut handles = [ptr::null_mut(); 2];
+    if let Some(icon) = &icon {
+        // Each gets the image closest to the size the system wants, which it scales if it's not exact
+        for (handle, metric) in handles.iter_mut().zip([SM_CXSMICON, SM_CXICON]) {
+            let width = win32.dl.GetSystemMetricsForDpi(metric, dpi).unwrap_or_else(|| GetSystemMetrics(metric));
+            let image = icon.image(width.max(1) as u32);
+            *handle = create_icon(&image.rgba, image.size, None);
+        }
+    }
+    user_data.icon = icon;
+
+    // The old icons can only be destroyed once the window has stopped using them
+    let _ = SendMessageW(hwnd, WM_SETICON, ICON_SMALL, handles[0] as LPARAM);
+    let _ = SendMessageW(hwnd, WM_SETICON, ICON_BIG, handles[1] as LPARAM);
+    for old_handle in mem::replace(&mut user_data.icon_handles, handles) {
+        if !old_handle.is_null() {
+            let _ = DestroyIcon(old_handle);
+        }
+    }
+}
+
+/// Replaces the cursor (destroying the old one if it was ours), showing it right away if the mouse is in the window.
+unsafe fn set_cursor(hwnd: HWND, user_data: &mut WindowImplData, cursor: CursorIcon) {
+    let old_cursor = mem::replace(&mut user_data.cursor, load_cursor(&cursor, user_data.current_dpi));
+    let old_icon = mem::replace(&mut user_data.cursor_icon, cursor);
+
+    // Immediately update the cursor icon if it's within the client area.
+    let mut mouse_pos: POINT = mem::zeroed();
+    if GetCursorPos(&mut mouse_pos) != 0 && WindowFromPoint(POINT { ..mouse_pos }) == hwnd {
+        let client_area = client_area_screen_space(hwnd);
+        if PtInRect(&client_area, mouse_pos) != 0 {
+            let _ = SetCursor(user_data.cursor);
+        }
+    }
+
+    // This is after the new one is shown, as the one being shown can't be destroyed
+    if matches!(old_icon, CursorIcon::Custom(_)) && !old_cursor.is_null() {
+        let _ = DestroyIcon(old_cursor);
+    }
+}
+
+/// Applies the cursor grab with `ClipCursor` while the window is focused and not minimized,
+/// otherwise releases it. The clip is in screen space, so this has to be redone on moves and resizes.
+unsafe fn update_cursor_clip(hwnd: HWND, user_data: &mut WindowImplData) {
+    let grabbed = user_data.cursor_grab != CursorGrab::None && user_data.is_focused && !user_data.is_minimized;
+    if grabbed {
+        let mut rect = client_area_screen_space(hwnd);
+        if user_data.cursor_grab == CursorGrab::Locked {
+            // There's no real locking in Win32, but clipping to a single pixel does the same
+            let (x, y) = user_data.cursor_lock_position;
+            let x = (rect.left + x).min(rect.right - 1).max(rect.left);
+            let y = (rect.top + y).min(rect.bottom - 1).max(rect.top);
+            rect = RECT { left: x, top: y, right: x + 1, bottom: y + 1 };
+        }
+        let _ = ClipCursor(&rect);
+    } else if user_data.is_cursor_clipped {
+        let _ = ClipCursor(ptr::null());
+    }
+    user_data.is_cursor_clipped = grabbed;
+}
+
+/// An `IDropTarget`, which OLE calls into as files are dragged over and dropped on a window.
+///
+/// It's only referenced by OLE for as long as it's registered, so reference counting is a no-op.
+#[repr(C)]
+struct DropTarget {
+    vtbl: *const IDropTargetVtbl,
+    hwnd: HWND,
+
+    /// The files being dragged, if the drag has any.
+    paths: Option<Vec<PathBuf>>,
+}
+
+static DROP_TARGET_VTBL: IDropTargetVtbl = IDropTargetVtbl {
+    QueryInterface: drop_target_query_interface,
+    AddRef: drop_target_ref_count,
+    Release: drop_target_ref_count,
+    DragEnter: drop_target_drag_enter,
+    DragOver: drop_target_drag_over,
+    DragLeave: drop_target_drag_leave,
+    Drop: drop_target_drop,
+};
+
+/// Registers the window as a drop target, initializing OLE on the thread for it.
+unsafe fn register_drop_target(hwnd: HWND) -> Option<Box<DropTarget>> {
+    if OleInitialize(ptr::null_mut()) < 0 {
+        return None
+    }
+    let mut target = Box::new(DropTarget { vtbl: &DROP_TARGET_VTBL, hwnd, paths: None });
+    if RegisterDragDrop(hwnd, (&mut *target as *mut DropTarget).cast()) == S_OK {
+        Some(target)
+    } else {
+        OleUninitialize();
+        None
+    }
+}
+
+/// Reads the files out of a dragged `IDataObject`, if it has any.
+///
+/// They're kept as UTF-16 as it was given, since file names don't need to be valid UTF-16.
+unsafe fn drop_paths(data_object: *mut IDataObject) -> Option<Vec<PathBuf>> {
+    let format = FORMATETC {
+        cfFormat: CF_HDROP,
+        ptd: ptr::null_mut(),
+        dwAspect: DVASPECT_CONTENT,
+        lindex: -1,
+        tymed: TYMED_HGLOBAL,
+    };
+    let mut medium = mem::zeroed::<STGMEDIUM>();
+    if ((*(*data_object).lpVtbl).GetData)(data_object, &format, &mut medium) != S_OK {
+        return None
+    }
+    let hdrop = medium.hGlobal as HDROP;
+    let mut paths = Vec::new();
+    let mut buffer = Vec::new();
+    for i in 0..DragQueryFileW(hdrop, UINT::MAX, ptr::null_mut(), 0) {
+        // The length doesn't include the null terminator, which is written too
+        buffer.resize(DragQueryFileW(hdrop, i, ptr::null_mut(), 0) as usize + 1, 0);
+        let len = DragQueryFileW(hdrop, i, buffer.as_mut_ptr(), buffer.len() as UINT) as usize;
+        paths.push(PathBuf::from(OsString::from_wide(&buffer[..len.min(buffer.len())])));
+    }
+    ReleaseStgMedium(&mut medium);
+    Some(paths).filter(|paths| !paths.is_empty())
+}
+
+/// Converts a drop target's screen position to the client area, scaled like the mouse is.
+unsafe fn drop_position(hwnd: HWND, user_data: &WindowImplData, pt: POINTL) -> (Point, Scale) {
+    let mut point = POINT { x: pt.x, y: pt.y };
+    let _ = ScreenToClient(hwnd, &mut point);
+    let dpi_scale = user_data.current_dpi as f64 / BASE_DPI as f64;
+    let position = Point::Physical(point.x, point.y);
+    if user_data.is_dpi_logical {
+        (position.to_logical(dpi_scale), dpi_scale)
+    } else {
+        (position, dpi_scale)
+    }
+}
+
+/// Copying is the only effect, if the source allows it and there's files to copy.
+unsafe fn drop_effect(target: &DropTarget, effect: *mut DWORD) {
+    *effect = if target.paths.is_some() { *effect & DROPEFFECT_COPY } else { DROPEFFECT_NONE };
+}
+
+unsafe extern "system" fn drop_target_query_interface(
+    this: *mut IDropTarget,
+    riid: *const GUID,
+    ppv: *mut *mut c_void,
+) -> HRESULT {
+    if *riid == IID_IUnknown || *riid == IID_IDropTarget {
+        *ppv = this.cast();
+        S_OK
+    } else {
+        *ppv = ptr::null_mut();
+        E_NOINTERFACE
+    }
+}
+
+unsafe extern "system" fn drop_target_ref_count(_this: *mut IDropTarget) -> ULONG {
+    1
+}
+
+unsafe extern "system" fn drop_target_drag_enter(
+    this: *mut IDropTarget,
+    data_object: *mut IDataObject,
+    _key_state: DWORD,
+    pt: POINTL,
+    effect: *mut DWORD,
+) -> HRESULT {
+    let target = &mut *this.cast::<DropTarget>();
+    target.paths = drop_paths(data_object);
+    if let Some(paths) = &target.paths {
+        let user_data = user_data(target.hwnd);
+        let (position, scale) = drop_position(target.hwnd, user_data, pt);
+        user_data.push_event_with_paths(paths, |paths| Event::DragEnter((paths, position, scale)));
+    }
+    drop_effect(target, effect);
+    S_OK
+}
+
+unsafe extern "system" fn drop_target_drag_over(
+    this: *mut IDropTarget,
+    _key_state: DWORD,
+    pt: POINTL,
+    effect: *mut DWORD,
+) -> HRESULT {
+    let target = &mut *this.cast::<DropTarget>();
+    if target.paths.is_some() {
+        let user_data = user_data(target.hwnd);
+        user_data.push_event(Event::DragOver(drop_position(target.hwnd, user_data, pt)));
+    }
+    drop_effect(target, effect);
+    S_OK
+}
+
+unsafe extern "system" fn drop_target_drag_leave(this: *mut IDropTarget) -> HRESULT {
+    let target = &mut *this.cast::<DropTarget>();
+    if target.paths.take().is_some() {
+        user_data(target.hwnd).push_event(Event::DragLeave);
+    }
+    S_OK
+}
+
+unsafe extern "system" fn drop_target_drop(
+    this: *mut IDropTarget,
+    _data_object: *mut IDataObject,
+    _key_state: DWORD,
+    pt: POINTL,
+    effect: *mut DWORD,
+) -> HRESULT {
+    let target = &mut *this.cast::<DropTarget>();
+    drop_effect(target, effect);
+    if let Some(paths) = target.paths.take() {
+        let user_data = user_data(target.hwnd);
+        let (position, scale) = drop_position(target.hwnd, user_data, pt);
+        user_data.push_event_with_paths(&paths, |paths| Event::Drop((paths, position, scale)));
+    }
+    S_OK
+}
+
+/// Opens the clipboard, trying again for a bit if another program has it open, as that's usually brief.
+unsafe fn open_clipboard(hwnd: HWND) -> bool {
+    for _ in 0..10 {
+        if OpenClipboard(hwnd) != FALSE {
+            return true
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    false
+}
+
+/// Gets the ID of a clipboard format, registering custom ones by name (which gets the existing ID if there is one).
+unsafe fn clipboard_format_id(format: &ClipboardFormat) -> UINT {
+    match format {
+        ClipboardFormat::Text => CF_UNICODETEXT.into(),
+        ClipboardFormat::Image => CF_DIBV5.into(),
+        ClipboardFormat::Custom(name) => {
+            let mut wstr = Vec::new();
+            RegisterClipboardFormatW(str_to_wstr(name, &mut wstr))
+        },
+    }
+}
+
+/// Gets the formats the clipboard is in, including the ones Windows converts to (such as text from ANSI text).
+unsafe fn clipboard_formats(hwnd: HWND) -> Vec<ClipboardFormat> {
+    let mut formats = Vec::new();
+    if !open_clipboard(hwnd) {
+        return formats
+    }
+    let mut id = 0;
+    loop {
+        id = EnumClipboardFormats(id);
+        let format = match id as CLIPFORMAT {
+            _ if id == 0 => break,
+            CF_UNICODETEXT => ClipboardFormat::Text,
+            CF_DIB | CF_DIBV5 => ClipboardFormat::Image,
+
+            // Registered formats are the only ones with names, the rest are predefined ones we don't know
+            0xC000..=0xFFFF => {
+                let mut name = [0 as WCHAR; 256];
+                let len = GetClipboardFormatNameW(id, name.as_mut_ptr(), name.len() as c_int);
+                if len <= 0 {
+                    continue
+                }
+                ClipboardFormat::Custom(wstr_to_string(&name[..len as usize]))
+            },
+            _ => continue,
+        };
+        if !formats.contains(&format) {
+            formats.push(format);
+        }
+    }
+    let _ = CloseClipboard();
+    formats
+}
+
+/// Gets the clipboard data in a format, as UTF-8 text, a BMP file for images, or as-is for custom formats.
+unsafe fn clipboard_data(hwnd: HWND, format: &ClipboardFormat) -> Option<Vec<u8>> {
+    // `CF_DIBV5` is the only one that's sure to have alpha, but some programs only put `CF_DIB` on the clipboard
+    let ids = match format {
+        ClipboardFormat::Image => vec![CF_DIBV5.into(), CF_DIB.into()],
+        _ => vec![clipboard_format_id(format)],
+    };
+    if !open_clipboard(hwnd) {
+        return None
+    }
+    let bytes = ids.into_iter().filter(|&id| id != 0).find_map(|id| global_bytes(GetClipboardData(id)));
+    let _ = CloseClipboard();
+    let bytes = bytes?;
+    Some(match format {
+        ClipboardFormat::Text => {
+            let wstr = bytes.chunks_exact(2).map(|c| WCHAR::from_le_bytes([c[0], c[1]])).collect::<Vec<_>>();
+            wstr_to_string(&wstr).into_bytes()
+        },
+        ClipboardFormat::Image => clipboard::dib_to_bmp(&bytes),
+        ClipboardFormat::Custom(_) => bytes,
+    })
+}
+
+/// Copies the memory of a global memory object, which is what clipboard data is kept in.
+unsafe fn global_bytes(handle: HGLOBAL) -> Option<Vec<u8>> {
+    if handle.is_null() {
+        return None
+    }
+    let memory = GlobalLock(handle);
+    if memory.is_null() {
+        return None
+    }
+    let bytes = slice::from_raw_parts(memory.cast::<u8>(), GlobalSize(handle)).to_vec();
+    let _ = GlobalUnlock(handle);
+    Some(bytes)
+}
+
+/// Replaces the contents of the clipboard, taking it over with the window.
+unsafe fn set_clipboard(hwnd: HWND, contents: &ClipboardContents) -> bool {
+    if !open_clipboard(hwnd) {
+        return false
+    }
+    let mut success = EmptyClipboard() != FALSE;
+    for (format, bytes) in &contents.data {
+        let wstr: Vec<WCHAR>;
+        let bytes = match format {
+            // Text is UTF-16, and has to be null-terminated
+            ClipboardFormat::Text => {
+                wstr = String::from_utf8_lossy(bytes).encode_utf16().chain(Some(0)).collect();
+                slice::from_raw_parts(wstr.as_ptr().cast::<u8>(), wstr.len() * mem::size_of::<WCHAR>())
+            },
+            ClipboardFormat::Image => clipboard::bmp_to_dib(bytes),
+            ClipboardFormat::Custom(_) => bytes.as_slice(),
+        };
+        let id = clipboard_format_id(format);
+        success &= id != 0 && set_clipboard_data(id, bytes);
+    }
+    let _ = CloseClipboard();
+    success
+}
+
+/// Puts data on the opened clipboard, in a global memory object that the clipboard then owns.
+unsafe fn set_clipboard_data(id: UINT, bytes: &[u8]) -> bool {
+    // Zero-sized global memory objects are discarded, which can't be locked
+    let handle = GlobalAlloc(GMEM_MOVEABLE, bytes.len().max(1));
+    if handle.is_null() {
+        return false
+    }
+    let memory = GlobalLock(handle);
+    if memory.is_null() {
+        let _ = GlobalFree(handle);
+        return false
+    }
+    ptr::copy_nonoverlapping(bytes.as_ptr(), memory.cast::<u8>(), bytes.len());
+    let _ = GlobalUnlock(handle);
+    if SetClipboardData(id, handle).is_null() {
+        let _ = GlobalFree(handle);
+        return false
+    }
+    true
+}
+
+/// Implementation container for `gl::GlContext`.
+///
+/// Everything is done on the calling thread, as that's where the context is current.
+pub struct GlContextImpl {
+    hwnd: HWND,
+    dc: HDC,
+    hglrc: HGLRC,
+    opengl32: HMODULE,
+    wgl: &'static WglDL,
+    ext: WglExtDL,
+}
+
+// Contexts can move between threads, as long as they're not current on any
+unsafe impl Send for GlContextImpl {}
+
+impl GlContextImpl {
+    #[inline]
+    pub fn make_current(&self) -> bool {
+        unsafe { self.wgl.wglMakeCurrent(self.dc, self.hglrc) == Some(TRUE) }
+    }
+
+    #[inline]
+    pub fn make_not_current(&self) -> bool {
+        self.is_current() && unsafe { self.wgl.wglMakeCurrent(ptr::null_mut(), ptr::null_mut()) == Some(TRUE) }
+    }
+
+    #[inline]
+    pub fn is_current(&self) -> bool {
+        unsafe { self.wgl.wglGetCurrentContext() == Some(self.hglrc) }
+    }
+
+    #[inline]
+    pub fn swap_buffers(&self) -> bool {
+        unsafe { SwapBuffers(self.dc) != FALSE }
+    }
+
+    #[inline]
+    pub fn set_swap_interval(&self, interval: i32) -> bool {
+        unsafe { self.ext.wglSwapIntervalEXT(interval) == Some(TRUE) }
+    }
+
+    #[inline]
+    pub fn get_proc_address(&self, name: &CStr) -> *const c_void {
+        unsafe { wgl_get_proc_address(self.opengl32, name.as_ptr()) as *const c_void }
+    }
+
+    #[inline]
+    pub fn resize(&self, _width: u32, _height: u32) {
+        // The window is the drawable, so it's always the right size
+    }
+}
+
+impl ops::Drop for GlContextImpl {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = self.make_not_current();
+            let _ = self.wgl.wglDeleteContext(self.hglrc);
+            let _ = ReleaseDC(self.hwnd, self.dc);
+        }
+    }
+}
+
+unsafe fn create_gl_context(hwnd: HWND, builder: &GlContextBuilder) -> Result<GlContextImpl, Error> {
+    let wgl = WGL.get().as_ref().ok_or(Error::Unsupported("OpenGL"))?;
+    let ext = load_wgl_extensions(wgl)?;
+    let dc = GetDC(hwnd);
+    if dc.is_null() {
+        return Err(Error::ContextCreation(GetLastError()))
+    }
+    let hglrc = set_gl_pixel_format(&ext, dc, builder).and_then(|()| create_wgl_context(wgl, &ext, dc, builder));
+    match hglrc {
+        Ok(hglrc) => Ok(GlContextImpl { hwnd, dc, hglrc, opengl32: opengl32(), wgl, ext }),
+        Err(err) => {
+            let _ = ReleaseDC(hwnd, dc);
+            Err(err)
+        },
+    }
+}
+
+/// Loads the WGL extensions, which takes a current context, so one is made on a throwaway window.
+///
+/// The pixel format of a window can only be set once, which is why it can't be our own window.
+unsafe fn load_wgl_extensions(wgl: &WglDL) -> Result<WglExtDL, Error> {
+    let mut class_name = Vec::new();
+    let class_name = str_to_wstr("STATIC", &mut class_name);
+    let hwnd = CreateWindowExW(
+        0,
+        class_name,
+        class_name,
+        WS_POPUP,
+        0,
+        0,
+        1,
+        1,
+        ptr::null_mut(),
+        ptr::null_mut(),
+        this_hinstance(),
+        ptr::null_mut(),
+    );
+    if hwnd.is_null() {
+        return Err(Error::ContextCreation(GetLastError()))
+    }
+    let dc = GetDC(hwnd);
+    let mut pfd: PIXELFORMATDESCRIPTOR = mem::zeroed();
+    pfd.nSize = mem::size_of::<PIXELFORMATDESCRIPTOR>() as WORD;
+    pfd.nVersion = 1;
+    pfd.dwFlags = PFD_DRAW_TO_WINDOW | PFD_SUPPORT_OPENGL | PFD_DOUBLEBUFFER;
+    pfd.iPixelType = PFD_TYPE_RGBA;
+    pfd.cColorBits = 24;
+    pfd.iLayerType = PFD_MAIN_PLANE;
+    let format = ChoosePixelFormat(dc, &pfd);
+    let hglrc = if format != 0 && SetPixelFormat(dc, format, &pfd) != FALSE {
+        wgl.wglCreateContext(dc).unwrap_or(ptr::null_mut())
+    } else {
+        ptr::null_mut()
+    };
+    let result = if hglrc.is_null() {
+        Err(Error::ContextCreation(GetLastError()))
+    } else {
+        // Whatever was current before is put back, so this can't pull the rug out from under anyone
+        let (prev_dc, prev_hglrc) =
+            (wgl.wglGetCurrentDC().unwrap_or(ptr::null_mut()), wgl.wglGetCurrentContext().unwrap_or(ptr::null_mut()));
+        let _ = wgl.wglMakeCurrent(dc, hglrc);
+        let ext = WglExtDL::link();
+        let _ = wgl.wglMakeCurrent(prev_dc, prev_hglrc);
+        let _ = wgl.wglDeleteContext(hglrc);
+        Ok(ext)
+    };
+    let _ = ReleaseDC(hwnd, dc);
+    let _ = DestroyWindow(hwnd);
+    result
+}
+
+/// Picks a pixel format for the window, unless it already has one (from a previous context).
+unsafe fn set_gl_pixel_format(ext: &WglExtDL, dc: HDC, builder: &GlContextBuilder) -> Result<(), Error> {
+    if GetPixelFormat(dc) != 0 {
+        return Ok(())
+    }
+    let mut pfd: PIXELFORMATDESCRIPTOR = mem::zeroed();
+    pfd.nSize = mem::size_of::<PIXELFORMATDESCRIPTOR>() as WORD;
+    pfd.nVersion = 1;
+    let mut attributes = vec![
+        WGL_DRAW_TO_WINDOW_ARB, TRUE,
+        WGL_SUPPORT_OPENGL_ARB, TRUE,
+        WGL_DOUBLE_BUFFER_ARB, TRUE,
+        WGL_PIXEL_TYPE_ARB, WGL_TYPE_RGBA_ARB,
+        WGL_COLOR_BITS_ARB, 24,
+        WGL_ALPHA_BITS_ARB, 8,
+        WGL_DEPTH_BITS_ARB, builder.depth_bits.into(),
+        WGL_STENCIL_BITS_ARB, builder.stencil_bits.into(),
+    ];
+    if builder.samples > 0 {
+        attributes.extend_from_slice(&[WGL_SAMPLE_BUFFERS_ARB, 1, WGL_SAMPLES_ARB, builder.samples.into()]);
+    }
+    if builder.srgb {
+        attributes.extend_from_slice(&[WGL_FRAMEBUFFER_SRGB_CAPABLE_ARB, TRUE]);
+    }
+    attributes.push(0);
+    let mut format = 0;
+    let mut count = 0;
+    let format = match ext.wglChoosePixelFormatARB(dc, attributes.as_ptr(), ptr::null(), 1, &mut format, &mut count) {
+        Some(chosen) if chosen == FALSE || count == 0 => return Err(Error::Unsupported("pixel format")),
+        Some(_) => format,
+        None if builder.samples > 0 || builder.srgb => return Err(Error::Unsupported("WGL_ARB_pixel_format")),
+        None => {
+            pfd.dwFlags = PFD_DRAW_TO_WINDOW | PFD_SUPPORT_OPENGL | PFD_DOUBLEBUFFER;
+            pfd.iPixelType = PFD_TYPE_RGBA;
+            pfd.cColorBits = 24;
+            pfd.cAlphaBits = 8;
+            pfd.cDepthBits = builder.depth_bits;
+            pfd.cStencilBits = builder.stencil_bits;
+            pfd.iLayerType = PFD_MAIN_PLANE;
+            ChoosePixelFormat(dc, &pfd)
+        },
+    };
+    if format == 0 {
+        return Err(Error::Unsupported("pixel format"))
+    }
+    let _ = DescribePixelFormat(dc, format, mem::size_of::<PIXELFORMATDESCRIPTOR>() as UINT, &mut pfd);
+    if SetPixelFormat(dc, format, &pfd) == FALSE {
+        return Err(Error::ContextCreation(GetLastError()))
+    }
+    Ok(())
+}
+
+unsafe fn create_wgl_context(wgl: &WglDL, ext: &WglExtDL, dc: HDC, builder: &GlContextBuilder) -> Result<HGLRC, Error> {
+    let (major, minor) = builder.version;
+    let profile = match builder.profile {
+        GlProfile::Core => WGL_CONTEXT_CORE_PROFILE_BIT_ARB,
+        GlProfile::Compatibility => WGL_CONTEXT_COMPATIBILITY_PROFILE_BIT_ARB,
+    };
+    let flags = if builder.debug { WGL_CONTEXT_DEBUG_BIT_ARB } else { 0 };
+    let attributes = [
+        WGL_CONTEXT_MAJOR_VERSION_ARB, major.into(),
+        WGL_CONTEXT_MINOR_VERSION_ARB, minor.into(),
+        WGL_CONTEXT_PROFILE_MASK_ARB, profile,
+        WGL_CONTEXT_FLAGS_ARB, flags,
+        0,
+    ];
+    let hglrc = match ext.wglCreateContextAttribsARB(dc, ptr::null_mut(), attributes.as_ptr()) {
+        Some(hglrc) => Some(hglrc),
+        // Legacy contexts are whatever the driver gives out, usually compatibility profile
+        None if builder.version >= (3, 0) || builder.debug => return Err(Error::Unsupported("WGL_ARB_create_context")),
+        None => wgl.wglCreateContext(dc),
+    };
+    match hglrc {
+        Some(hglrc) if !hglrc.is_null() => Ok(hglrc),
+        _ => Err(Error::ContextCreation(GetLastError())),
+    }
+}
+
 #[inline]
 unsafe fn user_data<'a>(hwnd: HWND) -> &'a mut WindowImplData {
     &mut *(get_window_data(hwnd, GWL_USERDATA) as *mut WindowImplData)
@@ -881,9 +2374,9 @@ unsafe extern "system" fn window_proc(
     lparam: LPARAM,
 ) -> LRESULT {
     macro_rules! mouse_event {
-        ($v:ident , $b:ident) => {{
+        ($b:ident , $pressed:expr) => {{
             #[cfg(feature = "input")] {
-                user_data(hwnd).push_event(Event::$v(MouseButton::$b));
+                mouse_button(hwnd, user_data(hwnd), MouseButton::$b, $pressed);
             }
             0
         }};
@@ -905,7 +2398,10 @@ unsafe extern "system" fn window_proc(
             // `lpCreateParams` is the first field, so `CREATESTRUCTW *` is `WindowImplCreateParams **`
             let _params = &mut **(lparam as *const *mut WindowImplCreateParams);
 
-            // ...
+            // The position is final by now, and shouldn't be reported as a move
+            let mut rect: RECT = mem::zeroed();
+            let _ = GetWindowRect(hwnd, &mut rect);
+            user_data(hwnd).outer_position = (rect.left, rect.top);
 
             0 // OK
         },
@@ -915,17 +2411,74 @@ unsafe extern "system" fn window_proc(
         // Nothing can actually be done once this message is received, and you always return 0.
         WM_DESTROY => {
             // Make sure it was received from the window being dropped, and not manually sent.
-            if user_data(hwnd).destroy_flag.load(atomic::Ordering::Acquire) {
+            let user_data = user_data(hwnd);
+            if user_data.destroy_flag.load(atomic::Ordering::Acquire) {
+                // Exclusive fullscreen changes the monitor's video mode, which has to be undone
+                if let Some(device) = user_data.fullscreen_device.take() {
+                    let _ = ChangeDisplaySettingsExW(
+                        device.as_ptr(),
+                        ptr::null_mut(),
+                        ptr::null_mut(),
+                        0,
+                        ptr::null_mut(),
+                    );
+                }
+
+                // Raw input would otherwise stay registered to a window that doesn't exist
+                #[cfg(feature = "input")]
+                set_raw_mouse_motion(hwnd, user_data, false);
+
+                // OLE holds on to the drop target until it's revoked, which has to be done while the window exists
+                if user_data.accept_drops {
+                    let _ = RevokeDragDrop(hwnd);
+                }
+                let _ = WIN32.dl.RemoveClipboardFormatListener(hwnd);
+
+                if matches!(user_data.cursor_icon, CursorIcon::Custom(_)) && !user_data.cursor.is_null() {
+                    let _ = DestroyIcon(user_data.cursor);
+                }
+                for icon in user_data.icon_handles {
+                    if !icon.is_null() {
+                        let _ = DestroyIcon(icon);
+                    }
+                }
+
+                // The clip is global, so it'd otherwise outlive the window
+                if user_data.is_cursor_clipped {
+                    let _ = ClipCursor(ptr::null());
+                }
+
                 // Send `WM_QUIT` with exit code 0
                 PostQuitMessage(0);
             }
             0
         },
 
-        // TODO: ...
+        // Received after the window has moved.
+        // wParam: Unused, should be ignored.
+        // lParam: LOWORD=x, HIWORD=y (signed, of the client area in screen space)
+        // Return 0.
         WM_MOVE => {
-            // TODO: ...
-            DefWindowProcW(hwnd, msg, wparam, lparam)
+            // Minimized windows are "moved" off to (-32000, -32000), which is nothing to report
+            if IsIconic(hwnd) == FALSE {
+                // The frame is what's positioned, and it's not in the parameters, so it's queried
+                let user_data = user_data(hwnd);
+                let mut rect: RECT = mem::zeroed();
+                let _ = GetWindowRect(hwnd, &mut rect);
+                if user_data.outer_position != (rect.left, rect.top) {
+                    user_data.outer_position = (rect.left, rect.top);
+                    let position = Point::Physical(rect.left, rect.top);
+                    let dpi_scale = user_data.current_dpi as f64 / BASE_DPI as f64;
+                    let event = if user_data.is_dpi_logical {
+                        Event::Move((position.to_logical(dpi_scale), dpi_scale))
+                    } else {
+                        Event::Move((position, dpi_scale))
+                    };
+                    user_data.push_event(event);
+                }
+                update_cursor_clip(hwnd, user_data);
+            }
+            0
         },
 
         // [ Event 0x0004 is not known to exist ]
@@ -973,6 +2526,9 @@ unsafe extern "system" fn window_proc(
             }
             user_data.push_events(events.slice());
 
+            // This also releases the cursor when minimized, and takes it back when restored
+            update_cursor_clip(hwnd, user_data);
+
             0
         },
 
@@ -1019,7 +2575,7 @@ unsafe extern "system" fn window_proc(
                 user_data.push_event(Event::Focus(true));
             }
 
-            // TODO: Cursor lock nonsense
+            update_cursor_clip(hwnd, user_data);
 
             0
         },
@@ -1032,6 +2588,7 @@ unsafe extern "system" fn window_proc(
                 user_data.is_focused = false;
                 user_data.push_event(Event::Focus(false));
             }
+            update_cursor_clip(hwnd, user_data);
             0
         },
 
@@ -1053,8 +2610,17 @@ unsafe extern "system" fn window_proc(
         WM_SETREDRAW => 0,
 
         // Received when a system function says we should repaint some of the window.
-        // Since we don't care about Win32, we just ignore this. Return 0.
-        WM_PAINT => 0,
+        // The last image from `present_pixels` (if any) is redrawn, and validating the area stops the repeats.
+        // wParam & lParam are unused. Return 0.
+        WM_PAINT => {
+            let mut paint: PAINTSTRUCT = mem::zeroed();
+            let dc = BeginPaint(hwnd, &mut paint);
+            if !dc.is_null() {
+                let _ = draw_frame(dc, user_data(hwnd), None);
+                let _ = EndPaint(hwnd, &paint);
+            }
+            0
+        },
 
         // Received when a window is requested to close.
         // wParam & lParam are unused. Return 0.
@@ -1117,12 +2683,124 @@ unsafe extern "system" fn window_proc(
         // Return 0.
         WM_KEYDOWN | WM_KEYUP => {
             #[cfg(feature = "input")]
-            if let Some(key) = translate_vk(wparam) {
-                user_data(hwnd).push_event(map_tr_state(extend_key(key, lparam), lparam));
+            user_data(hwnd).push_event(key_event(wparam, lparam));
+            0
+        },
+
+        // Received when a key press has been translated into a character (by `TranslateMessage`).
+        // wParam: UTF-16 code unit, so characters outside of the BMP come as two surrogates in a row.
+        // lParam: Same as `WM_KEYDOWN`.
+        // Return 0.
+        WM_CHAR => {
+            #[cfg(feature = "input")]
+            {
+                let user_data = user_data(hwnd);
+                let unit = wparam as u16;
+                if (0xD800..0xDC00).contains(&unit) {
+                    user_data.high_surrogate = Some(unit);
+                } else {
+                    // Lone surrogates can only come from broken input methods, so they're dropped
+                    let units = user_data.high_surrogate.take().into_iter().chain(Some(unit));
+                    for character in char::decode_utf16(units).filter_map(Result::ok) {
+                        user_data.push_event(Event::Character(character));
+                    }
+                }
+            }
+            0
+        },
+
+        // Received when an application (not `TranslateMessage`) sends a UTF-32 character.
+        // wParam: UTF-32 character, or `UNICODE_NOCHAR` to ask if this message is supported at all.
+        // lParam: Same as `WM_KEYDOWN`.
+        // Return `TRUE` to `UNICODE_NOCHAR`, otherwise 0.
+        WM_UNICHAR => {
+            if wparam == UNICODE_NOCHAR {
+                return TRUE as LRESULT
+            }
+            #[cfg(feature = "input")]
+            {
+                let user_data = user_data(hwnd);
+                if user_data.text_input {
+                    if let Some(character) = char::from_u32(wparam as u32) {
+                        user_data.push_event(Event::Character(character));
+                    }
+                }
+            }
+            0
+        },
+
+        // Received for characters typed while Alt is held, which `DefWindowProcW` turns into menu
+        // mnemonics (and a beep when there's no menu), so they're ignored like the system keys are.
+        WM_SYSCHAR => 0,
+
+        // Received when the window is activated, to tell it which parts of the IME UI to show.
+        // wParam: Whether the window is active.
+        // lParam: `ISC_*` flags for what the IME should show itself.
+        // The composition is reported as events, so that part of the UI is hidden.
+        WM_IME_SETCONTEXT => {
+            #[cfg(feature = "input")]
+            let lparam = lparam & !ISC_SHOWUICOMPOSITIONWINDOW;
+            DefWindowProcW(hwnd, msg, wparam, lparam)
+        },
+
+        // Received when the IME starts a composition.
+        // wParam & lParam: Unused.
+        // Return 0 (`DefWindowProcW` would open the IME's own composition window).
+        WM_IME_STARTCOMPOSITION => {
+            #[cfg(feature = "input")]
+            {
+                let user_data = user_data(hwnd);
+                update_ime_position(hwnd, user_data);
+                user_data.push_event(Event::Ime(ImeEvent::Start));
+            }
+            0
+        },
+
+        // Received when the IME's composition has changed.
+        // wParam: The last character changed (in the composition string).
+        // lParam: `GCS_*` flags for which parts of the composition changed.
+        // Return 0 (`DefWindowProcW` would send committed text as `WM_IME_CHAR` messages).
+        WM_IME_COMPOSITION => {
+            #[cfg(feature = "input")]
+            {
+                let user_data = user_data(hwnd);
+                let himc = ImmGetContext(hwnd);
+                if !himc.is_null() {
+                    let flags = lparam as DWORD;
+                    if flags & GCS_RESULTSTR != 0 {
+                        let text = String::from_utf16_lossy(&composition_string(himc, GCS_RESULTSTR));
+                        user_data.push_event_with_text(&text, |text| Event::Ime(ImeEvent::Commit(text)));
+                    }
+                    if flags & GCS_COMPSTR != 0 {
+                        let wide = composition_string(himc, GCS_COMPSTR);
+
+                        // The cursor is in UTF-16 code units, but the cursor range is in bytes of UTF-8
+                        let cursor = if flags & GCS_CURSORPOS != 0 {
+                            let cursor = ImmGetCompositionStringW(himc, GCS_CURSORPOS, ptr::null_mut(), 0);
+                            let cursor = cursor.max(0) as usize;
+                            let byte = String::from_utf16_lossy(&wide[..cursor.min(wide.len())]).len();
+                            Some((byte, byte))
+                        } else {
+                            None
+                        };
+                        let text = String::from_utf16_lossy(&wide);
+                        user_data.push_event_with_text(&text, |text| Event::Ime(ImeEvent::Preedit(text, cursor)));
+                    }
+                    let _ = ImmReleaseContext(hwnd, himc);
+                }
             }
             0
         },
 
+        // Received when the IME's composition has ended, by being committed or cancelled.
+        // wParam & lParam: Unused.
+        // Return 0.
+        WM_IME_ENDCOMPOSITION => {
+            #[cfg(feature = "input")]
+            user_data(hwnd).push_event(Event::Ime(ImeEvent::End));
+            0
+        },
+
         // Same as `WM_KEYDOWN` & `WM_KEYUP` but with a few (horrific) bitfield quirks.
         WM_SYSKEYDOWN | WM_SYSKEYUP => {
             let mut user_data = user_data(hwnd);
@@ -1178,6 +2856,46 @@ unsafe extern "system" fn window_proc(
             DefWindowProcW(hwnd, msg, wparam, lparam)
         },
 
+        // Received when the DPI of the window has changed, such as by being moved to another monitor.
+        // wParam: LOWORD=new X DPI, HIWORD=new Y DPI (they're always the same).
+        // lParam: `*const RECT` of the suggested new outer area, scaled for the new DPI.
+        // Return 0.
+        WM_DPICHANGED => {
+            let user_data = user_data(hwnd);
+            let dpi = (wparam & 0xFFFF) as UINT;
+            if user_data.current_dpi != dpi {
+                user_data.current_dpi = dpi;
+                user_data.push_event(Event::ScaleChanged(dpi as f64 / BASE_DPI as f64));
+
+                // Custom cursors may have a better image for the new DPI
+                if let CursorIcon::Custom(cursor) = &user_data.cursor_icon {
+                    set_cursor(hwnd, user_data, CursorIcon::Custom(cursor.clone()));
+                }
+
+                // Same goes for icons, as the sizes the system wants depend on the DPI
+                if user_data.icon.is_some() {
+                    set_icon(hwnd, user_data, user_data.icon.clone());
+                }
+
+                // Maximized & fullscreen windows are already sized by the system, and the position
+                // is kept the same while minimized, so only normal windows need moving around
+                if user_data.fullscreen.is_none() && !user_data.is_maximized && !user_data.is_minimized {
+                    let suggested = &*(lparam as *const RECT);
+                    let (width, height) = if user_data.is_dpi_logical {
+                        rect_to_size2d(suggested)
+                    } else {
+                        // Physical sizes are exact pixel values, so the frame is all that's rescaled
+                        let (cw, ch) = user_data.client_area_size;
+                        let (fw, fh) = frame_size(&user_data.style, dpi);
+                        (cw as LONG + fw, ch as LONG + fh)
+                    };
+                    const MASK: UINT = SWP_NOACTIVATE | SWP_NOOWNERZORDER | SWP_NOZORDER;
+                    let _ = SetWindowPos(hwnd, ptr::null_mut(), suggested.left, suggested.top, width, height, MASK);
+                }
+            }
+            0
+        },
+
         // Received when the user clicks a window menu control (formerly "system menu").
         // wParam: Command enum.
         // lParam: Mouse position (screen coords, word+word) or accelerator flags in hiword.
@@ -1208,8 +2926,10 @@ unsafe extern "system" fn window_proc(
                 // On some versions of windows, the border padding reports `WM_MOUSEMOVE`!
                 // It's not even client area, but it does, and only when `WS_THICKFRAME` is unset?!
                 // So if SM_CXBORDER is 1 and SM_CXBORDERPADDING is 4 you'd get -5 <= x <= width+5!
-                if x >= 0 && (x as u32) < cw && y >= 0 && (y as u32) < ch {
-                    let point = Point::Physical(x as u32, y as u32);
+                // While the mouse is captured for held buttons though, it can be anywhere.
+                let is_inside = x >= 0 && (x as u32) < cw && y >= 0 && (y as u32) < ch;
+                if is_inside || !user_data.held_buttons.is_empty() {
+                    let point = Point::Physical(x as i32, y as i32);
                     let dpi_scale = user_data.current_dpi as f64 / BASE_DPI as f64;
                     let event = if user_data.is_dpi_logical {
                         Event::MouseMove((point.to_logical(dpi_scale), dpi_scale))
@@ -1223,44 +2943,115 @@ unsafe extern "system" fn window_proc(
             0
         },
 
+        // Received when a registered raw input device has input, see `set_raw_mouse_motion`.
+        // wParam: `RIM_INPUT` if the window is in the foreground, `RIM_INPUTSINK` otherwise.
+        // lParam: `HRAWINPUT` to read the input from with `GetRawInputData`.
+        // Return 0, after passing it on to `DefWindowProcW` which cleans up the input.
+        WM_INPUT => {
+            #[cfg(feature = "input")]
+            {
+                let user_data = user_data(hwnd);
+                let mut input = mem::MaybeUninit::<RAWINPUT>::zeroed();
+                let mut size = mem::size_of::<RAWINPUT>() as UINT;
+                let (buffer, header_size) = (input.as_mut_ptr().cast(), mem::size_of::<RAWINPUTHEADER>() as UINT);
+                let read = GetRawInputData(lparam as HRAWINPUT, RID_INPUT, buffer, &mut size, header_size);
+                let input = &*input.as_ptr();
+
+                // Absolute motion comes from tablets & remote desktop, and isn't a delta at all
+                if user_data.raw_mouse_motion
+                    && read != 0
+                    && read != UINT::MAX
+                    && input.header.dwType == RIM_TYPEMOUSE
+                    && input.mouse.usFlags & MOUSE_MOVE_ABSOLUTE == 0
+                    && (input.mouse.lLastX != 0 || input.mouse.lLastY != 0)
+                {
+                    let delta = (input.mouse.lLastX.into(), input.mouse.lLastY.into());
+                    user_data.push_event(Event::MouseMotionRaw(delta));
+                }
+            }
+            DefWindowProcW(hwnd, msg, wparam, lparam)
+        },
+
         // Received when the mouse buttons are down/up. Return 0.
         // wParam indicates what other buttons are down.
         // lParam contains the X and Y coordinate.
-        WM_LBUTTONDOWN => mouse_event!(MouseDown, Left),
-        WM_LBUTTONUP => mouse_event!(MouseUp, Left),
-        WM_RBUTTONDOWN => mouse_event!(MouseDown, Right),
-        WM_RBUTTONUP => mouse_event!(MouseUp, Right),
-        WM_MBUTTONDOWN => mouse_event!(MouseDown, Middle),
-        WM_MBUTTONUP => mouse_event!(MouseUp, Middle),
+        WM_LBUTTONDOWN => mouse_event!(Left, true),
+        WM_LBUTTONUP => mouse_event!(Left, false),
+        WM_RBUTTONDOWN => mouse_event!(Right, true),
+        WM_RBUTTONUP => mouse_event!(Right, false),
+        WM_MBUTTONDOWN => mouse_event!(Middle, true),
+        WM_MBUTTONUP => mouse_event!(Middle, false),
         _ev @ WM_XBUTTONDOWN | _ev @ WM_XBUTTONUP => {
             #[cfg(feature = "input")]
             {
                 // For X buttons, the HIWORD in wParam indicates which X button it is.
-                let user_data = user_data(hwnd);
-                let event = if _ev == WM_XBUTTONDOWN {
-                    Event::MouseDown
-                } else {
-                    Event::MouseUp
+                let button = match ((wparam >> 16) & 0xFFFF) as WORD {
+                    XBUTTON1 => Some(MouseButton::Mouse4),
+                    XBUTTON2 => Some(MouseButton::Mouse5),
+                    _ => None,
                 };
-                match ((wparam >> 16) & 0xFFFF) as WORD {
-                    XBUTTON1 => user_data.push_event(event(MouseButton::Mouse4)),
-                    XBUTTON2 => user_data.push_event(event(MouseButton::Mouse5)),
-                    _ => (),
+                if let Some(button) = button {
+                    mouse_button(hwnd, user_data(hwnd), button, _ev == WM_XBUTTONDOWN);
+                }
+            }
+            0
+        },
+
+        // Received when the mouse capture is lost, which is either our own `ReleaseCapture`,
+        // or something else taking it while buttons are held, such as the window losing focus.
+        // wParam: Unused, set to zero.
+        // lParam: `HWND` of the window gaining the capture, which may be null.
+        // Return 0.
+        WM_CAPTURECHANGED => {
+            #[cfg(feature = "input")]
+            {
+                // The buttons will be released somewhere we won't hear about, so they're released now
+                let user_data = user_data(hwnd);
+                let modifiers = modifier_state();
+                for button in mem::take(&mut user_data.held_buttons) {
+                    user_data.push_event(Event::MouseUp((button, modifiers)));
                 }
             }
             0
         },
 
-        // Received when the mouse wheel is rotated.
+        // Received when touch or a pen (Win8+) touches down, moves or hovers, lifts up,
+        // or is taken away from the window, such as by a system gesture.
+        // The mouse doesn't send these, as `EnableMouseInPointer` isn't used.
+        // wParam: LOWORD=pointer id, HIWORD=pointer flags
+        // lParam: LOWORD=screen x, HIWORD=screen y
+        // Return 0 to stop the system from sending mouse messages for it, otherwise pass it on.
+        WM_POINTERDOWN | WM_POINTERUPDATE | WM_POINTERUP | WM_POINTERCAPTURECHANGED => {
+            #[cfg(feature = "input")]
+            {
+                if pointer_event(hwnd, user_data(hwnd), msg, wparam) {
+                    return 0
+                }
+            }
+            DefWindowProcW(hwnd, msg, wparam, lparam)
+        },
+
+        // Received when the mouse wheel is rotated, or tilted for `WM_MOUSEHWHEEL`.
+        // Precision touchpads send these too, with deltas smaller than a notch.
         // wParam: HIWORD=delta in WHEEL_DELTA(120) multiples, LOWORD=vk state (see msdn)
         // lParam: HIWORD=mouse x, LOWORD=mouse y
         // Return 0.
-        WM_MOUSEWHEEL => {
+        WM_MOUSEWHEEL | WM_MOUSEHWHEEL => {
             #[cfg(feature = "input")]
             {
                 let delta = ((wparam >> 16) & 0xFFFF) as c_short;
                 if delta != 0 {
-                    user_data(hwnd).push_event(Event::MouseWheel(NonZeroI32::new_unchecked(delta.into())));
+                    let user_data = user_data(hwnd);
+                    let modifiers = modifier_state();
+                    let lines = delta as f64 / WHEEL_DELTA as f64;
+                    let scroll = if msg == WM_MOUSEWHEEL {
+                        let delta = NonZeroI32::new_unchecked(delta.into());
+                        user_data.push_event(Event::MouseWheel((delta, modifiers)));
+                        ScrollDelta::Lines(0.0, lines)
+                    } else {
+                        ScrollDelta::Lines(lines, 0.0)
+                    };
+                    user_data.push_event(Event::MouseScroll((scroll, None, modifiers)));
                 }
             }
             0
@@ -1311,35 +3102,81 @@ unsafe extern "system" fn window_proc(
                 }
 
                 // Set styles, refresh
-                update_window_style(hwnd, &user_data.style);
+                update_window_style(hwnd, user_data);
             }
             0
         },
 
         // Custom event: Set the window cursor that's sent to `WM_SETCURSOR`.
-        // wParam: `Cursor as u32`
-        // lParam: Unused, set to zero.
+        // wParam: Non-zero if lParam is boxed (sent asynchronously) and must be freed.
+        // lParam: `*const CursorIcon`
         // Return 0.
         RAMEN_WM_SETCURSOR => {
-            let user_data = user_data(hwnd);
-            let cursor = mem::transmute::<_, Cursor>(wparam as u32);
-            let rsrc = cursor_to_int_resource(cursor);
-
-            // `LoadImageW` is not only superseding `LoadCursorW` but it's ~20µs faster. Wow, use this!
-            user_data.cursor = if !rsrc.is_null() {
-                LoadImageW(ptr::null_mut(), rsrc, IMAGE_CURSOR, 0, 0, LR_DEFAULTSIZE | LR_SHARED).cast()
+            let cursor = if wparam != 0 {
+                *Box::from_raw(lparam as *mut CursorIcon)
             } else {
-                ptr::null_mut()
+                (*(lparam as *const CursorIcon)).clone()
             };
+            set_cursor(hwnd, user_data(hwnd), cursor);
+            0
+        },
 
-            // Immediately update the cursor icon if it's within the client area.
-            let mut mouse_pos: POINT = mem::zeroed();
-            if GetCursorPos(&mut mouse_pos) != 0 && WindowFromPoint(POINT { ..mouse_pos }) == hwnd {
-                let client_area = client_area_screen_space(hwnd);
-                if PtInRect(&client_area, mouse_pos) != 0 {
-                    let _ = SetCursor(user_data.cursor);
+        // Custom event: Set how the cursor is held inside the window.
+        // wParam: `CursorGrab as WPARAM`
+        // lParam: Unused, set to zero.
+        // Return 0.
+        RAMEN_WM_SETCURSORGRAB => {
+            let user_data = user_data(hwnd);
+            user_data.cursor_grab = match wparam {
+                1 => CursorGrab::Confined,
+                2 => CursorGrab::Locked,
+                _ => CursorGrab::None,
+            };
+            if user_data.cursor_grab == CursorGrab::Locked {
+                // Lock the cursor where it is, which gets clamped to the client area if it's outside
+                let mut point: POINT = mem::zeroed();
+                if GetCursorPos(&mut point) != 0 && ScreenToClient(hwnd, &mut point) != 0 {
+                    user_data.cursor_lock_position = (point.x, point.y);
                 }
             }
+            update_cursor_clip(hwnd, user_data);
+            0
+        },
+
+        // Custom event: Move the cursor to a position relative to the client area.
+        // wParam: Non-zero if lParam is boxed (sent asynchronously) and must be freed.
+        // lParam: `*const Point`
+        // Return 0.
+        RAMEN_WM_SETCURSORPOS => {
+            let position = if wparam != 0 {
+                *Box::from_raw(lparam as *mut Point)
+            } else {
+                *(lparam as *const Point)
+            };
+            let user_data = user_data(hwnd);
+            let (x, y) = position.as_physical(user_data.current_dpi as f64 / BASE_DPI as f64);
+
+            // The lock is moved first, as `SetCursorPos` can't go outside of the clip
+            user_data.cursor_lock_position = (x, y);
+            update_cursor_clip(hwnd, user_data);
+            let mut point = POINT { x, y };
+            if ClientToScreen(hwnd, &mut point) != 0 {
+                let _ = SetCursorPos(point.x, point.y);
+            }
+            0
+        },
+
+        // Custom event: Set the title bar and taskbar icons.
+        // wParam: Non-zero if lParam is boxed (sent asynchronously) and must be freed.
+        // lParam: `*const Option<Icon>`
+        // Return 0.
+        RAMEN_WM_SETICON => {
+            let icon = if wparam != 0 {
+                *Box::from_raw(lparam as *mut Option<Icon>)
+            } else {
+                (*(lparam as *const Option<Icon>)).clone()
+            };
+            set_icon(hwnd, user_data(hwnd), icon);
             0
         },
 
@@ -1365,7 +3202,99 @@ unsafe extern "system" fn window_proc(
             let resizable = wparam != 0;
             if user_data.style.resizable != resizable {
                 user_data.style.resizable = resizable;
-                update_window_style(hwnd, &user_data.style);
+                update_window_style(hwnd, user_data);
+            }
+            0
+        },
+
+        // Custom event: Set whether typed text is translated into `Event::Character`.
+        // wParam: If non-zero, enabled, otherwise disabled.
+        // lParam: Unused, set to zero.
+        // Return 0.
+        RAMEN_WM_SETTEXTINPUT => {
+            #[cfg(feature = "input")]
+            {
+                let user_data = user_data(hwnd);
+                user_data.text_input = wparam != 0;
+                user_data.high_surrogate = None;
+                update_ime_context(hwnd, user_data);
+            }
+            0
+        },
+
+        // Custom event: Set whether raw mouse motion is reported.
+        // wParam: If non-zero, enabled, otherwise disabled.
+        // lParam: Unused, set to zero.
+        // Return 0.
+        RAMEN_WM_SETRAWMOUSE => {
+            #[cfg(feature = "input")]
+            set_raw_mouse_motion(hwnd, user_data(hwnd), wparam != 0);
+            0
+        },
+
+        // Custom event: Set whether the IME can be used.
+        // wParam: If non-zero, allowed, otherwise disallowed.
+        // lParam: Unused, set to zero.
+        // Return 0.
+        RAMEN_WM_SETIMEALLOWED => {
+            #[cfg(feature = "input")]
+            {
+                let user_data = user_data(hwnd);
+                user_data.ime_allowed = wparam != 0;
+                update_ime_context(hwnd, user_data);
+            }
+            0
+        },
+
+        // Custom event: Set the area the IME candidate window should stay clear of.
+        // wParam: Non-zero if lParam is boxed (sent asynchronously) and must be freed.
+        // lParam: `*const (Point, Size)`
+        // Return 0.
+        RAMEN_WM_SETIMEAREA => {
+            #[cfg(feature = "input")]
+            {
+                let area = if wparam != 0 {
+                    *Box::from_raw(lparam as *mut (Point, Size))
+                } else {
+                    *(lparam as *const (Point, Size))
+                };
+                let user_data = user_data(hwnd);
+                user_data.ime_cursor_area = Some(area);
+                update_ime_position(hwnd, user_data);
+            }
+            0
+        },
+
+        // Custom event: Set whether the window is borderless, keeping the inner size the same.
+        // wParam: If non-zero, borderless, otherwise not borderless.
+        // lParam: Unused, set to zero.
+        // Return 0.
+        RAMEN_WM_SETBORDERLESS => {
+            let user_data = user_data(hwnd);
+            let borderless = wparam != 0;
+            if user_data.style.borderless != borderless {
+                let (old_fw, old_fh) = frame_size(&user_data.style, user_data.current_dpi);
+                user_data.style.borderless = borderless;
+                let (new_fw, new_fh) = frame_size(&user_data.style, user_data.current_dpi);
+
+                if user_data.fullscreen.is_some() {
+                    // Only takes effect after leaving fullscreen, so that's where to keep the size
+                    let rect = &mut user_data.restore_placement.rcNormalPosition;
+                    rect.right += new_fw - old_fw;
+                    rect.bottom += new_fh - old_fh;
+                } else if user_data.is_maximized || user_data.is_minimized {
+                    update_window_style(hwnd, user_data);
+                } else {
+                    let (width, height) = user_data.client_area_size;
+                    let style = &user_data.style;
+                    let _ = set_window_data(hwnd, GWL_STYLE, style_as_win32(style) as usize);
+                    let _ = set_window_data(hwnd, GWL_EXSTYLE, style_as_win32_ex(style) as usize);
+
+                    // Updating the frame and the size at once, so the client area never changes
+                    let (owidth, oheight) = (width as LONG + new_fw, height as LONG + new_fh);
+                    const MASK: UINT = SWP_NOMOVE | SWP_NOOWNERZORDER | SWP_NOZORDER | SWP_FRAMECHANGED;
+                    let _ = SetWindowPos(hwnd, ptr::null_mut(), 0, 0, owidth, oheight, MASK);
+                }
             }
             0
         },
@@ -1377,6 +3306,22 @@ unsafe extern "system" fn window_proc(
             let inner_size = &*(lparam as *const Size);
             let user_data = user_data(hwnd);
 
+            // While fullscreen, this is the size for when the window leaves fullscreen
+            if user_data.fullscreen.is_some() {
+                user_data.is_dpi_logical = matches!(inner_size, Size::Logical(..));
+                let (owidth, oheight) = adjust_window_for_dpi(
+                    WIN32.get(),
+                    *inner_size,
+                    style_as_win32(&user_data.style),
+                    style_as_win32_ex(&user_data.style),
+                    user_data.current_dpi,
+                );
+                let rect = &mut user_data.restore_placement.rcNormalPosition;
+                rect.right = rect.left + owidth;
+                rect.bottom = rect.top + oheight;
+                return 0
+            }
+
             user_data.client_area_size = inner_size.as_physical(user_data.current_dpi as f64 / BASE_DPI as f64);
             user_data.is_dpi_logical = matches!(inner_size, Size::Logical(..));
             let (owidth, oheight) = adjust_window_for_dpi(
@@ -1415,6 +3360,59 @@ unsafe extern "system" fn window_proc(
             0
         },
 
+        // Custom event: Query the outer position (or inner position, for `RAMEN_WM_GETINNERPOS`).
+        // wParam: `*mut Point` (out)
+        // lParam: `*mut Scale` (out)
+        RAMEN_WM_GETOUTERPOS | RAMEN_WM_GETINNERPOS => {
+            let user_data = user_data(hwnd);
+            let out_position = wparam as *mut Point;
+            let out_scale = lparam as *mut Scale;
+
+            let dpi_factor = user_data.current_dpi as f64 / BASE_DPI as f64;
+            let (x, y) = if msg == RAMEN_WM_GETINNERPOS {
+                let mut point = POINT { x: 0, y: 0 };
+                let _ = ClientToScreen(hwnd, &mut point);
+                (point.x, point.y)
+            } else {
+                user_data.outer_position
+            };
+            let position = Point::Physical(x, y);
+
+            if user_data.is_dpi_logical {
+                *out_position = position.to_logical(dpi_factor);
+            } else {
+                *out_position = position;
+            }
+            *out_scale = dpi_factor;
+
+            0
+        },
+
+        // Custom event: Set the outer position.
+        // wParam: Non-zero if lParam is boxed (sent asynchronously) and must be freed.
+        // lParam: `*const Point`
+        // Return 0.
+        RAMEN_WM_SETPOSITION => {
+            let position = if wparam != 0 {
+                *Box::from_raw(lparam as *mut Point)
+            } else {
+                *(lparam as *const Point)
+            };
+            let user_data = user_data(hwnd);
+            let (x, y) = position.as_physical(user_data.current_dpi as f64 / BASE_DPI as f64);
+
+            if user_data.fullscreen.is_some() {
+                // While fullscreen, this is the position for when the window leaves fullscreen
+                let rect = &mut user_data.restore_placement.rcNormalPosition;
+                let (width, height) = rect_to_size2d(rect);
+                *rect = RECT { left: x, top: y, right: x + width, bottom: y + height };
+            } else {
+                const MASK: UINT = SWP_NOSIZE | SWP_NOOWNERZORDER | SWP_NOZORDER;
+                let _ = SetWindowPos(hwnd, ptr::null_mut(), x, y, 0, 0, MASK);
+            }
+            0
+        },
+
         // Custom event: Query whether we're in logical DPI mode. Niche thing.
         // wParam & lParam: Unused.
         // Non-zero return if logical.
@@ -1422,10 +3420,33 @@ unsafe extern "system" fn window_proc(
             user_data(hwnd).is_dpi_logical as LPARAM
         },
 
+        // Custom event: Copy an image into the window, keeping it for `WM_PAINT`.
+        // wParam: Unused, set to zero.
+        // lParam: `*const (&[u32], (u32, u32), &[Rect])`, the pixels, their size & the damaged areas.
+        // Non-zero return if it was drawn.
+        RAMEN_WM_PRESENT => {
+            let user_data = user_data(hwnd);
+            let (pixels, size, damage) = *(lparam as *const (&[u32], (u32, u32), &[Rect]));
+            user_data.frame.clear();
+            user_data.frame.extend_from_slice(pixels);
+            user_data.frame_size = size;
+            let dc = GetDC(hwnd);
+            if dc.is_null() {
+                return 0
+            }
+            let drawn = draw_frame(dc, user_data, Some(damage));
+            let _ = ReleaseDC(hwnd, dc);
+            drawn as LRESULT
+        },
+
         RAMEN_WM_SETMAXIMIZED => {
             let user_data = user_data(hwnd);
             let maximized = wparam != 0;
-            if user_data.is_maximized != maximized {
+            if user_data.fullscreen.is_some() {
+                // Maximized or not, that's how the window leaves fullscreen
+                let show = if maximized { SW_SHOWMAXIMIZED } else { SW_SHOWNORMAL };
+                user_data.restore_placement.showCmd = show;
+            } else if user_data.is_maximized != maximized {
                 let button = if maximized { SC_MAXIMIZE } else { SC_RESTORE };
                 let _ = DefWindowProcW(hwnd, WM_SYSCOMMAND, button, 0);
                 user_data.is_maximized = maximized;
@@ -1434,6 +3455,31 @@ unsafe extern "system" fn window_proc(
             0
         },
 
+        // Custom event: Enter, switch, or leave fullscreen.
+        // wParam: Non-zero if lParam is boxed (sent asynchronously) and must be freed.
+        // lParam: `*const Option<Fullscreen>`
+        // Return 0.
+        RAMEN_WM_SETFULLSCREEN => {
+            let fullscreen = if wparam != 0 {
+                *Box::from_raw(lparam as *mut Option<window::Fullscreen>)
+            } else {
+                (*(lparam as *const Option<window::Fullscreen>)).clone()
+            };
+            let user_data = user_data(hwnd);
+            if user_data.fullscreen != fullscreen {
+                set_fullscreen(hwnd, user_data, fullscreen);
+            }
+            0
+        },
+
+        // Received when the contents of the clipboard have changed (Vista+, see `AddClipboardFormatListener`).
+        // wParam & lParam: Unused.
+        // Return 0.
+        WM_CLIPBOARDUPDATE => {
+            user_data(hwnd).push_event(Event::ClipboardChanged);
+            0
+        },
+
         _ => DefWindowProcW(hwnd, msg, wparam, lparam),
     }
 }
@@ -1701,7 +3747,7 @@ fn translate_vk(wparam: WPARAM) -> Option<Key> {
 }
 
 #[cfg(feature = "input")]
-fn sys_key_event(wparam: WPARAM, lparam: LPARAM) -> Option<Event> {
+unsafe fn sys_key_event(wparam: WPARAM, lparam: LPARAM) -> Option<Event> {
     let alt_bit = (lparam & (1 << 29)) != 0;
     let transition_state = (lparam & (1 << 31)) != 0;
 
@@ -1711,19 +3757,33 @@ fn sys_key_event(wparam: WPARAM, lparam: LPARAM) -> Option<Event> {
         return None
     }
 
-    let virtual_key = translate_vk(wparam)?;
-    let key = extend_key(virtual_key, lparam);
+    Some(key_event(wparam, lparam))
+}
 
-    Some(map_tr_state(key, lparam))
+#[cfg(feature = "input")]
+unsafe fn key_event(wparam: WPARAM, lparam: LPARAM) -> Event {
+    let scancode = scancode(lparam);
+    let key = match translate_vk(wparam) {
+        Some(key) => extend_key(key, scancode),
+        None => Key::Unknown(scancode),
+    };
+    map_tr_state((key, scancode, modifier_state()), lparam)
 }
 
+/// Gets the scancode out of the lParam of a key message, with `0xE000` added if it's an extended key.
 #[cfg(feature = "input")]
-fn extend_key(key: Key, lparam: LPARAM) -> Key {
-    let scancode = (lparam & 0x00FF0000) >> 16u8;
+fn scancode(lparam: LPARAM) -> ScanCode {
+    let scancode = ((lparam >> 16) & 0xFF) as u32;
     let extended_bit = (lparam & (1 << 24)) != 0;
+    ScanCode(if extended_bit { 0xE000 | scancode } else { scancode })
+}
+
+#[cfg(feature = "input")]
+fn extend_key(key: Key, scancode: ScanCode) -> Key {
+    let extended_bit = scancode.0 & 0xE000 != 0;
 
     match key {
-        Key::LShift if scancode == 54 => Key::RShift,
+        Key::LShift if scancode.0 == 54 => Key::RShift,
         Key::LControl if extended_bit => Key::RControl,
         Key::LAlt if extended_bit => Key::RAlt,
         x => x,
@@ -1731,7 +3791,7 @@ fn extend_key(key: Key, lparam: LPARAM) -> Key {
 }
 
 #[cfg(feature = "input")]
-fn map_tr_state(key: Key, lparam: LPARAM) -> Event {
+fn map_tr_state(key: (Key, ScanCode, Modifiers), lparam: LPARAM) -> Event {
     if (lparam & (1 << 31)) == 0 {
         if (lparam & (1 << 30)) != 0 {
             Event::KeyboardRepeat(key)
@@ -1742,3 +3802,31 @@ fn map_tr_state(key: Key, lparam: LPARAM) -> Event {
         Event::KeyboardUp(key)
     }
 }
+
+/// Gets the modifier state as of the message being processed, which includes the message itself.
+#[cfg(feature = "input")]
+unsafe fn modifier_state() -> Modifiers {
+    // The high bit is set while a key is down, and the low bit is toggled by each press
+    let is_down = |vk: u8| GetKeyState(vk.into()) < 0;
+    let is_toggled = |vk: u8| GetKeyState(vk.into()) & 1 != 0;
+    let mut modifiers = Modifiers::empty();
+    if is_down(VK_SHIFT) {
+        modifiers.insert(Modifiers::SHIFT);
+    }
+    if is_down(VK_CONTROL) {
+        modifiers.insert(Modifiers::CTRL);
+    }
+    if is_down(VK_MENU) {
+        modifiers.insert(Modifiers::ALT);
+    }
+    if is_down(VK_LWIN) || is_down(VK_RWIN) {
+        modifiers.insert(Modifiers::SUPER);
+    }
+    if is_toggled(VK_CAPITAL) {
+        modifiers.insert(Modifiers::CAPS_LOCK);
+    }
+    if is_toggled(VK_NUMLOCK) {
+        modifiers.insert(Modifiers::NUM_LOCK);
+    }
+    modifiers
+}
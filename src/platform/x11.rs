@@ -0,0 +1,13 @@
+//! X11-specific implementations and API extensions.
+
+pub(crate) mod ffi;
+pub(crate) mod imp;
+
+// Required re-exports
+pub(crate) use imp::{available_monitors, spawn_window, vulkan_instance_extensions};
+pub(crate) type WindowRepr = imp::WindowImpl;
+pub(crate) type GlContextRepr = imp::GlContextImpl;
+
+// Bonus
+pub use ffi::Display;
+pub use imp::WindowExt;
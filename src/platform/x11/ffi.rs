@@ -0,0 +1,1358 @@
+// xlib api goes here
+
+#![allow(bad_style, clippy::upper_case_acronyms)]
+// Some of it is only for input handling
+#![cfg_attr(not(feature = "input"), allow(dead_code))]
+
+// Opaque types
+/// Opaque Xlib connection to an X server.
+pub enum Display {}
+#[doc(hidden)]
+pub enum Visual {}
+#[doc(hidden)]
+pub enum _XGC {}
+#[doc(hidden)]
+pub enum _XIM {}
+#[doc(hidden)]
+pub enum _XIC {}
+
+// Typedefs
+pub use crate::platform::unix::ffi::*;
+use crate::vulkan::{VkFlags, VkInstance, VkResult, VkStructureType, VkSurfaceKHR};
+use std::{ffi::CStr, mem, ptr};
+pub type Atom = XID;
+pub type Bool = c_int;
+pub type Colormap = XID;
+pub type Cursor = XID;
+pub type Drawable = XID;
+pub type GC = *mut _XGC;
+pub type KeyCode = c_uchar;
+pub type KeySym = XID;
+pub type Pixmap = XID;
+pub type RRCrtc = XID;
+pub type RRMode = XID;
+pub type RROutput = XID;
+pub type Rotation = c_ushort;
+pub type ShmSeg = c_ulong;
+pub type Status = c_int;
+pub type Time = c_ulong;
+pub type VisualID = c_ulong;
+pub type Window = XID;
+pub type XID = c_ulong;
+pub type XIC = *mut _XIC;
+pub type XICCEncodingStyle = c_int;
+pub type XIM = *mut _XIM;
+pub type XIMFeedback = c_ulong;
+pub type XPointer = *mut c_char;
+pub type XVaNestedList = *mut c_void;
+
+// Function typedefs
+pub type XErrorHandler = unsafe extern "C" fn(*mut Display, *mut XErrorEvent) -> c_int;
+pub type XICProc = unsafe extern "C" fn(XIC, XPointer, XPointer) -> Bool;
+pub type XIfEventPredicate = unsafe extern "C" fn(*mut Display, *mut XEvent, XPointer) -> Bool;
+
+// Constants
+pub const AnyPropertyType: Atom = 0;
+pub const CopyFromParent: c_int = 0;
+pub const CurrentTime: Time = 0;
+pub const False: Bool = 0;
+pub const GrabModeAsync: c_int = 1;
+pub const GrabSuccess: c_int = 0;
+pub const InputOutput: c_uint = 1;
+pub const LSBFirst: c_int = 0;
+pub const MSBFirst: c_int = 1;
+pub const PropModeAppend: c_int = 2;
+pub const PropModeReplace: c_int = 0;
+pub const PropertyDelete: c_int = 1;
+pub const PropertyNewValue: c_int = 0;
+pub const Success: c_int = 0;
+pub const True: Bool = 1;
+pub const ZPixmap: c_int = 2;
+
+// Input methods
+pub const XIMPreeditCallbacks: c_long = 0x0002;
+pub const XIMPreeditNothing: c_long = 0x0008;
+pub const XIMPreeditNone: c_long = 0x0010;
+pub const XIMStatusNothing: c_long = 0x0400;
+pub const XIMStatusNone: c_long = 0x0800;
+pub const XIMForwardChar: c_int = 0;
+pub const XIMBackwardChar: c_int = 1;
+pub const XIMAbsolutePosition: c_int = 10;
+pub const XBufferOverflow: c_int = -1;
+pub const XLookupChars: c_int = 2;
+pub const XLookupBoth: c_int = 4;
+pub const XNClientWindow: &[u8] = b"clientWindow\0";
+pub const XNFocusWindow: &[u8] = b"focusWindow\0";
+pub const XNInputStyle: &[u8] = b"inputStyle\0";
+pub const XNPreeditAttributes: &[u8] = b"preeditAttributes\0";
+pub const XNPreeditCaretCallback: &[u8] = b"preeditCaretCallback\0";
+pub const XNPreeditDoneCallback: &[u8] = b"preeditDoneCallback\0";
+pub const XNPreeditDrawCallback: &[u8] = b"preeditDrawCallback\0";
+pub const XNPreeditStartCallback: &[u8] = b"preeditStartCallback\0";
+pub const XNSpotLocation: &[u8] = b"spotLocation\0";
+
+// Predefined atoms
+pub const XA_ATOM: Atom = 4;
+pub const XA_CARDINAL: Atom = 6;
+pub const XA_INTEGER: Atom = 19;
+pub const XA_STRING: Atom = 31;
+pub const XA_WM_NAME: Atom = 39;
+
+// Event types
+pub const KeyPress: c_int = 2;
+pub const KeyRelease: c_int = 3;
+pub const ButtonPress: c_int = 4;
+pub const ButtonRelease: c_int = 5;
+pub const MotionNotify: c_int = 6;
+pub const EnterNotify: c_int = 7;
+pub const LeaveNotify: c_int = 8;
+pub const FocusIn: c_int = 9;
+pub const FocusOut: c_int = 10;
+pub const Expose: c_int = 12;
+pub const ConfigureNotify: c_int = 22;
+pub const PropertyNotify: c_int = 28;
+pub const SelectionClear: c_int = 29;
+pub const SelectionRequest: c_int = 30;
+pub const SelectionNotify: c_int = 31;
+pub const ClientMessage: c_int = 33;
+pub const GenericEvent: c_int = 35;
+
+// Event masks
+pub const NoEventMask: c_long = 0;
+pub const KeyPressMask: c_long = 1 << 0;
+pub const KeyReleaseMask: c_long = 1 << 1;
+pub const ButtonPressMask: c_long = 1 << 2;
+pub const ButtonReleaseMask: c_long = 1 << 3;
+pub const EnterWindowMask: c_long = 1 << 4;
+pub const LeaveWindowMask: c_long = 1 << 5;
+pub const PointerMotionMask: c_long = 1 << 6;
+pub const ExposureMask: c_long = 1 << 15;
+pub const StructureNotifyMask: c_long = 1 << 17;
+pub const SubstructureNotifyMask: c_long = 1 << 19;
+pub const SubstructureRedirectMask: c_long = 1 << 20;
+pub const FocusChangeMask: c_long = 1 << 21;
+pub const PropertyChangeMask: c_long = 1 << 22;
+
+// Window attribute masks
+pub const CWBackPixel: c_ulong = 1 << 1;
+pub const CWEventMask: c_ulong = 1 << 11;
+
+// Size hint flags
+pub const USPosition: c_long = 1 << 0;
+pub const PPosition: c_long = 1 << 2;
+pub const PMinSize: c_long = 1 << 4;
+pub const PMaxSize: c_long = 1 << 5;
+
+// Text property encodings
+pub const XStdICCTextStyle: XICCEncodingStyle = 3;
+
+// Focus & crossing details
+pub const NotifyGrab: c_int = 1;
+pub const NotifyUngrab: c_int = 2;
+
+// XInput 2
+pub const XIAllMasterDevices: c_int = 1;
+pub const XI_RawMotion: c_int = 17;
+pub const XI_TouchBegin: c_int = 18;
+pub const XI_TouchUpdate: c_int = 19;
+pub const XI_TouchEnd: c_int = 20;
+
+// Pointer buttons
+pub const Button1: c_uint = 1;
+pub const Button2: c_uint = 2;
+pub const Button3: c_uint = 3;
+pub const Button4: c_uint = 4;
+pub const Button5: c_uint = 5;
+
+// Modifier masks
+pub const ShiftMask: c_uint = 1 << 0;
+pub const LockMask: c_uint = 1 << 1;
+pub const ControlMask: c_uint = 1 << 2;
+pub const Mod1Mask: c_uint = 1 << 3;
+pub const Mod2Mask: c_uint = 1 << 4;
+pub const Mod4Mask: c_uint = 1 << 6;
+
+// Cursor font glyphs (X11/cursorfont.h)
+pub const XC_X_cursor: c_uint = 0;
+pub const XC_bottom_left_corner: c_uint = 12;
+pub const XC_bottom_right_corner: c_uint = 14;
+pub const XC_crosshair: c_uint = 34;
+pub const XC_fleur: c_uint = 52;
+pub const XC_hand2: c_uint = 60;
+pub const XC_left_ptr: c_uint = 68;
+pub const XC_question_arrow: c_uint = 92;
+pub const XC_sb_h_double_arrow: c_uint = 108;
+pub const XC_sb_v_double_arrow: c_uint = 116;
+pub const XC_watch: c_uint = 150;
+pub const XC_xterm: c_uint = 152;
+
+// RandR mode flags & statuses (X11/extensions/randr.h)
+pub const RR_Interlace: c_ulong = 0x0010;
+pub const RR_DoubleScan: c_ulong = 0x0020;
+pub const RRSetConfigSuccess: Status = 0;
+
+// XFixes selection events (X11/extensions/xfixeswire.h)
+pub const XFixesSelectionNotify: c_int = 0;
+pub const XFixesSetSelectionOwnerNotifyMask: c_ulong = 1 << 0;
+pub const XFixesSelectionWindowDestroyNotifyMask: c_ulong = 1 << 1;
+pub const XFixesSelectionClientCloseNotifyMask: c_ulong = 1 << 2;
+
+// _NET_WM_STATE client message actions
+pub const _NET_WM_STATE_REMOVE: c_long = 0;
+pub const _NET_WM_STATE_ADD: c_long = 1;
+
+// _MOTIF_WM_HINTS bits (Xm/MwmUtil.h)
+pub const MWM_HINTS_FUNCTIONS: c_ulong = 1 << 0;
+pub const MWM_HINTS_DECORATIONS: c_ulong = 1 << 1;
+pub const MWM_FUNC_RESIZE: c_ulong = 1 << 1;
+pub const MWM_FUNC_MOVE: c_ulong = 1 << 2;
+pub const MWM_FUNC_MINIMIZE: c_ulong = 1 << 3;
+pub const MWM_FUNC_MAXIMIZE: c_ulong = 1 << 4;
+pub const MWM_FUNC_CLOSE: c_ulong = 1 << 5;
+pub const MWM_DECOR_BORDER: c_ulong = 1 << 1;
+pub const MWM_DECOR_RESIZEH: c_ulong = 1 << 2;
+pub const MWM_DECOR_TITLE: c_ulong = 1 << 3;
+pub const MWM_DECOR_MENU: c_ulong = 1 << 4;
+pub const MWM_DECOR_MINIMIZE: c_ulong = 1 << 5;
+pub const MWM_DECOR_MAXIMIZE: c_ulong = 1 << 6;
+
+// Structs
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct XAnyEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub window: Window,
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct XKeyEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub window: Window,
+    pub root: Window,
+    pub subwindow: Window,
+    pub time: Time,
+    pub x: c_int,
+    pub y: c_int,
+    pub x_root: c_int,
+    pub y_root: c_int,
+    pub state: c_uint,
+    pub keycode: c_uint,
+    pub same_screen: Bool,
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct XButtonEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub window: Window,
+    pub root: Window,
+    pub subwindow: Window,
+    pub time: Time,
+    pub x: c_int,
+    pub y: c_int,
+    pub x_root: c_int,
+    pub y_root: c_int,
+    pub state: c_uint,
+    pub button: c_uint,
+    pub same_screen: Bool,
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct XMotionEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub window: Window,
+    pub root: Window,
+    pub subwindow: Window,
+    pub time: Time,
+    pub x: c_int,
+    pub y: c_int,
+    pub x_root: c_int,
+    pub y_root: c_int,
+    pub state: c_uint,
+    pub is_hint: c_char,
+    pub same_screen: Bool,
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct XCrossingEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub window: Window,
+    pub root: Window,
+    pub subwindow: Window,
+    pub time: Time,
+    pub x: c_int,
+    pub y: c_int,
+    pub x_root: c_int,
+    pub y_root: c_int,
+    pub mode: c_int,
+    pub detail: c_int,
+    pub same_screen: Bool,
+    pub focus: Bool,
+    pub state: c_uint,
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct XFocusChangeEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub window: Window,
+    pub mode: c_int,
+    pub detail: c_int,
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct XExposeEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub window: Window,
+    pub x: c_int,
+    pub y: c_int,
+    pub width: c_int,
+    pub height: c_int,
+    pub count: c_int,
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct XConfigureEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub event: Window,
+    pub window: Window,
+    pub x: c_int,
+    pub y: c_int,
+    pub width: c_int,
+    pub height: c_int,
+    pub border_width: c_int,
+    pub above: Window,
+    pub override_redirect: Bool,
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct XPropertyEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub window: Window,
+    pub atom: Atom,
+    pub time: Time,
+    pub state: c_int,
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct XClientMessageEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub window: Window,
+    pub message_type: Atom,
+    pub format: c_int,
+    // This is a union of `char[20]`, `short[10]` and `long[5]`, the last one being the largest.
+    pub data: [c_long; 5],
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct XSelectionEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub requestor: Window,
+    pub selection: Atom,
+    pub target: Atom,
+    /// The property the selection was converted into, or `None` (0) if it couldn't be.
+    pub property: Atom,
+    pub time: Time,
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct XSelectionRequestEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub owner: Window,
+    pub requestor: Window,
+    pub selection: Atom,
+    pub target: Atom,
+    /// The property to convert the selection into, which obsolete clients leave as `None` (0).
+    pub property: Atom,
+    pub time: Time,
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct XSelectionClearEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub window: Window,
+    pub selection: Atom,
+    pub time: Time,
+}
+/// Extension events that don't fit in an `XEvent`, the data of which is fetched with `XGetEventData`.
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct XGenericEventCookie {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub extension: c_int,
+    pub evtype: c_int,
+    pub cookie: c_uint,
+    pub data: *mut c_void,
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct XErrorEvent {
+    pub type_: c_int,
+    pub display: *mut Display,
+    pub resourceid: XID,
+    pub serial: c_ulong,
+    pub error_code: c_uchar,
+    pub request_code: c_uchar,
+    pub minor_code: c_uchar,
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub union XEvent {
+    pub type_: c_int,
+    pub any: XAnyEvent,
+    pub key: XKeyEvent,
+    pub button: XButtonEvent,
+    pub motion: XMotionEvent,
+    pub crossing: XCrossingEvent,
+    pub focus_change: XFocusChangeEvent,
+    pub expose: XExposeEvent,
+    pub configure: XConfigureEvent,
+    pub property: XPropertyEvent,
+    pub client_message: XClientMessageEvent,
+    pub selection_request: XSelectionRequestEvent,
+    pub selection_clear: XSelectionClearEvent,
+    pub selection: XSelectionEvent,
+    pub cookie: XGenericEventCookie,
+    pub pad: [c_long; 24],
+}
+#[repr(C)]
+pub struct XSetWindowAttributes {
+    pub background_pixmap: Pixmap,
+    pub background_pixel: c_ulong,
+    pub border_pixmap: Pixmap,
+    pub border_pixel: c_ulong,
+    pub bit_gravity: c_int,
+    pub win_gravity: c_int,
+    pub backing_store: c_int,
+    pub backing_planes: c_ulong,
+    pub backing_pixel: c_ulong,
+    pub save_under: Bool,
+    pub event_mask: c_long,
+    pub do_not_propagate_mask: c_long,
+    pub override_redirect: Bool,
+    pub colormap: Colormap,
+    pub cursor: Cursor,
+}
+#[repr(C)]
+pub struct AspectRatio {
+    pub x: c_int,
+    pub y: c_int,
+}
+#[repr(C)]
+pub struct XSizeHints {
+    pub flags: c_long,
+    pub x: c_int,
+    pub y: c_int,
+    pub width: c_int,
+    pub height: c_int,
+    pub min_width: c_int,
+    pub min_height: c_int,
+    pub max_width: c_int,
+    pub max_height: c_int,
+    pub width_inc: c_int,
+    pub height_inc: c_int,
+    pub min_aspect: AspectRatio,
+    pub max_aspect: AspectRatio,
+    pub base_width: c_int,
+    pub base_height: c_int,
+    pub win_gravity: c_int,
+}
+#[repr(C)]
+pub struct XIEventMask {
+    pub deviceid: c_int,
+    pub mask_len: c_int,
+    pub mask: *mut c_uchar,
+}
+#[repr(C)]
+pub struct XIValuatorState {
+    pub mask_len: c_int,
+    pub mask: *mut c_uchar,
+    pub values: *mut f64,
+}
+#[repr(C)]
+pub struct XIButtonState {
+    pub mask_len: c_int,
+    pub mask: *mut c_uchar,
+}
+#[repr(C)]
+pub struct XIModifierState {
+    pub base: c_int,
+    pub latched: c_int,
+    pub locked: c_int,
+    pub effective: c_int,
+}
+/// `XIGroupState` is the same as `XIModifierState`.
+pub type XIGroupState = XIModifierState;
+#[repr(C)]
+pub struct XIDeviceEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub extension: c_int,
+    pub evtype: c_int,
+    pub time: Time,
+    pub deviceid: c_int,
+    pub sourceid: c_int,
+    /// For touch events, this is the touch ID.
+    pub detail: c_int,
+    pub root: Window,
+    pub event: Window,
+    pub child: Window,
+    pub root_x: f64,
+    pub root_y: f64,
+    pub event_x: f64,
+    pub event_y: f64,
+    pub flags: c_int,
+    pub buttons: XIButtonState,
+    pub valuators: XIValuatorState,
+    pub mods: XIModifierState,
+    pub group: XIGroupState,
+}
+#[repr(C)]
+pub struct XIRawEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub extension: c_int,
+    pub evtype: c_int,
+    pub time: Time,
+    pub deviceid: c_int,
+    pub sourceid: c_int,
+    pub detail: c_int,
+    pub flags: c_int,
+    pub valuators: XIValuatorState,
+    /// The values before acceleration, one for each bit set in `valuators.mask`.
+    pub raw_values: *mut f64,
+}
+#[repr(C)]
+pub struct XRRMonitorInfo {
+    pub name: Atom,
+    pub primary: Bool,
+    pub automatic: Bool,
+    pub noutput: c_int,
+    pub x: c_int,
+    pub y: c_int,
+    pub width: c_int,
+    pub height: c_int,
+    pub mwidth: c_int,
+    pub mheight: c_int,
+    pub outputs: *mut RROutput,
+}
+#[repr(C)]
+pub struct XRRModeInfo {
+    pub id: RRMode,
+    pub width: c_uint,
+    pub height: c_uint,
+    pub dotClock: c_ulong,
+    pub hSyncStart: c_uint,
+    pub hSyncEnd: c_uint,
+    pub hTotal: c_uint,
+    pub hSkew: c_uint,
+    pub vSyncStart: c_uint,
+    pub vSyncEnd: c_uint,
+    pub vTotal: c_uint,
+    pub name: *mut c_char,
+    pub nameLength: c_uint,
+    pub modeFlags: c_ulong,
+}
+#[repr(C)]
+pub struct XRRScreenResources {
+    pub timestamp: Time,
+    pub configTimestamp: Time,
+    pub ncrtc: c_int,
+    pub crtcs: *mut RRCrtc,
+    pub noutput: c_int,
+    pub outputs: *mut RROutput,
+    pub nmode: c_int,
+    pub modes: *mut XRRModeInfo,
+}
+#[repr(C)]
+pub struct XRROutputInfo {
+    pub timestamp: Time,
+    pub crtc: RRCrtc,
+    pub name: *mut c_char,
+    pub nameLen: c_int,
+    pub mm_width: c_ulong,
+    pub mm_height: c_ulong,
+    pub connection: c_ushort,
+    pub subpixel_order: c_ushort,
+    pub ncrtc: c_int,
+    pub crtcs: *mut RRCrtc,
+    pub nclone: c_int,
+    pub clones: *mut RROutput,
+    pub nmode: c_int,
+    pub npreferred: c_int,
+    pub modes: *mut RRMode,
+}
+#[repr(C)]
+pub struct XRRCrtcInfo {
+    pub timestamp: Time,
+    pub x: c_int,
+    pub y: c_int,
+    pub width: c_uint,
+    pub height: c_uint,
+    pub mode: RRMode,
+    pub rotation: Rotation,
+    pub noutput: c_int,
+    pub outputs: *mut RROutput,
+    pub rotations: Rotation,
+    pub npossible: c_int,
+    pub possible: *mut RROutput,
+}
+#[repr(C)]
+pub struct XTextProperty {
+    pub value: *mut c_uchar,
+    pub encoding: Atom,
+    pub format: c_int,
+    pub nitems: c_ulong,
+}
+#[repr(C)]
+pub struct XClassHint {
+    pub res_name: *mut c_char,
+    pub res_class: *mut c_char,
+}
+
+dyn_link! {
+    pub struct X11DL(dlopen_local => *mut c_void | dlsym) {
+        "libX11.so.6" | "libX11.so" {
+            // Connection management
+            fn XInitThreads() -> Status;
+            fn XOpenDisplay(display_name: *const c_char) -> *mut Display;
+            fn XCloseDisplay(display: *mut Display) -> c_int;
+            fn XConnectionNumber(display: *mut Display) -> c_int;
+            fn XDefaultScreen(display: *mut Display) -> c_int;
+            fn XDefaultVisual(display: *mut Display, screen_number: c_int) -> *mut Visual;
+            fn XDisplayString(display: *mut Display) -> *mut c_char;
+            fn XVisualIDFromVisual(visual: *mut Visual) -> VisualID;
+            fn XDefaultDepth(display: *mut Display, screen_number: c_int) -> c_int;
+            fn XDisplayWidth(display: *mut Display, screen_number: c_int) -> c_int;
+            fn XDisplayHeight(display: *mut Display, screen_number: c_int) -> c_int;
+            fn XRootWindow(display: *mut Display, screen_number: c_int) -> Window;
+            fn XBlackPixel(display: *mut Display, screen_number: c_int) -> c_ulong;
+            fn XSetErrorHandler(handler: Option<XErrorHandler>) -> Option<XErrorHandler>;
+            fn XFlush(display: *mut Display) -> c_int;
+            fn XQueryExtension(
+                display: *mut Display,
+                name: *const c_char,
+                major_opcode_return: *mut c_int,
+                first_event_return: *mut c_int,
+                first_error_return: *mut c_int,
+            ) -> Bool;
+            fn XGetEventData(display: *mut Display, cookie: *mut XGenericEventCookie) -> Bool;
+            fn XFreeEventData(display: *mut Display, cookie: *mut XGenericEventCookie) -> ();
+            fn XSync(display: *mut Display, discard: Bool) -> c_int;
+            fn XMaxRequestSize(display: *mut Display) -> c_long;
+            fn XExtendedMaxRequestSize(display: *mut Display) -> c_long;
+            fn XFree(data: *mut c_void) -> c_int;
+
+            // Window management
+            fn XCreateWindow(
+                display: *mut Display,
+                parent: Window,
+                x: c_int,
+                y: c_int,
+                width: c_uint,
+                height: c_uint,
+                border_width: c_uint,
+                depth: c_int,
+                class: c_uint,
+                visual: *mut Visual,
+                valuemask: c_ulong,
+                attributes: *mut XSetWindowAttributes,
+            ) -> Window;
+            fn XDestroyWindow(display: *mut Display, w: Window) -> c_int;
+            fn XMapWindow(display: *mut Display, w: Window) -> c_int;
+            fn XUnmapWindow(display: *mut Display, w: Window) -> c_int;
+            fn XMoveWindow(display: *mut Display, w: Window, x: c_int, y: c_int) -> c_int;
+            fn XResizeWindow(display: *mut Display, w: Window, width: c_uint, height: c_uint) -> c_int;
+            fn XSelectInput(display: *mut Display, w: Window, event_mask: c_long) -> c_int;
+            fn XSetClassHint(display: *mut Display, w: Window, class_hints: *mut XClassHint) -> c_int;
+            fn XSetWMNormalHints(display: *mut Display, w: Window, hints: *mut XSizeHints) -> c_int;
+            fn XSetWMProtocols(display: *mut Display, w: Window, protocols: *mut Atom, count: c_int) -> Status;
+            fn XSetTextProperty(display: *mut Display, w: Window, text_prop: *mut XTextProperty, property: Atom) -> ();
+            fn Xutf8TextListToTextProperty(
+                display: *mut Display,
+                list: *mut *mut c_char,
+                count: c_int,
+                style: XICCEncodingStyle,
+                text_prop_return: *mut XTextProperty,
+            ) -> c_int;
+            fn XDefaultGC(display: *mut Display, screen_number: c_int) -> GC;
+            fn XTranslateCoordinates(
+                display: *mut Display,
+                src_w: Window,
+                dest_w: Window,
+                src_x: c_int,
+                src_y: c_int,
+                dest_x_return: *mut c_int,
+                dest_y_return: *mut c_int,
+                child_return: *mut Window,
+            ) -> Bool;
+
+            // Images
+            fn XCreateImage(
+                display: *mut Display,
+                visual: *mut Visual,
+                depth: c_uint,
+                format: c_int,
+                offset: c_int,
+                data: *mut c_char,
+                width: c_uint,
+                height: c_uint,
+                bitmap_pad: c_int,
+                bytes_per_line: c_int,
+            ) -> *mut XImage;
+            fn XDestroyImage(ximage: *mut XImage) -> c_int;
+            fn XPutImage(
+                display: *mut Display,
+                d: Drawable,
+                gc: GC,
+                image: *mut XImage,
+                src_x: c_int,
+                src_y: c_int,
+                dest_x: c_int,
+                dest_y: c_int,
+                width: c_uint,
+                height: c_uint,
+            ) -> c_int;
+
+            // Properties & atoms
+            fn XInternAtom(display: *mut Display, atom_name: *const c_char, only_if_exists: Bool) -> Atom;
+            fn XGetAtomName(display: *mut Display, atom: Atom) -> *mut c_char;
+            fn XInternAtoms(
+                display: *mut Display,
+                names: *mut *mut c_char,
+                count: c_int,
+                only_if_exists: Bool,
+                atoms_return: *mut Atom,
+            ) -> Status;
+            fn XChangeProperty(
+                display: *mut Display,
+                w: Window,
+                property: Atom,
+                type_: Atom,
+                format: c_int,
+                mode: c_int,
+                data: *const c_uchar,
+                nelements: c_int,
+            ) -> c_int;
+            fn XDeleteProperty(display: *mut Display, w: Window, property: Atom) -> c_int;
+            fn XGetWindowProperty(
+                display: *mut Display,
+                w: Window,
+                property: Atom,
+                long_offset: c_long,
+                long_length: c_long,
+                delete: Bool,
+                req_type: Atom,
+                actual_type_return: *mut Atom,
+                actual_format_return: *mut c_int,
+                nitems_return: *mut c_ulong,
+                bytes_after_return: *mut c_ulong,
+                prop_return: *mut *mut c_uchar,
+            ) -> c_int;
+
+            // Selections
+            fn XConvertSelection(
+                display: *mut Display,
+                selection: Atom,
+                target: Atom,
+                property: Atom,
+                requestor: Window,
+                time: Time,
+            ) -> c_int;
+            fn XSetSelectionOwner(display: *mut Display, selection: Atom, owner: Window, time: Time) -> c_int;
+            fn XGetSelectionOwner(display: *mut Display, selection: Atom) -> Window;
+
+            // Event loop
+            fn XPending(display: *mut Display) -> c_int;
+            fn XNextEvent(display: *mut Display, event_return: *mut XEvent) -> c_int;
+            fn XPeekEvent(display: *mut Display, event_return: *mut XEvent) -> c_int;
+            fn XCheckIfEvent(
+                display: *mut Display,
+                event_return: *mut XEvent,
+                predicate: XIfEventPredicate,
+                arg: XPointer,
+            ) -> Bool;
+            fn XSendEvent(
+                display: *mut Display,
+                w: Window,
+                propagate: Bool,
+                event_mask: c_long,
+                event_send: *mut XEvent,
+            ) -> Status;
+
+            // Keyboard & mouse related
+            fn XLookupString(
+                event: *mut XKeyEvent,
+                buffer_return: *mut c_char,
+                bytes_buffer: c_int,
+                keysym_return: *mut KeySym,
+                status_in_out: *mut c_void,
+            ) -> c_int;
+            fn XkbKeycodeToKeysym(display: *mut Display, kc: KeyCode, group: c_uint, level: c_uint) -> KeySym;
+            fn XkbSetDetectableAutoRepeat(display: *mut Display, detectable: Bool, supported_rtrn: *mut Bool) -> Bool;
+            fn XGrabPointer(
+                display: *mut Display,
+                grab_window: Window,
+                owner_events: Bool,
+                event_mask: c_uint,
+                pointer_mode: c_int,
+                keyboard_mode: c_int,
+                confine_to: Window,
+                cursor: Cursor,
+                time: Time,
+            ) -> c_int;
+            fn XUngrabPointer(display: *mut Display, time: Time) -> c_int;
+            fn XQueryPointer(
+                display: *mut Display,
+                w: Window,
+                root_return: *mut Window,
+                child_return: *mut Window,
+                root_x_return: *mut c_int,
+                root_y_return: *mut c_int,
+                win_x_return: *mut c_int,
+                win_y_return: *mut c_int,
+                mask_return: *mut c_uint,
+            ) -> Bool;
+            fn XWarpPointer(
+                display: *mut Display,
+                src_w: Window,
+                dest_w: Window,
+                src_x: c_int,
+                src_y: c_int,
+                src_width: c_uint,
+                src_height: c_uint,
+                dest_x: c_int,
+                dest_y: c_int,
+            ) -> c_int;
+
+            // Input methods (`XCreateIC` is in `X11Variadic`)
+            fn XSetLocaleModifiers(modifier_list: *const c_char) -> *mut c_char;
+            fn XOpenIM(
+                display: *mut Display,
+                db: *mut c_void,
+                res_name: *mut c_char,
+                res_class: *mut c_char,
+            ) -> XIM;
+            fn XCloseIM(im: XIM) -> Status;
+            fn XDestroyIC(ic: XIC) -> ();
+            fn XSetICFocus(ic: XIC) -> ();
+            fn XUnsetICFocus(ic: XIC) -> ();
+            fn XFilterEvent(event: *mut XEvent, w: Window) -> Bool;
+            fn Xutf8LookupString(
+                ic: XIC,
+                event: *mut XKeyEvent,
+                buffer_return: *mut c_char,
+                bytes_buffer: c_int,
+                keysym_return: *mut KeySym,
+                status_return: *mut c_int,
+            ) -> c_int;
+
+            // Cursors
+            fn XCreateBitmapFromData(
+                display: *mut Display,
+                d: Drawable,
+                data: *const c_char,
+                width: c_uint,
+                height: c_uint,
+            ) -> Pixmap;
+            fn XCreateFontCursor(display: *mut Display, shape: c_uint) -> Cursor;
+            fn XCreatePixmapCursor(
+                display: *mut Display,
+                source: Pixmap,
+                mask: Pixmap,
+                foreground_color: *mut XColor,
+                background_color: *mut XColor,
+                x: c_uint,
+                y: c_uint,
+            ) -> Cursor;
+            fn XDefineCursor(display: *mut Display, w: Window, cursor: Cursor) -> c_int;
+            fn XFreeCursor(display: *mut Display, cursor: Cursor) -> c_int;
+            fn XFreePixmap(display: *mut Display, pixmap: Pixmap) -> c_int;
+        },
+
+        "libXcursor.so.1" | "libXcursor.so" {
+            /// Loads a named cursor from the user's cursor theme, which looks far better than the core font.
+            fn XcursorLibraryLoadCursor(display: *mut Display, file: *const c_char) -> Cursor;
+            /// Allocates an ARGB image for making a cursor out of, with uninitialized pixels.
+            fn XcursorImageCreate(width: c_int, height: c_int) -> *mut XcursorImage;
+            fn XcursorImageDestroy(image: *mut XcursorImage) -> ();
+            fn XcursorImageLoadCursor(display: *mut Display, image: *const XcursorImage) -> Cursor;
+        },
+
+        "libXi.so.6" | "libXi.so" {
+            /// (XInput 2) Negotiates the version, which decides what events are sent to us.
+            fn XIQueryVersion(
+                display: *mut Display,
+                major_version_inout: *mut c_int,
+                minor_version_inout: *mut c_int,
+            ) -> Status;
+            fn XISelectEvents(display: *mut Display, win: Window, masks: *mut XIEventMask, num_masks: c_int) -> Status;
+        },
+
+        "libXrandr.so.2" | "libXrandr.so" {
+            /// (RandR 1.5+) Lists the monitors, which can each be made up of several outputs.
+            fn XRRGetMonitors(
+                display: *mut Display,
+                window: Window,
+                get_active: Bool,
+                nmonitors: *mut c_int,
+            ) -> *mut XRRMonitorInfo;
+            fn XRRFreeMonitors(monitors: *mut XRRMonitorInfo) -> ();
+            fn XRRGetScreenResourcesCurrent(display: *mut Display, window: Window) -> *mut XRRScreenResources;
+            fn XRRFreeScreenResources(resources: *mut XRRScreenResources) -> ();
+            fn XRRGetOutputInfo(
+                display: *mut Display,
+                resources: *mut XRRScreenResources,
+                output: RROutput,
+            ) -> *mut XRROutputInfo;
+            fn XRRFreeOutputInfo(output_info: *mut XRROutputInfo) -> ();
+            fn XRRGetCrtcInfo(
+                display: *mut Display,
+                resources: *mut XRRScreenResources,
+                crtc: RRCrtc,
+            ) -> *mut XRRCrtcInfo;
+            fn XRRFreeCrtcInfo(crtc_info: *mut XRRCrtcInfo) -> ();
+            fn XRRSetCrtcConfig(
+                display: *mut Display,
+                resources: *mut XRRScreenResources,
+                crtc: RRCrtc,
+                timestamp: Time,
+                x: c_int,
+                y: c_int,
+                mode: RRMode,
+                rotation: Rotation,
+                outputs: *mut RROutput,
+                noutputs: c_int,
+            ) -> Status;
+        },
+
+        "libXfixes.so.3" | "libXfixes.so" {
+            /// Checks for XFixes, which can tell us about changes to selections (the clipboard).
+            fn XFixesQueryExtension(
+                display: *mut Display,
+                event_base_return: *mut c_int,
+                error_base_return: *mut c_int,
+            ) -> Bool;
+            fn XFixesSelectSelectionInput(display: *mut Display, window: Window, selection: Atom, event_mask: c_ulong) -> ();
+        },
+
+        "libXext.so.6" | "libXext.so" {
+            /// Checks for MIT-SHM, which lets images be copied to the server through shared memory.
+            fn XShmQueryExtension(display: *mut Display) -> Bool;
+            fn XShmAttach(display: *mut Display, shminfo: *mut XShmSegmentInfo) -> Bool;
+            fn XShmDetach(display: *mut Display, shminfo: *mut XShmSegmentInfo) -> Bool;
+            fn XShmCreateImage(
+                display: *mut Display,
+                visual: *mut Visual,
+                depth: c_uint,
+                format: c_int,
+                data: *mut c_char,
+                shminfo: *mut XShmSegmentInfo,
+                width: c_uint,
+                height: c_uint,
+            ) -> *mut XImage;
+            fn XShmPutImage(
+                display: *mut Display,
+                d: Drawable,
+                gc: GC,
+                image: *mut XImage,
+                src_x: c_int,
+                src_y: c_int,
+                dst_x: c_int,
+                dst_y: c_int,
+                src_width: c_uint,
+                src_height: c_uint,
+                send_event: Bool,
+            ) -> Bool;
+        },
+    }
+}
+
+#[repr(C)]
+pub struct XImage {
+    pub width: c_int,
+    pub height: c_int,
+    pub xoffset: c_int,
+    pub format: c_int,
+    pub data: *mut c_char,
+    pub byte_order: c_int,
+    pub bitmap_unit: c_int,
+    pub bitmap_bit_order: c_int,
+    pub bitmap_pad: c_int,
+    pub depth: c_int,
+    pub bytes_per_line: c_int,
+    pub bits_per_pixel: c_int,
+    pub red_mask: c_ulong,
+    pub green_mask: c_ulong,
+    pub blue_mask: c_ulong,
+    pub obdata: XPointer,
+    /// Image manipulation routines (`create_image`, `destroy_image`, `get_pixel`, `put_pixel`, `sub_image` & `add_pixel`).
+    pub f: [Option<unsafe extern "C" fn()>; 6],
+}
+
+/// (MIT-SHM)
+#[repr(C)]
+pub struct XShmSegmentInfo {
+    pub shmseg: ShmSeg,
+    pub shmid: c_int,
+    pub shmaddr: *mut c_char,
+    pub readOnly: Bool,
+}
+
+#[repr(C)]
+pub struct XcursorImage {
+    pub version: c_uint,
+    pub size: c_uint,
+    pub width: c_uint,
+    pub height: c_uint,
+    pub xhot: c_uint,
+    pub yhot: c_uint,
+    pub delay: c_uint,
+    /// Premultiplied ARGB, one `u32` per pixel.
+    pub pixels: *mut c_uint,
+}
+
+#[repr(C)]
+pub struct XColor {
+    pub pixel: c_ulong,
+    pub red: c_ushort,
+    pub green: c_ushort,
+    pub blue: c_ushort,
+    pub flags: c_char,
+    pub pad: c_char,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct XPoint {
+    pub x: c_short,
+    pub y: c_short,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct XICCallback {
+    pub client_data: XPointer,
+    pub callback: Option<XICProc>,
+}
+
+#[repr(C)]
+pub struct XIMText {
+    pub length: c_ushort,
+    pub feedback: *mut XIMFeedback,
+    pub encoding_is_wchar: Bool,
+    /// Union of `char*` and `wchar_t*`, depending on `encoding_is_wchar`.
+    pub string: *mut c_void,
+}
+
+#[repr(C)]
+pub struct XIMPreeditDrawCallbackStruct {
+    pub caret: c_int,
+    pub chg_first: c_int,
+    pub chg_length: c_int,
+    pub text: *mut XIMText,
+}
+
+#[repr(C)]
+pub struct XIMPreeditCaretCallbackStruct {
+    pub position: c_int,
+    pub direction: c_int,
+    pub style: c_int,
+}
+
+/// Variadic functions in libX11, which `dyn_link!` can't describe, so they're looked up by hand.
+pub struct X11Variadic {
+    pub XCreateIC: Option<unsafe extern "C" fn(im: XIM, ...) -> XIC>,
+    pub XSetICValues: Option<unsafe extern "C" fn(ic: XIC, ...) -> *mut c_char>,
+    pub XVaCreateNestedList: Option<unsafe extern "C" fn(unused: c_int, ...) -> XVaNestedList>,
+}
+
+impl X11Variadic {
+    pub unsafe fn link() -> Self {
+        // This is the same library that `X11DL` has open, so it's only a reference count increase
+        let mut handle = ptr::null_mut();
+        for name in &[c_string!("libX11.so.6"), c_string!("libX11.so")] {
+            handle = dlopen_local(name.as_ptr().cast());
+            if !handle.is_null() {
+                break
+            }
+        }
+        if handle.is_null() {
+            return Self { XCreateIC: None, XSetICValues: None, XVaCreateNestedList: None }
+        }
+        let create_ic = dlsym(handle, c_string!("XCreateIC").as_ptr().cast());
+        let set_ic_values = dlsym(handle, c_string!("XSetICValues").as_ptr().cast());
+        let create_nested_list = dlsym(handle, c_string!("XVaCreateNestedList").as_ptr().cast());
+        Self {
+            XCreateIC: mem::transmute::<*mut c_void, Option<unsafe extern "C" fn(XIM, ...) -> XIC>>(create_ic),
+            XSetICValues: mem::transmute::<*mut c_void, Option<unsafe extern "C" fn(XIC, ...) -> *mut c_char>>(
+                set_ic_values,
+            ),
+            XVaCreateNestedList: mem::transmute::<*mut c_void, Option<unsafe extern "C" fn(c_int, ...) -> XVaNestedList>>(
+                create_nested_list,
+            ),
+        }
+    }
+}
+
+impl X11DL {
+    pub unsafe fn link() -> Option<Self> {
+        let instance = Self::_link();
+        // Everything in libX11 is required, libXcursor and friends are optional
+        if instance.XOpenDisplay.is_some() {
+            Some(instance)
+        } else {
+            None
+        }
+    }
+}
+
+// OpenGL (GL/glx.h & GL/glxext.h), with `libGL.so.1` only opened once a context is made
+pub enum __GLXFBConfigRec {}
+pub enum __GLXcontextRec {}
+pub type GLXContext = *mut __GLXcontextRec;
+pub type GLXDrawable = XID;
+pub type GLXFBConfig = *mut __GLXFBConfigRec;
+
+pub const GLX_BLUE_SIZE: c_int = 10;
+pub const GLX_CONTEXT_COMPATIBILITY_PROFILE_BIT_ARB: c_int = 0x00000002;
+pub const GLX_CONTEXT_CORE_PROFILE_BIT_ARB: c_int = 0x00000001;
+pub const GLX_CONTEXT_DEBUG_BIT_ARB: c_int = 0x00000001;
+pub const GLX_CONTEXT_FLAGS_ARB: c_int = 0x2094;
+pub const GLX_CONTEXT_MAJOR_VERSION_ARB: c_int = 0x2091;
+pub const GLX_CONTEXT_MINOR_VERSION_ARB: c_int = 0x2092;
+pub const GLX_CONTEXT_PROFILE_MASK_ARB: c_int = 0x9126;
+pub const GLX_DEPTH_SIZE: c_int = 12;
+pub const GLX_DOUBLEBUFFER: c_int = 5;
+pub const GLX_DRAWABLE_TYPE: c_int = 0x8010;
+pub const GLX_FRAMEBUFFER_SRGB_CAPABLE_ARB: c_int = 0x20B2;
+pub const GLX_GREEN_SIZE: c_int = 9;
+pub const GLX_RED_SIZE: c_int = 8;
+pub const GLX_RENDER_TYPE: c_int = 0x8011;
+pub const GLX_RGBA_BIT: c_int = 0x00000001;
+pub const GLX_RGBA_TYPE: c_int = 0x8014;
+pub const GLX_SAMPLE_BUFFERS: c_int = 100000;
+pub const GLX_SAMPLES: c_int = 100001;
+pub const GLX_STENCIL_SIZE: c_int = 13;
+pub const GLX_VISUAL_ID: c_int = 0x800B;
+pub const GLX_WINDOW_BIT: c_int = 0x00000001;
+pub const GLX_X_RENDERABLE: c_int = 0x8012;
+
+/// (GLX_ARB_create_context)
+pub type PFNGLXCREATECONTEXTATTRIBSARBPROC = unsafe extern "C" fn(
+    dpy: *mut Display,
+    config: GLXFBConfig,
+    share_context: GLXContext,
+    direct: Bool,
+    attrib_list: *const c_int,
+) -> GLXContext;
+/// (GLX_EXT_swap_control)
+pub type PFNGLXSWAPINTERVALEXTPROC = unsafe extern "C" fn(dpy: *mut Display, drawable: GLXDrawable, interval: c_int);
+/// (GLX_MESA_swap_control)
+pub type PFNGLXSWAPINTERVALMESAPROC = unsafe extern "C" fn(interval: c_uint) -> c_int;
+
+dyn_link! {
+    pub struct GlxDL(dlopen_local => *mut c_void | dlsym) {
+        "libGL.so.1" | "libGL.so" {
+            fn glXChooseFBConfig(
+                dpy: *mut Display,
+                screen: c_int,
+                attrib_list: *const c_int,
+                nelements: *mut c_int,
+            ) -> *mut GLXFBConfig;
+            fn glXGetFBConfigAttrib(dpy: *mut Display, config: GLXFBConfig, attribute: c_int, value: *mut c_int) -> c_int;
+            fn glXCreateNewContext(
+                dpy: *mut Display,
+                config: GLXFBConfig,
+                render_type: c_int,
+                share_list: GLXContext,
+                direct: Bool,
+            ) -> GLXContext;
+            fn glXDestroyContext(dpy: *mut Display, ctx: GLXContext) -> ();
+            fn glXGetCurrentContext() -> GLXContext;
+            fn glXGetProcAddressARB(proc_name: *const c_uchar) -> *mut c_void;
+            fn glXMakeCurrent(dpy: *mut Display, drawable: GLXDrawable, ctx: GLXContext) -> Bool;
+            fn glXQueryExtensionsString(dpy: *mut Display, screen: c_int) -> *const c_char;
+            fn glXSwapBuffers(dpy: *mut Display, drawable: GLXDrawable) -> ();
+        },
+    }
+}
+
+impl GlxDL {
+    pub unsafe fn link() -> Option<Self> {
+        let dl = Self::_link();
+        let _ = dl.glXCreateNewContext?;
+        Some(dl)
+    }
+}
+
+// Vulkan (vulkan/vulkan_xlib.h)
+pub const VK_KHR_XLIB_SURFACE_EXTENSION_NAME: &CStr =
+    unsafe { CStr::from_bytes_with_nul_unchecked(c_string!("VK_KHR_xlib_surface")) };
+pub const VK_STRUCTURE_TYPE_XLIB_SURFACE_CREATE_INFO_KHR: VkStructureType = 1000004000;
+
+#[repr(C)]
+pub struct VkXlibSurfaceCreateInfoKHR {
+    pub sType: VkStructureType,
+    pub pNext: *const c_void,
+    pub flags: VkFlags,
+    pub dpy: *mut Display,
+    pub window: Window,
+}
+
+pub type PFN_vkCreateXlibSurfaceKHR = unsafe extern "system" fn(
+    instance: VkInstance,
+    pCreateInfo: *const VkXlibSurfaceCreateInfoKHR,
+    pAllocator: *const c_void,
+    pSurface: *mut VkSurfaceKHR,
+) -> VkResult;
+
+// The few keysyms that are matched against, from X11/keysymdef.h and X11/XF86keysym.h
+pub const XK_space: KeySym = 0x0020;
+pub const XK_apostrophe: KeySym = 0x0027;
+pub const XK_plus: KeySym = 0x002b;
+pub const XK_comma: KeySym = 0x002c;
+pub const XK_minus: KeySym = 0x002d;
+pub const XK_period: KeySym = 0x002e;
+pub const XK_slash: KeySym = 0x002f;
+pub const XK_0: KeySym = 0x0030;
+pub const XK_9: KeySym = 0x0039;
+pub const XK_semicolon: KeySym = 0x003b;
+pub const XK_less: KeySym = 0x003c;
+pub const XK_equal: KeySym = 0x003d;
+pub const XK_A: KeySym = 0x0041;
+pub const XK_Z: KeySym = 0x005a;
+pub const XK_bracketleft: KeySym = 0x005b;
+pub const XK_backslash: KeySym = 0x005c;
+pub const XK_bracketright: KeySym = 0x005d;
+pub const XK_grave: KeySym = 0x0060;
+pub const XK_a: KeySym = 0x0061;
+pub const XK_z: KeySym = 0x007a;
+pub const XK_ISO_Level3_Shift: KeySym = 0xfe03;
+pub const XK_BackSpace: KeySym = 0xff08;
+pub const XK_Tab: KeySym = 0xff09;
+pub const XK_Clear: KeySym = 0xff0b;
+pub const XK_Return: KeySym = 0xff0d;
+pub const XK_Pause: KeySym = 0xff13;
+pub const XK_Scroll_Lock: KeySym = 0xff14;
+pub const XK_Escape: KeySym = 0xff1b;
+pub const XK_Kanji: KeySym = 0xff21;
+pub const XK_Muhenkan: KeySym = 0xff22;
+pub const XK_Henkan: KeySym = 0xff23;
+pub const XK_Hiragana_Katakana: KeySym = 0xff27;
+pub const XK_Hangul: KeySym = 0xff31;
+pub const XK_Hangul_Hanja: KeySym = 0xff34;
+pub const XK_Home: KeySym = 0xff50;
+pub const XK_Left: KeySym = 0xff51;
+pub const XK_Up: KeySym = 0xff52;
+pub const XK_Right: KeySym = 0xff53;
+pub const XK_Down: KeySym = 0xff54;
+pub const XK_Prior: KeySym = 0xff55;
+pub const XK_Next: KeySym = 0xff56;
+pub const XK_End: KeySym = 0xff57;
+pub const XK_Select: KeySym = 0xff60;
+pub const XK_Print: KeySym = 0xff61;
+pub const XK_Execute: KeySym = 0xff62;
+pub const XK_Insert: KeySym = 0xff63;
+pub const XK_Menu: KeySym = 0xff67;
+pub const XK_Help: KeySym = 0xff6a;
+pub const XK_Mode_switch: KeySym = 0xff7e;
+pub const XK_Num_Lock: KeySym = 0xff7f;
+pub const XK_KP_Enter: KeySym = 0xff8d;
+pub const XK_KP_Home: KeySym = 0xff95;
+pub const XK_KP_Left: KeySym = 0xff96;
+pub const XK_KP_Up: KeySym = 0xff97;
+pub const XK_KP_Right: KeySym = 0xff98;
+pub const XK_KP_Down: KeySym = 0xff99;
+pub const XK_KP_Prior: KeySym = 0xff9a;
+pub const XK_KP_Next: KeySym = 0xff9b;
+pub const XK_KP_End: KeySym = 0xff9c;
+pub const XK_KP_Begin: KeySym = 0xff9d;
+pub const XK_KP_Insert: KeySym = 0xff9e;
+pub const XK_KP_Delete: KeySym = 0xff9f;
+pub const XK_KP_Multiply: KeySym = 0xffaa;
+pub const XK_KP_Add: KeySym = 0xffab;
+pub const XK_KP_Separator: KeySym = 0xffac;
+pub const XK_KP_Subtract: KeySym = 0xffad;
+pub const XK_KP_Decimal: KeySym = 0xffae;
+pub const XK_KP_Divide: KeySym = 0xffaf;
+pub const XK_KP_0: KeySym = 0xffb0;
+pub const XK_KP_9: KeySym = 0xffb9;
+pub const XK_F1: KeySym = 0xffbe;
+pub const XK_F24: KeySym = 0xffd5;
+pub const XK_Shift_L: KeySym = 0xffe1;
+pub const XK_Shift_R: KeySym = 0xffe2;
+pub const XK_Control_L: KeySym = 0xffe3;
+pub const XK_Control_R: KeySym = 0xffe4;
+pub const XK_Caps_Lock: KeySym = 0xffe5;
+pub const XK_Meta_L: KeySym = 0xffe7;
+pub const XK_Meta_R: KeySym = 0xffe8;
+pub const XK_Alt_L: KeySym = 0xffe9;
+pub const XK_Alt_R: KeySym = 0xffea;
+pub const XK_Super_L: KeySym = 0xffeb;
+pub const XK_Super_R: KeySym = 0xffec;
+pub const XK_Delete: KeySym = 0xffff;
+pub const XF86XK_AudioLowerVolume: KeySym = 0x1008ff11;
+pub const XF86XK_AudioMute: KeySym = 0x1008ff12;
+pub const XF86XK_AudioRaiseVolume: KeySym = 0x1008ff13;
+pub const XF86XK_AudioPlay: KeySym = 0x1008ff14;
+pub const XF86XK_AudioStop: KeySym = 0x1008ff15;
+pub const XF86XK_AudioPrev: KeySym = 0x1008ff16;
+pub const XF86XK_AudioNext: KeySym = 0x1008ff17;
+pub const XF86XK_HomePage: KeySym = 0x1008ff18;
+pub const XF86XK_Mail: KeySym = 0x1008ff19;
+pub const XF86XK_Search: KeySym = 0x1008ff1b;
+pub const XF86XK_Calculator: KeySym = 0x1008ff1d;
+pub const XF86XK_Back: KeySym = 0x1008ff26;
+pub const XF86XK_Forward: KeySym = 0x1008ff27;
+pub const XF86XK_Stop: KeySym = 0x1008ff28;
+pub const XF86XK_Refresh: KeySym = 0x1008ff29;
+pub const XF86XK_Sleep: KeySym = 0x1008ff2f;
+pub const XF86XK_Favorites: KeySym = 0x1008ff30;
+pub const XF86XK_AudioPause: KeySym = 0x1008ff31;
+pub const XF86XK_AudioMedia: KeySym = 0x1008ff32;
+pub const XF86XK_MyComputer: KeySym = 0x1008ff33;
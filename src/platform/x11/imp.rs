@@ -0,0 +1,3891 @@
+#![allow(
+    // Xlib constants such as `ButtonPress` and `XK_space` are matched on as they're named in C.
+    non_upper_case_globals,
+)]
+
+use crate::{
+    clipboard::{ClipboardContents, ClipboardFormat},
+    error::Error,
+    event::{CloseReason, Event, EventPaths},
+    gl::{self, GlContextBuilder, GlProfile},
+    monitor::{Monitor, Point, Rect, Scale, Size, VideoMode},
+    platform::unix::imp::{self as unix, Message, MessageQueue},
+    util::{self, sync::{Condvar, Mutex}, CreateSender, CreateSlot, EventQueue, LazyCell},
+    vulkan::{self, PFN_vkGetInstanceProcAddr, PFN_vkVoidFunction, VkInstance, VkSurfaceKHR},
+    window::{self, Cursor, CursorGrab, CursorIcon, CustomCursor, Icon, WindowBuilder},
+};
+use std::{
+    cell::{Cell, UnsafeCell},
+    ffi::{CStr, CString},
+    mem,
+    ops,
+    path::PathBuf,
+    ptr,
+    slice,
+    sync::Arc,
+    thread,
+    time::{Duration, Instant},
+};
+
+#[cfg(feature = "raw-window-handle")]
+use raw_window_handle::{HandleError, RawDisplayHandle, RawWindowHandle, XlibDisplayHandle, XlibWindowHandle};
+
+#[cfg(feature = "input")]
+use crate::{
+    event::{EventText, ImeEvent, Key, Modifiers, MouseButton, ScanCode, ScrollDelta, Touch, TouchPhase},
+    util::TouchMouse,
+};
+
+// TODO: Maybe deglob
+use crate::platform::x11::ffi::{self, *};
+
+// Global immutable struct containing dynamically acquired API state
+static X11: LazyCell<Option<X11State>> = LazyCell::new(X11State::new);
+
+/// GLX, which is only loaded once an OpenGL context is made.
+static GLX: LazyCell<Option<GlxDL>> = LazyCell::new(|| unsafe { GlxDL::link() });
+
+/// The DPI that a scale factor of 1.0 corresponds to, same as on Win32.
+const BASE_DPI: f64 = 96.0;
+/// TODO: Measure this
+const MAX_EVENTS_PER_SWAP: usize = 4096;
+/// How long the owner of the clipboard has to send each reply before we give up on it.
+const CLIPBOARD_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Every event the window thread is interested in.
+const EVENT_MASK: c_long = KeyPressMask
+    | KeyReleaseMask
+    | ButtonPressMask
+    | ButtonReleaseMask
+    | EnterWindowMask
+    | LeaveWindowMask
+    | PointerMotionMask
+    | ExposureMask
+    | StructureNotifyMask
+    | FocusChangeMask
+    | PropertyChangeMask;
+
+thread_local! {
+    /// Error code of the last X protocol error received on this thread (see `x_error_handler`).
+    static LAST_ERROR: Cell<c_uchar> = const { Cell::new(0) };
+}
+
+struct X11State {
+    /// Dynamically linked Xlib (and friends) functions.
+    dl: X11DL,
+
+    /// Variadic Xlib functions, linked separately.
+    #[cfg(feature = "input")]
+    variadic: X11Variadic,
+}
+
+impl X11State {
+    fn new() -> Option<Self> {
+        unsafe {
+            let dl = X11DL::link()?;
+
+            // Every window has its own connection on its own thread, however Xlib has global state.
+            // This has to be called before anything else happens, which is why it's done here.
+            let _ = dl.XInitThreads();
+
+            // The default error handler exits the process, which is not a great look for a library.
+            let _ = dl.XSetErrorHandler(Some(x_error_handler));
+
+            // Input methods are opened in whatever locale the program is in, which is left alone as it's
+            // process-wide (see `Window::set_ime_allowed`), but `XMODIFIERS` is what picks the input method
+            #[cfg(feature = "input")]
+            let _ = dl.XSetLocaleModifiers(c_string!("").as_ptr().cast());
+
+            Some(Self {
+                dl,
+                #[cfg(feature = "input")]
+                variadic: X11Variadic::link(),
+            })
+        }
+    }
+}
+
+/// Records protocol errors instead of exiting the process (see `X11State::new`).
+unsafe extern "C" fn x_error_handler(_display: *mut Display, event: *mut XErrorEvent) -> c_int {
+    LAST_ERROR.with(|e| e.set((*event).error_code));
+    0
+}
+
+/// Generates the `Atoms` struct, interned all at once with a single round trip.
+///
+/// Atoms which aren't valid identifiers are given a name, like `name = "atom"`.
+macro_rules! atoms {
+    (@name $name:ident) => { c_string!(stringify!($name)) };
+    (@name $name:ident $atom:literal) => { c_string!($atom) };
+    ($($name:ident $(= $atom:literal)?),* $(,)?) => {
+        #[allow(non_snake_case)]
+        #[repr(C)]
+        struct Atoms {
+            $($name: Atom,)*
+        }
+
+        impl Atoms {
+            unsafe fn intern(xlib: &X11DL, display: *mut Display) -> Self {
+                let mut names = [$(atoms!(@name $name $($atom)?).as_ptr() as *mut c_char,)*];
+                let mut atoms = mem::MaybeUninit::<Self>::zeroed();
+                let _ = xlib.XInternAtoms(
+                    display,
+                    names.as_mut_ptr(),
+                    names.len() as c_int,
+                    False,
+                    atoms.as_mut_ptr().cast(),
+                );
+                atoms.assume_init()
+            }
+        }
+    };
+}
+
+atoms! {
+    CLIPBOARD,
+    INCR,
+    MULTIPLE,
+    RAMEN_CLIPBOARD,
+    RESOURCE_MANAGER,
+    SAVE_TARGETS,
+    TARGETS,
+    TIMESTAMP,
+    UTF8_STRING,
+    WM_DELETE_WINDOW,
+    WM_PROTOCOLS,
+    XdndActionCopy,
+    XdndAware,
+    XdndDrop,
+    XdndEnter,
+    XdndFinished,
+    XdndLeave,
+    XdndPosition,
+    XdndSelection,
+    XdndStatus,
+    XdndTypeList,
+    _MOTIF_WM_HINTS,
+    _NET_FRAME_EXTENTS,
+    _NET_WM_FULLSCREEN_MONITORS,
+    _NET_WM_ICON,
+    _NET_WM_NAME,
+    _NET_WM_PID,
+    _NET_WM_PING,
+    _NET_WM_STATE,
+    _NET_WM_STATE_FULLSCREEN,
+    _NET_WM_STATE_HIDDEN,
+    _NET_WM_STATE_MAXIMIZED_HORZ,
+    _NET_WM_STATE_MAXIMIZED_VERT,
+    image_bmp = "image/bmp",
+    text_plain_utf8 = "text/plain;charset=utf-8",
+    text_uri_list = "text/uri-list",
+}
+
+/// The version of the XDND protocol we implement, which is the latest one.
+const XDND_VERSION: c_long = 5;
+
+/// A drag & drop over the window, from another client that implements XDND.
+struct Drag {
+    /// The window that the files are being dragged from, which is who we reply to.
+    source: ffi::Window,
+
+    /// Where the cursor was at the last `XdndPosition`, which is also where the files are dropped.
+    position: Point,
+
+    /// Whether the drag offers files (as `text/uri-list`), as those are all that's accepted.
+    has_files: bool,
+
+    /// The dragged files, once they've been converted from `XdndSelection`.
+    paths: Option<Vec<PathBuf>>,
+
+    /// Whether `XdndSelection` is being converted, which is asked for at the first `XdndPosition`.
+    is_converting: bool,
+
+    /// Whether the files were dropped before the conversion finished, so the drop is finished after.
+    is_dropped: bool,
+}
+
+/// Clipboard data too big for a single request, being sent in chunks with `INCR`.
+struct IncrTransfer {
+    /// The window that asked for the data, which deletes its property each time it wants the next chunk.
+    requestor: ffi::Window,
+
+    /// The property on the requestor that each chunk is put in.
+    property: Atom,
+
+    /// The target the data was converted to, which each chunk's property has as its type.
+    target: Atom,
+
+    /// A copy of the data, so that it's all sent even if the clipboard changes in the meantime.
+    bytes: Vec<u8>,
+
+    /// How many bytes have been sent so far.
+    sent: usize,
+
+    /// When the requestor is given up on if it hasn't asked for the next chunk, as it might be gone.
+    deadline: Instant,
+}
+
+/// Implementation container for `window::Window`
+pub struct WindowImpl {
+    display: *mut Display,
+    queue: Arc<MessageQueue>,
+    #[cfg(feature = "raw-window-handle")]
+    screen: c_int,
+    thread: Option<thread::JoinHandle<()>>,
+    user: *mut WindowImplData, // 'thread
+    window: ffi::Window,
+}
+
+// Pointers automatically lose Send and Sync, so...
+unsafe impl Send for WindowImpl {}
+unsafe impl Sync for WindowImpl {}
+
+/// X11 specific extensions to the [`Window`](crate::window::Window) API.
+pub trait WindowExt {
+    /// Gets the Xlib [`Display`] connection owned by the window thread.
+    ///
+    /// Each window has its own connection, which is closed when the window is dropped.
+    ///
+    /// Returns `None` if the window wasn't created with the X11 backend.
+    fn xlib_display(&self) -> Option<*mut Display>;
+
+    /// Gets the X11 window ID (a.k.a. `Window` in Xlib, or `XID`).
+    ///
+    /// Returns `None` if the window wasn't created with the X11 backend.
+    fn xlib_window(&self) -> Option<c_ulong>;
+}
+
+impl WindowExt for window::Window {
+    #[inline]
+    fn xlib_display(&self) -> Option<*mut Display> {
+        match &self.0 {
+            unix::WindowImpl::X11(window) => Some(window.display),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn xlib_window(&self) -> Option<c_ulong> {
+        match &self.0 {
+            unix::WindowImpl::X11(window) => Some(window.window),
+            _ => None,
+        }
+    }
+}
+
+/// User data structure.
+///
+/// Mostly unsynchronized (and is therefore for the window thread only), handle with care.
+pub struct WindowImplData {
+    /// Xlib function table (lives as long as the process)
+    xlib: &'static X11DL,
+
+    /// This window thread's connection to the X server
+    display: *mut Display,
+
+    /// The X11 window itself
+    window: ffi::Window,
+
+    /// Interned atoms for this connection
+    atoms: Atoms,
+
+    /// Current size of the client area (inner area)
+    client_area_size: (u32, u32),
+
+    /// Last known position of the window frame (outer area), relative to the root window
+    outer_position: (i32, i32),
+
+    /// Whether the position was picked by the user, and not left up to the window manager
+    is_user_positioned: bool,
+
+    /// The current DPI scale factor, read from `Xft.dpi`.
+    current_scale: Scale,
+
+    /// The cursor currently defined on the window (owned, freed on change)
+    cursor: ffi::Cursor,
+
+    /// How the pointer is held inside the window while it's focused.
+    cursor_grab: CursorGrab,
+
+    /// Where the pointer is held while locked, relative to the window.
+    cursor_lock_position: (i32, i32),
+
+    /// The fullscreen mode that was asked for, which is applied again whenever the window is mapped
+    fullscreen: Option<window::Fullscreen>,
+
+    /// How a monitor was set up before exclusive fullscreen changed its video mode, to put it back afterwards.
+    saved_crtc: Option<SavedCrtc>,
+
+    /// Whether things should be scaled according to DPI.
+    is_dpi_logical: bool,
+
+    /// Indicates whether the message loop should stop and the window be destroyed.
+    destroy_flag: bool,
+
+    /// The current window style which all the X11 hints are built out of.
+    style: window::Style,
+
+    // Very lightweight event-swap system...
+    // Read `EventQueue::push_many` for more info
+    event_queue: EventQueue<Event, MAX_EVENTS_PER_SWAP>,
+
+    /// Bitset of keycodes that are currently held down, to tell apart key repeats.
+    #[cfg(feature = "input")]
+    key_states: [u8; 32],
+
+    /// Input method & input context for text input (both null if there's no input method)
+    #[cfg(feature = "input")]
+    im: XIM,
+    #[cfg(feature = "input")]
+    ic: XIC,
+
+    /// Input context which reports compositions to us, used instead of `ic` while the IME is allowed.
+    /// This is null if the input method can't do that.
+    #[cfg(feature = "input")]
+    ime_ic: XIC,
+
+    /// The preedit callbacks given to `ime_ic`, which point back to this struct.
+    #[cfg(feature = "input")]
+    ime_callbacks: [XICCallback; 4],
+
+    /// The preedit text of the composition in progress (if `is_composing`), and the caret within it.
+    #[cfg(feature = "input")]
+    preedit: Vec<char>,
+    #[cfg(feature = "input")]
+    preedit_caret: usize,
+
+    /// Whether `ime_ic` is used, see `Window::set_ime_allowed`.
+    #[cfg(feature = "input")]
+    ime_allowed: bool,
+
+    /// The text area that the input method should avoid covering, relative to the window.
+    #[cfg(feature = "input")]
+    ime_cursor_area: Option<(Point, Size)>,
+
+    /// Whether key presses are looked up as text for `Event::Character`.
+    #[cfg(feature = "input")]
+    text_input: bool,
+
+    /// The major opcode of XInput 2.1+, which raw mouse motion comes from (`None` if unsupported).
+    #[cfg(feature = "input")]
+    xi_opcode: Option<c_int>,
+
+    /// Whether the server has XInput 2.2+, which touch events come from.
+    #[cfg(feature = "input")]
+    xi_touch: bool,
+
+    /// Whether raw mouse motion is selected on the root window, see `Window::set_raw_mouse_motion`.
+    #[cfg(feature = "input")]
+    raw_mouse_motion: bool,
+
+    /// Mouse buttons that are down, which the pointer is implicitly grabbed for until they're all released.
+    #[cfg(feature = "input")]
+    held_buttons: Vec<MouseButton>,
+
+    /// The drag & drop over the window, if there is one (only if it accepts drops).
+    drag: Option<Drag>,
+
+    /// The contents of the clipboard while the window owns it, which are sent to whoever asks for them.
+    clipboard: Option<ClipboardContents>,
+
+    /// When the window took the clipboard, which is what it answers `TIMESTAMP` with.
+    clipboard_time: Time,
+
+    /// Clipboard data being sent in chunks, which can go on after the clipboard is taken from us.
+    incr_transfers: Vec<IncrTransfer>,
+
+    /// The event type of XFixes selection notifications, which clipboard changes come from (`None` if unsupported).
+    xfixes_selection_event: Option<c_int>,
+
+    /// Where `Window::present_pixels` copies to, which is made on the first call.
+    frame: Option<Frame>,
+
+    /// Whether OpenGL or Vulkan presents to the window, in which case our frame stays out of the way.
+    has_external_surface: bool,
+
+    /// Whether touch is reported as the mouse too, which the server stops doing once touch is selected.
+    #[cfg(feature = "input")]
+    touch_emulates_mouse: bool,
+    #[cfg(feature = "input")]
+    touch_mouse: TouchMouse,
+
+    // State flag dump
+    is_cursor_grabbed: bool,
+    is_focused: bool,
+    is_fullscreen: bool,
+    is_maximized: bool,
+    is_minimized: bool,
+    #[cfg(feature = "input")]
+    is_composing: bool,
+    #[cfg(feature = "input")]
+    is_mouse_inside: bool,
+}
+
+pub fn spawn_window(builder: &WindowBuilder) -> Result<WindowImpl, Error> {
+    let builder = builder.clone();
+
+    let xlib = &X11.get().as_ref().ok_or(Error::Unavailable("failed to load libX11"))?.dl;
+    let display = unsafe { xlib.XOpenDisplay(ptr::null()) }.unwrap_or(ptr::null_mut());
+    if display.is_null() {
+        return Err(Error::Unavailable("failed to connect to the X server"))
+    }
+
+    let queue = Arc::new(MessageQueue::new());
+    let queue2 = Arc::clone(&queue); // remote thread's handle object
+    let recv: Arc<CreateSlot<WindowImpl>> = Arc::new((Mutex::new(None), Condvar::new()));
+    let sender = CreateSender(Arc::clone(&recv)); // remote thread's handle object
+
+    // HACK: Since Rust doesn't trust us to share pointers, we move a `usize`
+    let display = display as usize;
+    let thread = thread::spawn(move || unsafe {
+        let display = display as *mut Display;
+        let xlib = &X11.get().as_ref().unwrap().dl;
+
+        let screen = xlib.XDefaultScreen(display).unwrap_or(0);
+        let root = xlib.XRootWindow(display, screen).unwrap_or(0);
+        let scale = xft_dpi(xlib, display).unwrap_or(BASE_DPI) / BASE_DPI;
+        let (width, height) = builder.inner_size.as_physical(scale);
+
+        // Changes to `Xft.dpi` come with `RESOURCE_MANAGER` changing on the root window, see `update_scale`.
+        // The connection is this window's own, so there's nobody else's event mask to replace.
+        let _ = xlib.XSelectInput(display, root, PropertyChangeMask);
+        let (x, y) = builder.position.map(|p| p.as_physical(scale)).unwrap_or((0, 0));
+
+        // Creation errors are reported asynchronously, so the request is synced below to catch them
+        LAST_ERROR.with(|e| e.set(0));
+        let mut attributes: XSetWindowAttributes = mem::zeroed();
+        attributes.background_pixel = xlib.XBlackPixel(display, screen).unwrap_or(0);
+        attributes.event_mask = EVENT_MASK;
+        let window = xlib.XCreateWindow(
+            display,
+            root,
+            x,
+            y,
+            width.max(1), // zero sizes are a `BadValue`
+            height.max(1),
+            0, // border width
+            CopyFromParent,
+            InputOutput,
+            ptr::null_mut(), // `CopyFromParent` visual
+            CWBackPixel | CWEventMask,
+            &mut attributes,
+        ).unwrap_or(0);
+        let _ = xlib.XSync(display, False);
+        let error = LAST_ERROR.with(Cell::get);
+        if window == 0 || error != 0 {
+            let _ = xlib.XCloseDisplay(display);
+            sender.send(Err(Error::WindowCreation(error.into())));
+            return
+        }
+
+        #[cfg(feature = "input")]
+        let xinput2 = xinput2_version(xlib, display);
+        let user_data: UnsafeCell<WindowImplData> = UnsafeCell::new(WindowImplData {
+            xlib,
+            display,
+            window,
+            atoms: Atoms::intern(xlib, display),
+            client_area_size: (width, height),
+            outer_position: (x, y),
+            is_user_positioned: builder.position.is_some(),
+            current_scale: scale,
+            cursor: 0,
+            cursor_grab: CursorGrab::None,
+            cursor_lock_position: (0, 0),
+            fullscreen: None, // set before mapping
+            saved_crtc: None,
+            is_dpi_logical: matches!(builder.inner_size, Size::Logical(..)),
+            destroy_flag: false,
+            style: builder.style.clone(),
+
+            event_queue: EventQueue::new(),
+
+            #[cfg(feature = "input")]
+            key_states: [0; 32],
+
+            #[cfg(feature = "input")]
+            im: ptr::null_mut(),
+            #[cfg(feature = "input")]
+            ic: ptr::null_mut(),
+            #[cfg(feature = "input")]
+            ime_ic: ptr::null_mut(),
+            #[cfg(feature = "input")]
+            ime_callbacks: [XICCallback { client_data: ptr::null_mut(), callback: None }; 4],
+            #[cfg(feature = "input")]
+            preedit: Vec::new(),
+            #[cfg(feature = "input")]
+            preedit_caret: 0,
+            #[cfg(feature = "input")]
+            ime_allowed: builder.ime_allowed,
+            #[cfg(feature = "input")]
+            ime_cursor_area: None,
+            #[cfg(feature = "input")]
+            text_input: builder.text_input,
+            #[cfg(feature = "input")]
+            xi_opcode: xinput2.map(|(opcode, _)| opcode),
+            #[cfg(feature = "input")]
+            xi_touch: matches!(xinput2, Some((_, minor)) if minor >= 2),
+            #[cfg(feature = "input")]
+            raw_mouse_motion: false,
+            #[cfg(feature = "input")]
+            held_buttons: Vec::new(),
+            drag: None,
+            clipboard: None,
+            clipboard_time: CurrentTime,
+            incr_transfers: Vec::new(),
+            frame: None,
+            has_external_surface: false,
+            xfixes_selection_event: None,
+            #[cfg(feature = "input")]
+            touch_emulates_mouse: builder.touch_emulates_mouse,
+            #[cfg(feature = "input")]
+            touch_mouse: TouchMouse::default(),
+
+            is_cursor_grabbed: false,
+            is_focused: false,
+            is_fullscreen: false,
+            is_maximized: false,
+            is_minimized: false,
+            #[cfg(feature = "input")]
+            is_composing: false,
+            #[cfg(feature = "input")]
+            is_mouse_inside: false,
+        });
+        let data = &mut *user_data.get();
+
+        // Ask the window manager to be told about close requests & liveness checks
+        let mut protocols = [data.atoms.WM_DELETE_WINDOW, data.atoms._NET_WM_PING];
+        let _ = xlib.XSetWMProtocols(display, window, protocols.as_mut_ptr(), protocols.len() as c_int);
+
+        // `_NET_WM_PING` is only really useful if the window manager can kill us, which needs a PID
+        let pid = [getpid() as c_long];
+        let _ = xlib.XChangeProperty(
+            display,
+            window,
+            data.atoms._NET_WM_PID,
+            XA_CARDINAL,
+            32,
+            PropModeReplace,
+            pid.as_ptr().cast(),
+            1,
+        );
+
+        // `WM_CLASS` is both the instance name and the class name, which we set to the same thing
+        let class_name = CString::new(builder.class_name.as_ref()).unwrap_or_default().into_raw();
+        let mut class_hint = XClassHint { res_name: class_name, res_class: class_name };
+        let _ = xlib.XSetClassHint(display, window, &mut class_hint);
+        mem::drop(CString::from_raw(class_name));
+
+        // Drag & drop sources only talk to windows which say what XDND version they know
+        if builder.accept_drops {
+            let version = [XDND_VERSION];
+            let _ = xlib.XChangeProperty(
+                display,
+                window,
+                data.atoms.XdndAware,
+                XA_ATOM,
+                32,
+                PropModeReplace,
+                version.as_ptr().cast(),
+                1,
+            );
+        }
+
+        data.xfixes_selection_event = select_clipboard_changes(data);
+
+        // Keys being held down should send repeated presses, not a release-press pair every time
+        #[cfg(feature = "input")]
+        {
+            let _ = xlib.XkbSetDetectableAutoRepeat(display, True, ptr::null_mut());
+            open_input_context(data);
+            set_raw_mouse_motion(data, builder.raw_mouse_motion);
+            select_touch_events(data);
+        }
+
+        set_title(data, builder.title.as_ref());
+        if let Some(icon) = &builder.icon {
+            set_icon(data, Some(icon));
+        }
+        update_window_style(data);
+        set_cursor(data, &builder.cursor);
+        data.fullscreen = set_video_mode(data, builder.fullscreen.clone());
+        if data.style.visible {
+            map_window(data);
+        }
+        let _ = xlib.XFlush(display);
+
+        sender.send(Ok(WindowImpl {
+            display,
+            queue: Arc::clone(&queue2),
+            #[cfg(feature = "raw-window-handle")]
+            screen,
+            thread: None, // filled in by caller
+            user: user_data.get(),
+            window,
+        }));
+
+        // No longer needed, free memory
+        mem::drop(builder);
+        mem::drop(sender);
+
+        // Run message loop until the window is dropped
+        let mut fds = [
+            pollfd { fd: xlib.XConnectionNumber(display).unwrap_or(-1), events: POLLIN, revents: 0 },
+            pollfd { fd: queue2.pipe_read, events: POLLIN, revents: 0 },
+        ];
+        let mut event = mem::MaybeUninit::<XEvent>::zeroed();
+        'message_loop: loop {
+            // Process everything the X server has sent so far
+            while xlib.XPending(display).unwrap_or(0) != 0 {
+                let _ = xlib.XNextEvent(display, event.as_mut_ptr());
+
+                // Input methods take the key presses that make up composed text, sending it later
+                #[cfg(feature = "input")]
+                if data.text_input && xlib.XFilterEvent(event.as_mut_ptr(), 0) == Some(True) {
+                    continue
+                }
+                process_event(data, &*event.as_ptr());
+            }
+
+            // Process everything the owning thread has sent so far
+            let messages = queue2.take();
+            let count = messages.len();
+            for message in messages {
+                process_message(data, message);
+            }
+            queue2.mark_handled(count);
+            if data.destroy_flag {
+                break 'message_loop
+            }
+
+            // Handling messages might have queued up events (Xlib reads them during round trips),
+            // which won't wake up `poll`, so check again before going to sleep.
+            if xlib.XPending(display).unwrap_or(0) != 0 {
+                continue
+            }
+            if poll(fds.as_mut_ptr(), fds.len() as c_ulong, -1) < 0 && *__errno_location() != EINTR {
+                panic!("Hard error {} in poll loop!", *__errno_location());
+            }
+            if fds[1].revents != 0 {
+                queue2.drain_pipe();
+            }
+        }
+
+        // Clean up, in reverse order
+        #[cfg(feature = "input")]
+        {
+            if !data.ime_ic.is_null() {
+                let _ = xlib.XDestroyIC(data.ime_ic);
+            }
+            if !data.ic.is_null() {
+                let _ = xlib.XDestroyIC(data.ic);
+            }
+            if !data.im.is_null() {
+                let _ = xlib.XCloseIM(data.im);
+            }
+        }
+        if data.cursor != 0 {
+            let _ = xlib.XFreeCursor(display, data.cursor);
+        }
+        data.frame = None;
+        restore_crtc(data);
+        let _ = xlib.XDestroyWindow(display, window);
+        let _ = xlib.XCloseDisplay(display);
+    });
+
+    // Wait until the thread is done creating the window or notifying us why it couldn't do that
+    match util::create_wait(&recv) {
+        Ok(mut window) => {
+            window.thread = Some(thread);
+            Ok(window)
+        },
+        Err(err) => {
+            let _ = thread.join();
+            Err(err)
+        },
+    }
+}
+
+impl WindowImpl {
+    /// Handles a message synchronously, like `SendMessageW` on Win32.
+    fn send(&self, message: Message) {
+        // Sending a message from the window thread (ex. inside `execute`) would wait forever,
+        // so in that case it's handled right away, which is also what Win32 does.
+        if self.thread.as_ref().map(|t| t.thread().id()) == Some(thread::current().id()) {
+            unsafe { process_message(&mut *self.user, message) }
+        } else {
+            self.queue.send(message)
+        }
+    }
+
+    /// Handles a message asynchronously, like `PostMessageW` on Win32.
+    #[inline]
+    fn post(&self, message: Message) {
+        let _ = self.queue.post(message);
+    }
+
+    #[inline]
+    pub fn execute<F, T>(&self, f: F) -> T
+    where
+        F: Send + FnOnce() -> T,
+        T: Send,
+    {
+        let mut result = mem::MaybeUninit::<T>::uninit();
+
+        // SAFETY: `send` blocks until the window thread has responded.
+        let out_ptr = result.as_mut_ptr();
+        let mut f = Some(Box::new(move || unsafe {
+            *out_ptr = f();
+        }) as Box<dyn FnOnce() + '_>);
+
+        // The message can't carry a lifetime (just like an `LPARAM` can't), so it's erased here.
+        let f_ptr = unsafe {
+            mem::transmute::<*mut Option<Box<dyn FnOnce() + '_>>, *mut Option<Box<dyn FnOnce()>>>(&mut f)
+        };
+        self.send(Message::Execute(f_ptr));
+        unsafe { result.assume_init() }
+    }
+
+    pub fn events(&self) -> &[Event] {
+        // SAFETY: The event buffer isn't swapped until `swap_events` is called (takes &mut self)
+        unsafe { (&*self.user).event_queue.backbuffer() }
+    }
+
+    #[inline]
+    pub fn events_text(&self) -> &str {
+        // SAFETY: Same as `events`
+        unsafe { (&*self.user).event_queue.backbuffer_text() }
+    }
+
+    #[inline]
+    pub fn events_paths(&self) -> &[PathBuf] {
+        // SAFETY: Same as `events`
+        unsafe { (&*self.user).event_queue.backbuffer_paths() }
+    }
+
+    #[inline]
+    pub fn clipboard_data(&self, format: &ClipboardFormat) -> Option<Vec<u8>> {
+        let mut bytes = None;
+        self.send(Message::GetClipboard(format, &mut bytes));
+        bytes
+    }
+
+    #[inline]
+    pub fn clipboard_formats(&self) -> Vec<ClipboardFormat> {
+        let mut formats = Vec::new();
+        self.send(Message::GetClipboardFormats(&mut formats));
+        formats
+    }
+
+    pub unsafe fn create_gl_context(&self, builder: &GlContextBuilder) -> Result<GlContextImpl, Error> {
+        let context = create_gl_context(self.display, self.window, builder)?;
+        self.send(Message::SetExternalSurface);
+        Ok(context)
+    }
+
+    pub unsafe fn create_vulkan_surface(
+        &self,
+        instance: VkInstance,
+        get_instance_proc_addr: Option<PFN_vkGetInstanceProcAddr>,
+    ) -> Result<VkSurfaceKHR, Error> {
+        let create_surface = mem::transmute::<PFN_vkVoidFunction, PFN_vkCreateXlibSurfaceKHR>(
+            unix::vulkan_instance_proc_addr(instance, get_instance_proc_addr, c_string!("vkCreateXlibSurfaceKHR"))
+                .ok_or(Error::Unsupported("VK_KHR_xlib_surface"))?,
+        );
+        let create_info = VkXlibSurfaceCreateInfoKHR {
+            sType: VK_STRUCTURE_TYPE_XLIB_SURFACE_CREATE_INFO_KHR,
+            pNext: ptr::null(),
+            flags: 0,
+            dpy: self.display,
+            window: self.window,
+        };
+        let mut surface = 0;
+        match create_surface(instance, &create_info, ptr::null(), &mut surface) {
+            vulkan::VK_SUCCESS => {
+                self.send(Message::SetExternalSurface);
+                Ok(surface)
+            },
+            result => Err(Error::SurfaceCreation(result)),
+        }
+    }
+
+    #[inline]
+    pub fn current_monitor(&self) -> Option<Monitor> {
+        let mut monitor = None;
+        self.send(Message::GetCurrentMonitor(&mut monitor));
+        monitor
+    }
+
+    #[inline]
+    pub fn inner_position(&self) -> (Point, Scale) {
+        let mut position = mem::MaybeUninit::<Point>::uninit();
+        let mut scale = mem::MaybeUninit::<Scale>::uninit();
+        self.send(Message::GetPosition(true, position.as_mut_ptr(), scale.as_mut_ptr()));
+        unsafe { (position.assume_init(), scale.assume_init()) }
+    }
+
+    #[inline]
+    pub fn inner_size(&self) -> (Size, Scale) {
+        let mut size = mem::MaybeUninit::<Size>::uninit();
+        let mut scale = mem::MaybeUninit::<Scale>::uninit();
+        self.send(Message::GetInnerSize(size.as_mut_ptr(), scale.as_mut_ptr()));
+        unsafe { (size.assume_init(), scale.assume_init()) }
+    }
+
+    #[inline]
+    pub fn is_dpi_logical(&self) -> bool {
+        let mut logical = false;
+        self.send(Message::IsDpiLogical(&mut logical));
+        logical
+    }
+
+    #[inline]
+    pub fn outer_position(&self) -> (Point, Scale) {
+        let mut position = mem::MaybeUninit::<Point>::uninit();
+        let mut scale = mem::MaybeUninit::<Scale>::uninit();
+        self.send(Message::GetPosition(false, position.as_mut_ptr(), scale.as_mut_ptr()));
+        unsafe { (position.assume_init(), scale.assume_init()) }
+    }
+
+    #[inline]
+    pub fn present_pixels(&self, pixels: &[u32], size: (u32, u32), damage: &[Rect]) -> Result<(), Error> {
+        let mut result = Ok(());
+        self.send(Message::PresentPixels(pixels, size, damage, &mut result));
+        result
+    }
+
+    #[cfg(feature = "raw-window-handle")]
+    #[inline]
+    pub fn raw_display_handle(&self) -> Result<RawDisplayHandle, HandleError> {
+        Ok(XlibDisplayHandle::new(ptr::NonNull::new(self.display.cast()), self.screen).into())
+    }
+
+    #[cfg(feature = "raw-window-handle")]
+    #[inline]
+    pub fn raw_window_handle(&self) -> Result<RawWindowHandle, HandleError> {
+        Ok(XlibWindowHandle::new(self.window as _).into())
+    }
+
+    #[inline]
+    pub fn set_borderless(&self, borderless: bool) {
+        self.send(Message::SetBorderless(borderless));
+    }
+
+    #[inline]
+    pub fn set_borderless_async(&self, borderless: bool) {
+        self.post(Message::SetBorderless(borderless));
+    }
+
+    #[inline]
+    pub fn set_clipboard(&self, contents: &ClipboardContents) -> bool {
+        let mut success = false;
+        self.send(Message::SetClipboard(contents, &mut success));
+        success
+    }
+
+    #[inline]
+    pub fn set_controls(&self, controls: Option<window::Controls>) {
+        self.send(Message::SetControls(controls));
+    }
+
+    #[inline]
+    pub fn set_controls_async(&self, controls: Option<window::Controls>) {
+        self.post(Message::SetControls(controls));
+    }
+
+    #[inline]
+    pub fn set_cursor(&self, cursor: CursorIcon) {
+        self.send(Message::SetCursor(cursor));
+    }
+
+    #[inline]
+    pub fn set_cursor_async(&self, cursor: CursorIcon) {
+        self.post(Message::SetCursor(cursor));
+    }
+
+    #[inline]
+    pub fn set_cursor_grab(&self, grab: CursorGrab) {
+        self.send(Message::SetCursorGrab(grab));
+    }
+
+    #[inline]
+    pub fn set_cursor_grab_async(&self, grab: CursorGrab) {
+        self.post(Message::SetCursorGrab(grab));
+    }
+
+    #[inline]
+    pub fn set_cursor_position(&self, position: Point) {
+        self.send(Message::SetCursorPosition(position));
+    }
+
+    #[inline]
+    pub fn set_cursor_position_async(&self, position: Point) {
+        self.post(Message::SetCursorPosition(position));
+    }
+
+    #[inline]
+    pub fn set_fullscreen(&self, fullscreen: Option<window::Fullscreen>) {
+        self.send(Message::SetFullscreen(fullscreen));
+    }
+
+    #[inline]
+    pub fn set_fullscreen_async(&self, fullscreen: Option<window::Fullscreen>) {
+        self.post(Message::SetFullscreen(fullscreen));
+    }
+
+    #[inline]
+    pub fn set_icon(&self, icon: Option<Icon>) {
+        self.send(Message::SetIcon(icon));
+    }
+
+    #[inline]
+    pub fn set_icon_async(&self, icon: Option<Icon>) {
+        self.post(Message::SetIcon(icon));
+    }
+
+    #[cfg(feature = "input")]
+    #[inline]
+    pub fn set_ime_allowed(&self, allowed: bool) {
+        self.send(Message::SetImeAllowed(allowed));
+    }
+
+    #[cfg(feature = "input")]
+    #[inline]
+    pub fn set_ime_allowed_async(&self, allowed: bool) {
+        self.post(Message::SetImeAllowed(allowed));
+    }
+
+    #[cfg(feature = "input")]
+    #[inline]
+    pub fn set_ime_cursor_area(&self, position: Point, size: Size) {
+        self.send(Message::SetImeCursorArea(position, size));
+    }
+
+    #[cfg(feature = "input")]
+    #[inline]
+    pub fn set_ime_cursor_area_async(&self, position: Point, size: Size) {
+        self.post(Message::SetImeCursorArea(position, size));
+    }
+
+    #[inline]
+    pub fn set_inner_size(&self, size: Size) {
+        self.send(Message::SetInnerSize(size));
+    }
+
+    #[inline]
+    pub fn set_maximized(&self, maximized: bool) {
+        self.send(Message::SetMaximized(maximized));
+    }
+
+    #[inline]
+    pub fn set_maximized_async(&self, maximized: bool) {
+        self.post(Message::SetMaximized(maximized));
+    }
+
+    #[inline]
+    pub fn set_position(&self, position: Point) {
+        self.send(Message::SetPosition(position));
+    }
+
+    #[inline]
+    pub fn set_position_async(&self, position: Point) {
+        self.post(Message::SetPosition(position));
+    }
+
+    #[cfg(feature = "input")]
+    #[inline]
+    pub fn set_raw_mouse_motion(&self, enabled: bool) {
+        self.send(Message::SetRawMouseMotion(enabled));
+    }
+
+    #[cfg(feature = "input")]
+    #[inline]
+    pub fn set_raw_mouse_motion_async(&self, enabled: bool) {
+        self.post(Message::SetRawMouseMotion(enabled));
+    }
+
+    #[inline]
+    pub fn set_resizable(&self, resizable: bool) {
+        self.send(Message::SetResizable(resizable));
+    }
+
+    #[inline]
+    pub fn set_resizable_async(&self, resizable: bool) {
+        self.post(Message::SetResizable(resizable));
+    }
+
+    #[cfg(feature = "input")]
+    #[inline]
+    pub fn set_text_input(&self, enabled: bool) {
+        self.send(Message::SetTextInput(enabled));
+    }
+
+    #[cfg(feature = "input")]
+    #[inline]
+    pub fn set_text_input_async(&self, enabled: bool) {
+        self.post(Message::SetTextInput(enabled));
+    }
+
+    #[inline]
+    pub fn set_title(&self, title: &str) {
+        self.send(Message::SetTitle(title.to_owned()));
+    }
+
+    #[inline]
+    pub fn set_title_async(&self, title: &str) {
+        self.post(Message::SetTitle(title.to_owned()));
+    }
+
+    #[inline]
+    pub fn set_visible(&self, visible: bool) {
+        self.send(Message::SetVisible(visible));
+    }
+
+    #[inline]
+    pub fn set_visible_async(&self, visible: bool) {
+        self.post(Message::SetVisible(visible));
+    }
+
+    pub fn swap_events(&mut self) {
+        // SAFETY: `&mut self` guarantees no slices from `events` are alive
+        unsafe { (&*self.user).event_queue.swap() }
+    }
+}
+
+impl WindowImplData {
+    #[inline]
+    pub fn push_event(&mut self, event: Event) {
+        self.push_events(&[event]);
+    }
+
+    #[inline]
+    pub fn push_events(&mut self, events: &[Event]) {
+        self.event_queue.push_many(events);
+    }
+
+    #[inline]
+    #[cfg(feature = "input")]
+    pub fn push_event_with_text(&mut self, text: &str, f: impl FnOnce(EventText) -> Event) {
+        self.event_queue.push_with_text(text, |offset, len| f(EventText { offset, len }));
+    }
+
+    #[inline]
+    pub fn push_event_with_paths(&mut self, paths: &[PathBuf], f: impl FnOnce(EventPaths) -> Event) {
+        self.event_queue.push_with_paths(paths, |offset, len| f(EventPaths { offset, len }));
+    }
+
+    #[inline]
+    fn scale_point(&self, point: Point) -> Point {
+        if self.is_dpi_logical {
+            point.to_logical(self.current_scale)
+        } else {
+            point
+        }
+    }
+
+    #[inline]
+    fn scale_size(&self, size: Size) -> Size {
+        if self.is_dpi_logical {
+            size.to_logical(self.current_scale)
+        } else {
+            size
+        }
+    }
+}
+
+impl ops::Drop for WindowImpl {
+    fn drop(&mut self) {
+        // Signal the window it's OK to close, and wait for the thread to naturally return
+        self.post(Message::Destroy);
+        let _ = self.thread.take().map(thread::JoinHandle::join);
+    }
+}
+
+/// Implementation container for `gl::GlContext`.
+///
+/// The context has a connection of its own, so that GLX and the window thread stay out of each other's way.
+pub struct GlContextImpl {
+    xlib: &'static X11DL,
+    glx: &'static GlxDL,
+    display: *mut Display,
+    window: ffi::Window,
+    context: GLXContext,
+    swap_interval_ext: Option<PFNGLXSWAPINTERVALEXTPROC>,
+    swap_interval_mesa: Option<PFNGLXSWAPINTERVALMESAPROC>,
+}
+
+// Contexts can move between threads, as long as they're not current on any
+unsafe impl Send for GlContextImpl {}
+
+impl GlContextImpl {
+    #[inline]
+    pub fn make_current(&self) -> bool {
+        unsafe { self.glx.glXMakeCurrent(self.display, self.window, self.context) == Some(True) }
+    }
+
+    #[inline]
+    pub fn make_not_current(&self) -> bool {
+        self.is_current() && unsafe { self.glx.glXMakeCurrent(self.display, 0, ptr::null_mut()) == Some(True) }
+    }
+
+    #[inline]
+    pub fn is_current(&self) -> bool {
+        unsafe { self.glx.glXGetCurrentContext() == Some(self.context) }
+    }
+
+    #[inline]
+    pub fn swap_buffers(&self) -> bool {
+        unsafe { self.glx.glXSwapBuffers(self.display, self.window).is_some() }
+    }
+
+    pub fn set_swap_interval(&self, interval: i32) -> bool {
+        unsafe {
+            if let Some(swap_interval) = self.swap_interval_ext {
+                swap_interval(self.display, self.window, interval);
+                true
+            } else if let Some(swap_interval) = self.swap_interval_mesa {
+                // The MESA variant has no late swap tearing, nor a way to ask for it
+                interval >= 0 && swap_interval(interval as c_uint) == 0
+            } else {
+                false
+            }
+        }
+    }
+
+    #[inline]
+    pub fn get_proc_address(&self, name: &CStr) -> *const c_void {
+        unsafe { self.glx.glXGetProcAddressARB(name.as_ptr().cast()).unwrap_or(ptr::null_mut()) }
+    }
+
+    #[inline]
+    pub fn resize(&self, _width: u32, _height: u32) {
+        // The window is the drawable, so it's always the right size
+    }
+}
+
+impl ops::Drop for GlContextImpl {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = self.make_not_current();
+            let _ = self.glx.glXDestroyContext(self.display, self.context);
+            let _ = self.xlib.XCloseDisplay(self.display);
+        }
+    }
+}
+
+unsafe fn create_gl_context(
+    window_display: *mut Display,
+    window: ffi::Window,
+    builder: &GlContextBuilder,
+) -> Result<GlContextImpl, Error> {
+    let xlib = &X11.get().as_ref().unwrap().dl;
+    let glx = GLX.get().as_ref().ok_or(Error::Unsupported("OpenGL"))?;
+    let display = xlib
+        .XOpenDisplay(xlib.XDisplayString(window_display).unwrap_or(ptr::null_mut()))
+        .unwrap_or(ptr::null_mut());
+    if display.is_null() {
+        return Err(Error::Unavailable("failed to connect to the X server"))
+    }
+    match create_glx_context(xlib, glx, display, builder) {
+        Ok((context, extensions)) => {
+            let swap_interval = |name: &[u8]| glx.glXGetProcAddressARB(name.as_ptr()).filter(|f| !f.is_null());
+            let swap_interval_ext = gl::has_extension(extensions, "GLX_EXT_swap_control")
+                .then(|| swap_interval(c_string!("glXSwapIntervalEXT")))
+                .flatten()
+                .map(|f| mem::transmute::<*mut c_void, PFNGLXSWAPINTERVALEXTPROC>(f));
+            let swap_interval_mesa = gl::has_extension(extensions, "GLX_MESA_swap_control")
+                .then(|| swap_interval(c_string!("glXSwapIntervalMESA")))
+                .flatten()
+                .map(|f| mem::transmute::<*mut c_void, PFNGLXSWAPINTERVALMESAPROC>(f));
+            Ok(GlContextImpl { xlib, glx, display, window, context, swap_interval_ext, swap_interval_mesa })
+        },
+        Err(err) => {
+            let _ = xlib.XCloseDisplay(display);
+            Err(err)
+        },
+    }
+}
+
+/// Creates the context with a config that matches the visual of the window, which is the default one.
+///
+/// Also hands out the GLX extension list, which lives as long as the connection.
+unsafe fn create_glx_context<'a>(
+    xlib: &X11DL,
+    glx: &GlxDL,
+    display: *mut Display,
+    builder: &GlContextBuilder,
+) -> Result<(GLXContext, &'a [u8]), Error> {
+    let screen = xlib.XDefaultScreen(display).unwrap_or(0);
+    let extensions = match glx.glXQueryExtensionsString(display, screen) {
+        Some(extensions) if !extensions.is_null() => CStr::from_ptr(extensions).to_bytes(),
+        _ => &[],
+    };
+    let visual = xlib.XDefaultVisual(display, screen).unwrap_or(ptr::null_mut());
+    let visual_id = xlib.XVisualIDFromVisual(visual).unwrap_or(0);
+
+    let mut attributes = vec![
+        GLX_X_RENDERABLE, True,
+        GLX_DRAWABLE_TYPE, GLX_WINDOW_BIT,
+        GLX_RENDER_TYPE, GLX_RGBA_BIT,
+        GLX_DOUBLEBUFFER, True,
+        GLX_RED_SIZE, 8,
+        GLX_GREEN_SIZE, 8,
+        GLX_BLUE_SIZE, 8,
+        GLX_DEPTH_SIZE, builder.depth_bits.into(),
+        GLX_STENCIL_SIZE, builder.stencil_bits.into(),
+    ];
+    if builder.samples > 0 {
+        attributes.extend_from_slice(&[GLX_SAMPLE_BUFFERS, 1, GLX_SAMPLES, builder.samples.into()]);
+    }
+    if builder.srgb {
+        attributes.extend_from_slice(&[GLX_FRAMEBUFFER_SRGB_CAPABLE_ARB, True]);
+    }
+    attributes.push(0);
+    let mut count = 0;
+    let configs = glx.glXChooseFBConfig(display, screen, attributes.as_ptr(), &mut count).unwrap_or(ptr::null_mut());
+    if configs.is_null() {
+        return Err(Error::Unsupported("pixel format"))
+    }
+    let config = slice::from_raw_parts(configs, count.max(0) as usize).iter().copied().find(|&config| {
+        let mut id = 0;
+        glx.glXGetFBConfigAttrib(display, config, GLX_VISUAL_ID, &mut id) == Some(Success) && id as VisualID == visual_id
+    });
+    let _ = xlib.XFree(configs.cast());
+    let config = config.ok_or(Error::Unsupported("pixel format"))?;
+
+    // Bad versions & such are reported as protocol errors, so the request is synced below to catch them
+    LAST_ERROR.with(|e| e.set(0));
+    let create_context_attribs = gl::has_extension(extensions, "GLX_ARB_create_context")
+        .then(|| glx.glXGetProcAddressARB(c_string!("glXCreateContextAttribsARB").as_ptr()))
+        .flatten()
+        .filter(|f| !f.is_null())
+        .map(|f| mem::transmute::<*mut c_void, PFNGLXCREATECONTEXTATTRIBSARBPROC>(f));
+    let context = if let Some(create_context_attribs) = create_context_attribs {
+        let (major, minor) = builder.version;
+        let profile = match builder.profile {
+            GlProfile::Core => GLX_CONTEXT_CORE_PROFILE_BIT_ARB,
+            GlProfile::Compatibility => GLX_CONTEXT_COMPATIBILITY_PROFILE_BIT_ARB,
+        };
+        let flags = if builder.debug { GLX_CONTEXT_DEBUG_BIT_ARB } else { 0 };
+        let mut attributes = vec![
+            GLX_CONTEXT_MAJOR_VERSION_ARB, major.into(),
+            GLX_CONTEXT_MINOR_VERSION_ARB, minor.into(),
+            GLX_CONTEXT_FLAGS_ARB, flags,
+        ];
+        if gl::has_extension(extensions, "GLX_ARB_create_context_profile") {
+            attributes.extend_from_slice(&[GLX_CONTEXT_PROFILE_MASK_ARB, profile]);
+        }
+        attributes.push(0);
+        create_context_attribs(display, config, ptr::null_mut(), True, attributes.as_ptr())
+    } else if builder.version >= (3, 0) || builder.debug {
+        return Err(Error::Unsupported("GLX_ARB_create_context"))
+    } else {
+        glx.glXCreateNewContext(display, config, GLX_RGBA_TYPE, ptr::null_mut(), True).unwrap_or(ptr::null_mut())
+    };
+    let _ = xlib.XSync(display, False);
+    let error = LAST_ERROR.with(Cell::get);
+    if context.is_null() || error != 0 {
+        if !context.is_null() {
+            let _ = glx.glXDestroyContext(display, context);
+        }
+        return Err(Error::ContextCreation(error.into()))
+    }
+    Ok((context, extensions))
+}
+
+/// Reads the `Xft.dpi` resource, which is what desktop environments set for (global) DPI scaling.
+///
+/// Resources are kept by `xrdb` in `RESOURCE_MANAGER` on the root window of the first screen, which is read directly,
+/// as `XResourceManagerString` is only a copy from when the connection was opened.
+unsafe fn xft_dpi(xlib: &X11DL, display: *mut Display) -> Option<f64> {
+    let atom = xlib.XInternAtom(display, c_string!("RESOURCE_MANAGER").as_ptr().cast(), True)?;
+    if atom == 0 {
+        return None
+    }
+    let root = xlib.XRootWindow(display, 0)?;
+    let mut actual_type: Atom = 0;
+    let mut actual_format: c_int = 0;
+    let mut count: c_ulong = 0;
+    let mut bytes_after: c_ulong = 0;
+    let mut resources: *mut c_uchar = ptr::null_mut();
+    let status = xlib.XGetWindowProperty(
+        display,
+        root,
+        atom,
+        0,
+        1 << 20, // in 32-bit units, so 4MiB, which is far more than resources ever take
+        False,
+        XA_STRING,
+        &mut actual_type,
+        &mut actual_format,
+        &mut count,
+        &mut bytes_after,
+        &mut resources,
+    );
+    let mut dpi = None;
+    if status == Some(Success) && !resources.is_null() {
+        if actual_format == 8 {
+            dpi = parse_xft_dpi(std::slice::from_raw_parts(resources, count as usize));
+        }
+        let _ = xlib.XFree(resources.cast());
+    }
+    dpi
+}
+
+/// Finds the `Xft.dpi` entry in the resource manager string (a.k.a. `xrdb -query` output).
+fn parse_xft_dpi(resources: &[u8]) -> Option<f64> {
+    resources
+        .split(|&b| b == b'\n')
+        .filter_map(|line| std::str::from_utf8(line).ok())
+        .find_map(|line| line.strip_prefix("Xft.dpi:")?.trim().parse::<f64>().ok())
+        .filter(|dpi| *dpi > 0.0)
+}
+
+pub fn vulkan_instance_extensions() -> &'static [&'static CStr] {
+    &[vulkan::VK_KHR_SURFACE_EXTENSION_NAME, VK_KHR_XLIB_SURFACE_EXTENSION_NAME]
+}
+
+pub fn available_monitors() -> Vec<Monitor> {
+    let xlib = match X11.get() {
+        Some(x11) => &x11.dl,
+        None => return Vec::new(),
+    };
+    unsafe {
+        let display = xlib.XOpenDisplay(ptr::null()).unwrap_or(ptr::null_mut());
+        if display.is_null() {
+            return Vec::new()
+        }
+        let monitors = query_monitors(xlib, display);
+        let _ = xlib.XCloseDisplay(display);
+        monitors
+    }
+}
+
+/// Lists the monitors with RandR, or pretends the whole screen is one monitor if that's unavailable.
+unsafe fn query_monitors(xlib: &X11DL, display: *mut Display) -> Vec<Monitor> {
+    let screen = xlib.XDefaultScreen(display).unwrap_or(0);
+    let root = xlib.XRootWindow(display, screen).unwrap_or(0);
+    let scale = xft_dpi(xlib, display).unwrap_or(BASE_DPI) / BASE_DPI;
+    let bit_depth = xlib.XDefaultDepth(display, screen).unwrap_or(24) as u16;
+    let work_area = net_work_area(xlib, display, root);
+    let mut monitors = Vec::new();
+
+    let mut count: c_int = 0;
+    let infos = xlib.XRRGetMonitors(display, root, True, &mut count).unwrap_or(ptr::null_mut());
+    if !infos.is_null() {
+        let resources = xlib.XRRGetScreenResourcesCurrent(display, root).unwrap_or(ptr::null_mut());
+        for info in std::slice::from_raw_parts(infos, count.max(0) as usize) {
+            let (width, height) = (info.width.max(0) as u32, info.height.max(0) as u32);
+            let rect = Rect { x: info.x, y: info.y, width, height };
+
+            // Monitors made of several outputs are mostly a video wall thing, so the first one will do
+            let (refresh_rate, video_modes) = if !resources.is_null() && info.noutput > 0 {
+                output_modes(xlib, display, resources, *info.outputs, bit_depth)
+            } else {
+                (0, Vec::new())
+            };
+            monitors.push(Monitor {
+                name: atom_name(xlib, display, info.name),
+                rect,
+                work_area: work_area.and_then(|area| area.intersection(rect)).unwrap_or(rect),
+                scale,
+                refresh_rate,
+                is_primary: info.primary != False,
+                video_modes,
+            });
+        }
+        if !resources.is_null() {
+            let _ = xlib.XRRFreeScreenResources(resources);
+        }
+        let _ = xlib.XRRFreeMonitors(infos);
+    }
+
+    if monitors.is_empty() {
+        let width = xlib.XDisplayWidth(display, screen).unwrap_or(0).max(0) as u32;
+        let height = xlib.XDisplayHeight(display, screen).unwrap_or(0).max(0) as u32;
+        let rect = Rect { x: 0, y: 0, width, height };
+        monitors.push(Monitor {
+            name: String::from("default"),
+            rect,
+            work_area: work_area.and_then(|area| area.intersection(rect)).unwrap_or(rect),
+            scale,
+            refresh_rate: 0,
+            is_primary: true,
+            video_modes: Vec::new(),
+        });
+    }
+    monitors
+}
+
+/// Gets the current refresh rate and all the video modes of a RandR output.
+unsafe fn output_modes(
+    xlib: &X11DL,
+    display: *mut Display,
+    resources: *mut XRRScreenResources,
+    output: RROutput,
+    bit_depth: u16,
+) -> (u32, Vec<VideoMode>) {
+    let info = xlib.XRRGetOutputInfo(display, resources, output).unwrap_or(ptr::null_mut());
+    if info.is_null() {
+        return (0, Vec::new())
+    }
+    let all_modes = std::slice::from_raw_parts((*resources).modes, (*resources).nmode.max(0) as usize);
+    let find_mode = |id: RRMode| all_modes.iter().find(|mode| mode.id == id);
+
+    let mut video_modes = Vec::new();
+    for &id in std::slice::from_raw_parts((*info).modes, (*info).nmode.max(0) as usize) {
+        if let Some(mode) = find_mode(id) {
+            let video_mode = VideoMode {
+                size: (mode.width, mode.height),
+                bit_depth,
+                refresh_rate: mode_refresh_rate(mode),
+            };
+            if !video_modes.contains(&video_mode) {
+                video_modes.push(video_mode);
+            }
+        }
+    }
+
+    let mut refresh_rate = 0;
+    if (*info).crtc != 0 {
+        let crtc = xlib.XRRGetCrtcInfo(display, resources, (*info).crtc).unwrap_or(ptr::null_mut());
+        if !crtc.is_null() {
+            refresh_rate = find_mode((*crtc).mode).map(mode_refresh_rate).unwrap_or(0);
+            let _ = xlib.XRRFreeCrtcInfo(crtc);
+        }
+    }
+    let _ = xlib.XRRFreeOutputInfo(info);
+    (refresh_rate, video_modes)
+}
+
+/// Calculates the refresh rate of a RandR mode in millihertz, from its timings.
+fn mode_refresh_rate(mode: &XRRModeInfo) -> u32 {
+    let mut vtotal = mode.vTotal as u64;
+    if mode.modeFlags & RR_DoubleScan != 0 {
+        vtotal *= 2;
+    }
+    if mode.modeFlags & RR_Interlace != 0 {
+        vtotal /= 2;
+    }
+    match mode.hTotal as u64 * vtotal {
+        0 => 0,
+        dots => ((mode.dotClock as u64 * 1000 + dots / 2) / dots) as u32,
+    }
+}
+
+/// Reads `_NET_WORKAREA`, the area of the desktop not covered by panels, of the first workspace.
+unsafe fn net_work_area(xlib: &X11DL, display: *mut Display, root: ffi::Window) -> Option<Rect> {
+    let atom = xlib.XInternAtom(display, c_string!("_NET_WORKAREA").as_ptr().cast(), True)?;
+    if atom == 0 {
+        return None
+    }
+    let mut actual_type: Atom = 0;
+    let mut actual_format: c_int = 0;
+    let mut count: c_ulong = 0;
+    let mut bytes_after: c_ulong = 0;
+    let mut values: *mut c_uchar = ptr::null_mut();
+    let status = xlib.XGetWindowProperty(
+        display,
+        root,
+        atom,
+        0,
+        4,
+        False,
+        XA_CARDINAL,
+        &mut actual_type,
+        &mut actual_format,
+        &mut count,
+        &mut bytes_after,
+        &mut values,
+    );
+    let mut area = None;
+    if status == Some(Success) && !values.is_null() {
+        if actual_format == 32 && count >= 4 {
+            // Format 32 properties are arrays of `long`, regardless of pointer width
+            let values = std::slice::from_raw_parts(values as *const c_long, 4);
+            area = Some(Rect {
+                x: values[0] as i32,
+                y: values[1] as i32,
+                width: values[2] as u32,
+                height: values[3] as u32,
+            });
+        }
+        let _ = xlib.XFree(values.cast());
+    }
+    area
+}
+
+/// Gets the name of an atom, such as a RandR monitor's name.
+unsafe fn atom_name(xlib: &X11DL, display: *mut Display, atom: Atom) -> String {
+    let name = xlib.XGetAtomName(display, atom).unwrap_or(ptr::null_mut());
+    if name.is_null() {
+        return String::new()
+    }
+    let string = std::ffi::CStr::from_ptr(name).to_string_lossy().into_owned();
+    let _ = xlib.XFree(name.cast());
+    string
+}
+
+/// Gets the position of the inner area relative to the root window, which is where the window manager
+/// has reparented it to (if it reparents windows), so `ConfigureNotify` coordinates can't be used as-is.
+unsafe fn inner_position(data: &WindowImplData) -> (i32, i32) {
+    let xlib = data.xlib;
+    let screen = xlib.XDefaultScreen(data.display).unwrap_or(0);
+    let root = xlib.XRootWindow(data.display, screen).unwrap_or(0);
+    let (mut x, mut y, mut child) = (0, 0, 0);
+    let _ = xlib.XTranslateCoordinates(data.display, data.window, root, 0, 0, &mut x, &mut y, &mut child);
+    (x, y)
+}
+
+/// Gets the position of the frame relative to the root window, going by `_NET_FRAME_EXTENTS`.
+/// Window managers that don't set it are assumed to have no frame.
+unsafe fn outer_position(data: &WindowImplData) -> (i32, i32) {
+    let xlib = data.xlib;
+    let (x, y) = inner_position(data);
+    let mut actual_type: Atom = 0;
+    let mut actual_format: c_int = 0;
+    let mut count: c_ulong = 0;
+    let mut bytes_after: c_ulong = 0;
+    let mut values: *mut c_uchar = ptr::null_mut();
+    let status = xlib.XGetWindowProperty(
+        data.display,
+        data.window,
+        data.atoms._NET_FRAME_EXTENTS,
+        0,
+        4,
+        False,
+        XA_CARDINAL,
+        &mut actual_type,
+        &mut actual_format,
+        &mut count,
+        &mut bytes_after,
+        &mut values,
+    );
+    let mut position = (x, y);
+    if status == Some(Success) && !values.is_null() {
+        if actual_format == 32 && count >= 4 {
+            // Left, right, top, bottom, as an array of `long` (see `net_work_area`)
+            let extents = std::slice::from_raw_parts(values as *const c_long, 4);
+            position = (x - extents[0] as i32, y - extents[2] as i32);
+        }
+        let _ = xlib.XFree(values.cast());
+    }
+    position
+}
+
+/// Finds the monitor that most of the window is on.
+unsafe fn current_monitor(data: &WindowImplData) -> Option<Monitor> {
+    let (x, y) = inner_position(data);
+    let (width, height) = data.client_area_size;
+    let rect = Rect { x, y, width, height };
+    query_monitors(data.xlib, data.display)
+        .into_iter()
+        .filter_map(|monitor| {
+            let overlap = monitor.rect.intersection(rect)?;
+            Some((overlap.width as u64 * overlap.height as u64, monitor))
+        })
+        .max_by_key(|(area, _)| *area)
+        .map(|(_, monitor)| monitor)
+}
+
+/// Finds the XInput extension's opcode and minor version,
+/// if it's XInput 2.1 or later (needed for raw events outside of grabs).
+///
+/// Touch events need XInput 2.2, which is asked for, but the server may only have 2.1.
+#[cfg(feature = "input")]
+unsafe fn xinput2_version(xlib: &X11DL, display: *mut Display) -> Option<(c_int, c_int)> {
+    let (mut opcode, mut event, mut error) = (0, 0, 0);
+    let name = c_string!("XInputExtension");
+    if xlib.XQueryExtension(display, name.as_ptr().cast(), &mut opcode, &mut event, &mut error) != Some(True) {
+        return None
+    }
+    let (mut major, mut minor) = (2, 2);
+    match xlib.XIQueryVersion(display, &mut major, &mut minor) {
+        Some(Success) if (major, minor) >= (2, 1) => Some((opcode, minor)),
+        _ => None,
+    }
+}
+
+/// Selects (or deselects) raw mouse motion, which XInput only sends to the root window.
+#[cfg(feature = "input")]
+unsafe fn set_raw_mouse_motion(data: &mut WindowImplData, enabled: bool) {
+    if data.xi_opcode.is_none() || data.raw_mouse_motion == enabled {
+        return
+    }
+    let xlib = data.xlib;
+    let screen = xlib.XDefaultScreen(data.display).unwrap_or(0);
+    let root = xlib.XRootWindow(data.display, screen).unwrap_or(0);
+
+    // An empty mask deselects everything this connection selected before
+    let mut bits = [0u8; (XI_RawMotion as usize >> 3) + 1];
+    if enabled {
+        bits[XI_RawMotion as usize >> 3] |= 1 << (XI_RawMotion & 7);
+    }
+    let mut mask = XIEventMask {
+        deviceid: XIAllMasterDevices,
+        mask_len: bits.len() as c_int,
+        mask: bits.as_mut_ptr(),
+    };
+    let _ = xlib.XISelectEvents(data.display, root, &mut mask, 1);
+    let _ = xlib.XFlush(data.display);
+    data.raw_mouse_motion = enabled;
+}
+
+/// Selects touch events on the window, if the server has them.
+///
+/// Touch that's selected by a client isn't emulated as the pointer for it anymore, which is done by `TouchMouse`.
+#[cfg(feature = "input")]
+unsafe fn select_touch_events(data: &WindowImplData) {
+    if !data.xi_touch {
+        return
+    }
+    let mut bits = [0u8; (XI_TouchEnd as usize >> 3) + 1];
+    for &evtype in &[XI_TouchBegin, XI_TouchUpdate, XI_TouchEnd] {
+        bits[evtype as usize >> 3] |= 1 << (evtype & 7);
+    }
+    let mut mask = XIEventMask {
+        deviceid: XIAllMasterDevices,
+        mask_len: bits.len() as c_int,
+        mask: bits.as_mut_ptr(),
+    };
+    let _ = data.xlib.XISelectEvents(data.display, data.window, &mut mask, 1);
+}
+
+/// Opens the input method chosen by `XMODIFIERS` (or the built-in one) and an input context for the window.
+///
+/// The input context is kept even while text input is disabled, as it's only used when enabled.
+#[cfg(feature = "input")]
+unsafe fn open_input_context(data: &mut WindowImplData) {
+    let xlib = data.xlib;
+    let create_ic = match X11.get().as_ref().and_then(|x11| x11.variadic.XCreateIC) {
+        Some(f) => f,
+        None => return,
+    };
+    data.im = xlib.XOpenIM(data.display, ptr::null_mut(), ptr::null_mut(), ptr::null_mut()).unwrap_or(ptr::null_mut());
+    if data.im.is_null() {
+        return
+    }
+
+    // Composition is shown by the input method itself, and if it can't, then nowhere at all
+    for &style in &[XIMPreeditNothing | XIMStatusNothing, XIMPreeditNone | XIMStatusNone] {
+        data.ic = create_ic(
+            data.im,
+            XNInputStyle.as_ptr(),
+            style,
+            XNClientWindow.as_ptr(),
+            data.window,
+            XNFocusWindow.as_ptr(),
+            data.window,
+            ptr::null::<c_char>(),
+        );
+        if !data.ic.is_null() {
+            break
+        }
+    }
+    if data.ic.is_null() {
+        let _ = xlib.XCloseIM(data.im);
+        data.im = ptr::null_mut();
+        return
+    }
+
+    // For the IME, the composition is drawn by the program instead, which is told about it with callbacks
+    let create_nested_list = match X11.get().as_ref().and_then(|x11| x11.variadic.XVaCreateNestedList) {
+        Some(f) => f,
+        None => return,
+    };
+    let client_data = data as *mut WindowImplData as XPointer;
+    let procs: [XICProc; 4] = [preedit_start_callback, preedit_done_callback, preedit_draw_callback, preedit_caret_callback];
+    for (callback, proc) in data.ime_callbacks.iter_mut().zip(procs.iter()) {
+        *callback = XICCallback { client_data, callback: Some(*proc) };
+    }
+    let mut spot = XPoint { x: 0, y: 0 };
+    let preedit_attributes = create_nested_list(
+        0,
+        XNPreeditStartCallback.as_ptr(),
+        &data.ime_callbacks[0] as *const XICCallback,
+        XNPreeditDoneCallback.as_ptr(),
+        &data.ime_callbacks[1] as *const XICCallback,
+        XNPreeditDrawCallback.as_ptr(),
+        &data.ime_callbacks[2] as *const XICCallback,
+        XNPreeditCaretCallback.as_ptr(),
+        &data.ime_callbacks[3] as *const XICCallback,
+        XNSpotLocation.as_ptr(),
+        &mut spot as *mut XPoint,
+        ptr::null::<c_char>(),
+    );
+    if preedit_attributes.is_null() {
+        return
+    }
+    data.ime_ic = create_ic(
+        data.im,
+        XNInputStyle.as_ptr(),
+        XIMPreeditCallbacks | XIMStatusNothing,
+        XNClientWindow.as_ptr(),
+        data.window,
+        XNFocusWindow.as_ptr(),
+        data.window,
+        XNPreeditAttributes.as_ptr(),
+        preedit_attributes,
+        ptr::null::<c_char>(),
+    );
+    let _ = xlib.XFree(preedit_attributes);
+}
+
+/// Gets the input context in use, which is null if there's no input method.
+#[cfg(feature = "input")]
+fn active_ic(data: &WindowImplData) -> XIC {
+    if data.ime_allowed && !data.ime_ic.is_null() {
+        data.ime_ic
+    } else {
+        data.ic
+    }
+}
+
+/// Gives the input context in use focus while the window has focus and text input is enabled.
+#[cfg(feature = "input")]
+unsafe fn update_ic_focus(data: &mut WindowImplData) {
+    let active = active_ic(data);
+    for &ic in &[data.ic, data.ime_ic] {
+        if !ic.is_null() {
+            if ic == active && data.is_focused && data.text_input {
+                let _ = data.xlib.XSetICFocus(ic);
+            } else {
+                let _ = data.xlib.XUnsetICFocus(ic);
+            }
+        }
+    }
+
+    // An input context losing focus won't call back about its composition anymore
+    if data.is_composing && (active != data.ime_ic || !data.text_input) {
+        data.is_composing = false;
+        data.preedit.clear();
+        data.push_event(Event::Ime(ImeEvent::End));
+    }
+}
+
+/// Tells the input method where to put its candidate window, which is right below the cursor area.
+#[cfg(feature = "input")]
+unsafe fn update_ime_spot(data: &mut WindowImplData) {
+    let variadic = match X11.get().as_ref() {
+        Some(x11) if !data.ime_ic.is_null() => &x11.variadic,
+        _ => return,
+    };
+    if let (Some(set_ic_values), Some(create_nested_list), Some((position, size))) =
+        (variadic.XSetICValues, variadic.XVaCreateNestedList, data.ime_cursor_area)
+    {
+        let (x, y) = position.as_physical(data.current_scale);
+        let (_, height) = size.as_physical(data.current_scale);
+        let clamp = |n: i64| n.max(c_short::MIN.into()).min(c_short::MAX.into()) as c_short;
+        let mut spot = XPoint { x: clamp(x.into()), y: clamp(i64::from(y) + i64::from(height)) };
+        let preedit_attributes = create_nested_list(0, XNSpotLocation.as_ptr(), &mut spot as *mut XPoint, ptr::null::<c_char>());
+        if !preedit_attributes.is_null() {
+            let _ = set_ic_values(data.ime_ic, XNPreeditAttributes.as_ptr(), preedit_attributes, ptr::null::<c_char>());
+            let _ = data.xlib.XFree(preedit_attributes);
+        }
+    }
+}
+
+/// Reports the preedit text and the caret within it as an `ImeEvent::Preedit`.
+#[cfg(feature = "input")]
+fn push_preedit(data: &mut WindowImplData) {
+    let text: String = data.preedit.iter().collect();
+    let caret = data.preedit[..data.preedit_caret.min(data.preedit.len())]
+        .iter()
+        .map(|c| c.len_utf8())
+        .sum::<usize>();
+    data.push_event_with_text(&text, |text| Event::Ime(ImeEvent::Preedit(text, Some((caret, caret)))));
+}
+
+#[cfg(feature = "input")]
+unsafe extern "C" fn preedit_start_callback(_ic: XIC, client_data: XPointer, _call_data: XPointer) -> Bool {
+    let data = &mut *(client_data as *mut WindowImplData);
+    data.is_composing = true;
+    data.preedit.clear();
+    data.preedit_caret = 0;
+    data.push_event(Event::Ime(ImeEvent::Start));
+    -1 // no length limit
+}
+
+#[cfg(feature = "input")]
+unsafe extern "C" fn preedit_done_callback(_ic: XIC, client_data: XPointer, _call_data: XPointer) -> Bool {
+    let data = &mut *(client_data as *mut WindowImplData);
+    if data.is_composing {
+        data.is_composing = false;
+        data.preedit.clear();
+        data.push_event(Event::Ime(ImeEvent::End));
+    }
+    0
+}
+
+#[cfg(feature = "input")]
+unsafe extern "C" fn preedit_draw_callback(_ic: XIC, client_data: XPointer, call_data: XPointer) -> Bool {
+    let data = &mut *(client_data as *mut WindowImplData);
+    let call_data = &*(call_data as *const XIMPreeditDrawCallbackStruct);
+
+    // The text replaces a range of characters, or if it's null, the range is just deleted
+    let mut replacement = Vec::new();
+    if let Some(text) = call_data.text.as_ref() {
+        if text.encoding_is_wchar != 0 {
+            let wide = std::slice::from_raw_parts(text.string as *const u32, text.length.into());
+            replacement.extend(wide.iter().filter_map(|&c| char::from_u32(c)));
+        } else if !text.string.is_null() {
+            // This is in the encoding of the locale, which is hopefully UTF-8
+            let bytes = std::ffi::CStr::from_ptr(text.string as *const c_char).to_bytes();
+            replacement.extend(String::from_utf8_lossy(bytes).chars());
+        }
+    }
+    let first = (call_data.chg_first.max(0) as usize).min(data.preedit.len());
+    let last = (first + call_data.chg_length.max(0) as usize).min(data.preedit.len());
+    let _ = data.preedit.splice(first..last, replacement);
+    data.preedit_caret = call_data.caret.max(0) as usize;
+    push_preedit(data);
+    0
+}
+
+#[cfg(feature = "input")]
+unsafe extern "C" fn preedit_caret_callback(_ic: XIC, client_data: XPointer, call_data: XPointer) -> Bool {
+    let data = &mut *(client_data as *mut WindowImplData);
+    let call_data = &mut *(call_data as *mut XIMPreeditCaretCallbackStruct);
+    data.preedit_caret = match call_data.direction {
+        XIMAbsolutePosition => call_data.position.max(0) as usize,
+        XIMForwardChar => (data.preedit_caret + 1).min(data.preedit.len()),
+        XIMBackwardChar => data.preedit_caret.saturating_sub(1),
+        _ => data.preedit_caret,
+    };
+    call_data.position = data.preedit_caret as c_int;
+    push_preedit(data);
+    0
+}
+
+/// Looks up the text typed by a key press, composed by the input method if there is one.
+#[cfg(feature = "input")]
+unsafe fn lookup_text(data: &mut WindowImplData, event: &XEvent) {
+    let xlib = data.xlib;
+    let key = &event.key as *const XKeyEvent as *mut XKeyEvent;
+    let mut keysym: KeySym = 0;
+    let mut buffer = [0u8; 32];
+
+    let ic = active_ic(data);
+    if ic.is_null() {
+        // Without an input method there's no composing, and the text is only ever Latin-1
+        let len = xlib
+            .XLookupString(key, buffer.as_mut_ptr().cast(), buffer.len() as c_int, &mut keysym, ptr::null_mut())
+            .unwrap_or(0);
+        for &byte in &buffer[..len.max(0) as usize] {
+            data.push_event(Event::Character(char::from(byte)));
+        }
+        return
+    }
+
+    let mut status = 0;
+    let mut lookup = |buffer: &mut [u8], status: &mut c_int| {
+        let len = buffer.len() as c_int;
+        xlib.Xutf8LookupString(ic, key, buffer.as_mut_ptr().cast(), len, &mut keysym, status).unwrap_or(0)
+    };
+    let len = lookup(&mut buffer, &mut status);
+    let mut overflow = Vec::new();
+    let text = if status == XBufferOverflow {
+        // Committed text from an input method can be any length, and the length needed is returned
+        overflow.resize(len as usize, 0);
+        let len = lookup(&mut overflow, &mut status);
+        &overflow[..len.max(0) as usize]
+    } else {
+        &buffer[..len.max(0) as usize]
+    };
+    if status == XLookupChars || status == XLookupBoth {
+        let text = String::from_utf8_lossy(text);
+        if ic == data.ime_ic && event.key.keycode == 0 {
+            // The input method sends what it commits as key presses of its own, without a keycode
+            data.push_event_with_text(&text, |text| Event::Ime(ImeEvent::Commit(text)));
+        } else {
+            for character in text.chars() {
+                data.push_event(Event::Character(character));
+            }
+        }
+    }
+}
+
+/// Keeps track of which keys are held down, returning whether a press is a repeat,
+/// or `None` if the keycode isn't a key at all.
+///
+/// Input methods send the text they commit as key presses of their own with a keycode of 0,
+/// which no key has (they start at 8), so those are only there for their text.
+#[cfg(feature = "input")]
+fn track_key(key_states: &mut [u8; 32], keycode: KeyCode, pressed: bool) -> Option<bool> {
+    if keycode == 0 {
+        return None
+    }
+    let (byte, bit) = (keycode as usize / 8, 1 << (keycode % 8));
+    let is_repeat = pressed && key_states[byte] & bit != 0;
+    if pressed {
+        key_states[byte] |= bit;
+    } else {
+        key_states[byte] &= !bit;
+    }
+    Some(is_repeat)
+}
+
+/// Builds a cursor out of the user's cursor theme, falling back to the core cursor font.
+unsafe fn load_cursor(xlib: &X11DL, display: *mut Display, cursor: Cursor) -> ffi::Cursor {
+    let (name, glyph): (&[u8], c_uint) = match cursor {
+        Cursor::Arrow => (c_string!("left_ptr"), XC_left_ptr),
+        Cursor::Blank => return blank_cursor(xlib, display),
+        Cursor::Cross => (c_string!("crosshair"), XC_crosshair),
+        Cursor::Hand => (c_string!("hand2"), XC_hand2),
+        Cursor::Help => (c_string!("question_arrow"), XC_question_arrow),
+        Cursor::IBeam => (c_string!("xterm"), XC_xterm),
+        Cursor::Progress => (c_string!("left_ptr_watch"), XC_watch),
+        Cursor::ResizeNESW => (c_string!("fd_double_arrow"), XC_bottom_left_corner),
+        Cursor::ResizeNS => (c_string!("sb_v_double_arrow"), XC_sb_v_double_arrow),
+        Cursor::ResizeNWSE => (c_string!("bd_double_arrow"), XC_bottom_right_corner),
+        Cursor::ResizeWE => (c_string!("sb_h_double_arrow"), XC_sb_h_double_arrow),
+        Cursor::ResizeAll => (c_string!("fleur"), XC_fleur),
+        Cursor::Unavailable => (c_string!("crossed_circle"), XC_X_cursor),
+        Cursor::Wait => (c_string!("watch"), XC_watch),
+    };
+    match xlib.XcursorLibraryLoadCursor(display, name.as_ptr().cast()) {
+        Some(cursor) if cursor != 0 => cursor,
+        _ => xlib.XCreateFontCursor(display, glyph).unwrap_or(0),
+    }
+}
+
+/// X11 doesn't have an invisible cursor, so we make one out of a transparent 1x1 bitmap.
+unsafe fn blank_cursor(xlib: &X11DL, display: *mut Display) -> ffi::Cursor {
+    let screen = xlib.XDefaultScreen(display).unwrap_or(0);
+    let root = xlib.XRootWindow(display, screen).unwrap_or(0);
+    let data = [0 as c_char];
+    let pixmap = xlib.XCreateBitmapFromData(display, root, data.as_ptr(), 1, 1).unwrap_or(0);
+    let mut black: XColor = mem::zeroed();
+    let cursor = xlib
+        .XCreatePixmapCursor(display, pixmap, pixmap, &mut black, &mut black, 0, 0)
+        .unwrap_or(0);
+    let _ = xlib.XFreePixmap(display, pixmap);
+    cursor
+}
+
+/// Builds a cursor out of the image for the current scale, which needs libXcursor for the alpha channel.
+/// Without it, the arrow is used instead.
+unsafe fn load_custom_cursor(data: &WindowImplData, cursor: &CustomCursor) -> ffi::Cursor {
+    let xlib = data.xlib;
+    let cursor_image = cursor.image(data.current_scale);
+    let (width, height) = cursor_image.size;
+    let image = xlib.XcursorImageCreate(width as c_int, height as c_int).unwrap_or(ptr::null_mut());
+    if image.is_null() {
+        return load_cursor(xlib, data.display, Cursor::Arrow)
+    }
+    (*image).xhot = cursor_image.hotspot.0;
+    (*image).yhot = cursor_image.hotspot.1;
+    let pixels = std::slice::from_raw_parts_mut((*image).pixels, width as usize * height as usize);
+    for (pixel, rgba) in pixels.iter_mut().zip(cursor_image.rgba.chunks_exact(4)) {
+        let alpha = rgba[3] as c_uint;
+        let premultiply = |channel: u8| (channel as c_uint * alpha + 127) / 255;
+        *pixel = alpha << 24 | premultiply(rgba[0]) << 16 | premultiply(rgba[1]) << 8 | premultiply(rgba[2]);
+    }
+    let new_cursor = xlib.XcursorImageLoadCursor(data.display, image).unwrap_or(0);
+    let _ = xlib.XcursorImageDestroy(image);
+    new_cursor
+}
+
+/// Sets the cursor that's shown inside the window, freeing the previous one.
+unsafe fn set_cursor(data: &mut WindowImplData, cursor: &CursorIcon) {
+    let xlib = data.xlib;
+    let new_cursor = match cursor {
+        CursorIcon::System(cursor) => load_cursor(xlib, data.display, *cursor),
+        CursorIcon::Custom(cursor) => load_custom_cursor(data, cursor),
+    };
+    let _ = xlib.XDefineCursor(data.display, data.window, new_cursor);
+    if data.cursor != 0 {
+        let _ = xlib.XFreeCursor(data.display, data.cursor);
+    }
+    data.cursor = new_cursor;
+}
+
+/// Grabs the pointer while the window is focused and not minimized, otherwise ungrabs it.
+///
+/// Grabbing fails while someone else has the pointer (such as the window manager moving a window),
+/// so this is tried again whenever the pointer comes back.
+unsafe fn update_cursor_grab(data: &mut WindowImplData) {
+    let xlib = data.xlib;
+    let grab = data.cursor_grab != CursorGrab::None && data.is_focused && !data.is_minimized;
+    if grab && !data.is_cursor_grabbed {
+        // Events still go where they normally would, the grab is only used for confining
+        let mask = ButtonPressMask | ButtonReleaseMask | EnterWindowMask | LeaveWindowMask | PointerMotionMask;
+        let status = xlib.XGrabPointer(
+            data.display,
+            data.window,
+            True,
+            mask as c_uint,
+            GrabModeAsync,
+            GrabModeAsync,
+            data.window,
+            0,
+            CurrentTime,
+        );
+        data.is_cursor_grabbed = status == Some(GrabSuccess);
+        if data.is_cursor_grabbed && data.cursor_grab == CursorGrab::Locked {
+            warp_cursor(data, cursor_lock_position(data));
+        }
+    } else if !grab && data.is_cursor_grabbed {
+        let _ = xlib.XUngrabPointer(data.display, CurrentTime);
+        data.is_cursor_grabbed = false;
+    }
+    let _ = xlib.XFlush(data.display);
+}
+
+/// Where the pointer is held while locked, kept inside the window as it may have been resized since.
+fn cursor_lock_position(data: &WindowImplData) -> (i32, i32) {
+    let (width, height) = data.client_area_size;
+    let (x, y) = data.cursor_lock_position;
+    (x.min(width as i32 - 1).max(0), y.min(height as i32 - 1).max(0))
+}
+
+/// Moves the pointer to a position relative to the window.
+unsafe fn warp_cursor(data: &WindowImplData, (x, y): (i32, i32)) {
+    let _ = data.xlib.XWarpPointer(data.display, 0, data.window, 0, 0, 0, 0, x, y);
+}
+
+/// Sets `_NET_WM_ICON`, which holds every image so the window manager can pick the sizes it needs.
+unsafe fn set_icon(data: &mut WindowImplData, icon: Option<&Icon>) {
+    let xlib = data.xlib;
+    match icon {
+        Some(icon) => {
+            // Each image is its width and height followed by ARGB pixels, all as 32-bit values stored in longs
+            let mut property: Vec<c_ulong> = Vec::new();
+            for image in icon.images.iter() {
+                property.push(image.size.0 as c_ulong);
+                property.push(image.size.1 as c_ulong);
+                property.extend(image.rgba.chunks_exact(4).map(|p| {
+                    u32::from_be_bytes([p[3], p[0], p[1], p[2]]) as c_ulong
+                }));
+            }
+            let _ = xlib.XChangeProperty(
+                data.display,
+                data.window,
+                data.atoms._NET_WM_ICON,
+                XA_CARDINAL,
+                32,
+                PropModeReplace,
+                property.as_ptr().cast(),
+                property.len() as c_int,
+            );
+        },
+        None => {
+            let _ = xlib.XDeleteProperty(data.display, data.window, data.atoms._NET_WM_ICON);
+        },
+    }
+}
+
+/// Sets both the legacy and the EWMH (UTF-8) window title.
+///
+/// `WM_NAME` can't be UTF-8, so it's Latin-1 (`STRING`) if the title fits, otherwise `COMPOUND_TEXT`.
+unsafe fn set_title(data: &mut WindowImplData, title: &str) {
+    let xlib = data.xlib;
+    let _ = xlib.XChangeProperty(
+        data.display,
+        data.window,
+        data.atoms._NET_WM_NAME,
+        data.atoms.UTF8_STRING,
+        8,
+        PropModeReplace,
+        title.as_ptr(),
+        title.len() as c_int,
+    );
+
+    // It's a C string, so anything past a null wouldn't make it anyway
+    let title = CString::new(title.split('\0').next().unwrap_or_default()).unwrap_or_default();
+    let mut list = [title.as_ptr() as *mut c_char];
+    let mut property = mem::zeroed::<XTextProperty>();
+    // Characters with no equivalent are replaced, which is reported with a positive return value
+    let status = xlib.Xutf8TextListToTextProperty(data.display, list.as_mut_ptr(), 1, XStdICCTextStyle, &mut property);
+    if matches!(status, Some(status) if status >= Success) {
+        let _ = xlib.XSetTextProperty(data.display, data.window, &mut property, XA_WM_NAME);
+        let _ = xlib.XFree(property.value.cast());
+    }
+}
+
+/// Convenience function to take a `window::Style` and slap it on the window as hints.
+///
+/// Borders, controls and the ability to resize are all up to the window manager in X11,
+/// so all we can do is ask nicely via `_MOTIF_WM_HINTS` and `WM_NORMAL_HINTS`.
+unsafe fn update_window_style(data: &mut WindowImplData) {
+    let xlib = data.xlib;
+    let style = &data.style;
+
+    let mut functions = MWM_FUNC_MOVE;
+    let mut decorations = 0;
+    if !style.borderless {
+        decorations |= MWM_DECOR_BORDER | MWM_DECOR_TITLE;
+    }
+    if style.resizable {
+        functions |= MWM_FUNC_RESIZE;
+        decorations |= MWM_DECOR_RESIZEH;
+    }
+    if let Some(controls) = &style.controls {
+        decorations |= MWM_DECOR_MENU;
+        if controls.minimize {
+            functions |= MWM_FUNC_MINIMIZE;
+            decorations |= MWM_DECOR_MINIMIZE;
+        }
+        if controls.maximize {
+            functions |= MWM_FUNC_MAXIMIZE;
+            decorations |= MWM_DECOR_MAXIMIZE;
+        }
+        if controls.close {
+            functions |= MWM_FUNC_CLOSE;
+        }
+    }
+    if style.borderless {
+        // Any decoration bit at all makes most window managers draw a frame
+        decorations = 0;
+    }
+
+    let hints: [c_ulong; 5] = [MWM_HINTS_FUNCTIONS | MWM_HINTS_DECORATIONS, functions, decorations, 0, 0];
+    let _ = xlib.XChangeProperty(
+        data.display,
+        data.window,
+        data.atoms._MOTIF_WM_HINTS,
+        data.atoms._MOTIF_WM_HINTS,
+        32,
+        PropModeReplace,
+        hints.as_ptr().cast(),
+        hints.len() as c_int,
+    );
+
+    update_size_hints(data);
+}
+
+/// Non-resizable windows have their minimum and maximum size set to the current size.
+/// Window managers tend to ignore the initial position unless it's marked as user-specified.
+unsafe fn update_size_hints(data: &mut WindowImplData) {
+    let mut hints: XSizeHints = mem::zeroed();
+    if data.is_user_positioned {
+        let (x, y) = data.outer_position;
+        hints.flags = USPosition | PPosition;
+        hints.x = x;
+        hints.y = y;
+    }
+    if !data.style.resizable {
+        let (width, height) = data.client_area_size;
+        hints.flags |= PMinSize | PMaxSize;
+        hints.min_width = width as c_int;
+        hints.min_height = height as c_int;
+        hints.max_width = width as c_int;
+        hints.max_height = height as c_int;
+    }
+    let _ = data.xlib.XSetWMNormalHints(data.display, data.window, &mut hints);
+}
+
+/// Maps the window, with the initial `_NET_WM_STATE` the window manager should give it.
+unsafe fn map_window(data: &mut WindowImplData) {
+    let xlib = data.xlib;
+
+    // The window manager forgets the state of unmapped windows, and it's set by hand until mapped
+    if data.fullscreen.is_some() {
+        let state = [data.atoms._NET_WM_STATE_FULLSCREEN];
+        let _ = xlib.XChangeProperty(
+            data.display,
+            data.window,
+            data.atoms._NET_WM_STATE,
+            XA_ATOM,
+            32,
+            PropModeAppend,
+            state.as_ptr().cast(),
+            state.len() as c_int,
+        );
+    }
+    let _ = xlib.XMapWindow(data.display, data.window);
+
+    // Which monitor is a client message only, so it waits until the window manager knows about the window
+    if data.fullscreen.is_some() {
+        send_fullscreen_monitor(data);
+    }
+}
+
+/// Asks the window manager to add or remove up to two `_NET_WM_STATE` atoms (zero if unused).
+unsafe fn send_wm_state(data: &WindowImplData, add: bool, first: Atom, second: Atom) {
+    let action = if add { _NET_WM_STATE_ADD } else { _NET_WM_STATE_REMOVE };
+    // The last one's the source indication, which is a normal application
+    send_root_message(data, data.atoms._NET_WM_STATE, [action, first as c_long, second as c_long, 1, 0]);
+}
+
+/// Sends a client message about the window to the root window, which is how EWMH asks things of the window manager.
+unsafe fn send_root_message(data: &WindowImplData, message_type: Atom, message: [c_long; 5]) {
+    let xlib = data.xlib;
+    let screen = xlib.XDefaultScreen(data.display).unwrap_or(0);
+    let root = xlib.XRootWindow(data.display, screen).unwrap_or(0);
+    let mut event: XEvent = mem::zeroed();
+    event.client_message = XClientMessageEvent {
+        type_: ClientMessage,
+        serial: 0,
+        send_event: True,
+        display: data.display,
+        window: data.window,
+        message_type,
+        format: 32,
+        data: message,
+    };
+    let mask = SubstructureNotifyMask | SubstructureRedirectMask;
+    let _ = xlib.XSendEvent(data.display, root, False, mask, &mut event);
+}
+
+/// Asks the window manager to (un)maximize the window.
+unsafe fn set_maximized(data: &mut WindowImplData, maximized: bool) {
+    let (horz, vert) = (data.atoms._NET_WM_STATE_MAXIMIZED_HORZ, data.atoms._NET_WM_STATE_MAXIMIZED_VERT);
+    send_wm_state(data, maximized, horz, vert);
+}
+
+/// Asks the window manager to make the window fullscreen, or to restore it.
+///
+/// Restoring the previous placement is up to the window manager, which is what it does anyway.
+/// Exclusive fullscreen switches the video mode of the monitor with RandR, which is switched back on leaving.
+unsafe fn set_fullscreen(data: &mut WindowImplData, fullscreen: Option<window::Fullscreen>) {
+    data.fullscreen = set_video_mode(data, fullscreen);
+    if data.style.visible {
+        if data.fullscreen.is_some() {
+            send_fullscreen_monitor(data);
+        }
+        send_wm_state(data, data.fullscreen.is_some(), data.atoms._NET_WM_STATE_FULLSCREEN, 0);
+    }
+}
+
+/// Switches the video mode of the monitor for exclusive fullscreen, or back if it was switched before.
+///
+/// This gives back the fullscreen mode that the window ended up with, which is borderless if that failed.
+unsafe fn set_video_mode(
+    data: &mut WindowImplData,
+    fullscreen: Option<window::Fullscreen>,
+) -> Option<window::Fullscreen> {
+    // Put the monitor back the way it was, even if just switching to another video mode
+    restore_crtc(data);
+
+    match fullscreen {
+        Some(window::Fullscreen::Exclusive(monitor, mode)) => {
+            data.saved_crtc = find_monitor(data, &monitor).and_then(|(_, output)| set_crtc_mode(data, output, mode));
+            if data.saved_crtc.is_some() {
+                Some(window::Fullscreen::Exclusive(monitor, mode))
+            } else {
+                Some(window::Fullscreen::Borderless(Some(monitor)))
+            }
+        },
+        fullscreen => fullscreen,
+    }
+}
+
+/// Asks the window manager to cover the monitor that fullscreen was asked for on, if it was.
+unsafe fn send_fullscreen_monitor(data: &WindowImplData) {
+    let monitor = data.fullscreen.as_ref().and_then(window::Fullscreen::monitor);
+    if let Some((index, _)) = monitor.and_then(|monitor| find_monitor(data, monitor)) {
+        // Top, bottom, left & right edges, which are all the same monitor, then the source indication
+        send_root_message(data, data.atoms._NET_WM_FULLSCREEN_MONITORS, [index, index, index, index, 1]);
+    }
+}
+
+/// Finds a monitor by its name, which RandR keeps unique, giving its first output and its index.
+/// The index is the same as with Xinerama, which is what EWMH goes by.
+unsafe fn find_monitor(data: &WindowImplData, monitor: &Monitor) -> Option<(c_long, RROutput)> {
+    let xlib = data.xlib;
+    let screen = xlib.XDefaultScreen(data.display).unwrap_or(0);
+    let root = xlib.XRootWindow(data.display, screen).unwrap_or(0);
+    let mut count: c_int = 0;
+    let infos = xlib.XRRGetMonitors(data.display, root, True, &mut count).unwrap_or(ptr::null_mut());
+    if infos.is_null() {
+        return None
+    }
+    let found = std::slice::from_raw_parts(infos, count.max(0) as usize)
+        .iter()
+        .enumerate()
+        .find(|(_, info)| info.noutput > 0 && atom_name(xlib, data.display, info.name) == monitor.name)
+        .map(|(index, info)| (index as c_long, *info.outputs));
+    let _ = xlib.XRRFreeMonitors(infos);
+    found
+}
+
+/// The configuration of a RandR CRTC from before exclusive fullscreen changed it.
+struct SavedCrtc {
+    crtc: RRCrtc,
+    position: (c_int, c_int),
+    mode: RRMode,
+    rotation: Rotation,
+    outputs: Vec<RROutput>,
+}
+
+/// Switches the CRTC driving an output to a video mode, returning how it was set up if that worked.
+unsafe fn set_crtc_mode(data: &WindowImplData, output: RROutput, mode: VideoMode) -> Option<SavedCrtc> {
+    let xlib = data.xlib;
+    let screen = xlib.XDefaultScreen(data.display).unwrap_or(0);
+    let root = xlib.XRootWindow(data.display, screen).unwrap_or(0);
+    let resources = xlib.XRRGetScreenResourcesCurrent(data.display, root).unwrap_or(ptr::null_mut());
+    if resources.is_null() {
+        return None
+    }
+    let info = xlib.XRRGetOutputInfo(data.display, resources, output).unwrap_or(ptr::null_mut());
+    let crtc_info = if !info.is_null() && (*info).crtc != 0 {
+        xlib.XRRGetCrtcInfo(data.display, resources, (*info).crtc).unwrap_or(ptr::null_mut())
+    } else {
+        ptr::null_mut()
+    };
+
+    // The video mode came from the output's modes to begin with, so it's looked for there
+    let mut saved = None;
+    if !crtc_info.is_null() {
+        let all_modes = std::slice::from_raw_parts((*resources).modes, (*resources).nmode.max(0) as usize);
+        let id = std::slice::from_raw_parts((*info).modes, (*info).nmode.max(0) as usize)
+            .iter()
+            .copied()
+            .find(|&id| {
+                all_modes.iter().any(|other| {
+                    let size = (other.width, other.height);
+                    other.id == id && size == mode.size && mode_refresh_rate(other) == mode.refresh_rate
+                })
+            });
+        let crtc = &*crtc_info;
+        if let Some(id) = id {
+            // Changing the mode also drops any errors, such as the screen being too small for it
+            LAST_ERROR.with(|e| e.set(0));
+            let status = xlib.XRRSetCrtcConfig(
+                data.display,
+                resources,
+                (*info).crtc,
+                CurrentTime,
+                crtc.x,
+                crtc.y,
+                id,
+                crtc.rotation,
+                crtc.outputs,
+                crtc.noutput,
+            );
+            let _ = xlib.XSync(data.display, False);
+            if status == Some(RRSetConfigSuccess) && LAST_ERROR.with(Cell::get) == 0 {
+                saved = Some(SavedCrtc {
+                    crtc: (*info).crtc,
+                    position: (crtc.x, crtc.y),
+                    mode: crtc.mode,
+                    rotation: crtc.rotation,
+                    outputs: std::slice::from_raw_parts(crtc.outputs, crtc.noutput.max(0) as usize).to_vec(),
+                });
+            }
+        }
+        let _ = xlib.XRRFreeCrtcInfo(crtc_info);
+    }
+    if !info.is_null() {
+        let _ = xlib.XRRFreeOutputInfo(info);
+    }
+    let _ = xlib.XRRFreeScreenResources(resources);
+    saved
+}
+
+/// Puts a monitor back the way it was before exclusive fullscreen, if its video mode was changed.
+unsafe fn restore_crtc(data: &mut WindowImplData) {
+    let mut saved = match data.saved_crtc.take() {
+        Some(saved) => saved,
+        None => return,
+    };
+    let xlib = data.xlib;
+    let screen = xlib.XDefaultScreen(data.display).unwrap_or(0);
+    let root = xlib.XRootWindow(data.display, screen).unwrap_or(0);
+    let resources = xlib.XRRGetScreenResourcesCurrent(data.display, root).unwrap_or(ptr::null_mut());
+    if resources.is_null() {
+        return
+    }
+    let _ = xlib.XRRSetCrtcConfig(
+        data.display,
+        resources,
+        saved.crtc,
+        CurrentTime,
+        saved.position.0,
+        saved.position.1,
+        saved.mode,
+        saved.rotation,
+        saved.outputs.as_mut_ptr(),
+        saved.outputs.len() as c_int,
+    );
+    let _ = xlib.XRRFreeScreenResources(resources);
+}
+
+/// Reads `Xft.dpi` again after the resources changed, yielding `ScaleChanged` if the scale did.
+///
+/// Like with `WM_DPICHANGED` on Win32, logical windows are resized to keep their logical size,
+/// unless they're maximized or fullscreen, where the size is up to the window manager.
+unsafe fn update_scale(data: &mut WindowImplData) {
+    let scale = xft_dpi(data.xlib, data.display).unwrap_or(BASE_DPI) / BASE_DPI;
+    if data.current_scale == scale {
+        return
+    }
+    let old_scale = mem::replace(&mut data.current_scale, scale);
+    data.push_event(Event::ScaleChanged(scale));
+
+    if data.is_dpi_logical && data.fullscreen.is_none() && !data.is_maximized {
+        let (width, height) = data.client_area_size;
+        let (width, height) = Size::Physical(width, height).to_logical(old_scale).as_physical(scale);
+        data.client_area_size = (width, height);
+        update_size_hints(data);
+        let _ = data.xlib.XResizeWindow(data.display, data.window, width.max(1), height.max(1));
+        let event = Event::Resize((data.scale_size(Size::Physical(width, height)), scale));
+        data.push_event(event);
+    }
+}
+
+/// Reads `_NET_WM_STATE` after it was changed, yielding the state change events if needed.
+unsafe fn update_wm_state(data: &mut WindowImplData) {
+    let xlib = data.xlib;
+    let mut actual_type: Atom = 0;
+    let mut actual_format: c_int = 0;
+    let mut count: c_ulong = 0;
+    let mut bytes_after: c_ulong = 0;
+    let mut atoms: *mut c_uchar = ptr::null_mut();
+    let status = xlib.XGetWindowProperty(
+        data.display,
+        data.window,
+        data.atoms._NET_WM_STATE,
+        0,
+        1024,
+        False,
+        XA_ATOM,
+        &mut actual_type,
+        &mut actual_format,
+        &mut count,
+        &mut bytes_after,
+        &mut atoms,
+    );
+    let state: &[Atom] = if status == Some(Success) && !atoms.is_null() && actual_format == 32 {
+        // Format 32 properties are arrays of `long`, regardless of pointer width
+        std::slice::from_raw_parts(atoms.cast(), count as usize)
+    } else {
+        &[]
+    };
+
+    let has_horz = state.contains(&data.atoms._NET_WM_STATE_MAXIMIZED_HORZ);
+    let has_vert = state.contains(&data.atoms._NET_WM_STATE_MAXIMIZED_VERT);
+    let maximized = has_horz && has_vert;
+    let minimized = state.contains(&data.atoms._NET_WM_STATE_HIDDEN);
+    let fullscreen = state.contains(&data.atoms._NET_WM_STATE_FULLSCREEN);
+    if !atoms.is_null() {
+        let _ = xlib.XFree(atoms.cast());
+    }
+
+    if data.is_fullscreen != fullscreen {
+        data.is_fullscreen = fullscreen;
+        data.push_event(Event::Fullscreen(fullscreen));
+    }
+    if data.is_maximized != maximized {
+        data.is_maximized = maximized;
+        data.push_event(Event::Maximize(maximized));
+    }
+    if data.is_minimized != minimized {
+        data.is_minimized = minimized;
+        data.push_event(Event::Minimize(minimized));
+        update_cursor_grab(data);
+    }
+}
+
+/// Reads all of a window property, returning its type, format (8, 16 or 32) and data.
+///
+/// Format 32 properties are arrays of `long`, regardless of pointer width, which is the data here too.
+unsafe fn read_property(
+    data: &WindowImplData,
+    window: ffi::Window,
+    property: Atom,
+    delete: bool,
+) -> (Atom, c_int, Vec<u8>) {
+    let xlib = data.xlib;
+    let mut actual_type: Atom = 0;
+    let mut actual_format: c_int = 0;
+    let mut count: c_ulong = 0;
+    let mut bytes_after: c_ulong = 0;
+    let mut values: *mut c_uchar = ptr::null_mut();
+    let status = xlib.XGetWindowProperty(
+        data.display,
+        window,
+        property,
+        0,
+        c_long::MAX / 4, // the length is in 32-bit units
+        if delete { True } else { False },
+        AnyPropertyType,
+        &mut actual_type,
+        &mut actual_format,
+        &mut count,
+        &mut bytes_after,
+        &mut values,
+    );
+    let mut bytes = Vec::new();
+    if status == Some(Success) && !values.is_null() {
+        let item_size = match actual_format {
+            8 => 1,
+            16 => mem::size_of::<c_short>(),
+            32 => mem::size_of::<c_long>(),
+            _ => 0,
+        };
+        bytes.extend_from_slice(std::slice::from_raw_parts(values, count as usize * item_size));
+        let _ = xlib.XFree(values.cast());
+    }
+    (actual_type, actual_format, bytes)
+}
+
+/// Sends an XDND message to the window that files are being dragged from, which starts with our window.
+unsafe fn send_xdnd(data: &WindowImplData, source: ffi::Window, message_type: Atom, body: [c_long; 4]) {
+    let mut event: XEvent = mem::zeroed();
+    event.client_message = XClientMessageEvent {
+        type_: ClientMessage,
+        serial: 0,
+        send_event: True,
+        display: data.display,
+        window: source,
+        message_type,
+        format: 32,
+        data: [data.window as c_long, body[0], body[1], body[2], body[3]],
+    };
+    let _ = data.xlib.XSendEvent(data.display, source, False, NoEventMask, &mut event);
+}
+
+/// Handles the messages of a drag & drop, as the target of the XDND protocol.
+///
+/// The files aren't known until `XdndSelection` is converted, which is asked for at the first position,
+/// so `Event::DragEnter` is only sent once they arrive (see `xdnd_selection`).
+unsafe fn xdnd_message(data: &mut WindowImplData, message: &XClientMessageEvent) {
+    let source = message.data[0] as ffi::Window;
+    if message.message_type == data.atoms.XdndEnter {
+        // Up to three types fit in the message, and if there's more, they're all in `XdndTypeList`
+        let types: Vec<Atom> = if message.data[1] & 1 != 0 {
+            let (_, _, list) = read_property(data, source, data.atoms.XdndTypeList, false);
+            let size = mem::size_of::<c_long>();
+            list.chunks_exact(size).map(|atom| atom.as_ptr().cast::<c_long>().read_unaligned() as Atom).collect()
+        } else {
+            message.data[2..].iter().map(|&atom| atom as Atom).collect()
+        };
+        data.drag = Some(Drag {
+            source,
+            position: Point::Physical(0, 0),
+            has_files: types.contains(&data.atoms.text_uri_list),
+            paths: None,
+            is_converting: false,
+            is_dropped: false,
+        });
+        return
+    }
+
+    // The rest of the messages are only for the drag that entered
+    let mut drag = match data.drag.take() {
+        Some(drag) if drag.source == source => drag,
+        drag => {
+            data.drag = drag;
+            return
+        },
+    };
+    let xlib = data.xlib;
+    if message.message_type == data.atoms.XdndPosition {
+        // The position is relative to the root window, packed in as `x << 16 | y`
+        let (root_x, root_y) = ((message.data[2] >> 16) as c_int & 0xFFFF, message.data[2] as c_int & 0xFFFF);
+        let screen = xlib.XDefaultScreen(data.display).unwrap_or(0);
+        let root = xlib.XRootWindow(data.display, screen).unwrap_or(0);
+        let (mut x, mut y, mut child) = (0, 0, 0);
+        let _ = xlib.XTranslateCoordinates(data.display, root, data.window, root_x, root_y, &mut x, &mut y, &mut child);
+        drag.position = data.scale_point(Point::Physical(x, y));
+
+        if drag.paths.is_some() {
+            data.push_event(Event::DragOver((drag.position, data.current_scale)));
+        } else if drag.has_files && !drag.is_converting {
+            let (selection, target) = (data.atoms.XdndSelection, data.atoms.text_uri_list);
+            let time = message.data[3] as Time;
+            let _ = xlib.XConvertSelection(data.display, selection, target, selection, data.window, time);
+            drag.is_converting = true;
+        }
+
+        // The empty rectangle asks for every position, and copying is the only action we do
+        let action = if drag.has_files { data.atoms.XdndActionCopy as c_long } else { 0 };
+        send_xdnd(data, source, data.atoms.XdndStatus, [drag.has_files as c_long, 0, 0, action]);
+        data.drag = Some(drag);
+    } else if message.message_type == data.atoms.XdndLeave {
+        if drag.paths.is_some() {
+            data.push_event(Event::DragLeave);
+        }
+    } else if message.message_type == data.atoms.XdndDrop {
+        if drag.is_converting {
+            drag.is_dropped = true;
+            data.drag = Some(drag);
+        } else {
+            finish_drop(data, drag);
+        }
+    } else {
+        data.drag = Some(drag);
+    }
+}
+
+/// Handles the dragged files having been converted from `XdndSelection`, finishing the drop if it happened already.
+unsafe fn xdnd_selection(data: &mut WindowImplData, event: &XSelectionEvent) {
+    let mut drag = match data.drag.take() {
+        Some(drag) if drag.is_converting => drag,
+        drag => {
+            data.drag = drag;
+            return
+        },
+    };
+    drag.is_converting = false;
+    let paths = match event.property {
+        0 => Vec::new(), // the source couldn't convert it after all
+        property => unix::parse_uri_list(&read_property(data, data.window, property, true).2),
+    };
+    if paths.is_empty() {
+        drag.has_files = false;
+    } else {
+        let (position, scale) = (drag.position, data.current_scale);
+        data.push_event_with_paths(&paths, |paths| Event::DragEnter((paths, position, scale)));
+        drag.paths = Some(paths);
+    }
+    if drag.is_dropped {
+        finish_drop(data, drag);
+    } else {
+        data.drag = Some(drag);
+    }
+}
+
+/// Reports the files of a drag as dropped (if there were any) and tells its source that we're done with it.
+unsafe fn finish_drop(data: &mut WindowImplData, drag: Drag) {
+    let accepted = drag.paths.is_some();
+    if let Some(paths) = drag.paths {
+        let (position, scale) = (drag.position, data.current_scale);
+        data.push_event_with_paths(&paths, |paths| Event::Drop((paths, position, scale)));
+    }
+    let action = if accepted { data.atoms.XdndActionCopy as c_long } else { 0 };
+    send_xdnd(data, drag.source, data.atoms.XdndFinished, [accepted as c_long, action, 0, 0]);
+}
+
+/// Asks XFixes to tell us whenever the clipboard changes owner, returning the event type it's reported as.
+unsafe fn select_clipboard_changes(data: &WindowImplData) -> Option<c_int> {
+    let xlib = data.xlib;
+    let (mut event_base, mut error_base) = (0, 0);
+    if xlib.XFixesQueryExtension(data.display, &mut event_base, &mut error_base)? != True {
+        return None
+    }
+    let mask = XFixesSetSelectionOwnerNotifyMask
+        | XFixesSelectionWindowDestroyNotifyMask
+        | XFixesSelectionClientCloseNotifyMask;
+    xlib.XFixesSelectSelectionInput(data.display, data.window, data.atoms.CLIPBOARD, mask)?;
+    Some(event_base + XFixesSelectionNotify)
+}
+
+/// Gets the targets that clipboard data in a format is exchanged as, the first one being preferred.
+unsafe fn clipboard_targets(data: &WindowImplData, format: &ClipboardFormat) -> Vec<Atom> {
+    match format {
+        ClipboardFormat::Text => vec![data.atoms.UTF8_STRING, data.atoms.text_plain_utf8],
+        ClipboardFormat::Image => vec![data.atoms.image_bmp],
+        ClipboardFormat::Custom(name) => match CString::new(name.as_str()) {
+            Ok(name) => vec![data.xlib.XInternAtom(data.display, name.as_ptr(), False).unwrap_or(0)],
+            Err(_) => Vec::new(),
+        },
+    }
+}
+
+/// Gets the formats the clipboard is in, from the targets its owner can convert it to.
+unsafe fn clipboard_formats(data: &mut WindowImplData) -> Vec<ClipboardFormat> {
+    if let Some(contents) = &data.clipboard {
+        return contents.formats()
+    }
+    let targets = convert_clipboard(data, data.atoms.TARGETS).unwrap_or_default();
+    let meta_targets = [data.atoms.MULTIPLE, data.atoms.SAVE_TARGETS, data.atoms.TARGETS, data.atoms.TIMESTAMP];
+    let mut formats = Vec::new();
+    for target in targets.chunks_exact(mem::size_of::<c_long>()) {
+        let target = target.as_ptr().cast::<c_long>().read_unaligned() as Atom;
+        let format = if target == data.atoms.UTF8_STRING || target == data.atoms.text_plain_utf8 {
+            ClipboardFormat::Text
+        } else if target == data.atoms.image_bmp {
+            ClipboardFormat::Image
+        } else if target == 0 || meta_targets.contains(&target) {
+            continue
+        } else {
+            ClipboardFormat::Custom(atom_name(data.xlib, data.display, target))
+        };
+        if !formats.contains(&format) {
+            formats.push(format);
+        }
+    }
+    formats
+}
+
+/// Gets the clipboard data in a format, trying each target it could be in.
+unsafe fn clipboard_data(data: &mut WindowImplData, format: &ClipboardFormat) -> Option<Vec<u8>> {
+    if let Some(contents) = &data.clipboard {
+        return contents.get(format).map(<[u8]>::to_vec)
+    }
+    clipboard_targets(data, format).into_iter().find_map(|target| convert_clipboard(data, target))
+}
+
+/// Converts the clipboard to a target, waiting for its owner to send it over, which is done in chunks if it's big.
+unsafe fn convert_clipboard(data: &mut WindowImplData, target: Atom) -> Option<Vec<u8>> {
+    let xlib = data.xlib;
+    let (selection, property) = (data.atoms.CLIPBOARD, data.atoms.RAMEN_CLIPBOARD);
+    if target == 0 || xlib.XGetSelectionOwner(data.display, selection)? == 0 {
+        return None
+    }
+    let _ = xlib.XDeleteProperty(data.display, data.window, property);
+    let _ = xlib.XConvertSelection(data.display, selection, target, property, data.window, CurrentTime);
+    let notify = wait_for_clipboard(data, SelectionNotify)?;
+    if notify.selection.property == 0 {
+        return None // the owner couldn't convert it
+    }
+    let (type_, _, mut bytes) = read_property(data, data.window, property, true);
+    if type_ != data.atoms.INCR {
+        return Some(bytes)
+    }
+
+    // Deleting the `INCR` property asks for the first chunk, and each chunk is deleted to ask for the next,
+    // until an empty one marks the end
+    bytes.clear();
+    loop {
+        let _ = wait_for_clipboard(data, PropertyNotify)?;
+        let (_, _, chunk) = read_property(data, data.window, property, true);
+        if chunk.is_empty() {
+            break Some(bytes)
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+}
+
+/// Waits for the clipboard owner to reply to a conversion, with either a `SelectionNotify`,
+/// or a `PropertyNotify` with a chunk of the data, giving up after `CLIPBOARD_TIMEOUT`.
+///
+/// Selection requests (and the chunks of our own `INCR` transfers) are still answered meanwhile,
+/// as the owner might be waiting on us too, such as when it's another window in this process.
+unsafe fn wait_for_clipboard(data: &mut WindowImplData, type_: c_int) -> Option<XEvent> {
+    unsafe extern "C" fn predicate(_display: *mut Display, event: *mut XEvent, arg: XPointer) -> Bool {
+        let (type_, window, selection, property) = *arg.cast::<(c_int, ffi::Window, Atom, Atom)>();
+        let event = &*event;
+        let matches = match event.type_ {
+            SelectionClear | SelectionRequest => true,
+            SelectionNotify => {
+                type_ == SelectionNotify && event.selection.requestor == window && event.selection.selection == selection
+            },
+            PropertyNotify if event.property.window != window => event.property.state == PropertyDelete,
+            PropertyNotify => {
+                let property_event = &event.property;
+                type_ == PropertyNotify
+                    && property_event.window == window
+                    && property_event.atom == property
+                    && property_event.state == PropertyNewValue
+            },
+            _ => false,
+        };
+        matches as Bool
+    }
+
+    let xlib = data.xlib;
+    let mut arg = (type_, data.window, data.atoms.CLIPBOARD, data.atoms.RAMEN_CLIPBOARD);
+    let mut fd = pollfd { fd: xlib.XConnectionNumber(data.display).unwrap_or(-1), events: POLLIN, revents: 0 };
+    let mut event = mem::MaybeUninit::<XEvent>::zeroed();
+    let deadline = Instant::now() + CLIPBOARD_TIMEOUT;
+    loop {
+        let arg_ptr = (&mut arg as *mut (c_int, ffi::Window, Atom, Atom)).cast();
+        while xlib.XCheckIfEvent(data.display, event.as_mut_ptr(), predicate, arg_ptr) == Some(True) {
+            let event = &*event.as_ptr();
+            match event.type_ {
+                SelectionClear | SelectionRequest => process_event(data, event),
+                PropertyNotify if event.property.window != data.window => process_event(data, event),
+                _ => return Some(*event),
+            }
+        }
+        let timeout = deadline.saturating_duration_since(Instant::now());
+        if timeout.is_zero() {
+            return None
+        }
+        let _ = poll(&mut fd, 1, timeout.as_millis().max(1) as c_int);
+    }
+}
+
+/// Gets the current server time, for when there's no event to take it from.
+///
+/// Changing a property is the usual way to get it, as the `PropertyNotify` that follows has the time.
+unsafe fn server_time(data: &mut WindowImplData) -> Option<Time> {
+    let (display, window, property) = (data.display, data.window, data.atoms.RAMEN_CLIPBOARD);
+    let _ = data.xlib.XChangeProperty(display, window, property, XA_STRING, 8, PropModeReplace, ptr::null(), 0);
+    let event = wait_for_clipboard(data, PropertyNotify)?;
+    Some(event.property.time)
+}
+
+/// Takes ownership of the clipboard with new contents, which are sent to whoever asks for them from now on.
+unsafe fn set_clipboard(data: &mut WindowImplData, contents: &ClipboardContents) -> bool {
+    let xlib = data.xlib;
+    // The ICCCM doesn't allow taking it with `CurrentTime`, as it'd be ambiguous which client took it last
+    let time = match server_time(data) {
+        Some(time) => time,
+        None => return false,
+    };
+    let _ = xlib.XSetSelectionOwner(data.display, data.atoms.CLIPBOARD, data.window, time);
+    if xlib.XGetSelectionOwner(data.display, data.atoms.CLIPBOARD) != Some(data.window) {
+        return false
+    }
+    data.clipboard = Some(contents.clone());
+    data.clipboard_time = time;
+
+    // XFixes tells every client about the change, including us, otherwise it's only us that knows
+    if data.xfixes_selection_event.is_none() {
+        data.push_event(Event::ClipboardChanged);
+    }
+    true
+}
+
+/// Answers another client asking for the contents of the clipboard, which we have while we own it.
+unsafe fn answer_selection_request(data: &mut WindowImplData, request: &XSelectionRequestEvent) {
+    // Obsolete clients don't say what property they want the data in, which means the target's name
+    let property = if request.property == 0 { request.target } else { request.property };
+    let converted = request.selection == data.atoms.CLIPBOARD
+        && convert_contents(data, request.requestor, request.target, property);
+    let mut event: XEvent = mem::zeroed();
+    event.selection = XSelectionEvent {
+        type_: SelectionNotify,
+        serial: 0,
+        send_event: True,
+        display: data.display,
+        requestor: request.requestor,
+        selection: request.selection,
+        target: request.target,
+        property: if converted { property } else { 0 },
+        time: request.time,
+    };
+    let _ = data.xlib.XSendEvent(data.display, request.requestor, False, NoEventMask, &mut event);
+    let _ = data.xlib.XFlush(data.display);
+}
+
+/// Puts the clipboard contents as a target into the property of a requestor, returning whether it could.
+///
+/// Data too big to fit in a single request is sent in chunks with `INCR`, which starts here.
+unsafe fn convert_contents(data: &mut WindowImplData, requestor: ffi::Window, target: Atom, property: Atom) -> bool {
+    let xlib = data.xlib;
+    let contents = match &data.clipboard {
+        Some(contents) => contents,
+        None => return false,
+    };
+    if target == data.atoms.TARGETS {
+        let mut targets = vec![data.atoms.TARGETS, data.atoms.MULTIPLE, data.atoms.TIMESTAMP];
+        for (format, _) in &contents.data {
+            targets.extend(clipboard_targets(data, format));
+        }
+        let _ = xlib.XChangeProperty(
+            data.display,
+            requestor,
+            property,
+            XA_ATOM,
+            32,
+            PropModeReplace,
+            targets.as_ptr().cast(),
+            targets.len() as c_int,
+        );
+        return true
+    }
+    if target == data.atoms.TIMESTAMP {
+        let time = data.clipboard_time as c_long;
+        let time = (&time as *const c_long).cast();
+        let _ = xlib.XChangeProperty(data.display, requestor, property, XA_INTEGER, 32, PropModeReplace, time, 1);
+        return true
+    }
+    if target == data.atoms.MULTIPLE {
+        return convert_multiple(data, requestor, property)
+    }
+
+    let bytes = match contents.data.iter().find(|(format, _)| clipboard_targets(data, format).contains(&target)) {
+        Some((_, bytes)) => bytes,
+        None => return false,
+    };
+
+    // The requestor deletes the `INCR` property once it's ready for the first chunk,
+    // which is when its `PropertyNotify` is needed, so it's selected beforehand
+    let max_chunk_size = max_chunk_size(data);
+    if bytes.len() > max_chunk_size {
+        let _ = xlib.XSelectInput(data.display, requestor, PropertyChangeMask);
+        let size = bytes.len().min(c_long::MAX as usize) as c_long;
+        let _ = xlib.XChangeProperty(
+            data.display,
+            requestor,
+            property,
+            data.atoms.INCR,
+            32,
+            PropModeReplace,
+            (&size as *const c_long).cast(),
+            1,
+        );
+        let transfer = IncrTransfer {
+            requestor,
+            property,
+            target,
+            bytes: bytes.clone(),
+            sent: 0,
+            deadline: Instant::now() + CLIPBOARD_TIMEOUT,
+        };
+        data.incr_transfers.push(transfer);
+        return true
+    }
+    let _ = xlib.XChangeProperty(
+        data.display,
+        requestor,
+        property,
+        target,
+        8,
+        PropModeReplace,
+        bytes.as_ptr(),
+        bytes.len() as c_int,
+    );
+    true
+}
+
+/// Answers a `MULTIPLE` request, which lists pairs of targets & properties to convert to in its property.
+///
+/// The list is written back with the targets which couldn't be converted replaced by `None`.
+unsafe fn convert_multiple(data: &mut WindowImplData, requestor: ffi::Window, property: Atom) -> bool {
+    let (type_, format, bytes) = read_property(data, requestor, property, false);
+    if format != 32 {
+        return false
+    }
+    let mut pairs = bytes
+        .chunks_exact(mem::size_of::<c_long>())
+        .map(|atom| atom.as_ptr().cast::<c_long>().read_unaligned() as Atom)
+        .collect::<Vec<_>>();
+    for pair in pairs.chunks_exact_mut(2) {
+        let (target, pair_property) = (pair[0], pair[1]);
+        if target == data.atoms.MULTIPLE
+            || pair_property == 0
+            || !convert_contents(data, requestor, target, pair_property)
+        {
+            pair[0] = 0;
+        }
+    }
+    let _ = data.xlib.XChangeProperty(
+        data.display,
+        requestor,
+        property,
+        type_,
+        32,
+        PropModeReplace,
+        pairs.as_ptr().cast(),
+        pairs.len() as c_int,
+    );
+    true
+}
+
+/// Gets how many bytes of clipboard data can be put in a property at once.
+unsafe fn max_chunk_size(data: &WindowImplData) -> usize {
+    // Request sizes are in 4-byte units, and the rest of the request takes up a bit of it
+    let xlib = data.xlib;
+    let max_request_size = match xlib.XExtendedMaxRequestSize(data.display) {
+        Some(0) | None => xlib.XMaxRequestSize(data.display).unwrap_or(0),
+        Some(size) => size,
+    };
+    (max_request_size.max(0) as usize * 4).saturating_sub(32)
+}
+
+/// Sends the next chunk of an `INCR` transfer, now that the requestor has deleted the last one.
+///
+/// An empty chunk marks the end, after which the requestor's property changes aren't needed anymore.
+unsafe fn send_incr_chunk(data: &mut WindowImplData, event: &XPropertyEvent) {
+    let now = Instant::now();
+    data.incr_transfers.retain(|transfer| transfer.deadline > now);
+    let index = match data
+        .incr_transfers
+        .iter()
+        .position(|transfer| transfer.requestor == event.window && transfer.property == event.atom)
+    {
+        Some(index) => index,
+        None => return,
+    };
+
+    let xlib = data.xlib;
+    let max_chunk_size = max_chunk_size(data);
+    let transfer = &mut data.incr_transfers[index];
+    let chunk = &transfer.bytes[transfer.sent..];
+    let chunk = &chunk[..chunk.len().min(max_chunk_size)];
+    let _ = xlib.XChangeProperty(
+        data.display,
+        transfer.requestor,
+        transfer.property,
+        transfer.target,
+        8,
+        PropModeReplace,
+        chunk.as_ptr(),
+        chunk.len() as c_int,
+    );
+    transfer.sent += chunk.len();
+    transfer.deadline = now + CLIPBOARD_TIMEOUT;
+    if chunk.is_empty() {
+        let requestor = data.incr_transfers.swap_remove(index).requestor;
+        if !data.incr_transfers.iter().any(|transfer| transfer.requestor == requestor) {
+            let _ = xlib.XSelectInput(data.display, requestor, NoEventMask);
+        }
+    }
+    let _ = xlib.XFlush(data.display);
+}
+
+/// The image that `Window::present_pixels` copies into, at the size of the client area.
+///
+/// It's kept around so that exposed parts of the window can be redrawn, as the server doesn't keep them.
+struct Frame {
+    xlib: &'static X11DL,
+    display: *mut Display,
+    gc: GC,
+    image: *mut XImage,
+    /// The shared memory the image lives in, with MIT-SHM. It's boxed, as the image points to it.
+    shm: Option<Box<XShmSegmentInfo>>,
+    /// Where the pixels live otherwise, as the image only borrows them.
+    pixels: Vec<u32>,
+    size: (u32, u32),
+}
+
+/// The byte order of our pixels, which images are in.
+const NATIVE_BYTE_ORDER: c_int = if cfg!(target_endian = "little") { LSBFirst } else { MSBFirst };
+
+impl Frame {
+    unsafe fn new(xlib: &'static X11DL, display: *mut Display, (width, height): (u32, u32)) -> Result<Self, Error> {
+        let screen = xlib.XDefaultScreen(display).unwrap_or(0);
+        let visual = xlib.XDefaultVisual(display, screen).unwrap_or(ptr::null_mut());
+        let depth = xlib.XDefaultDepth(display, screen).unwrap_or(0) as c_uint;
+        let mut frame = Self {
+            xlib,
+            display,
+            gc: xlib.XDefaultGC(display, screen).unwrap_or(ptr::null_mut()),
+            image: ptr::null_mut(),
+            shm: None,
+            pixels: Vec::new(),
+            size: (width, height),
+        };
+        if let Some((image, shm)) = create_shm_image(xlib, display, visual, depth, (width, height)) {
+            frame.image = image;
+            frame.shm = Some(shm);
+        } else {
+            let image = xlib
+                .XCreateImage(display, visual, depth, ZPixmap, 0, ptr::null_mut(), width, height, 32, 0)
+                .unwrap_or(ptr::null_mut());
+            if image.is_null() {
+                return Err(Error::Unsupported("images of the default visual"))
+            }
+            frame.image = image;
+            frame.pixels = vec![0; width as usize * height as usize];
+            (*image).data = frame.pixels.as_mut_ptr().cast();
+
+            // Xlib swaps the bytes around if the server's order is different
+            (*image).byte_order = NATIVE_BYTE_ORDER;
+        }
+        let image = &*frame.image;
+        let is_xrgb = image.red_mask == 0xFF0000 && image.green_mask == 0xFF00 && image.blue_mask == 0xFF;
+        if !is_xrgb || image.bits_per_pixel != 32 || image.bytes_per_line as usize != width as usize * 4 {
+            return Err(Error::Unsupported("visuals other than 24-bit TrueColor"))
+        }
+        Ok(frame)
+    }
+
+    fn pixels_mut(&mut self) -> &mut [u32] {
+        match &self.shm {
+            Some(shm) => unsafe {
+                slice::from_raw_parts_mut(shm.shmaddr.cast(), self.size.0 as usize * self.size.1 as usize)
+            },
+            None => &mut self.pixels,
+        }
+    }
+
+    /// Copies part of the image to the same spot on the window.
+    unsafe fn put(&self, window: ffi::Window, area: Rect) {
+        let (xlib, display) = (self.xlib, self.display);
+        let Rect { x, y, width, height } = area;
+        if self.shm.is_some() {
+            let _ = xlib.XShmPutImage(display, window, self.gc, self.image, x, y, x, y, width, height, False);
+        } else {
+            let _ = xlib.XPutImage(display, window, self.gc, self.image, x, y, x, y, width, height);
+        }
+    }
+}
+
+impl ops::Drop for Frame {
+    fn drop(&mut self) {
+        unsafe {
+            if let Some(shm) = &mut self.shm {
+                let _ = self.xlib.XShmDetach(self.display, &mut **shm);
+            }
+            if !self.image.is_null() {
+                // The image never owns its pixels, so they're not for it to free
+                (*self.image).data = ptr::null_mut();
+                let _ = self.xlib.XDestroyImage(self.image);
+            }
+            if let Some(shm) = &self.shm {
+                let _ = shmdt(shm.shmaddr.cast());
+            }
+        }
+    }
+}
+
+/// Creates an image in shared memory with MIT-SHM, if the server has it and can get at our memory.
+unsafe fn create_shm_image(
+    xlib: &X11DL,
+    display: *mut Display,
+    visual: *mut Visual,
+    depth: c_uint,
+    (width, height): (u32, u32),
+) -> Option<(*mut XImage, Box<XShmSegmentInfo>)> {
+    if xlib.XShmQueryExtension(display) != Some(True) {
+        return None
+    }
+    let mut shm = Box::new(XShmSegmentInfo { shmseg: 0, shmid: -1, shmaddr: ptr::null_mut(), readOnly: False });
+    let image = xlib
+        .XShmCreateImage(display, visual, depth, ZPixmap, ptr::null_mut(), &mut *shm, width, height)
+        .unwrap_or(ptr::null_mut());
+    if image.is_null() {
+        return None
+    }
+
+    // Nobody swaps the bytes in shared memory, so it's only any good if the server's order is ours
+    if (*image).byte_order == NATIVE_BYTE_ORDER {
+        shm.shmid = shmget(IPC_PRIVATE, (*image).bytes_per_line as usize * height as usize, IPC_CREAT | 0o600);
+    }
+    // Like the rest of System V, failure is -1 rather than null
+    let address = if shm.shmid != -1 { shmat(shm.shmid, ptr::null(), 0) } else { !0 as *mut c_void };
+    let mut attached = false;
+    if address as isize != -1 {
+        shm.shmaddr = address.cast();
+
+        // Servers can't attach to our memory over the network, which is reported as an error
+        LAST_ERROR.with(|e| e.set(0));
+        attached = xlib.XShmAttach(display, &mut *shm) == Some(True);
+        let _ = xlib.XSync(display, False);
+        attached &= LAST_ERROR.with(Cell::get) == 0;
+    }
+
+    // Once both sides are done with the segment (or never got to it), it goes away
+    if shm.shmid != -1 {
+        let _ = shmctl(shm.shmid, IPC_RMID, ptr::null_mut());
+    }
+    if !attached {
+        if address as isize != -1 {
+            let _ = shmdt(address);
+        }
+        let _ = xlib.XDestroyImage(image);
+        return None
+    }
+    (*image).data = shm.shmaddr;
+    Some((image, shm))
+}
+
+/// Copies an image into the frame, stretching it to the client area, and from there onto the window.
+unsafe fn present_pixels(data: &mut WindowImplData, pixels: &[u32], size: (u32, u32), damage: &[Rect]) -> Result<(), Error> {
+    let client_size = data.client_area_size;
+    if data.has_external_surface || client_size.0 == 0 || client_size.1 == 0 {
+        return Ok(())
+    }
+
+    // A new frame has nothing in it yet, so it's drawn in full
+    let is_new = data.frame.as_ref().map(|frame| frame.size) != Some(client_size);
+    if is_new {
+        data.frame = None;
+        data.frame = Some(Frame::new(data.xlib, data.display, client_size)?);
+    }
+    let whole = [Rect { x: 0, y: 0, width: size.0, height: size.1 }];
+    let damage = if is_new { &whole[..] } else { damage };
+    let frame = data.frame.as_mut().unwrap();
+    for &rect in damage {
+        let area = util::scale_rect(rect, size, client_size);
+        util::stretch_pixels(pixels, size, frame.pixels_mut(), client_size, area);
+        frame.put(data.window, area);
+    }
+
+    // The server reads shared memory whenever it gets around to it, so the next frame has to wait for that
+    if frame.shm.is_some() {
+        let _ = data.xlib.XSync(data.display, False);
+    } else {
+        let _ = data.xlib.XFlush(data.display);
+    }
+    Ok(())
+}
+
+/// Handles an event from the X server, the equivalent of `window_proc` on Win32.
+unsafe fn process_event(data: &mut WindowImplData, event: &XEvent) {
+    match event.type_ {
+        // Received when the window manager (or anyone, really) sends us a message.
+        // The ones we care about are `WM_PROTOCOLS` which we signed up for on creation,
+        // and XDND messages if we've said that we accept drops.
+        ClientMessage => {
+            let message = &event.client_message;
+            if message.message_type != data.atoms.WM_PROTOCOLS {
+                xdnd_message(data, message);
+                return
+            }
+            let protocol = message.data[0] as Atom;
+            if protocol == data.atoms.WM_DELETE_WINDOW {
+                // The window manager doesn't tell us why, but this is what the "X button" does.
+                data.push_event(Event::CloseRequest(CloseReason::SystemMenu));
+            } else if protocol == data.atoms._NET_WM_PING {
+                // The window manager checks whether we're responding, so bounce it back to the root.
+                let xlib = data.xlib;
+                let screen = xlib.XDefaultScreen(data.display).unwrap_or(0);
+                let root = xlib.XRootWindow(data.display, screen).unwrap_or(0);
+                let mut reply = *event;
+                reply.client_message.window = root;
+                let mask = SubstructureNotifyMask | SubstructureRedirectMask;
+                let _ = xlib.XSendEvent(data.display, root, False, mask, &mut reply);
+            }
+        },
+
+        // Received when a selection we asked for has been converted. The clipboard waits for its own
+        // (see `wait_for_clipboard`), so this is only drag & drop.
+        SelectionNotify if event.selection.selection == data.atoms.XdndSelection => {
+            xdnd_selection(data, &event.selection);
+        },
+
+        // Received when another client asks for the contents of a selection we own.
+        SelectionRequest => answer_selection_request(data, &event.selection_request),
+
+        // Received when another client has taken the clipboard from us, so our contents are gone.
+        SelectionClear if event.selection_clear.selection == data.atoms.CLIPBOARD => {
+            data.clipboard = None;
+            if data.xfixes_selection_event.is_none() {
+                data.push_event(Event::ClipboardChanged);
+            }
+        },
+
+        // Received when part of the window has to be redrawn, which the server leaves up to us.
+        // That's done from the last frame given to `Window::present_pixels`, if there's been one.
+        Expose => {
+            if let Some(frame) = &data.frame {
+                let expose = &event.expose;
+                let area = Rect { x: expose.x, y: expose.y, width: expose.width as u32, height: expose.height as u32 };
+                let (width, height) = frame.size;
+                if let Some(area) = area.intersection(Rect { x: 0, y: 0, width, height }) {
+                    frame.put(data.window, area);
+                }
+            }
+        },
+
+        // Received when the window has been moved, resized, or restacked.
+        ConfigureNotify => {
+            let configure = &event.configure;
+            let size = (configure.width as u32, configure.height as u32);
+            if data.client_area_size != size {
+                data.client_area_size = size;
+                let (width, height) = size;
+                let event = Event::Resize((data.scale_size(Size::Physical(width, height)), data.current_scale));
+                data.push_event(event);
+            }
+
+            // The coordinates are relative to the parent, which is likely the window manager's frame
+            let position = outer_position(data);
+            if data.outer_position != position {
+                data.outer_position = position;
+                let (x, y) = position;
+                let event = Event::Move((data.scale_point(Point::Physical(x, y)), data.current_scale));
+                data.push_event(event);
+            }
+        },
+
+        // Received when keyboard focus is gained or lost.
+        // Grab and ungrab modes are sent while the window manager is dragging the window around,
+        // or while some other application grabs the keyboard, neither of which change focus.
+        FocusIn | FocusOut => {
+            let focus_change = &event.focus_change;
+            if focus_change.mode == NotifyGrab || focus_change.mode == NotifyUngrab {
+                return
+            }
+            let focused = event.type_ == FocusIn;
+            if data.is_focused != focused {
+                data.is_focused = focused;
+                data.push_event(Event::Focus(focused));
+            }
+            update_cursor_grab(data);
+
+            // The input method only composes for the input context that has focus
+            #[cfg(feature = "input")]
+            update_ic_focus(data);
+
+            // Key releases aren't sent to unfocused windows, so forget what's held down
+            #[cfg(feature = "input")]
+            if !focused {
+                data.key_states = [0; 32];
+            }
+        },
+
+        // Received when a property on the window has changed. This is how window managers
+        // report maximizing and minimizing, unlike basically every other windowing system.
+        PropertyNotify if event.property.atom == data.atoms._NET_WM_STATE => update_wm_state(data),
+
+        // Received when the resources on the root window have changed, such as by `xrdb`.
+        // This is where `Xft.dpi` is, so it's how desktop environments change the scale of everything.
+        PropertyNotify if event.property.atom == data.atoms.RESOURCE_MANAGER => update_scale(data),
+
+        // Received when a client we're sending clipboard data to with `INCR` has taken the last chunk.
+        PropertyNotify if event.property.state == PropertyDelete && event.property.window != data.window => {
+            send_incr_chunk(data, &event.property)
+        },
+
+        // Received when the mouse enters or leaves the window.
+        // A failed pointer grab is tried again here, as the pointer is available again.
+        EnterNotify | LeaveNotify => {
+            let inside = event.type_ == EnterNotify;
+            #[cfg(feature = "input")]
+            {
+                if data.is_mouse_inside != inside {
+                    data.is_mouse_inside = inside;
+                    data.push_event(Event::MouseFocus(inside));
+                }
+
+                // Another client grabbing the pointer breaks the implicit grab from holding buttons,
+                // so their releases will go elsewhere, and they're released now
+                if !inside && event.crossing.mode == NotifyGrab {
+                    let modifiers = translate_modifiers(event.crossing.state);
+                    for button in mem::take(&mut data.held_buttons) {
+                        data.push_event(Event::MouseUp((button, modifiers)));
+                    }
+                }
+            }
+            if inside {
+                update_cursor_grab(data);
+            }
+        },
+
+        // Received when the mouse cursor has moved around in the window.
+        MotionNotify => {
+            let (x, y) = (event.motion.x, event.motion.y);
+
+            // There's no locking in X11, so the pointer is put back wherever it goes, which isn't movement
+            if data.is_cursor_grabbed && data.cursor_grab == CursorGrab::Locked {
+                let lock_position = cursor_lock_position(data);
+                if (x, y) != lock_position {
+                    warp_cursor(data, lock_position);
+                }
+            } else {
+                // While buttons are held the pointer is implicitly grabbed, so it keeps coming from outside
+                #[cfg(feature = "input")]
+                {
+                    let (cw, ch) = data.client_area_size;
+                    let is_inside = x >= 0 && (x as u32) < cw && y >= 0 && (y as u32) < ch;
+                    if is_inside || !data.held_buttons.is_empty() {
+                        let point = Point::Physical(x, y);
+                        let scale = data.current_scale;
+                        let event = if data.is_dpi_logical {
+                            Event::MouseMove((point.to_logical(scale), scale))
+                        } else {
+                            Event::MouseMove((point, scale))
+                        };
+                        data.push_event(event);
+                    }
+                }
+            }
+        },
+
+        // Received for extension events which don't fit in an `XEvent`, which we only select raw motion & touch for.
+        // Raw motion is sent to the root window no matter which window has focus, so that's filtered out.
+        #[cfg(feature = "input")]
+        GenericEvent => {
+            let xlib = data.xlib;
+            let mut cookie = event.cookie;
+            if Some(cookie.extension) != data.xi_opcode {
+                return
+            }
+            if xlib.XGetEventData(data.display, &mut cookie) != Some(True) {
+                return
+            }
+            if cookie.evtype == XI_RawMotion && data.raw_mouse_motion && data.is_focused {
+                // Only the valuators that changed are sent, packed in order, and the first two are X & Y
+                let raw = &*(cookie.data as *const XIRawEvent);
+                let mask = std::slice::from_raw_parts(raw.valuators.mask, raw.valuators.mask_len.max(0) as usize);
+                let bits = mask.first().copied().unwrap_or(0);
+                let mut values = raw.raw_values;
+                let mut delta = [0.0; 2];
+                for (axis, value) in delta.iter_mut().enumerate() {
+                    if bits & (1 << axis) != 0 {
+                        *value = *values;
+                        values = values.add(1);
+                    }
+                }
+                if delta != [0.0; 2] {
+                    data.push_event(Event::MouseMotionRaw((delta[0], delta[1])));
+                }
+            } else if matches!(cookie.evtype, XI_TouchBegin | XI_TouchUpdate | XI_TouchEnd) {
+                let touch = &*(cookie.data as *const XIDeviceEvent);
+                let phase = match cookie.evtype {
+                    XI_TouchBegin => TouchPhase::Start,
+                    XI_TouchEnd => TouchPhase::End,
+                    _ => TouchPhase::Move,
+                };
+                let scale = data.current_scale;
+                let mut position = Point::Physical(touch.event_x.round() as i32, touch.event_y.round() as i32);
+                if data.is_dpi_logical {
+                    position = position.to_logical(scale);
+                }
+                let modifiers = translate_modifiers(touch.mods.effective as c_uint);
+                let touch = Touch { id: touch.detail as u32 as u64, phase, position };
+                data.push_event(Event::Touch((touch, scale)));
+                if data.touch_emulates_mouse {
+                    for event in data.touch_mouse.emulate(&touch, scale, modifiers).iter().flatten() {
+                        data.push_event(*event);
+                    }
+                }
+            }
+            let _ = xlib.XFreeEventData(data.display, &mut cookie);
+        },
+
+        // Received when a mouse button is pressed or released.
+        // Buttons 4 through 7 are scroll wheel "clicks", and 8 & 9 are the side buttons.
+        #[cfg(feature = "input")]
+        ButtonPress | ButtonRelease => {
+            let pressed = event.type_ == ButtonPress;
+            let modifiers = translate_modifiers(event.button.state);
+            let button = match event.button.button {
+                Button1 => MouseButton::Left,
+                Button2 => MouseButton::Middle,
+                Button3 => MouseButton::Right,
+                Button4 | Button5 | 6 | 7 => {
+                    if pressed {
+                        let (x, y) = match event.button.button {
+                            Button4 => (0, 1),
+                            Button5 => (0, -1),
+                            6 => (-1, 0),
+                            _ => (1, 0),
+                        };
+                        if y != 0 {
+                            // One notch is 15 degrees, or 120 in Win32 units
+                            let delta = std::num::NonZeroI32::new_unchecked(y * 120);
+                            data.push_event(Event::MouseWheel((delta, modifiers)));
+                        }
+                        let delta = ScrollDelta::Lines(x.into(), y.into());
+                        data.push_event(Event::MouseScroll((delta, None, modifiers)));
+                    }
+                    return
+                },
+                8 => MouseButton::Mouse4,
+                9 => MouseButton::Mouse5,
+                _ => return,
+            };
+            if !pressed {
+                data.held_buttons.retain(|&b| b != button);
+            } else if !data.held_buttons.contains(&button) {
+                data.held_buttons.push(button);
+            }
+            let event = if pressed {
+                Event::MouseDown((button, modifiers))
+            } else {
+                Event::MouseUp((button, modifiers))
+            };
+            data.push_event(event);
+        },
+
+        // Received when a key is pressed or released.
+        #[cfg(feature = "input")]
+        KeyPress | KeyRelease => {
+            let xlib = data.xlib;
+            let keycode = event.key.keycode as KeyCode;
+
+            // Without detectable auto-repeat (`XkbSetDetectableAutoRepeat` can be unsupported),
+            // repeats are sent as a release and a press with the exact same timestamp.
+            if event.type_ == KeyRelease && xlib.XPending(data.display).unwrap_or(0) != 0 {
+                let mut next = mem::MaybeUninit::<XEvent>::zeroed();
+                let _ = xlib.XPeekEvent(data.display, next.as_mut_ptr());
+                let next = &*next.as_ptr();
+                if next.type_ == KeyPress && next.key.keycode == event.key.keycode && next.key.time == event.key.time {
+                    return
+                }
+            }
+
+            if event.type_ == KeyPress && data.text_input {
+                lookup_text(data, event);
+            }
+            let pressed = event.type_ == KeyPress;
+            let is_repeat = match track_key(&mut data.key_states, keycode, pressed) {
+                Some(is_repeat) => is_repeat,
+                None => return,
+            };
+
+            // Level 0 of group 0 is the "plain" keysym, ignoring modifiers
+            // X11 keycodes are Linux input event codes offset by 8, for historical reasons
+            let keysym = xlib.XkbKeycodeToKeysym(data.display, keycode, 0, 0).unwrap_or(0);
+            let scancode = ScanCode(u32::from(keycode).saturating_sub(8));
+            let key = translate_keysym(keysym).unwrap_or(Key::Unknown(scancode));
+
+            // The state is from before the event, so the key itself is applied on top
+            let mut modifiers = translate_modifiers(event.key.state);
+            if !is_repeat {
+                modifiers.update(key, pressed);
+            }
+            let event = if !pressed {
+                Event::KeyboardUp((key, scancode, modifiers))
+            } else if is_repeat {
+                Event::KeyboardRepeat((key, scancode, modifiers))
+            } else {
+                Event::KeyboardDown((key, scancode, modifiers))
+            };
+            data.push_event(event);
+        },
+
+        // Received from XFixes when the clipboard has changed owner, which means it has new contents.
+        type_ if Some(type_) == data.xfixes_selection_event => data.push_event(Event::ClipboardChanged),
+
+        _ => (),
+    }
+}
+
+/// Handles a message from the owning thread, the equivalent of the `RAMEN_WM_*` handlers on Win32.
+unsafe fn process_message(data: &mut WindowImplData, message: Message) {
+    let xlib = data.xlib;
+    match message {
+        Message::Destroy => data.destroy_flag = true,
+
+        Message::Execute(f) => {
+            // `FnOnce` requires the closure to consume itself, so it's done like this!
+            if let Some(f) = (&mut *f).take() {
+                f();
+            }
+        },
+
+        Message::GetInnerSize(out_size, out_scale) => {
+            let (width, height) = data.client_area_size;
+            *out_size = data.scale_size(Size::Physical(width, height));
+            *out_scale = data.current_scale;
+        },
+
+        Message::GetClipboard(format, out) => *out = clipboard_data(data, &*format),
+
+        Message::GetClipboardFormats(out) => *out = clipboard_formats(data),
+
+        Message::GetCurrentMonitor(out) => *out = current_monitor(data),
+
+        Message::GetPosition(inner, out_position, out_scale) => {
+            let (x, y) = if inner { inner_position(data) } else { outer_position(data) };
+            *out_position = data.scale_point(Point::Physical(x, y));
+            *out_scale = data.current_scale;
+        },
+
+        Message::IsDpiLogical(out) => *out = data.is_dpi_logical,
+
+        Message::PresentPixels(pixels, size, damage, out) => *out = present_pixels(data, &*pixels, size, &*damage),
+
+        // The frame is outside of the inner area either way, so the inner size stays the same
+        Message::SetBorderless(borderless) => {
+            if data.style.borderless != borderless {
+                data.style.borderless = borderless;
+                update_window_style(data);
+            }
+        },
+
+        Message::SetClipboard(contents, out) => *out = set_clipboard(data, &*contents),
+
+        Message::SetControls(controls) => {
+            if data.style.controls != controls {
+                data.style.controls = controls;
+                update_window_style(data);
+            }
+        },
+
+        Message::SetCursor(cursor) => set_cursor(data, &cursor),
+
+        Message::SetCursorGrab(grab) => {
+            if data.cursor_grab != grab {
+                if grab == CursorGrab::Locked {
+                    // Lock the pointer where it is, which gets clamped to the window if it's outside
+                    let (mut root, mut child) = (0, 0);
+                    let (mut root_x, mut root_y, mut x, mut y) = (0, 0, 0, 0);
+                    let mut mask = 0;
+                    let found = xlib.XQueryPointer(
+                        data.display,
+                        data.window,
+                        &mut root,
+                        &mut child,
+                        &mut root_x,
+                        &mut root_y,
+                        &mut x,
+                        &mut y,
+                        &mut mask,
+                    );
+                    if found == Some(True) {
+                        data.cursor_lock_position = (x, y);
+                    }
+                }
+
+                // Changing between confined and locked doesn't need a new grab, just a warp
+                data.cursor_grab = grab;
+                update_cursor_grab(data);
+                if data.is_cursor_grabbed && grab == CursorGrab::Locked {
+                    warp_cursor(data, cursor_lock_position(data));
+                    let _ = xlib.XFlush(data.display);
+                }
+            }
+        },
+
+        Message::SetCursorPosition(position) => {
+            data.cursor_lock_position = position.as_physical(data.current_scale);
+            warp_cursor(data, data.cursor_lock_position);
+            let _ = xlib.XFlush(data.display);
+        },
+
+        // Exposed areas are left for OpenGL or Vulkan to draw, instead of the last presented frame
+        Message::SetExternalSurface => {
+            data.has_external_surface = true;
+            data.frame = None;
+        },
+
+        Message::SetFullscreen(fullscreen) => {
+            if data.fullscreen != fullscreen {
+                set_fullscreen(data, fullscreen);
+            }
+        },
+
+        Message::SetIcon(icon) => set_icon(data, icon.as_ref()),
+
+        Message::SetInnerSize(size) => {
+            let (width, height) = size.as_physical(data.current_scale);
+            data.client_area_size = (width, height);
+            data.is_dpi_logical = matches!(size, Size::Logical(..));
+            update_size_hints(data);
+            let _ = xlib.XResizeWindow(data.display, data.window, width.max(1), height.max(1));
+        },
+
+        Message::SetMaximized(maximized) => {
+            if data.is_maximized != maximized {
+                set_maximized(data, maximized);
+            }
+        },
+
+        // With the default `NorthWestGravity`, the window manager places the frame's corner here
+        Message::SetPosition(position) => {
+            let (x, y) = position.as_physical(data.current_scale);
+            data.outer_position = (x, y);
+            data.is_user_positioned = true;
+            update_size_hints(data);
+            let _ = xlib.XMoveWindow(data.display, data.window, x, y);
+        },
+
+        #[cfg(feature = "input")]
+        Message::SetRawMouseMotion(enabled) => set_raw_mouse_motion(data, enabled),
+
+        Message::SetResizable(resizable) => {
+            if data.style.resizable != resizable {
+                data.style.resizable = resizable;
+                update_window_style(data);
+            }
+        },
+
+        #[cfg(feature = "input")]
+        Message::SetImeAllowed(allowed) => {
+            data.ime_allowed = allowed;
+            update_ic_focus(data);
+        },
+
+        #[cfg(feature = "input")]
+        Message::SetImeCursorArea(position, size) => {
+            data.ime_cursor_area = Some((position, size));
+            update_ime_spot(data);
+        },
+
+        #[cfg(feature = "input")]
+        Message::SetTextInput(enabled) => {
+            data.text_input = enabled;
+            update_ic_focus(data);
+        },
+
+        Message::SetTitle(title) => set_title(data, &title),
+
+        Message::SetVisible(visible) => {
+            if data.style.visible != visible {
+                data.style.visible = visible;
+                if visible {
+                    map_window(data);
+                } else {
+                    let _ = xlib.XUnmapWindow(data.display, data.window);
+                }
+            }
+        },
+    }
+}
+
+/// Translates the modifier mask of an input event, which xkbcommon shares the layout of.
+#[cfg(feature = "input")]
+pub(crate) fn translate_modifiers(state: c_uint) -> Modifiers {
+    const MASKS: [(c_uint, Modifiers); 6] = [
+        (ShiftMask, Modifiers::SHIFT),
+        (ControlMask, Modifiers::CTRL),
+        (Mod1Mask, Modifiers::ALT),
+        (Mod4Mask, Modifiers::SUPER),
+        (LockMask, Modifiers::CAPS_LOCK),
+        (Mod2Mask, Modifiers::NUM_LOCK),
+    ];
+    let mut modifiers = Modifiers::empty();
+    for &(mask, modifier) in MASKS.iter() {
+        if state & mask != 0 {
+            modifiers.insert(modifier);
+        }
+    }
+    modifiers
+}
+
+/// Translates a keysym, which is what the keyboard layout maps keycodes to, into a key.
+///
+/// Wayland uses this too, as xkbcommon keysyms have the same values.
+#[cfg(feature = "input")]
+pub(crate) fn translate_keysym(keysym: KeySym) -> Option<Key> {
+    Some(match keysym {
+        XK_0..=XK_9 => NUMBER_KEYS[keysym - XK_0],
+        XK_a..=XK_z => LETTER_KEYS[keysym - XK_a],
+        XK_A..=XK_Z => LETTER_KEYS[keysym - XK_A],
+        XK_F1..=XK_F24 => FUNCTION_KEYS[keysym - XK_F1],
+        XK_KP_0..=XK_KP_9 => NUMPAD_KEYS[keysym - XK_KP_0],
+
+        XK_space => Key::Space,
+        XK_apostrophe => Key::Oem7,
+        XK_plus | XK_equal => Key::Plus,
+        XK_comma => Key::Comma,
+        XK_minus => Key::Minus,
+        XK_period => Key::Period,
+        XK_slash => Key::Oem2,
+        XK_semicolon => Key::Oem1,
+        XK_less => Key::Oem102,
+        XK_bracketleft => Key::Oem4,
+        XK_backslash => Key::Oem5,
+        XK_bracketright => Key::Oem6,
+        XK_grave => Key::Oem3,
+
+        XK_BackSpace => Key::Backspace,
+        XK_Tab => Key::Tab,
+        XK_Clear => Key::Clear,
+        XK_Return | XK_KP_Enter => Key::Enter,
+        XK_Pause => Key::Pause,
+        XK_Scroll_Lock => Key::ScrollLock,
+        XK_Escape => Key::Escape,
+        XK_Delete => Key::Delete,
+        XK_Home => Key::Home,
+        XK_Left => Key::Left,
+        XK_Up => Key::Up,
+        XK_Right => Key::Right,
+        XK_Down => Key::Down,
+        XK_Prior => Key::PageUp,
+        XK_Next => Key::PageDown,
+        XK_End => Key::End,
+        XK_Select => Key::Select,
+        XK_Print => Key::PrintScreen,
+        XK_Execute => Key::Execute,
+        XK_Insert => Key::Insert,
+        XK_Menu => Key::Applications,
+        XK_Help => Key::Help,
+        XK_Num_Lock => Key::NumLock,
+        XK_Caps_Lock => Key::CapsLock,
+
+        XK_Shift_L => Key::LShift,
+        XK_Shift_R => Key::RShift,
+        XK_Control_L => Key::LControl,
+        XK_Control_R => Key::RControl,
+        XK_Alt_L | XK_Meta_L => Key::LAlt,
+        XK_Alt_R | XK_Meta_R | XK_ISO_Level3_Shift => Key::RAlt,
+        XK_Super_L => Key::LSuper,
+        XK_Super_R => Key::RSuper,
+
+        // With Num Lock off, the keypad reports navigation keysyms.
+        // These are mapped back to where they physically are, like Win32 scancodes would.
+        XK_KP_Insert => Key::Numpad0,
+        XK_KP_End => Key::Numpad1,
+        XK_KP_Down => Key::Numpad2,
+        XK_KP_Next => Key::Numpad3,
+        XK_KP_Left => Key::Numpad4,
+        XK_KP_Begin => Key::Numpad5,
+        XK_KP_Right => Key::Numpad6,
+        XK_KP_Home => Key::Numpad7,
+        XK_KP_Up => Key::Numpad8,
+        XK_KP_Prior => Key::Numpad9,
+        XK_KP_Delete | XK_KP_Decimal => Key::Decimal,
+        XK_KP_Multiply => Key::Multiply,
+        XK_KP_Add => Key::Add,
+        XK_KP_Separator => Key::Separator,
+        XK_KP_Subtract => Key::Subtract,
+        XK_KP_Divide => Key::Divide,
+
+        XK_Kanji | XK_Hangul_Hanja => Key::ImeKanji,
+        XK_Muhenkan => Key::ImeNonConvert,
+        XK_Henkan => Key::ImeConvert,
+        XK_Hiragana_Katakana | XK_Hangul => Key::ImeKana,
+        XK_Mode_switch => Key::ImeModeChange,
+
+        XF86XK_Back => Key::BrowserBack,
+        XF86XK_Favorites => Key::BrowserFavourites,
+        XF86XK_Forward => Key::BrowserForward,
+        XF86XK_HomePage => Key::BrowserHome,
+        XF86XK_Refresh => Key::BrowserRefresh,
+        XF86XK_Search => Key::BrowserSearch,
+        XF86XK_Stop => Key::BrowserStop,
+        XF86XK_AudioNext => Key::MediaNextTrack,
+        XF86XK_AudioPrev => Key::MediaPreviousTrack,
+        XF86XK_AudioPlay | XF86XK_AudioPause => Key::MediaPlayPause,
+        XF86XK_AudioStop => Key::MediaStop,
+        XF86XK_AudioLowerVolume => Key::VolumeDown,
+        XF86XK_AudioRaiseVolume => Key::VolumeUp,
+        XF86XK_AudioMute => Key::VolumeMute,
+        XF86XK_MyComputer => Key::LaunchApplication1,
+        XF86XK_Calculator => Key::LaunchApplication2,
+        XF86XK_Mail => Key::LaunchMail,
+        XF86XK_AudioMedia => Key::LaunchMediaSelect,
+        XF86XK_Sleep => Key::Sleep,
+
+        _ => return None,
+    })
+}
+
+#[cfg(feature = "input")]
+const NUMBER_KEYS: [Key; 10] = [
+    Key::Num0, Key::Num1, Key::Num2, Key::Num3, Key::Num4,
+    Key::Num5, Key::Num6, Key::Num7, Key::Num8, Key::Num9,
+];
+#[cfg(feature = "input")]
+const LETTER_KEYS: [Key; 26] = [
+    Key::A, Key::B, Key::C, Key::D, Key::E, Key::F, Key::G, Key::H, Key::I, Key::J, Key::K, Key::L, Key::M,
+    Key::N, Key::O, Key::P, Key::Q, Key::R, Key::S, Key::T, Key::U, Key::V, Key::W, Key::X, Key::Y, Key::Z,
+];
+#[cfg(feature = "input")]
+const FUNCTION_KEYS: [Key; 24] = [
+    Key::F1, Key::F2, Key::F3, Key::F4, Key::F5, Key::F6, Key::F7, Key::F8, Key::F9, Key::F10, Key::F11, Key::F12,
+    Key::F13, Key::F14, Key::F15, Key::F16, Key::F17, Key::F18, Key::F19, Key::F20, Key::F21, Key::F22, Key::F23, Key::F24,
+];
+#[cfg(feature = "input")]
+const NUMPAD_KEYS: [Key; 10] = [
+    Key::Numpad0, Key::Numpad1, Key::Numpad2, Key::Numpad3, Key::Numpad4,
+    Key::Numpad5, Key::Numpad6, Key::Numpad7, Key::Numpad8, Key::Numpad9,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::{mode_refresh_rate, parse_xft_dpi, XRRModeInfo, RR_Interlace};
+    #[cfg(feature = "input")]
+    use super::{track_key, translate_keysym, Key, XK_ISO_Level3_Shift, XK_a};
+
+    #[test]
+    fn xft_dpi() {
+        assert_eq!(parse_xft_dpi(b"Xft.antialias:\t1\nXft.dpi:\t144\nXft.hinting:\t1\n"), Some(144.0));
+        assert_eq!(parse_xft_dpi(b"Xft.dpi: 96.5"), Some(96.5));
+        assert_eq!(parse_xft_dpi(b"Xcursor.size:\t24\n"), None);
+        assert_eq!(parse_xft_dpi(b"Xft.dpi:\t0\n"), None);
+    }
+
+    #[cfg(feature = "input")]
+    #[test]
+    fn input_method_commits_are_not_keys() {
+        let mut key_states = [0; 32];
+        assert_eq!(track_key(&mut key_states, 0, true), None);
+        assert_eq!(track_key(&mut key_states, 0, true), None);
+        assert_eq!(key_states, [0; 32]);
+
+        assert_eq!(track_key(&mut key_states, 38, true), Some(false));
+        assert_eq!(track_key(&mut key_states, 38, true), Some(true));
+        assert_eq!(track_key(&mut key_states, 0, true), None);
+        assert_eq!(track_key(&mut key_states, 38, false), Some(false));
+        assert_eq!(key_states, [0; 32]);
+    }
+
+    #[cfg(feature = "input")]
+    #[test]
+    fn unlisted_keysyms() {
+        assert_eq!(translate_keysym(XK_a), Some(Key::A));
+        assert_eq!(translate_keysym(XK_ISO_Level3_Shift), Some(Key::RAlt));
+        // No symbol at all, and a Cyrillic letter, are reported with their scancode as `Key::Unknown` instead
+        assert_eq!(translate_keysym(0), None);
+        assert_eq!(translate_keysym(0x6c1), None);
+    }
+
+    #[test]
+    fn refresh_rate_from_timings() {
+        let mut mode: XRRModeInfo = unsafe { std::mem::zeroed() };
+        // CVT 1920x1080 at 59.96Hz
+        mode.dotClock = 173_000_000;
+        mode.hTotal = 2576;
+        mode.vTotal = 1120;
+        assert_eq!(mode_refresh_rate(&mode), 59963);
+        mode.modeFlags = RR_Interlace;
+        assert_eq!(mode_refresh_rate(&mode), 119926);
+        mode.hTotal = 0;
+        assert_eq!(mode_refresh_rate(&mode), 0);
+    }
+}
@@ -1,8 +1,15 @@
+use crate::{error::Error, monitor::Rect};
+#[cfg(feature = "input")]
+use crate::{
+    event::{Event, Modifiers, MouseButton, Touch, TouchPhase},
+    monitor::Scale,
+};
 use std::{
     cell::UnsafeCell,
     clone::Clone,
     mem::MaybeUninit,
     ops::Deref, ptr,
+    path::PathBuf,
     sync::{Arc, Once},
 };
 
@@ -45,10 +52,10 @@ macro_rules! dyn_link {
         }
 
         impl $s_ident {
-            #[allow(unused_doc_comments)]
+            #[allow(unused_doc_comments, clippy::missing_transmute_annotations, clippy::zero_ptr)]
             unsafe fn _link() -> Self {
                 let mut inst = ::std::mem::MaybeUninit::<Self>::uninit();
-                let mut inst_ref = &mut *(inst.as_mut_ptr());
+                let inst_ref = &mut *(inst.as_mut_ptr());
                 $(
                     let mut handle = 0 as $dlopen_ty;
                     for name in &[$(c_string!($module_name) ,)*] {
@@ -80,6 +87,7 @@ macro_rules! dyn_link {
             }
             $($(
                 $(#[$fn_outer])*
+                #[allow(clippy::too_many_arguments)]
                 $s_vis unsafe fn $sym_fn(&self, $($name : $ty ,)*) -> ::std::option::Option<$ret> {
                     self.$sym_fn.map(|f| f($($name ,)*))
                 }
@@ -123,6 +131,7 @@ impl<T: Copy, const N: usize> FixedVec<T, N> {
         }
     }
 
+    #[cfg_attr(not(any(windows, feature = "input")), allow(dead_code))]
     pub fn push(&mut self, item: &T) -> bool {
         self.push_many(unsafe {
             std::slice::from_raw_parts(item, 1)
@@ -230,7 +239,293 @@ impl<T: 'static + ?Sized> Clone for MaybeArc<T> {
             Self::Static(x) => Self::Static(x),
             Self::Dynamic(x) => Self::Dynamic(Arc::clone(x)),
         }
-    }   
+    }
+}
+
+/// Very lightweight event-swap system shared between a window thread and its owner.
+///
+/// The window thread pushes into the active buffer, while the owner reads the inactive one.
+/// Calling [`swap`](Self::swap) clears the inactive buffer and switches the two around.
+///
+/// Events can't own text, so each buffer has a string alongside it that events index into,
+/// and the same goes for paths, which are kept as they are since they might not be valid Unicode.
+pub struct EventQueue<T, const N: usize> {
+    sync: sync::Mutex<bool>,
+    ping: sync::Condvar,
+    is_primary: UnsafeCell<bool>,
+    primary: UnsafeCell<(FixedVec<T, N>, String, Vec<PathBuf>)>,
+    secondary: UnsafeCell<(FixedVec<T, N>, String, Vec<PathBuf>)>,
+}
+
+unsafe impl<T: Send, const N: usize> Send for EventQueue<T, N> {}
+unsafe impl<T: Send, const N: usize> Sync for EventQueue<T, N> {}
+
+impl<T: Copy, const N: usize> EventQueue<T, N> {
+    pub fn new() -> Self {
+        Self {
+            sync: sync::Mutex::new(false),
+            ping: sync::Condvar::new(),
+            is_primary: UnsafeCell::new(true),
+            primary: UnsafeCell::new((FixedVec::new(), String::new(), Vec::new())),
+            secondary: UnsafeCell::new((FixedVec::new(), String::new(), Vec::new())),
+        }
+    }
+
+    /// Gets the active buffer, along with its text and paths.
+    ///
+    /// # Safety
+    ///
+    /// `sync` must be locked for as long as the references are alive.
+    #[allow(clippy::mut_from_ref)]
+    unsafe fn active(&self) -> &mut (FixedVec<T, N>, String, Vec<PathBuf>) {
+        if *self.is_primary.get() {
+            &mut *self.primary.get()
+        } else {
+            &mut *self.secondary.get()
+        }
+    }
+
+    /// Gets the buffer (and its text and paths) which was active before the last swap.
+    ///
+    /// # Safety
+    ///
+    /// Only the owning thread may call this, and never at the same time as [`swap`](Self::swap).
+    unsafe fn inactive(&self) -> &(FixedVec<T, N>, String, Vec<PathBuf>) {
+        if *self.is_primary.get() {
+            &*self.secondary.get()
+        } else {
+            &*self.primary.get()
+        }
+    }
+
+    /// Locks `sync` once the active buffer has room for `count` more events.
+    fn lock_with_room(&self, count: usize) -> sync::MutexGuard<'_, bool> {
+        // If the window thread locks up, the window should too, eventually.
+        // This quirk of the event swap system stores a "is cvar waiting" in the mutex,
+        // making it so that if swap never occurs, this eventually will indefinitely block,
+        // and thus "Not Responding" will occur and a crash will snowball into the window too.
+        // This is similar to what Win32 does with its event buffer.
+        let mut lock = sync::mutex_lock(&self.sync);
+        loop {
+            // SAFETY: The active buffer is only touched while `sync` is locked
+            let (ev_buf, ..) = unsafe { self.active() };
+            if *lock || ev_buf.len + count > N {
+                *lock = true; // "the condvar should be pinged"
+                sync::condvar_wait(&self.ping, &mut lock);
+            } else {
+                break lock
+            }
+        }
+    }
+
+    /// Gets the events that were pushed before the last swap.
+    ///
+    /// # Safety
+    ///
+    /// Only the owning thread may call this, and never at the same time as [`swap`](Self::swap).
+    pub unsafe fn backbuffer(&self) -> &[T] {
+        // The backbuffer contains the "last" events, so use the opposite the active one
+        self.inactive().0.slice()
+    }
+
+    /// Gets the text that the events in the [`backbuffer`](Self::backbuffer) index into.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`backbuffer`](Self::backbuffer).
+    pub unsafe fn backbuffer_text(&self) -> &str {
+        &self.inactive().1
+    }
+
+    /// Gets the paths that the events in the [`backbuffer`](Self::backbuffer) index into.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`backbuffer`](Self::backbuffer).
+    pub unsafe fn backbuffer_paths(&self) -> &[PathBuf] {
+        &self.inactive().2
+    }
+
+    /// Pushes events to the active buffer. Only the window thread may call this.
+    pub fn push_many(&self, events: &[T]) {
+        let _lock = self.lock_with_room(events.len());
+
+        // SAFETY: `sync` is locked, and there's enough room
+        let _ = unsafe { self.active() }.0.push_many(events);
+    }
+
+    /// Pushes an event which refers to some text, given its offset & length in bytes.
+    /// Only the window thread may call this.
+    #[cfg_attr(not(feature = "input"), allow(dead_code))]
+    pub fn push_with_text(&self, text: &str, f: impl FnOnce(usize, usize) -> T) {
+        let _lock = self.lock_with_room(1);
+
+        // SAFETY: `sync` is locked, and there's enough room
+        let (ev_buf, text_buf, _) = unsafe { self.active() };
+        let offset = text_buf.len();
+        text_buf.push_str(text);
+        let _ = ev_buf.push(&f(offset, text.len()));
+    }
+
+    /// Pushes an event which refers to some paths, given the index of the first one & how many there are.
+    /// Only the window thread may call this.
+    pub fn push_with_paths(&self, paths: &[PathBuf], f: impl FnOnce(usize, usize) -> T) {
+        let _lock = self.lock_with_room(1);
+
+        // SAFETY: `sync` is locked, and there's enough room
+        let (ev_buf, _, path_buf) = unsafe { self.active() };
+        let offset = path_buf.len();
+        path_buf.extend_from_slice(paths);
+        let _ = ev_buf.push(&f(offset, paths.len()));
+    }
+
+    /// Clears the backbuffer and makes it the active one.
+    ///
+    /// # Safety
+    ///
+    /// Only the owning thread may call this, and no slices from [`backbuffer`](Self::backbuffer) may be alive.
+    pub unsafe fn swap(&self) {
+        let mut lock = sync::mutex_lock(&self.sync);
+
+        // clear backbuffer, switch to it
+        let is_primary = &mut *self.is_primary.get();
+        let (ev_buf, text_buf, path_buf) = if *is_primary {
+            &mut *self.secondary.get()
+        } else {
+            &mut *self.primary.get()
+        };
+        ev_buf.clear();
+        text_buf.clear();
+        path_buf.clear();
+        *is_primary = !*is_primary;
+
+        // deal with potential lockup (see `push_many`)
+        if *lock {
+            *lock = false; // "the request to ping the condvar is processed"
+            sync::condvar_notify1(&self.ping);
+        }
+    }
+}
+
+/// Mouse emulation from touch, for backends where it's up to us rather than the system.
+///
+/// The first finger to touch down acts as the left mouse button, until it's lifted.
+#[cfg(feature = "input")]
+#[cfg_attr(windows, allow(dead_code))]
+#[derive(Default)]
+pub struct TouchMouse {
+    finger: Option<u64>,
+}
+
+#[cfg(feature = "input")]
+#[cfg_attr(windows, allow(dead_code))]
+impl TouchMouse {
+    /// Gets the mouse events to report alongside a touch, if any.
+    pub fn emulate(&mut self, touch: &Touch, scale: Scale, modifiers: Modifiers) -> [Option<Event>; 2] {
+        let motion = Some(Event::MouseMove((touch.position, scale)));
+        match touch.phase {
+            TouchPhase::Start if self.finger.is_none() => {
+                self.finger = Some(touch.id);
+                [motion, Some(Event::MouseDown((MouseButton::Left, modifiers)))]
+            },
+            _ if self.finger != Some(touch.id) => [None, None],
+            TouchPhase::Start => [None, None],
+            TouchPhase::Move => [motion, None],
+            TouchPhase::End | TouchPhase::Cancel => {
+                self.finger = None;
+                [motion, Some(Event::MouseUp((MouseButton::Left, modifiers)))]
+            },
+        }
+    }
+}
+
+/// Clips the damage given to `Window::present_pixels` to an image of `size`, leaving out anything empty.
+///
+/// Without damage, it's the whole image.
+pub fn clip_damage(damage: Option<&[Rect]>, (width, height): (u32, u32)) -> Vec<Rect> {
+    let image = Rect { x: 0, y: 0, width, height };
+    match damage {
+        Some(damage) => damage.iter().filter_map(|rect| rect.intersection(image)).collect(),
+        None => vec![image],
+    }
+}
+
+/// Scales a rectangle in an image of size `from` to one of size `to`, rounding outwards to cover every pixel it touches.
+#[cfg_attr(feature = "headless", allow(dead_code))]
+pub fn scale_rect(rect: Rect, from: (u32, u32), to: (u32, u32)) -> Rect {
+    if from == to {
+        return rect
+    }
+    let floor = |v: i64, from: u32, to: u32| v * i64::from(to) / i64::from(from);
+    let ceil = |v: i64, from: u32, to: u32| (v * i64::from(to) + i64::from(from) - 1) / i64::from(from);
+    let left = floor(rect.x.into(), from.0, to.0);
+    let top = floor(rect.y.into(), from.1, to.1);
+    let right = ceil(i64::from(rect.x) + i64::from(rect.width), from.0, to.0);
+    let bottom = ceil(i64::from(rect.y) + i64::from(rect.height), from.1, to.1);
+    Rect { x: left as i32, y: top as i32, width: (right - left) as u32, height: (bottom - top) as u32 }
+}
+
+/// Copies an image of size `from` into one of size `to`, stretching it with nearest neighbour sampling.
+///
+/// Only `area` of the destination is written, which has to be inside of it.
+#[cfg_attr(any(windows, feature = "headless"), allow(dead_code))]
+pub fn stretch_pixels(src: &[u32], from: (u32, u32), dst: &mut [u32], to: (u32, u32), area: Rect) {
+    let (x, width) = (area.x as usize, area.width as usize);
+    for y in area.y as usize..area.y as usize + area.height as usize {
+        let dst_row = &mut dst[y * to.0 as usize..][x..x + width];
+        if from == to {
+            dst_row.copy_from_slice(&src[y * from.0 as usize..][x..x + width]);
+        } else {
+            // Sampling the middle of each pixel, so that shrinking by half takes every other pixel evenly
+            let src_y = (2 * y as u64 + 1) * u64::from(from.1) / (2 * u64::from(to.1));
+            let src_row = &src[src_y as usize * from.0 as usize..][..from.0 as usize];
+            for (dst_x, pixel) in (x..).zip(dst_row) {
+                *pixel = src_row[((2 * dst_x as u64 + 1) * u64::from(from.0) / (2 * u64::from(to.0))) as usize];
+            }
+        }
+    }
+}
+
+/// Where a window thread reports the result of creating its window, for the spawning thread to wait on.
+pub type CreateSlot<T> = (sync::Mutex<Option<Result<T, Error>>>, sync::Condvar);
+
+/// The window thread's end of a [`CreateSlot`].
+///
+/// If this is dropped before anything was sent, which only happens if the window thread panics,
+/// [`Error::ThreadPanicked`] is sent so that the spawning thread isn't left waiting forever.
+pub struct CreateSender<T>(pub Arc<CreateSlot<T>>);
+
+impl<T> CreateSender<T> {
+    pub fn send(&self, result: Result<T, Error>) {
+        let (mutex, condvar) = &*self.0;
+        let mut lock = sync::mutex_lock(mutex);
+        *lock = Some(result);
+        sync::condvar_notify1(condvar);
+    }
+}
+
+impl<T> Drop for CreateSender<T> {
+    fn drop(&mut self) {
+        let (mutex, condvar) = &*self.0;
+        let mut lock = sync::mutex_lock(mutex);
+        if lock.is_none() {
+            *lock = Some(Err(Error::ThreadPanicked));
+            sync::condvar_notify1(condvar);
+        }
+    }
+}
+
+/// Waits on a [`CreateSlot`] until the window thread has sent its result.
+pub fn create_wait<T>(slot: &CreateSlot<T>) -> Result<T, Error> {
+    let (mutex, condvar) = slot;
+    let mut lock = sync::mutex_lock(mutex);
+    loop {
+        if let Some(result) = lock.take() {
+            break result
+        } else {
+            sync::condvar_wait(condvar, &mut lock);
+        }
+    }
 }
 
 /// Wrapper for working with both `std` and `parking_lot`.
@@ -245,6 +540,13 @@ pub(crate) mod sync {
         cvar.notify_one();
     }
 
+    // Only the unix backends wake more than one thread at a time
+    #[cfg(unix)]
+    #[inline]
+    pub fn condvar_notify_all(cvar: &Condvar) {
+        cvar.notify_all();
+    }
+
     pub fn condvar_wait<T>(cvar: &Condvar, guard: &mut MutexGuard<T>) {
         // The signature in `std` is quite terrible and CONSUMES the guard
         // HACK: We "move it out" for the duration of the wait
@@ -255,7 +557,7 @@ pub(crate) mod sync {
         }
     }
 
-    pub fn mutex_lock<T>(mtx: &Mutex<T>) -> MutexGuard<T> {
+    pub fn mutex_lock<T>(mtx: &Mutex<T>) -> MutexGuard<'_, T> {
         mtx.lock().expect("mutex poisoned (this is a bug)")
     }
 }
@@ -268,13 +570,83 @@ pub(crate) mod sync {
         let _ = cvar.notify_one();
     }
 
+    // Only the unix backends wake more than one thread at a time
+    #[cfg(unix)]
+    #[inline]
+    pub fn condvar_notify_all(cvar: &Condvar) {
+        let _ = cvar.notify_all();
+    }
+
     #[inline]
     pub fn condvar_wait<T>(cvar: &Condvar, guard: &mut MutexGuard<T>) {
         cvar.wait(guard);
     }
 
     #[inline]
-    pub fn mutex_lock<T>(mtx: &Mutex<T>) -> MutexGuard<T> {
+    pub fn mutex_lock<T>(mtx: &Mutex<T>) -> MutexGuard<'_, T> {
         mtx.lock()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stretching() {
+        let src = [1, 2, 3, 4];
+        let mut dst = [0; 16];
+        let whole = Rect { x: 0, y: 0, width: 4, height: 4 };
+        stretch_pixels(&src, (2, 2), &mut dst, (4, 4), whole);
+        assert_eq!(dst, [1, 1, 2, 2, 1, 1, 2, 2, 3, 3, 4, 4, 3, 3, 4, 4]);
+
+        let damage = Rect { x: 1, y: 0, width: 1, height: 1 };
+        assert_eq!(scale_rect(damage, (2, 2), (4, 4)), Rect { x: 2, y: 0, width: 2, height: 2 });
+        assert_eq!(scale_rect(damage, (2, 2), (3, 3)), Rect { x: 1, y: 0, width: 2, height: 2 });
+        assert_eq!(clip_damage(Some(&[Rect { x: -1, y: 1, width: 2, height: 5 }]), (2, 2)), [Rect {
+            x: 0,
+            y: 1,
+            width: 1,
+            height: 1,
+        }]);
+    }
+
+    #[test]
+    fn damage_clipping() {
+        let rect = |x, y, width, height| Rect { x, y, width, height };
+        assert_eq!(clip_damage(None, (640, 480)), [rect(0, 0, 640, 480)]);
+        assert_eq!(clip_damage(Some(&[]), (640, 480)), []);
+
+        // Partly outside is cut down to the image, while entirely outside or empty is left out
+        let damage = [
+            rect(600, 400, 100, 100),
+            rect(-10, -10, 20, 20),
+            rect(640, 0, 10, 10),
+            rect(-20, 0, 20, 10),
+            rect(10, 10, 0, 5),
+            rect(100, 100, 10, 10),
+        ];
+        assert_eq!(clip_damage(Some(&damage), (640, 480)), [
+            rect(600, 400, 40, 80),
+            rect(0, 0, 10, 10),
+            rect(100, 100, 10, 10),
+        ]);
+    }
+
+    #[test]
+    fn scaled_damage_covers_every_pixel() {
+        let rect = |x, y, width, height| Rect { x, y, width, height };
+        assert_eq!(scale_rect(rect(1, 2, 3, 4), (10, 10), (10, 10)), rect(1, 2, 3, 4));
+
+        // Growing by a third: 1..3 is 1.33..4, which rounds out to 1..4
+        assert_eq!(scale_rect(rect(1, 1, 2, 2), (3, 3), (4, 4)), rect(1, 1, 3, 3));
+        assert_eq!(scale_rect(rect(1, 0, 1, 3), (3, 3), (4, 4)), rect(1, 0, 2, 4));
+
+        // Shrinking by half: a single pixel still covers the one it lands on, and odd edges round out
+        assert_eq!(scale_rect(rect(3, 3, 1, 1), (8, 8), (4, 4)), rect(1, 1, 1, 1));
+        assert_eq!(scale_rect(rect(1, 0, 2, 8), (8, 8), (4, 4)), rect(0, 0, 2, 4));
+
+        // Different ratios for each axis
+        assert_eq!(scale_rect(rect(0, 1, 1, 1), (2, 3), (5, 2)), rect(0, 0, 3, 2));
+    }
+}
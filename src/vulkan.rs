@@ -0,0 +1,60 @@
+//! Vulkan surface creation, without linking to Vulkan.
+//!
+//! Each backend has its own surface extension, which has to be enabled on the `VkInstance`
+//! along with `VK_KHR_surface`, as listed by [`required_instance_extensions`]:
+//!
+//! - Win32: `VK_KHR_win32_surface`
+//! - X11: `VK_KHR_xlib_surface`
+//! - Wayland: `VK_KHR_wayland_surface`
+//!
+//! Surfaces are then made with [`Window::create_vulkan_surface`](crate::window::Window::create_vulkan_surface).
+//! Nothing here needs the Vulkan headers or a Vulkan crate, the handles are passed around as their C types,
+//! so they can be cast to and from whatever bindings are in use.
+
+#![allow(non_camel_case_types)]
+
+use crate::platform::imp;
+use std::{
+    ffi::CStr,
+    os::raw::{c_char, c_void},
+};
+
+/// A `VkInstance` handle.
+pub type VkInstance = *mut c_void;
+
+/// A `VkSurfaceKHR` handle, which is 64 bits wide on every platform.
+pub type VkSurfaceKHR = u64;
+
+/// A `VkResult` code, where negative values are errors.
+pub type VkResult = i32;
+
+/// The generic function pointer returned by `vkGetInstanceProcAddr`.
+pub type PFN_vkVoidFunction = unsafe extern "system" fn();
+
+/// A pointer to `vkGetInstanceProcAddr`, which is how the surface creation function is looked up.
+pub type PFN_vkGetInstanceProcAddr =
+    unsafe extern "system" fn(instance: VkInstance, name: *const c_char) -> Option<PFN_vkVoidFunction>;
+
+// The rest is for the native backends, which `headless` compiles out
+#[cfg_attr(feature = "headless", allow(dead_code))]
+pub(crate) type VkFlags = u32;
+#[cfg_attr(feature = "headless", allow(dead_code))]
+pub(crate) type VkStructureType = i32;
+
+#[cfg_attr(feature = "headless", allow(dead_code))]
+pub(crate) const VK_SUCCESS: VkResult = 0;
+
+#[cfg_attr(feature = "headless", allow(dead_code))]
+pub(crate) const VK_KHR_SURFACE_EXTENSION_NAME: &CStr =
+    unsafe { CStr::from_bytes_with_nul_unchecked(c_string!("VK_KHR_surface")) };
+
+/// Lists the instance extensions that the current backend needs to create surfaces.
+///
+/// On free Unix-likes, this depends on whether windows would be created with Wayland or X11,
+/// which is decided the same way as when [`building`](crate::window::WindowBuilder::build) one.
+///
+/// With the `headless` backend, this is empty, as there's nothing to present to.
+#[inline]
+pub fn required_instance_extensions() -> &'static [&'static CStr] {
+    imp::vulkan_instance_extensions()
+}
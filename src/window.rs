@@ -1,13 +1,15 @@
 //! yeah
 
 use crate::{
+    clipboard::Clipboard,
     error::Error,
-    event::Event,
-    monitor::{/*Point, */ Scale, Size},
+    event::{Event, EventPaths, EventText},
+    monitor::{Monitor, Point, Rect, Scale, Size, VideoMode},
     platform::imp,
     util::{self, MaybeArc},
+    vulkan::{PFN_vkGetInstanceProcAddr, VkInstance, VkSurfaceKHR},
 };
-use std::borrow::Cow;
+use std::{borrow::Cow, path::PathBuf, sync::Arc};
 
 /// Represents the availability of the minimize, maximize, and close buttons on a [`Window`].
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -37,10 +39,12 @@ impl Controls {
         Self::new(true, false, true)
     }
 
+    #[cfg_attr(not(windows), allow(dead_code))]
     pub(crate) fn to_bits(&self) -> u32 {
         (self.minimize as u32) << 2 | (self.maximize as u32) << 1 | self.close as u32
     }
 
+    #[cfg_attr(not(windows), allow(dead_code))]
     pub(crate) fn from_bits(x: u32) -> Self {
         Self {
             minimize: x & (1 << 2) != 0,
@@ -104,9 +108,239 @@ pub enum Cursor {
     Wait,
 }
 
+/// A cursor made out of RGBA images, which can be shown with [`Window::set_cursor`].
+///
+/// There can be an image for each DPI scale, from which the closest one is picked for the window's scale.
+/// Cloning is cheap, as the images are shared.
+///
+/// # Example
+/// ```
+/// use ramen::window::CustomCursor;
+///
+/// // A 2x2 cursor, and a 4x4 one for a DPI scale of 2.0
+/// let cursor = CustomCursor::new(&[255; 2 * 2 * 4], (2, 2), (0, 0))
+///     .and_then(|cursor| cursor.with_image(2.0, &[255; 4 * 4 * 4], (4, 4), (0, 0)))
+///     .unwrap();
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct CustomCursor {
+    /// Sorted by scale, smallest first.
+    pub(crate) images: Arc<[CursorImage]>,
+}
+
+/// A single image of a [`CustomCursor`].
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct CursorImage {
+    pub(crate) rgba: Box<[u8]>,
+    pub(crate) size: (u32, u32),
+    pub(crate) hotspot: (u32, u32),
+    pub(crate) scale: Scale,
+}
+
+impl CustomCursor {
+    /// Creates a cursor from an image for a DPI scale of 1.0.
+    ///
+    /// The pixels are RGBA with 8 bits per channel and straight (not premultiplied) alpha,
+    /// going row by row from the top-left. The hotspot is the pixel that's the "tip" of the cursor.
+    ///
+    /// This fails with [`Error::InvalidParameter`] if the pixels don't match the size,
+    /// or the hotspot isn't within the image.
+    pub fn new(rgba: &[u8], size: (u32, u32), hotspot: (u32, u32)) -> Result<Self, Error> {
+        let image = CursorImage::new(1.0, rgba, size, hotspot)?;
+        Ok(Self { images: Arc::new([image]) })
+    }
+
+    /// Adds an image for another DPI scale, replacing any image that was already there for it.
+    ///
+    /// This fails in the same cases as [`new`](Self::new), as well as if the scale isn't positive.
+    pub fn with_image(self, scale: Scale, rgba: &[u8], size: (u32, u32), hotspot: (u32, u32)) -> Result<Self, Error> {
+        let image = CursorImage::new(scale, rgba, size, hotspot)?;
+        let mut images: Vec<CursorImage> = self.images.iter().filter(|i| i.scale != scale).cloned().collect();
+        let index = images.iter().position(|i| i.scale > scale).unwrap_or(images.len());
+        images.insert(index, image);
+        Ok(Self { images: images.into() })
+    }
+
+    /// Picks the image for a DPI scale, which is the smallest one that's big enough, or else the biggest.
+    #[cfg_attr(feature = "headless", allow(dead_code))]
+    pub(crate) fn image(&self, scale: Scale) -> &CursorImage {
+        let images = &self.images;
+        images.iter().find(|i| i.scale >= scale).unwrap_or(&images[images.len() - 1])
+    }
+}
+
+impl CursorImage {
+    fn new(scale: Scale, rgba: &[u8], (width, height): (u32, u32), hotspot: (u32, u32)) -> Result<Self, Error> {
+        if !(scale > 0.0 && scale.is_finite()) {
+            return Err(Error::InvalidParameter("cursor scale must be positive"))
+        }
+        if !is_rgba_of_size(rgba, (width, height)) {
+            return Err(Error::InvalidParameter("cursor pixels must be RGBA and match the size"))
+        }
+        if hotspot.0 >= width || hotspot.1 >= height {
+            return Err(Error::InvalidParameter("cursor hotspot must be inside the image"))
+        }
+        Ok(Self { rgba: rgba.into(), size: (width, height), hotspot, scale })
+    }
+}
+
+/// Checks that an image isn't empty, and that its pixels are RGBA and fill exactly the size.
+pub(crate) fn is_rgba_of_size(rgba: &[u8], (width, height): (u32, u32)) -> bool {
+    width != 0 && height != 0 && rgba.len() as u64 == width as u64 * height as u64 * 4
+}
+
+/// Either a system [`Cursor`] or a [`CustomCursor`], as shown by [`Window::set_cursor`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum CursorIcon {
+    System(Cursor),
+    Custom(CustomCursor),
+}
+
+impl From<Cursor> for CursorIcon {
+    fn from(cursor: Cursor) -> Self {
+        Self::System(cursor)
+    }
+}
+
+impl From<CustomCursor> for CursorIcon {
+    fn from(cursor: CustomCursor) -> Self {
+        Self::Custom(cursor)
+    }
+}
+
+impl From<&CustomCursor> for CursorIcon {
+    fn from(cursor: &CustomCursor) -> Self {
+        Self::Custom(cursor.clone())
+    }
+}
+
+/// Represents how the cursor is held inside of a [`Window`], see [`Window::set_cursor_grab`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum CursorGrab {
+    /// The cursor moves freely.
+    None,
+
+    /// The cursor can't leave the window's inner area.
+    Confined,
+
+    /// The cursor stays where it is, such as for camera controls with
+    /// [raw mouse motion](Window::set_raw_mouse_motion).
+    Locked,
+}
+
+/// A window icon made out of RGBA images, which can be set with [`Window::set_icon`].
+///
+/// The system shows icons at different sizes, such as a small one in the title bar and a large one in the taskbar,
+/// so there can be an image for each size, from which the closest one is picked. Cloning is cheap, as the images are shared.
+///
+/// # Example
+/// ```
+/// use ramen::window::Icon;
+///
+/// // A 16x16 icon for the title bar, and a 32x32 one for the taskbar
+/// let icon = Icon::new(&[255; 16 * 16 * 4], (16, 16))
+///     .and_then(|icon| icon.with_image(&[255; 32 * 32 * 4], (32, 32)))
+///     .unwrap();
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Icon {
+    /// Sorted by width, smallest first.
+    pub(crate) images: Arc<[IconImage]>,
+}
+
+/// A single image of an [`Icon`].
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct IconImage {
+    pub(crate) rgba: Box<[u8]>,
+    pub(crate) size: (u32, u32),
+}
+
+impl Icon {
+    /// Creates an icon from a single image, which is scaled by the system to whatever size it needs.
+    ///
+    /// The pixels are RGBA with 8 bits per channel and straight (not premultiplied) alpha,
+    /// going row by row from the top-left.
+    ///
+    /// This fails with [`Error::InvalidParameter`] if the pixels don't match the size.
+    pub fn new(rgba: &[u8], size: (u32, u32)) -> Result<Self, Error> {
+        let image = IconImage::new(rgba, size)?;
+        Ok(Self { images: Arc::new([image]) })
+    }
+
+    /// Adds an image of another size, replacing any image that was already there with the same width.
+    ///
+    /// This fails in the same cases as [`new`](Self::new).
+    pub fn with_image(self, rgba: &[u8], size: (u32, u32)) -> Result<Self, Error> {
+        let image = IconImage::new(rgba, size)?;
+        let mut images: Vec<IconImage> = self.images.iter().filter(|i| i.size.0 != size.0).cloned().collect();
+        let index = images.iter().position(|i| i.size.0 > size.0).unwrap_or(images.len());
+        images.insert(index, image);
+        Ok(Self { images: images.into() })
+    }
+
+    /// Picks the image for a width in pixels, which is the smallest one that's big enough, or else the biggest.
+    #[cfg_attr(any(not(windows), feature = "headless"), allow(dead_code))]
+    pub(crate) fn image(&self, width: u32) -> &IconImage {
+        let images = &self.images;
+        images.iter().find(|i| i.size.0 >= width).unwrap_or(&images[images.len() - 1])
+    }
+}
+
+impl IconImage {
+    fn new(rgba: &[u8], size: (u32, u32)) -> Result<Self, Error> {
+        if !is_rgba_of_size(rgba, size) {
+            return Err(Error::InvalidParameter("icon pixels must be RGBA and match the size"))
+        }
+        Ok(Self { rgba: rgba.into(), size })
+    }
+}
+
+/// Represents a fullscreen mode of a [`Window`], and the [`Monitor`] it's on.
+///
+/// Leaving fullscreen restores the window's previous style and placement.
+/// If the monitor has been disconnected since it was queried, the window stays on the one it's on.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Fullscreen {
+    /// A borderless window covering an entire monitor, leaving its video mode as it is.
+    /// With `None`, that's the monitor the window is on.
+    ///
+    /// This is the one you usually want, as switching between windows is instant.
+    Borderless(Option<Monitor>),
+
+    /// Exclusive fullscreen, switching a monitor to one of its [`video_modes`](Monitor::video_modes).
+    ///
+    /// If the video mode can't be set, this falls back to [`Borderless`](Self::Borderless) on the same monitor.
+    ///
+    /// Platform-specific:
+    /// - Wayland: Video modes can't be changed, so this is always the same as borderless.
+    /// - X11: The video mode is set with RandR, which the window manager has to cope with. Unlike on Win32,
+    ///   it's not reset by the system if the process crashes, only when the window leaves fullscreen.
+    Exclusive(Monitor, VideoMode),
+}
+
+impl Fullscreen {
+    /// Gets the monitor that was asked for, if any.
+    #[cfg_attr(feature = "headless", allow(dead_code))]
+    pub(crate) fn monitor(&self) -> Option<&Monitor> {
+        match self {
+            Self::Borderless(monitor) => monitor.as_ref(),
+            Self::Exclusive(monitor, _) => Some(monitor),
+        }
+    }
+}
+
+/// A pixel of an image given to [`Window::present_pixels`], as `0x00RRGGBB` (the top byte is ignored).
+///
+/// Unlike the RGBA bytes of an [`Icon`] or [`CustomCursor`], this is the format windows are drawn from
+/// on every platform, so frames are copied as they are, instead of converting every pixel of every frame.
+pub type Xrgb8888 = u32;
+
 /// Represents an open window. Dropping it closes the window.
 ///
 /// To instantiate windows, use a [`builder`](Self::builder).
+///
+/// With the `raw-window-handle` feature, this implements `HasWindowHandle` and `HasDisplayHandle`,
+/// for creating surfaces with graphics APIs and the libraries wrapping them.
 pub struct Window(pub(crate) imp::WindowRepr);
 
 /// Builder for creating [`Window`] instances.
@@ -114,11 +348,23 @@ pub struct Window(pub(crate) imp::WindowRepr);
 /// To create a builder, use [`Window::builder`].
 #[derive(Clone)]
 pub struct WindowBuilder {
+    pub(crate) accept_drops: bool,
     pub(crate) class_name: MaybeArc<str>,
-    pub(crate) cursor: Cursor,
+    pub(crate) cursor: CursorIcon,
+    pub(crate) fullscreen: Option<Fullscreen>,
+    pub(crate) icon: Option<Icon>,
+    #[cfg(feature = "input")]
+    pub(crate) ime_allowed: bool,
     pub(crate) inner_size: Size,
+    pub(crate) position: Option<Point>,
     pub(crate) style: Style,
+    #[cfg(feature = "input")]
+    pub(crate) raw_mouse_motion: bool,
+    #[cfg(feature = "input")]
+    pub(crate) text_input: bool,
     pub(crate) title: MaybeArc<str>,
+    #[cfg(feature = "input")]
+    pub(crate) touch_emulates_mouse: bool,
 }
 
 impl Window {
@@ -162,6 +408,71 @@ impl Window {
         self.0.swap_events()
     }
 
+    /// Gets the text attached to an event from the current [`events`](Self::events).
+    ///
+    /// # Panics
+    ///
+    /// May panic if the [`EventText`] is from before the last [`swap_events`](Self::swap_events).
+    #[inline]
+    pub fn event_text(&self, text: EventText) -> &str {
+        &self.0.events_text()[text.offset..][..text.len]
+    }
+
+    /// Gets the paths of the files attached to an event from the current [`events`](Self::events),
+    /// such as [`Event::Drop`].
+    ///
+    /// The paths are exactly as the system gave them, even if they aren't valid Unicode.
+    ///
+    /// # Panics
+    ///
+    /// May panic if the [`EventPaths`] is from before the last [`swap_events`](Self::swap_events).
+    #[inline]
+    pub fn event_paths(&self, paths: EventPaths) -> &[PathBuf] {
+        &self.0.events_paths()[paths.offset..][..paths.len]
+    }
+
+    /// Gets the system clipboard, which is read and written through this window.
+    ///
+    /// For more info, see the [`clipboard`](crate::clipboard) module.
+    #[inline]
+    pub fn clipboard(&self) -> Clipboard<'_> {
+        Clipboard::new(self)
+    }
+
+    /// Creates a Vulkan surface for the window, using the surface extension of the current backend.
+    ///
+    /// The surface creation function is looked up with `get_instance_proc_addr`,
+    /// or if that's `None`, with the system's Vulkan loader (which is only opened then).
+    ///
+    /// If the function can't be found, such as when its extension wasn't enabled,
+    /// this returns [`Error::Unsupported`] with the extension's name. Otherwise, if Vulkan
+    /// fails to make the surface, this returns [`Error::SurfaceCreation`] with the `VkResult`.
+    ///
+    /// For more info, see the [`vulkan`](crate::vulkan) module.
+    ///
+    /// # Safety
+    ///
+    /// - `instance` must be a valid `VkInstance`, created with all of the
+    ///   [`required_instance_extensions`](crate::vulkan::required_instance_extensions) enabled.
+    /// - `get_instance_proc_addr`, if given, must be the `vkGetInstanceProcAddr` that `instance` came from.
+    /// - The surface has to be destroyed with `vkDestroySurfaceKHR` before the window is dropped.
+    #[inline]
+    pub unsafe fn create_vulkan_surface(
+        &self,
+        instance: VkInstance,
+        get_instance_proc_addr: Option<PFN_vkGetInstanceProcAddr>,
+    ) -> Result<VkSurfaceKHR, Error> {
+        self.0.create_vulkan_surface(instance, get_instance_proc_addr)
+    }
+
+    /// Gets the monitor that the window is on, or the one it's mostly on if it spans several.
+    ///
+    /// This is `None` if the window isn't on any monitor, such as on Wayland before it's been shown.
+    #[inline]
+    pub fn current_monitor(&self) -> Option<Monitor> {
+        self.0.current_monitor()
+    }
+
     /// Gets the inner size of the window.
     ///
     /// It should be preferred to cache this and process events to listen for changes,
@@ -189,12 +500,83 @@ impl Window {
         self.0.inner_size()
     }
 
+    /// Gets the position of the top-left corner of the window's inner area, in desktop coordinates.
+    ///
+    /// The [`Point`] variant follows the same DPI mode as [`inner_size`](Self::inner_size).
+    ///
+    /// Wayland doesn't expose window positions, so this is always at the origin there.
+    #[inline]
+    pub fn inner_position(&self) -> (Point, Scale) {
+        self.0.inner_position()
+    }
+
     #[inline]
     pub fn is_dpi_logical(&self) -> bool {
         self.0.is_dpi_logical()
     }
 
-    // TODO: borderless
+    /// Gets the position of the top-left corner of the window's frame, in desktop coordinates.
+    ///
+    /// The [`Point`] variant follows the same DPI mode as [`inner_size`](Self::inner_size).
+    ///
+    /// Wayland doesn't expose window positions, so this is always at the origin there.
+    #[inline]
+    pub fn outer_position(&self) -> (Point, Scale) {
+        self.0.outer_position()
+    }
+
+    /// Copies an image into the inner area of the window, for drawing without a graphics API.
+    ///
+    /// The pixels are [`Xrgb8888`], going row by row from the top-left, and there have to be
+    /// `width * height` of them. If that isn't the physical size of the inner area, the image is stretched
+    /// to fill it (nearest neighbour), so it's best to follow [`Event::Resize`](crate::event::Event::Resize).
+    ///
+    /// `damage` lists the parts of the image that changed since the last call, in the image's pixels,
+    /// so that only those are copied. With `None`, the whole image is.
+    ///
+    /// The last image is kept by the window, which redraws parts of itself from it when the system asks.
+    /// This shouldn't be mixed with OpenGL or Vulkan on the same window.
+    ///
+    /// This fails with [`Error::InvalidParameter`] if the pixels don't match the size.
+    ///
+    /// Platform-specific:
+    /// - Win32: Copied with `SetDIBitsToDevice`, or `StretchDIBits` when stretching.
+    /// - X11: Copied through shared memory with MIT-SHM, or with `XPutImage` if the server can't do that,
+    ///   such as over the network. Only 24-bit TrueColor visuals are supported, which is what servers use.
+    ///   Once the window has an OpenGL context or Vulkan surface, this does nothing.
+    /// - Wayland: Copied into a `wl_shm` buffer, which is attached to the window as a whole.
+    pub fn present_pixels(
+        &self,
+        pixels: &[Xrgb8888],
+        width: u32,
+        height: u32,
+        damage: Option<&[Rect]>,
+    ) -> Result<(), Error> {
+        let len = width as usize * height as usize;
+        if len == 0 || pixels.len() != len {
+            return Err(Error::InvalidParameter("pixels must match the size, which can't be zero"))
+        }
+        let damage = util::clip_damage(damage, (width, height));
+        if damage.is_empty() {
+            return Ok(())
+        }
+        self.0.present_pixels(pixels, (width, height), &damage)
+    }
+
+    /// Sets whether the window is without a border, keeping the inner size the same.
+    ///
+    /// Borderless windows can't be resized by dragging the edges, regardless of
+    /// [`set_resizable`](Self::set_resizable).
+    #[inline]
+    pub fn set_borderless(&self, borderless: bool) {
+        self.0.set_borderless(borderless);
+    }
+
+    /// Non-blocking variant of [`set_borderless`](Self::set_borderless).
+    #[inline]
+    pub fn set_borderless_async(&self, borderless: bool) {
+        self.0.set_borderless_async(borderless);
+    }
 
     /// Sets the availability of the window controls.
     ///  `None` indicates that no control menu is desired.
@@ -210,15 +592,90 @@ impl Window {
     }
 
     /// Sets the cursor that's shown when the mouse is inside of the window's inner area.
+    ///
+    /// This can be either a system [`Cursor`] or a [`CustomCursor`].
     #[inline]
-    pub fn set_cursor(&self, cursor: Cursor) {
-        self.0.set_cursor(cursor);
+    pub fn set_cursor<T>(&self, cursor: T)
+    where
+        T: Into<CursorIcon>,
+    {
+        self.0.set_cursor(cursor.into());
     }
 
     /// Non-blocking variant of [`set_cursor`](Self::set_cursor).
     #[inline]
-    pub fn set_cursor_async(&self, cursor: Cursor) {
-        self.0.set_cursor_async(cursor);
+    pub fn set_cursor_async<T>(&self, cursor: T)
+    where
+        T: Into<CursorIcon>,
+    {
+        self.0.set_cursor_async(cursor.into());
+    }
+
+    /// Sets whether the cursor is confined to the window's inner area, locked in place, or free.
+    ///
+    /// The grab is only held while the window has keyboard focus and isn't minimized,
+    /// being released when it loses focus and taken again when it regains it.
+    ///
+    /// Platform-specific:
+    /// - Wayland: Needs the compositor to support `zwp_pointer_constraints_v1`.
+    /// - X11: Locking is emulated by moving the cursor back whenever it moves.
+    #[inline]
+    pub fn set_cursor_grab(&self, grab: CursorGrab) {
+        self.0.set_cursor_grab(grab);
+    }
+
+    /// Non-blocking variant of [`set_cursor_grab`](Self::set_cursor_grab).
+    #[inline]
+    pub fn set_cursor_grab_async(&self, grab: CursorGrab) {
+        self.0.set_cursor_grab_async(grab);
+    }
+
+    /// Moves the cursor to a position relative to the top-left of the inner area.
+    ///
+    /// A [`Logical`](Point::Logical) position is scaled by the window's current DPI.
+    /// While the cursor is [locked](CursorGrab::Locked), this is where it's locked to.
+    ///
+    /// Platform-specific:
+    /// - Wayland: The cursor can't be moved, so this only works while locked,
+    ///   where it's a hint for where the cursor should be once unlocked.
+    #[inline]
+    pub fn set_cursor_position(&self, position: Point) {
+        self.0.set_cursor_position(position);
+    }
+
+    /// Non-blocking variant of [`set_cursor_position`](Self::set_cursor_position).
+    #[inline]
+    pub fn set_cursor_position_async(&self, position: Point) {
+        self.0.set_cursor_position_async(position);
+    }
+
+    /// Sets whether the window is fullscreen, and in which mode. `None` leaves fullscreen.
+    ///
+    /// Leaving fullscreen restores the style and placement the window had beforehand.
+    #[inline]
+    pub fn set_fullscreen(&self, fullscreen: Option<Fullscreen>) {
+        self.0.set_fullscreen(fullscreen)
+    }
+
+    /// Non-blocking variant of [`set_fullscreen`](Self::set_fullscreen).
+    #[inline]
+    pub fn set_fullscreen_async(&self, fullscreen: Option<Fullscreen>) {
+        self.0.set_fullscreen_async(fullscreen)
+    }
+
+    /// Sets the icon shown in the title bar and taskbar, or the system's default icon for `None`.
+    ///
+    /// Platform-specific:
+    /// - Wayland: Not supported, as icons come from the desktop entry matching the class name.
+    #[inline]
+    pub fn set_icon(&self, icon: Option<Icon>) {
+        self.0.set_icon(icon);
+    }
+
+    /// Non-blocking variant of [`set_icon`](Self::set_icon).
+    #[inline]
+    pub fn set_icon_async(&self, icon: Option<Icon>) {
+        self.0.set_icon_async(icon);
     }
 
     /// BRUH
@@ -232,6 +689,51 @@ impl Window {
         self.0.set_inner_size(size)
     }
 
+    /// Sets whether an input method editor (IME) can be used to type text into the window,
+    /// which is reported with [`Event::Ime`](crate::event::Event::Ime).
+    ///
+    /// This only has an effect while [text input](Self::set_text_input) is enabled.
+    /// Since the IME takes over keystrokes while composing, it's best only allowed while a text field is focused.
+    ///
+    /// Platform-specific:
+    /// - X11: The input method is opened in the locale the program is in when the window is created.
+    ///   Programs start out in the "C" locale, where most input methods won't open and dead keys don't compose,
+    ///   and since the locale is shared by the whole process, it's left for the program to set. To use the one
+    ///   the user chose, call `setlocale(LC_CTYPE, "")` (such as through the `libc` crate) before creating windows.
+    #[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "input")))]
+    #[cfg_attr(not(feature = "nightly-docs"), cfg(feature = "input"))]
+    #[inline]
+    pub fn set_ime_allowed(&self, allowed: bool) {
+        self.0.set_ime_allowed(allowed);
+    }
+
+    /// Non-blocking variant of [`set_ime_allowed`](Self::set_ime_allowed).
+    #[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "input")))]
+    #[cfg_attr(not(feature = "nightly-docs"), cfg(feature = "input"))]
+    #[inline]
+    pub fn set_ime_allowed_async(&self, allowed: bool) {
+        self.0.set_ime_allowed_async(allowed);
+    }
+
+    /// Sets the area of the text being edited, relative to the top-left of the inner area,
+    /// so that the IME can place its candidate window next to it without covering it.
+    ///
+    /// A [`Logical`](Point::Logical) position or size is scaled by the window's current DPI.
+    #[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "input")))]
+    #[cfg_attr(not(feature = "nightly-docs"), cfg(feature = "input"))]
+    #[inline]
+    pub fn set_ime_cursor_area(&self, position: Point, size: Size) {
+        self.0.set_ime_cursor_area(position, size);
+    }
+
+    /// Non-blocking variant of [`set_ime_cursor_area`](Self::set_ime_cursor_area).
+    #[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "input")))]
+    #[cfg_attr(not(feature = "nightly-docs"), cfg(feature = "input"))]
+    #[inline]
+    pub fn set_ime_cursor_area_async(&self, position: Point, size: Size) {
+        self.0.set_ime_cursor_area_async(position, size);
+    }
+
     #[inline]
     pub fn set_maximized(&self, maximized: bool) {
         self.0.set_maximized(maximized)
@@ -242,6 +744,47 @@ impl Window {
         self.0.set_maximized_async(maximized)
     }
 
+    /// Moves the top-left corner of the window's frame to a position in desktop coordinates.
+    ///
+    /// While fullscreen, this sets where the window will be once it leaves fullscreen.
+    ///
+    /// Wayland doesn't allow clients to position their windows, so this does nothing there.
+    #[inline]
+    pub fn set_position(&self, position: Point) {
+        self.0.set_position(position);
+    }
+
+    /// Non-blocking variant of [`set_position`](Self::set_position).
+    #[inline]
+    pub fn set_position_async(&self, position: Point) {
+        self.0.set_position_async(position);
+    }
+
+    /// Sets whether physical mouse movement is reported with
+    /// [`Event::MouseMotionRaw`](crate::event::Event::MouseMotionRaw), such as for camera controls.
+    ///
+    /// This is independent of the cursor, so it keeps working while the cursor is hidden or can't move.
+    /// It's only reported while the window has keyboard focus.
+    ///
+    /// Platform-specific:
+    /// - Win32: Raw input is registered per process, so only the last window to enable it gets it.
+    /// - Wayland: Needs the compositor to support `zwp_relative_pointer_manager_v1`.
+    /// - X11: Needs XInput 2.1 or later.
+    #[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "input")))]
+    #[cfg_attr(not(feature = "nightly-docs"), cfg(feature = "input"))]
+    #[inline]
+    pub fn set_raw_mouse_motion(&self, enabled: bool) {
+        self.0.set_raw_mouse_motion(enabled);
+    }
+
+    /// Non-blocking variant of [`set_raw_mouse_motion`](Self::set_raw_mouse_motion).
+    #[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "input")))]
+    #[cfg_attr(not(feature = "nightly-docs"), cfg(feature = "input"))]
+    #[inline]
+    pub fn set_raw_mouse_motion_async(&self, enabled: bool) {
+        self.0.set_raw_mouse_motion_async(enabled);
+    }
+
     /// Sets whether the window is resizable by dragging the edges.
     #[inline]
     pub fn set_resizable(&self, resizable: bool) {
@@ -254,6 +797,25 @@ impl Window {
         self.0.set_resizable_async(resizable);
     }
 
+    /// Sets whether typed text is reported with [`Event::Character`](crate::event::Event::Character).
+    ///
+    /// Turning it off is useful when keys are only used as buttons, as it also stops dead keys
+    /// from being held onto to compose the next character.
+    #[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "input")))]
+    #[cfg_attr(not(feature = "nightly-docs"), cfg(feature = "input"))]
+    #[inline]
+    pub fn set_text_input(&self, enabled: bool) {
+        self.0.set_text_input(enabled);
+    }
+
+    /// Non-blocking variant of [`set_text_input`](Self::set_text_input).
+    #[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "input")))]
+    #[cfg_attr(not(feature = "nightly-docs"), cfg(feature = "input"))]
+    #[inline]
+    pub fn set_text_input_async(&self, enabled: bool) {
+        self.0.set_text_input_async(enabled);
+    }
+
     /// Sets the text that appears in the title bar of the window.
     ///
     /// Note that if the window is borderless, fullscreen, or simply has no title bar,
@@ -283,12 +845,39 @@ impl Window {
     }
 }
 
+// The native handles are destroyed when the window is dropped, which can't happen while they're borrowed.
+// With the `headless` backend, there's nothing to hand out, so both return `HandleError::NotSupported`.
+
+#[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "raw-window-handle")))]
+#[cfg(feature = "raw-window-handle")]
+impl raw_window_handle::HasDisplayHandle for Window {
+    #[inline]
+    fn display_handle(&self) -> Result<raw_window_handle::DisplayHandle<'_>, raw_window_handle::HandleError> {
+        self.0.raw_display_handle().map(|raw| unsafe { raw_window_handle::DisplayHandle::borrow_raw(raw) })
+    }
+}
+
+#[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "raw-window-handle")))]
+#[cfg(feature = "raw-window-handle")]
+impl raw_window_handle::HasWindowHandle for Window {
+    #[inline]
+    fn window_handle(&self) -> Result<raw_window_handle::WindowHandle<'_>, raw_window_handle::HandleError> {
+        self.0.raw_window_handle().map(|raw| unsafe { raw_window_handle::WindowHandle::borrow_raw(raw) })
+    }
+}
+
 impl WindowBuilder {
     pub(crate) const fn new() -> Self {
         Self {
+            accept_drops: false,
             class_name: MaybeArc::Static("ramen_window"),
-            cursor: Cursor::Arrow,
+            cursor: CursorIcon::System(Cursor::Arrow),
+            fullscreen: None,
+            icon: None,
+            #[cfg(feature = "input")]
+            ime_allowed: false,
             inner_size: Size::Logical(800.0, 608.0),
+            position: None,
             style: Style {
                 borderless: false,
                 resizable: true,
@@ -299,16 +888,33 @@ impl WindowBuilder {
                 #[cfg(windows)]
                 tool_window: false,
             },
+            #[cfg(feature = "input")]
+            raw_mouse_motion: false,
+            #[cfg(feature = "input")]
+            text_input: true,
             title: MaybeArc::Static("a nice window"),
+            #[cfg(feature = "input")]
+            touch_emulates_mouse: true,
         }
     }
 
+    /// Creates the window, or fails with an [`Error`] describing why it couldn't.
     pub fn build(&self) -> Result<Window, Error> {
         imp::spawn_window(self).map(Window)
     }
 }
 
 impl WindowBuilder {
+    /// Sets whether files can be dragged & dropped onto the window,
+    /// which is reported by [`Event::DragEnter`] and friends.
+    ///
+    /// Defaults to `false`.
+    #[inline]
+    pub fn accept_drops(&mut self, accept_drops: bool) -> &mut Self {
+        self.accept_drops = accept_drops;
+        self
+    }
+
     /// Sets whether the window is initially without a border.
     ///
     /// Defaults to `false`.
@@ -321,8 +927,8 @@ impl WindowBuilder {
     /// Sets the platform-specific window class name.
     ///
     /// - Win32: `lpszClassName` in
-    /// [`WNDCLASSEXW`](https://docs.microsoft.com/en-us/windows/win32/api/winuser/ns-winuser-wndclassexw)
-    /// - TODO: Other platforms!
+    ///   [`WNDCLASSEXW`](https://docs.microsoft.com/en-us/windows/win32/api/winuser/ns-winuser-wndclassexw)
+    /// - X11: Both the instance and class name in `WM_CLASS`
     ///
     /// Defaults to `"ramen_window"`.
     pub fn class_name<T>(&mut self, class_name: T) -> &mut Self
@@ -352,12 +958,44 @@ impl WindowBuilder {
         self
     }
 
-    /// Sets the initial cursor shown in the window.
+    /// Sets the initial cursor shown in the window, see [`Window::set_cursor`].
     ///
     /// Defaults to [`Cursor::Arrow`].
     #[inline]
-    pub fn cursor(&mut self, cursor: Cursor) -> &mut Self {
-        self.cursor = cursor;
+    pub fn cursor<T>(&mut self, cursor: T) -> &mut Self
+    where
+        T: Into<CursorIcon>,
+    {
+        self.cursor = cursor.into();
+        self
+    }
+
+    /// Sets whether the window is initially fullscreen, and in which mode.
+    ///
+    /// Defaults to `None`.
+    #[inline]
+    pub fn fullscreen(&mut self, fullscreen: Option<Fullscreen>) -> &mut Self {
+        self.fullscreen = fullscreen;
+        self
+    }
+
+    /// Sets the initial icon of the window, see [`Window::set_icon`].
+    ///
+    /// Defaults to `None`.
+    #[inline]
+    pub fn icon(&mut self, icon: Option<Icon>) -> &mut Self {
+        self.icon = icon;
+        self
+    }
+
+    /// Sets whether an input method editor (IME) can initially be used, see [`Window::set_ime_allowed`].
+    ///
+    /// Defaults to `false`.
+    #[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "input")))]
+    #[cfg_attr(not(feature = "nightly-docs"), cfg(feature = "input"))]
+    #[inline]
+    pub fn ime_allowed(&mut self, ime_allowed: bool) -> &mut Self {
+        self.ime_allowed = ime_allowed;
         self
     }
 
@@ -374,6 +1012,28 @@ impl WindowBuilder {
         self
     }
 
+    /// Sets the initial position of the top-left corner of the window's frame, in desktop coordinates.
+    ///
+    /// A [`Logical`](Point::Logical) position is scaled by the DPI of the primary monitor.
+    ///
+    /// Defaults to `None`, which lets the system pick a position.
+    #[inline]
+    pub fn position(&mut self, position: Point) -> &mut Self {
+        self.position = Some(position);
+        self
+    }
+
+    /// Sets whether physical mouse movement is initially reported, see [`Window::set_raw_mouse_motion`].
+    ///
+    /// Defaults to `false`.
+    #[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "input")))]
+    #[cfg_attr(not(feature = "nightly-docs"), cfg(feature = "input"))]
+    #[inline]
+    pub fn raw_mouse_motion(&mut self, raw_mouse_motion: bool) -> &mut Self {
+        self.raw_mouse_motion = raw_mouse_motion;
+        self
+    }
+
     /// Sets whether the window is initially resizable.
     ///
     /// Defaults to `true`.
@@ -392,6 +1052,17 @@ impl WindowBuilder {
         self
     }
 
+    /// Sets whether typed text is initially reported with [`Event::Character`](crate::event::Event::Character).
+    ///
+    /// Defaults to `true`.
+    #[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "input")))]
+    #[cfg_attr(not(feature = "nightly-docs"), cfg(feature = "input"))]
+    #[inline]
+    pub fn text_input(&mut self, text_input: bool) -> &mut Self {
+        self.text_input = text_input;
+        self
+    }
+
     /// Sets the initial window title.
     ///
     /// Defaults to `"a nice window"`.
@@ -422,6 +1093,21 @@ impl WindowBuilder {
         self
     }
 
+    /// Sets whether the first finger touching the window is also reported as the left mouse button,
+    /// alongside [`Event::Touch`](crate::event::Event::Touch).
+    ///
+    /// Turning it off is useful when touch is handled separately, so it isn't handled twice.
+    /// Pens are reported as the mouse either way.
+    ///
+    /// Defaults to `true`.
+    #[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "input")))]
+    #[cfg_attr(not(feature = "nightly-docs"), cfg(feature = "input"))]
+    #[inline]
+    pub fn touch_emulates_mouse(&mut self, touch_emulates_mouse: bool) -> &mut Self {
+        self.touch_emulates_mouse = touch_emulates_mouse;
+        self
+    }
+
     /// Sets whether the window is initially visible.
     ///
     /// Defaults to `true`.
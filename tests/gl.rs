@@ -0,0 +1,65 @@
+//! Tests making an OpenGL context for a real window, which are ignored by default as they need
+//! an X server (for GLX) or Wayland compositor (for EGL) to make the window with.
+//!
+//! Mesa's software rasterizer (llvmpipe) works, such as under Xvfb or weston's headless backend,
+//! which is how CI runs them:
+//!
+//! ```sh
+//! LIBGL_ALWAYS_SOFTWARE=1 env -u WAYLAND_DISPLAY xvfb-run -a cargo test --test gl -- --ignored
+//! ```
+
+#![cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android")), not(feature = "headless")))]
+
+use ramen::{gl::GlContext, window::Window};
+use std::{
+    ffi::CStr,
+    mem,
+    os::raw::{c_float, c_uchar, c_uint, c_void},
+};
+
+const GL_COLOR_BUFFER_BIT: c_uint = 0x4000;
+const GL_VERSION: c_uint = 0x1F02;
+
+type GlClearColor = unsafe extern "system" fn(red: c_float, green: c_float, blue: c_float, alpha: c_float);
+type GlClear = unsafe extern "system" fn(mask: c_uint);
+type GlGetString = unsafe extern "system" fn(name: c_uint) -> *const c_uchar;
+
+#[test]
+#[ignore = "needs OpenGL, such as llvmpipe, and an X server or Wayland compositor"]
+fn context() {
+    let window = Window::builder().title("ramen gl test").build().expect("failed to create a window");
+    let context = unsafe { GlContext::builder().version(3, 3).build(&window) };
+    let context = context.expect("failed to create a context");
+    assert!(context.make_current());
+    assert!(context.is_current());
+
+    unsafe {
+        let get_string = context.get_proc_address("glGetString");
+        let clear_color = context.get_proc_address("glClearColor");
+        let clear = context.get_proc_address("glClear");
+        assert!(!get_string.is_null() && !clear_color.is_null() && !clear.is_null());
+        let get_string = mem::transmute::<*const c_void, GlGetString>(get_string);
+        let clear_color = mem::transmute::<*const c_void, GlClearColor>(clear_color);
+        let clear = mem::transmute::<*const c_void, GlClear>(clear);
+
+        // The version string starts with "major.minor", which is at least what was asked for
+        let version = get_string(GL_VERSION);
+        assert!(!version.is_null());
+        let version = CStr::from_ptr(version.cast()).to_string_lossy();
+        let mut numbers = version.split(|c: char| !c.is_ascii_digit()).map(|n| n.parse::<u8>().unwrap_or(0));
+        let (major, minor) = (numbers.next().unwrap_or(0), numbers.next().unwrap_or(0));
+        assert!((major, minor) >= (3, 3), "got OpenGL {}", version);
+
+        clear_color(1.0, 0.5, 0.0, 1.0);
+        clear(GL_COLOR_BUFFER_BIT);
+    }
+    let (size, scale) = window.inner_size();
+    let (width, height) = size.as_physical(scale);
+    context.resize(width, height);
+    assert!(context.swap_buffers());
+    assert!(context.make_not_current());
+    assert!(!context.is_current());
+
+    // No driver has a version this far out, so it's refused rather than handing back a lesser one
+    assert!(unsafe { GlContext::builder().version(9, 9).build(&window) }.is_err());
+}
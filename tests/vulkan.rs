@@ -0,0 +1,98 @@
+//! Tests making a `VkSurfaceKHR` with a real Vulkan driver, which are ignored by default as they need one,
+//! along with an X server or Wayland compositor to make the window with.
+//!
+//! Mesa's software driver (lavapipe) works, such as under Xvfb, which is how CI runs them:
+//!
+//! ```sh
+//! VK_DRIVER_FILES=/usr/share/vulkan/icd.d/lvp_icd.x86_64.json \
+//!     env -u WAYLAND_DISPLAY xvfb-run -a cargo test --test vulkan -- --ignored
+//! ```
+
+#![cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android")), not(feature = "headless")))]
+#![allow(non_camel_case_types, non_snake_case)]
+
+use ramen::{
+    vulkan::{self, PFN_vkGetInstanceProcAddr, VkInstance, VkResult, VkSurfaceKHR},
+    window::Window,
+};
+use std::{
+    mem,
+    os::raw::{c_char, c_int, c_void},
+    ptr,
+};
+
+const RTLD_NOW: c_int = 2;
+const VK_STRUCTURE_TYPE_INSTANCE_CREATE_INFO: i32 = 1;
+
+extern "C" {
+    fn dlopen(filename: *const c_char, flag: c_int) -> *mut c_void;
+    fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void;
+}
+
+#[repr(C)]
+struct VkInstanceCreateInfo {
+    sType: i32,
+    pNext: *const c_void,
+    flags: u32,
+    pApplicationInfo: *const c_void,
+    enabledLayerCount: u32,
+    ppEnabledLayerNames: *const *const c_char,
+    enabledExtensionCount: u32,
+    ppEnabledExtensionNames: *const *const c_char,
+}
+
+type PFN_vkCreateInstance = unsafe extern "system" fn(
+    info: *const VkInstanceCreateInfo,
+    allocator: *const c_void,
+    instance: *mut VkInstance,
+) -> VkResult;
+type PFN_vkDestroyInstance = unsafe extern "system" fn(instance: VkInstance, allocator: *const c_void);
+type PFN_vkDestroySurfaceKHR =
+    unsafe extern "system" fn(instance: VkInstance, surface: VkSurfaceKHR, allocator: *const c_void);
+
+/// Looks up a Vulkan function by its null-terminated name, as the function pointer type `T`.
+unsafe fn load<T: Copy>(get_instance_proc_addr: PFN_vkGetInstanceProcAddr, instance: VkInstance, name: &[u8]) -> T {
+    let function = get_instance_proc_addr(instance, name.as_ptr().cast());
+    let function = function.unwrap_or_else(|| panic!("missing {}", String::from_utf8_lossy(name)));
+    mem::transmute_copy(&function)
+}
+
+#[test]
+#[ignore = "needs a Vulkan driver, such as lavapipe, and an X server or Wayland compositor"]
+fn surface() {
+    let window = Window::builder().title("ramen vulkan test").build().expect("failed to create a window");
+    unsafe {
+        let loader = dlopen(b"libvulkan.so.1\0".as_ptr().cast(), RTLD_NOW);
+        assert!(!loader.is_null(), "failed to open the Vulkan loader");
+        let get_instance_proc_addr = dlsym(loader, b"vkGetInstanceProcAddr\0".as_ptr().cast());
+        assert!(!get_instance_proc_addr.is_null());
+        let get_instance_proc_addr: PFN_vkGetInstanceProcAddr = mem::transmute(get_instance_proc_addr);
+
+        let extensions = vulkan::required_instance_extensions().iter().map(|ext| ext.as_ptr()).collect::<Vec<_>>();
+        let info = VkInstanceCreateInfo {
+            sType: VK_STRUCTURE_TYPE_INSTANCE_CREATE_INFO,
+            pNext: ptr::null(),
+            flags: 0,
+            pApplicationInfo: ptr::null(),
+            enabledLayerCount: 0,
+            ppEnabledLayerNames: ptr::null(),
+            enabledExtensionCount: extensions.len() as u32,
+            ppEnabledExtensionNames: extensions.as_ptr(),
+        };
+        let create_instance: PFN_vkCreateInstance =
+            load(get_instance_proc_addr, ptr::null_mut(), b"vkCreateInstance\0");
+        let mut instance = ptr::null_mut();
+        assert_eq!(create_instance(&info, ptr::null(), &mut instance), 0);
+        let destroy_instance: PFN_vkDestroyInstance = load(get_instance_proc_addr, instance, b"vkDestroyInstance\0");
+        let destroy_surface: PFN_vkDestroySurfaceKHR = load(get_instance_proc_addr, instance, b"vkDestroySurfaceKHR\0");
+
+        // Once with the loader we opened, and once with the one ramen opens on its own
+        for get_instance_proc_addr in [Some(get_instance_proc_addr), None] {
+            let surface = window.create_vulkan_surface(instance, get_instance_proc_addr);
+            let surface = surface.expect("failed to create a surface");
+            assert_ne!(surface, 0);
+            destroy_surface(instance, surface, ptr::null());
+        }
+        destroy_instance(instance, ptr::null());
+    }
+}
@@ -0,0 +1,34 @@
+//! Tests against a real Wayland compositor, which are ignored by default as they need one running.
+//!
+//! Weston's headless backend works, which is how CI runs them, with `WAYLAND_DISPLAY` set to its socket:
+//!
+//! ```sh
+//! weston --backend=headless --socket=wayland-ramen &
+//! WAYLAND_DISPLAY=wayland-ramen cargo test --test wayland -- --ignored
+//! ```
+
+#![cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android")), not(feature = "headless")))]
+
+use ramen::{monitor::Size, platform::wayland::WindowExt, window::Window};
+
+#[test]
+#[ignore = "needs a Wayland compositor, such as weston --backend=headless"]
+fn window() {
+    let mut window = Window::builder()
+        .title("ramen wayland test")
+        .inner_size(Size::Physical(320, 240))
+        .build()
+        .expect("failed to create a window");
+    assert!(window.wayland_display().is_some(), "the window wasn't made with Wayland, is WAYLAND_DISPLAY set?");
+    assert!(window.wayland_surface().is_some());
+
+    let (size, scale) = window.inner_size();
+    assert_eq!(size.as_physical(scale), (320, 240));
+    window.set_inner_size(Size::Physical(400, 300));
+    let (size, scale) = window.inner_size();
+    assert_eq!(size.as_physical(scale), (400, 300));
+
+    window.set_title("ramen wayland test, renamed");
+    window.set_visible(false);
+    window.swap_events();
+}
@@ -0,0 +1,58 @@
+//! Tests against a real X server, which are ignored by default as they need one running.
+//!
+//! Xvfb works, which is how CI runs them, as long as `WAYLAND_DISPLAY` isn't set,
+//! since Wayland would be picked otherwise:
+//!
+//! ```sh
+//! env -u WAYLAND_DISPLAY xvfb-run -a cargo test --test x11 -- --ignored
+//! ```
+
+#![cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android")), not(feature = "headless")))]
+
+use ramen::{clipboard::{ClipboardContents, ClipboardFormat}, monitor::Size, platform::x11::WindowExt, window::Window};
+
+#[test]
+#[ignore = "needs an X server, such as Xvfb"]
+fn window() {
+    let mut window = Window::builder()
+        .title("ramen x11 test")
+        .inner_size(Size::Physical(320, 240))
+        .build()
+        .expect("failed to create a window");
+    assert!(window.xlib_display().is_some(), "the window wasn't made with X11, is WAYLAND_DISPLAY set?");
+
+    let (size, scale) = window.inner_size();
+    assert_eq!(size.as_physical(scale), (320, 240));
+    window.set_inner_size(Size::Physical(400, 300));
+    let (size, scale) = window.inner_size();
+    assert_eq!(size.as_physical(scale), (400, 300));
+
+    window.set_title("ramen x11 test, renamed");
+    window.set_visible(false);
+    window.swap_events();
+}
+
+#[test]
+#[ignore = "needs an X server, such as Xvfb"]
+fn clipboard_round_trip() {
+    // Each window has its own connection, so they're as far apart as two clients would be
+    let source = Window::builder().title("ramen clipboard source").build().expect("failed to create a window");
+    let target = Window::builder().title("ramen clipboard target").build().expect("failed to create a window");
+    assert!(source.xlib_display().is_some(), "the window wasn't made with X11, is WAYLAND_DISPLAY set?");
+
+    assert!(source.clipboard().set_text("hello from ramen \u{2713}"));
+    assert_eq!(target.clipboard().text().as_deref(), Some("hello from ramen \u{2713}"));
+    // `TARGETS` also lists `MULTIPLE` & `TIMESTAMP`, which aren't formats
+    assert_eq!(target.clipboard().formats(), &[ClipboardFormat::Text]);
+
+    // Bigger than a single request can carry, so it's sent in chunks with `INCR`
+    let big = (0..32 << 20).map(|i| (i % 251) as u8).collect::<Vec<u8>>();
+    let mut contents = ClipboardContents::new();
+    contents.data("application/x-ramen-test", &big);
+    assert!(source.clipboard().set(&contents));
+    assert_eq!(target.clipboard().data("application/x-ramen-test"), Some(big));
+
+    // Going the other way takes the clipboard away from the first window
+    assert!(target.clipboard().set_text("and back"));
+    assert_eq!(source.clipboard().text().as_deref(), Some("and back"));
+}